@@ -0,0 +1,151 @@
+# A local stand-in for Thunderstore and the sync server, for testing Gale without
+# touching the real services. Start it, then point Gale at it:
+#
+#   python scripts/mock_thunderstore.py
+#   GALE_THUNDERSTORE_URL=http://localhost:8642 \
+#   GALE_SYNC_URL=http://localhost:8642/sync pnpm tauri dev
+#
+# GALE_THUNDERSTORE_URL is only read by debug builds. The tests in
+# src-tauri/src/thunderstore/tests.rs start the mock on a free port by themselves.
+#
+# Responses come from src-tauri/tests/fixtures/thunderstore:
+#
+#   packages/<game>.json      the package listing of a game (default.json for the rest)
+#   files/<Owner-Name-Ver>/   the contents of a package, zipped on download
+#   sync/<id>/meta.json       metadata of a synced profile
+#   sync/<id>/profile/        the contents of a synced profile, zipped on download
+#
+# Profiles pushed to the mock are kept in memory until it's stopped.
+
+import argparse
+import gzip
+import io
+import json
+import os
+import uuid
+import zipfile
+from datetime import datetime, timezone
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from os import path
+
+DEFAULT_FIXTURES = path.realpath(
+    path.join(__file__, '..', '..', 'src-tauri', 'tests', 'fixtures', 'thunderstore')
+)
+
+synced_profiles = {}
+
+
+def zip_dir(dir):
+    buffer = io.BytesIO()
+    with zipfile.ZipFile(buffer, 'w', zipfile.ZIP_DEFLATED) as zip:
+        for root, _, files in os.walk(dir):
+            for file in files:
+                full_path = path.join(root, file)
+                zip.write(full_path, path.relpath(full_path, dir).replace(os.sep, '/'))
+    return buffer.getvalue()
+
+
+def now():
+    return datetime.now(timezone.utc).isoformat()
+
+
+class Handler(BaseHTTPRequestHandler):
+    fixtures = DEFAULT_FIXTURES
+
+    def base_url(self):
+        return f'http://{self.headers["Host"]}'
+
+    def respond(self, status, body=b'', content_type='application/octet-stream'):
+        self.send_response(status)
+        self.send_header('Content-Type', content_type)
+        self.send_header('Content-Length', str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def respond_json(self, value, status=200):
+        self.respond(status, json.dumps(value).encode(), 'application/json')
+
+    def fixture(self, *parts):
+        return path.join(self.fixtures, *parts)
+
+    def do_GET(self):
+        parts = [part for part in self.path.split('?')[0].split('/') if part]
+
+        match parts:
+            case ['c', game, 'api', 'v1', 'package-listing-index']:
+                urls = [f'{self.base_url()}/chunks/{game}']
+                self.respond(200, gzip.compress(json.dumps(urls).encode()))
+
+            case ['chunks', game]:
+                listing = self.fixture('packages', f'{game}.json')
+                if not path.exists(listing):
+                    listing = self.fixture('packages', 'default.json')
+
+                with open(listing, 'rb') as file:
+                    self.respond(200, gzip.compress(file.read()))
+
+            case ['package', 'download', owner, name, version]:
+                dir = self.fixture('files', f'{owner}-{name}-{version}')
+                if path.isdir(dir):
+                    self.respond(200, zip_dir(dir), 'application/zip')
+                else:
+                    self.respond(404)
+
+            case ['sync', 'profile', id, 'meta']:
+                meta = self.fixture('sync', id, 'meta.json')
+                if path.exists(meta):
+                    with open(meta, 'rb') as file:
+                        self.respond(200, file.read(), 'application/json')
+                else:
+                    self.respond(404)
+
+            case ['sync', 'profile', id]:
+                dir = self.fixture('sync', id, 'profile')
+                if id in synced_profiles:
+                    self.respond(200, synced_profiles[id], 'application/zip')
+                elif path.isdir(dir):
+                    self.respond(200, zip_dir(dir), 'application/zip')
+                else:
+                    self.respond(404)
+
+            case _:
+                self.respond(404)
+
+    def do_POST(self):
+        if self.path.split('?')[0].rstrip('/') == '/sync/profile':
+            self.store_profile(str(uuid.uuid4()))
+        else:
+            self.respond(404)
+
+    def do_PUT(self):
+        parts = [part for part in self.path.split('?')[0].split('/') if part]
+
+        match parts:
+            case ['sync', 'profile', id]:
+                self.store_profile(id)
+            case _:
+                self.respond(404)
+
+    def store_profile(self, id):
+        length = int(self.headers.get('Content-Length', 0))
+        synced_profiles[id] = self.rfile.read(length)
+
+        timestamp = now()
+        self.respond_json({'id': id, 'createdAt': timestamp, 'updatedAt': timestamp})
+
+
+def main():
+    parser = argparse.ArgumentParser(description='Serves fake Thunderstore and sync APIs.')
+    parser.add_argument('--port', type=int, default=8642)
+    parser.add_argument('--fixtures', default=DEFAULT_FIXTURES)
+    args = parser.parse_args()
+
+    Handler.fixtures = path.realpath(args.fixtures)
+
+    server = ThreadingHTTPServer(('127.0.0.1', args.port), Handler)
+    print(f'Serving {Handler.fixtures} on http://localhost:{args.port}')
+    server.serve_forever()
+
+
+if __name__ == '__main__':
+    main()
@@ -0,0 +1,31 @@
+# Signs games.json with the maintainers' Ed25519 key, writing games.json.sig next to it.
+# Gale only accepts a fetched game list if the signature matches a key in src-tauri/keys/games.pub,
+# so run this after every change to games.json.
+#
+# Usage: python sign_games.py <private key in PEM format>
+
+import sys
+from base64 import b64encode
+
+from cryptography.hazmat.primitives.serialization import load_pem_private_key
+
+from utils import read_games
+
+def sign_games(key_path):
+    (json_path, _) = read_games()
+
+    with open(key_path, 'rb') as file:
+        key = load_pem_private_key(file.read(), password=None)
+
+    with open(json_path, 'rb') as file:
+        data = file.read()
+
+    with open(json_path + '.sig', 'w') as file:
+        file.write(b64encode(key.sign(data)).decode())
+
+if __name__ == '__main__':
+    if len(sys.argv) != 2:
+        print('usage: python sign_games.py <private key>')
+        sys.exit(1)
+
+    sign_games(sys.argv[1])
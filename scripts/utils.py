@@ -21,4 +21,6 @@ def read_games():
     
 def write_games(json_path, games):
     with open(json_path, 'w') as file:
-        file.write(json.dumps(games, indent=4))
\ No newline at end of file
+        file.write(json.dumps(games, indent=4))
+
+    print('games.json changed, sign it again with sign_games.py')
\ No newline at end of file
@@ -0,0 +1,16 @@
+//! Parses arbitrary BepInEx config files, then writes them back out.
+
+#![no_main]
+
+use gale_core::config::bepinex::File;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(file) = File::read(data) else {
+        return;
+    };
+
+    file.to_frontend();
+    file.write(Vec::new())
+        .expect("parsed file should be writable");
+});
@@ -0,0 +1,28 @@
+//! Checks that no zip entry name can point outside of the extraction directory.
+
+#![no_main]
+
+use std::path::{Component, Path};
+
+use gale_core::archive::entry_path;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|name: &str| {
+    let Some(path) = entry_path(name) else {
+        return;
+    };
+
+    let mut depth = 0usize;
+    for component in Path::new("root").join(path).components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::ParentDir => depth -= 1,
+            Component::CurDir => (),
+            other => panic!("unexpected component {other:?} in {name:?}"),
+        }
+
+        assert!(depth >= 1, "{name:?} escapes the root");
+    }
+
+    assert!(depth > 1, "{name:?} points at the root itself");
+});
@@ -0,0 +1,16 @@
+//! Parses arbitrary GDWeave config files.
+
+#![no_main]
+
+use gale_core::config::gd_weave::File;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(file) = File::read(data) else {
+        return;
+    };
+
+    let _ = file.to_frontend();
+    file.write(Vec::new())
+        .expect("parsed file should be writable");
+});
@@ -0,0 +1,23 @@
+//! Parses arbitrary package and version identifiers.
+
+#![no_main]
+
+use gale_core::ident::{PackageIdent, VersionIdent};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    if let Ok(ident) = text.parse::<VersionIdent>() {
+        let _ = (
+            ident.owner(),
+            ident.name(),
+            ident.version(),
+            ident.full_name(),
+        );
+        assert_eq!(ident.to_string().parse::<VersionIdent>().unwrap(), ident);
+    }
+
+    if let Ok(ident) = text.parse::<PackageIdent>() {
+        let _ = (ident.owner(), ident.name());
+        assert_eq!(ident.to_string().parse::<PackageIdent>().unwrap(), ident);
+    }
+});
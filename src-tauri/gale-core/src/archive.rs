@@ -0,0 +1,118 @@
+//! Checks for paths that come from package archives, which are made by anyone and
+//! can't be trusted to stay inside the directory they're extracted to.
+
+use std::{
+    borrow::Cow,
+    path::{Component, Path, PathBuf},
+};
+
+/// Whether `path` stays inside the directory it's relative to.
+///
+/// Absolute paths, prefixes like `C:` and `..` components that climb above the
+/// start are all rejected, as are paths with null bytes.
+pub fn is_enclosed(path: impl AsRef<Path>) -> bool {
+    depth(path.as_ref()).is_some()
+}
+
+/// Turns the name of a zip entry into a path relative to the extraction directory.
+///
+/// Backslashes are treated as separators on every platform, since some archives are
+/// made with them. Returns `None` if the entry would end up outside of the directory,
+/// or at the directory itself.
+pub fn entry_path(name: &str) -> Option<Cow<'_, Path>> {
+    let path: Cow<'_, Path> = if cfg!(unix) && name.contains('\\') {
+        PathBuf::from(name.replace('\\', "/")).into()
+    } else {
+        Path::new(name).into()
+    };
+
+    match depth(&path) {
+        Some(depth) if depth > 0 => Some(path),
+        _ => None,
+    }
+}
+
+/// How many directories deep `path` ends up, or `None` if it escapes.
+fn depth(path: &Path) -> Option<usize> {
+    if path.to_str().is_some_and(|str| str.contains('\0')) {
+        return None;
+    }
+
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => return None,
+            Component::ParentDir => depth = depth.checked_sub(1)?,
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => (),
+        }
+    }
+
+    Some(depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn rejects_escaping_paths() {
+        for name in ["../a", "a/../../b", "/etc/passwd", "", ".", "a/..", "a\0b"] {
+            assert!(entry_path(name).is_none(), "{name:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn accepts_nested_paths() {
+        for name in ["a", "a/b/c.dll", "./a", "a/../b", "BepInEx/plugins/Mod.dll"] {
+            assert!(entry_path(name).is_some(), "{name:?} should be accepted");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn normalizes_backslashes() {
+        assert_eq!(
+            entry_path(r"BepInEx\plugins\Mod.dll").unwrap(),
+            Path::new("BepInEx/plugins/Mod.dll")
+        );
+        assert!(entry_path(r"..\..\evil.dll").is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(name in any::<String>()) {
+            let _ = entry_path(&name);
+        }
+
+        #[test]
+        fn accepted_paths_stay_inside(name in r"[a-z./\\]{0,24}") {
+            if let Some(path) = entry_path(&name) {
+                let root = Path::new("/root");
+                let joined = root.join(&path);
+
+                let mut resolved = PathBuf::new();
+                for component in joined.components() {
+                    match component {
+                        Component::ParentDir => {
+                            resolved.pop();
+                        }
+                        Component::CurDir => (),
+                        component => resolved.push(component),
+                    }
+                }
+
+                prop_assert!(resolved.starts_with(root));
+                prop_assert_ne!(resolved.as_path(), root);
+            }
+        }
+
+        #[test]
+        fn parent_dir_prefix_is_rejected(name in r"[a-z/]{0,16}") {
+            let escaping = format!("../{name}");
+            prop_assert!(entry_path(&escaping).is_none());
+        }
+    }
+}
@@ -1,6 +1,10 @@
-use std::io::{BufRead, Write};
+use std::{
+    io::{BufRead, Write},
+    str::FromStr,
+};
 
-use eyre::{eyre, OptionExt, Result};
+use eyre::{bail, ensure, eyre, OptionExt, Result};
+use serde::Serialize;
 
 use super::frontend::{self, Num};
 
@@ -43,6 +47,37 @@ impl File {
         frontend::FileData { metadata, sections }
     }
 
+    /// Adds the sections and entries from `defaults` that this file doesn't have,
+    /// leaving existing values alone. Returns how many entries were added.
+    pub fn merge_missing(&mut self, defaults: File) -> usize {
+        let mut added = 0;
+
+        for default_section in defaults.sections {
+            let Some(section) = self
+                .sections
+                .iter_mut()
+                .find(|section| section.name == default_section.name)
+            else {
+                added += default_section.entries.len();
+                self.sections.push(default_section);
+                continue;
+            };
+
+            for entry in default_section.entries {
+                if section
+                    .entries
+                    .iter()
+                    .all(|other| other.name() != entry.name())
+                {
+                    section.entries.push(entry);
+                    added += 1;
+                }
+            }
+        }
+
+        added
+    }
+
     fn find_section(&mut self, name: &str) -> Result<&mut Section> {
         self.sections
             .iter_mut()
@@ -54,6 +89,33 @@ impl File {
         self.find_section(section)
             .and_then(|section| section.find_entry(entry))
     }
+
+    /// Tagged entries called `name`, optionally only those in `section`.
+    /// Both names are compared case-insensitively.
+    pub fn entries_named_mut<'a>(
+        &'a mut self,
+        section: Option<&'a str>,
+        name: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a mut Entry)> + 'a {
+        self.sections
+            .iter_mut()
+            .filter(move |s| section.map_or(true, |section| s.name.eq_ignore_ascii_case(section)))
+            .flat_map(
+                move |Section {
+                          name: section_name,
+                          entries,
+                      }| {
+                    let section_name: &String = section_name;
+
+                    entries.iter_mut().filter_map(move |entry| match entry {
+                        EntryKind::Normal(entry) if entry.name.eq_ignore_ascii_case(name) => {
+                            Some((section_name.as_str(), entry))
+                        }
+                        _ => None,
+                    })
+                },
+            )
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -163,6 +225,18 @@ pub enum Value {
 }
 
 impl Entry {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    pub fn set_value(&mut self, value: Value) {
+        self.value = value;
+    }
+
     fn reset(&mut self) -> Result<frontend::Value> {
         self.value = self.default_value.clone().ok_or_eyre("no default value")?;
         Ok(self.value.clone().into())
@@ -186,6 +260,64 @@ impl Value {
             _ => None,
         }
     }
+
+    /// Parses `text` as a value of the same type, keeping the options and range of `self`.
+    pub fn parse_as(&self, text: &str) -> Result<Self> {
+        let text = text.trim();
+
+        Ok(match self {
+            Self::Boolean(_) => match text.to_ascii_lowercase().as_str() {
+                "true" => Self::Boolean(true),
+                "false" => Self::Boolean(false),
+                _ => bail!("expected true or false"),
+            },
+            Self::String(_) => Self::String(text.to_owned()),
+            Self::Other(_) => Self::Other(text.to_owned()),
+            Self::Int32(num) => Self::Int32(parse_num(num, text)?),
+            Self::Single(num) => Self::Single(parse_num(num, text)?),
+            Self::Double(num) => Self::Double(parse_num(num, text)?),
+            Self::Enum { options, .. } => Self::Enum {
+                index: find_option(options, text)?,
+                options: options.clone(),
+            },
+            Self::Flags { options, .. } => Self::Flags {
+                indicies: text
+                    .split(',')
+                    .map(|flag| find_option(options, flag.trim()))
+                    .collect::<Result<_>>()?,
+                options: options.clone(),
+            },
+        })
+    }
+}
+
+fn parse_num<T>(num: &Num<T>, text: &str) -> Result<Num<T>>
+where
+    T: Serialize + ToString + FromStr + PartialOrd + Clone,
+{
+    let value: T = text
+        .parse()
+        .map_err(|_| eyre!("{} is not a valid number", text))?;
+
+    if let Some(range) = &num.range {
+        ensure!(
+            range.start <= value && value <= range.end,
+            "{} is outside of the allowed range",
+            text
+        );
+    }
+
+    Ok(Num {
+        value,
+        range: num.range.clone(),
+    })
+}
+
+fn find_option(options: &[String], text: &str) -> Result<usize> {
+    options
+        .iter()
+        .position(|option| option.eq_ignore_ascii_case(text))
+        .ok_or_else(|| eyre!("{} is not one of the options", text))
 }
 
 impl From<Value> for frontend::Value {
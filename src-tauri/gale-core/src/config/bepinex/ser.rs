@@ -68,13 +68,18 @@ impl<W: Write> Serializer<W> {
             Value::Int32(num) => write!(self, "{}", num.value),
             Value::Single(num) => write!(self, "{}", num.value),
             Value::Double(num) => write!(self, "{}", num.value),
-            Value::Enum { index, options } => write!(self, "{}", options[*index]),
+            // indices can come from the frontend, so don't trust them to be in range
+            Value::Enum { index, options } => match options.get(*index) {
+                Some(option) => write!(self, "{option}"),
+                None => Ok(()),
+            },
             Value::Flags { indicies, options } => {
                 if indicies.is_empty() {
                     return write!(self, "0");
                 }
 
-                for (i, option) in indicies.iter().map(|index| &options[*index]).enumerate() {
+                let selected = indicies.iter().filter_map(|index| options.get(*index));
+                for (i, option) in selected.enumerate() {
                     if i > 0 {
                         write!(self, ", ")?;
                     }
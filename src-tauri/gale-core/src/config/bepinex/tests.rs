@@ -194,3 +194,104 @@ fn check_from_string() {
 
     assert_eq!(left, right);
 }
+
+mod props {
+    use proptest::{option, prelude::*};
+
+    use super::*;
+
+    const NAME: &str = "[A-Za-z][A-Za-z0-9_.]{0,15}";
+    const DESCRIPTION: &str = "[A-Za-z0-9.,]{1,10}( [A-Za-z0-9.,]{1,10}){0,4}";
+
+    fn int(value: i32) -> Value {
+        Value::Int32(Num { value, range: None })
+    }
+
+    fn entry() -> impl Strategy<Value = EntryKind> {
+        prop_oneof![
+            (NAME, "[A-Za-z0-9_.!]{0,16}")
+                .prop_map(|(name, value)| EntryKind::orphaned(&name, &value)),
+            (NAME, DESCRIPTION, any::<bool>(), any::<bool>()).prop_map(
+                |(name, description, default, value)| {
+                    EntryKind::normal(
+                        &name,
+                        &description,
+                        Some(Value::Boolean(default)),
+                        Value::Boolean(value),
+                    )
+                }
+            ),
+            (NAME, DESCRIPTION, option::of(any::<i32>()), any::<i32>()).prop_map(
+                |(name, description, default, value)| {
+                    EntryKind::normal(&name, &description, default.map(int), int(value))
+                }
+            ),
+            (NAME, DESCRIPTION, "[A-Za-z0-9_.,]{0,16}").prop_map(|(name, description, value)| {
+                EntryKind::normal(&name, &description, None, Value::String(value))
+            }),
+        ]
+    }
+
+    fn metadata() -> impl Strategy<Value = Metadata> {
+        (
+            "[A-Za-z]{1,8}( [A-Za-z]{1,8}){0,2}",
+            "v?[0-9]\\.[0-9]\\.[0-9]",
+            NAME,
+        )
+            .prop_map(|(plugin_name, plugin_version, plugin_guid)| Metadata {
+                plugin_name,
+                plugin_version,
+                plugin_guid,
+            })
+    }
+
+    fn file() -> impl Strategy<Value = File> {
+        let section = (NAME, prop::collection::vec(entry(), 0..6))
+            .prop_map(|(name, entries)| Section::new(&name, entries));
+
+        (option::of(metadata()), prop::collection::vec(section, 0..4))
+            .prop_map(|(metadata, sections)| File { metadata, sections })
+    }
+
+    /// Lines that look enough like a config to get past the first checks of the parser.
+    fn config_line() -> impl Strategy<Value = String> {
+        prop_oneof![
+            Just(String::new()),
+            Just(de::FLAGS_MESSAGE.to_owned()),
+            "\\[[A-Za-z ]{0,8}\\]?",
+            "## ?[A-Za-z ]{0,16}",
+            "# (Setting type|Default value|Acceptable values): ?[A-Za-z0-9 ,.-]{0,16}",
+            "# Acceptable value range: From -?[0-9.,]{0,4} to -?[0-9.,]{0,4}",
+            "## Settings file was created by plugin [A-Za-z0-9 .]{0,12}",
+            "## Plugin GUID: [A-Za-z.]{0,12}",
+            "[A-Za-z ]{0,8}=? ?[A-Za-z0-9,. ]{0,12}",
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips(file in file()) {
+            let text = ser::to_string(&file).unwrap();
+            let parsed = de::from_reader(text.as_bytes()).unwrap();
+
+            prop_assert_eq!(parsed, file);
+        }
+
+        #[test]
+        fn never_panics(text in any::<String>()) {
+            let _ = de::from_reader(text.as_bytes());
+        }
+
+        #[test]
+        fn never_panics_on_config_like_input(
+            lines in prop::collection::vec(config_line(), 0..32)
+        ) {
+            let text = lines.join("\n");
+
+            if let Ok(file) = de::from_reader(text.as_bytes()) {
+                ser::to_string(&file).unwrap();
+                file.to_frontend();
+            }
+        }
+    }
+}
@@ -22,6 +22,32 @@ impl File {
         Ok(())
     }
 
+    /// Adds the keys from `defaults` that this file doesn't have, leaving existing
+    /// values alone. Returns how many keys were added.
+    pub fn merge_missing(&mut self, defaults: File) -> usize {
+        let mut added = 0;
+
+        for (key, value) in defaults.entries {
+            if !self.entries.contains_key(&key) {
+                self.entries.insert(key, value);
+                added += 1;
+            }
+        }
+
+        added
+    }
+
+    /// Entries called `name`, compared case-insensitively.
+    pub fn entries_named_mut<'a>(
+        &'a mut self,
+        name: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a mut Value)> + 'a {
+        self.entries
+            .iter_mut()
+            .filter(move |(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
     pub fn set(&mut self, name: impl Into<String>, value: frontend::Value) -> Result<()> {
         self.entries.insert(name.into(), value.try_into()?);
         Ok(())
@@ -54,6 +80,34 @@ impl File {
     }
 }
 
+/// Parses `text` as the same JSON type as `current`.
+pub fn parse_as(current: &Value, text: &str) -> Result<Value> {
+    let text = text.trim();
+
+    Ok(match current {
+        Value::Bool(_) => match text.to_ascii_lowercase().as_str() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => bail!("expected true or false"),
+        },
+        Value::String(_) => Value::String(text.to_owned()),
+        Value::Number(number) if number.is_i64() => {
+            let value: i64 = text
+                .parse()
+                .map_err(|_| eyre!("{} is not a valid integer", text))?;
+            Value::Number(value.into())
+        }
+        Value::Number(_) => {
+            let value: f64 = text
+                .parse()
+                .map_err(|_| eyre!("{} is not a valid number", text))?;
+            let number = Number::from_f64(value).ok_or_eyre("cannot use NaN or infinite value")?;
+            Value::Number(number)
+        }
+        value => bail!("unsupported JSON value type: {}", value),
+    })
+}
+
 impl TryFrom<Value> for frontend::Value {
     type Error = eyre::Error;
 
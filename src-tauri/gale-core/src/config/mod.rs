@@ -0,0 +1,9 @@
+//! Readers and writers for mod config files.
+//!
+//! Each format has its own `File` type that can be read, edited and written back
+//! without losing comments or ordering, and converted to the [`frontend`]
+//! representation shared by all formats.
+
+pub mod bepinex;
+pub mod frontend;
+pub mod gd_weave;
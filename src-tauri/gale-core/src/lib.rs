@@ -0,0 +1,22 @@
+//! The parts of [Gale](https://github.com/Kesomannen/gale) that work without the app,
+//! for bots and other tools that deal with Thunderstore packages and mod configs.
+//!
+//! - [`ident`]: package and version identifiers, like `BepInEx-BepInExPack-5.4.2100`.
+//! - [`archive`]: validating the paths of files in package archives.
+//! - [`config`]: reading, editing and writing BepInEx and GDWeave config files.
+//!
+//! More of Gale will move here over time. Until this crate reaches 1.0, breaking
+//! changes can happen in any minor version.
+//!
+//! ```
+//! use gale_core::ident::VersionIdent;
+//!
+//! let ident: VersionIdent = "BepInEx-BepInExPack-5.4.2100".parse().unwrap();
+//!
+//! assert_eq!(ident.full_name(), "BepInEx-BepInExPack");
+//! assert_eq!(ident.version(), "5.4.2100");
+//! ```
+
+pub mod archive;
+pub mod config;
+pub mod ident;
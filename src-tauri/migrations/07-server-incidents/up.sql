@@ -0,0 +1,8 @@
+CREATE TABLE server_incidents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    profile_id INTEGER NOT NULL,
+    occurred_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    exit_code INTEGER,
+    log_tail TEXT,
+    restarted BOOLEAN NOT NULL DEFAULT 0
+);
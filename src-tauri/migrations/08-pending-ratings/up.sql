@@ -0,0 +1,5 @@
+CREATE TABLE pending_ratings (
+    package_uuid UUID NOT NULL PRIMARY KEY,
+    rated BOOLEAN NOT NULL,
+    queued_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,7 @@
+CREATE TABLE package_stats (
+    package_uuid UUID NOT NULL,
+    recorded_on DATE NOT NULL DEFAULT CURRENT_DATE,
+    downloads INTEGER NOT NULL,
+    rating INTEGER NOT NULL,
+    PRIMARY KEY (package_uuid, recorded_on)
+);
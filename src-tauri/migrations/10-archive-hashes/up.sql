@@ -0,0 +1,5 @@
+CREATE TABLE archive_hashes (
+    version_uuid UUID NOT NULL PRIMARY KEY,
+    hash TEXT NOT NULL,
+    recorded_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1 @@
+DROP TABLE approved_packages;
@@ -0,0 +1,4 @@
+CREATE TABLE approved_packages (
+    package_uuid UUID NOT NULL PRIMARY KEY,
+    approved_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,2 @@
+ALTER TABLE profiles
+DROP COLUMN workshop_items;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE profiles
+ADD COLUMN workshop_items JSON;
\ No newline at end of file
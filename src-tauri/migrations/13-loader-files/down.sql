@@ -0,0 +1,2 @@
+ALTER TABLE profiles
+DROP COLUMN loader_files;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE profiles
+ADD COLUMN loader_files JSON;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE profiles
+DROP COLUMN notes;
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE profiles
+ADD COLUMN notes TEXT;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE game_visits;
\ No newline at end of file
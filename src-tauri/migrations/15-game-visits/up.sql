@@ -0,0 +1,4 @@
+CREATE TABLE game_visits (
+    game_slug TEXT NOT NULL PRIMARY KEY,
+    visited_at INTEGER NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE profiles
+ADD COLUMN readme TEXT;
\ No newline at end of file
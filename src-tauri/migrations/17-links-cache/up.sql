@@ -0,0 +1,5 @@
+CREATE TABLE links_cache (
+    version_id UUID NOT NULL PRIMARY KEY,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    content BLOB
+);
@@ -0,0 +1,11 @@
+CREATE TABLE audit_log (
+    id INTEGER PRIMARY KEY,
+    at INTEGER NOT NULL,
+    user TEXT NOT NULL,
+    command TEXT NOT NULL,
+    game_slug TEXT,
+    profile_name TEXT,
+    args TEXT NOT NULL
+);
+
+CREATE INDEX audit_log_at ON audit_log (at);
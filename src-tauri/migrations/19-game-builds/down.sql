@@ -0,0 +1 @@
+DROP TABLE game_builds;
\ No newline at end of file
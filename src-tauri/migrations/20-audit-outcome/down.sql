@@ -0,0 +1,10 @@
+DROP INDEX audit_log_request_id;
+
+ALTER TABLE audit_log
+DROP COLUMN request_id;
+
+ALTER TABLE audit_log
+DROP COLUMN succeeded;
+
+ALTER TABLE audit_log
+DROP COLUMN error;
\ No newline at end of file
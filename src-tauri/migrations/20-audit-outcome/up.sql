@@ -0,0 +1,10 @@
+ALTER TABLE audit_log
+ADD COLUMN request_id TEXT;
+
+ALTER TABLE audit_log
+ADD COLUMN succeeded BOOLEAN;
+
+ALTER TABLE audit_log
+ADD COLUMN error TEXT;
+
+CREATE INDEX audit_log_request_id ON audit_log (request_id);
\ No newline at end of file
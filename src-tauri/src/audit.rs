@@ -0,0 +1,238 @@
+//! Keeps a trail of the commands that change state, like deleting a profile or
+//! removing a mod, to answer "what happened to my mods" on shared machines.
+//!
+//! Every command that kiosk mode would block is recorded, see [`kiosk::is_read_only`].
+//! The entry is written when the command is invoked, and its outcome is filled in
+//! when the backend responds, see [`handle`]. Entries older than [`MAX_AGE`] are
+//! pruned on startup.
+//!
+//! [`kiosk::is_read_only`]: crate::prefs::kiosk::is_read_only
+
+use std::{
+    collections::HashSet,
+    env,
+    sync::{LazyLock, Mutex},
+};
+
+use chrono::{TimeDelta, Utc};
+use serde_json::Value;
+use tauri::{
+    command,
+    http::HeaderValue,
+    ipc::{CallbackFn, Invoke, InvokeBody, InvokeError, InvokeResponse},
+    webview::InvokeRequest,
+    AppHandle,
+};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::audit::{AuditEntry, AuditFilter},
+    prefs::kiosk,
+    state::ManagerExt,
+    util::cmd::Result,
+};
+
+pub const MAX_AGE: TimeDelta = TimeDelta::days(90);
+
+/// Arguments longer than this are cut off, since some commands take whole files.
+const MAX_ARGS_LEN: usize = 1000;
+
+/// Header that tags the invokes [`handle`] dispatches again, see [`DISPATCHED`].
+const REQUEST_ID_HEADER: &str = "gale-request-id";
+
+/// Request ids of the invokes [`handle`] is waiting on. The frontend can't know these
+/// in advance, so setting the header itself can't get a command past the log.
+static DISPATCHED: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Arguments that are never stored, wherever they appear. Covers the kiosk PIN,
+/// the Thunderstore token and webhook URLs, which are as good as passwords.
+const REDACTED_KEYS: &[&str] = &["pin", "kioskPin", "token", "webhooks"];
+
+/// Records `command` if it can change state. This happens before the command runs,
+/// so that a command that never returns still shows up.
+pub fn record(command: &str, payload: &InvokeBody, request_id: &str, app: &AppHandle) {
+    if kiosk::is_read_only(command) {
+        return;
+    }
+
+    // don't wait for a long-running command to release the manager
+    let (game_slug, profile_name) = match app.app_state().manager.try_lock() {
+        Ok(manager) => (
+            Some(manager.active_game.slug.to_string()),
+            Some(manager.active_profile().name.clone()),
+        ),
+        Err(_) => (None, None),
+    };
+
+    let entry = AuditEntry {
+        at: Utc::now(),
+        user: current_user(),
+        command: command.to_owned(),
+        game_slug,
+        profile_name,
+        args: format_args(payload),
+        succeeded: None,
+        error: None,
+    };
+
+    // not in the background, the outcome could otherwise arrive before the entry
+    if let Err(err) = app.db().record_audit(&entry, request_id) {
+        warn!("failed to record {} in audit log: {:#}", entry.command, err);
+    }
+}
+
+/// Runs `invoke` with `handler`, recording it along with its outcome if it can change state.
+///
+/// The response of a command can't be observed through its resolver, so the invoke
+/// is dispatched again with a resolver of our own, which passes the response on to
+/// the original one once the outcome is recorded.
+pub fn handle(invoke: Invoke, handler: fn(Invoke) -> bool) -> bool {
+    let command = invoke.message.command();
+    if kiosk::is_read_only(command) || is_dispatched(&invoke) {
+        return handler(invoke);
+    }
+
+    let app = invoke.message.webview_ref().app_handle().clone();
+    let request_id = Uuid::new_v4().to_string();
+    record(command, invoke.message.payload(), &request_id, &app);
+
+    let Invoke {
+        message, resolver, ..
+    } = invoke;
+
+    let webview = message.webview();
+    let url = match webview.url() {
+        Ok(url) => url,
+        Err(err) => {
+            finish(&request_id, Some(&err.to_string()), &app);
+            resolver.reject(err.to_string());
+            return true;
+        }
+    };
+
+    let mut headers = message.headers().clone();
+    headers.insert(
+        REQUEST_ID_HEADER,
+        HeaderValue::from_str(&request_id).expect("uuid should be a valid header value"),
+    );
+
+    let request = InvokeRequest {
+        cmd: message.command().to_owned(),
+        // the response goes through `resolver` instead
+        callback: CallbackFn(0),
+        error: CallbackFn(0),
+        url,
+        body: message.payload().clone(),
+        headers,
+        invoke_key: app.invoke_key().to_owned(),
+    };
+
+    DISPATCHED.lock().unwrap().insert(request_id.clone());
+
+    webview.on_message(
+        request,
+        Box::new(move |_, _, response, _, _| {
+            // in case it was rejected before reaching the handler
+            DISPATCHED.lock().unwrap().remove(&request_id);
+
+            match response {
+                InvokeResponse::Ok(body) => {
+                    finish(&request_id, None, &app);
+                    resolver.resolve(body);
+                }
+                InvokeResponse::Err(err) => {
+                    finish(&request_id, Some(&error_message(&err)), &app);
+                    resolver.invoke_error(err);
+                }
+            }
+        }),
+    );
+
+    true
+}
+
+fn is_dispatched(invoke: &Invoke) -> bool {
+    invoke
+        .message
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|request_id| DISPATCHED.lock().unwrap().remove(request_id))
+}
+
+/// Errors are sent as plain strings, or as objects with a message (see [`crate::util::cmd`]).
+fn error_message(err: &InvokeError) -> String {
+    match &err.0 {
+        Value::String(message) => message.clone(),
+        value => match value.get("message") {
+            Some(Value::String(message)) => message.clone(),
+            _ => value.to_string(),
+        },
+    }
+}
+
+/// Fills in how the command recorded under `request_id` went.
+pub fn finish(request_id: &str, error: Option<&str>, app: &AppHandle) {
+    if let Err(err) = app.db().finish_audit(request_id, error) {
+        warn!("failed to record command outcome in audit log: {:#}", err);
+    }
+}
+
+fn current_user() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_owned())
+}
+
+fn format_args(payload: &InvokeBody) -> String {
+    let mut args = match payload {
+        InvokeBody::Json(value) => {
+            let mut value = value.clone();
+            redact(&mut value);
+            value.to_string()
+        }
+        InvokeBody::Raw(bytes) => format!("<{} bytes>", bytes.len()),
+    };
+
+    if args.len() > MAX_ARGS_LEN {
+        let mut end = MAX_ARGS_LEN;
+        while !args.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        args.truncate(end);
+        args.push('…');
+    }
+
+    args
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if REDACTED_KEYS.contains(&key.as_str()) {
+                    *value = Value::String("<redacted>".to_owned());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact),
+        _ => (),
+    }
+}
+
+pub fn prune(app: &AppHandle) {
+    match app.db().prune_audit(Utc::now() - MAX_AGE) {
+        Ok(count) => debug!("pruned {} audit log entries", count),
+        Err(err) => warn!("failed to prune audit log: {:#}", err),
+    }
+}
+
+#[command]
+pub fn get_audit_log(filter: Option<AuditFilter>, app: AppHandle) -> Result<Vec<AuditEntry>> {
+    let entries = app.db().query_audit(&filter.unwrap_or_default())?;
+    Ok(entries)
+}
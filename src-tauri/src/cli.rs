@@ -1,14 +1,18 @@
 use std::{path::PathBuf, process, time::Duration};
 
 use clap::Parser;
-use eyre::{eyre, Context, OptionExt, Result};
+use eyre::{ensure, eyre, Context, OptionExt, Result};
 use tauri::AppHandle;
 use tracing::{debug, error, info};
 
 use crate::{
     game::{self},
     logger,
-    profile::{self, install::InstallOptions, ModManager},
+    profile::{
+        self,
+        install::{lock, InstallOptions},
+        server::{self, AutoUpdateOptions, ServerOptions, WatchdogOptions},
+    },
     state::ManagerExt,
 };
 
@@ -51,25 +55,78 @@ struct Cli {
     #[arg(short, long, value_name = "PATH")]
     install: Option<PathBuf>,
 
+    #[arg(long)]
+    locked: bool,
+
     #[arg(short, long)]
     launch: bool,
 
     #[arg(long)]
     no_gui: bool,
+
+    #[arg(long, value_name = "MINUTES")]
+    auto_update: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 60,
+        requires = "auto_update"
+    )]
+    warn_secs: u64,
+
+    #[arg(long, value_name = "COMMAND", requires = "auto_update")]
+    warn_command: Option<String>,
+
+    #[arg(long, value_name = "URL", requires = "auto_update")]
+    warn_url: Option<String>,
+
+    #[arg(long, value_name = "MAX_RESTARTS")]
+    watchdog: Option<u32>,
+
+    // these are read before the cli is parsed, see util::path
+    #[arg(long)]
+    portable: bool,
+
+    #[arg(long, value_name = "PATH")]
+    data_dir: Option<PathBuf>,
+
+    #[arg(long, value_name = "PATH")]
+    cache_dir: Option<PathBuf>,
+
+    #[arg(long, value_name = "PATH")]
+    temp_dir: Option<PathBuf>,
 }
 
 impl Cli {
     fn run(self, app: &AppHandle) -> Result<()> {
-        let mut manager = app.lock_manager();
-
         let Cli {
             game,
             profile,
             install,
+            locked,
             launch,
             no_gui,
+            auto_update,
+            warn_secs,
+            warn_command,
+            warn_url,
+            watchdog,
+            portable: _,
+            data_dir: _,
+            cache_dir: _,
+            temp_dir: _,
         } = self;
 
+        if app.lock_prefs().kiosk_mode {
+            ensure!(
+                install.is_none() && !locked && auto_update.is_none(),
+                "installing and updating mods is disabled in kiosk mode"
+            );
+        }
+
+        let mut manager = app.lock_manager();
+
         if let Some(slug) = &game {
             let game = game::from_slug(slug).ok_or_eyre("unknown game slug")?;
 
@@ -97,49 +154,76 @@ impl Cli {
             manager.save_all(app)?;
         }
 
-        if let Some(path) = install {
+        if auto_update.is_some() || watchdog.is_some() {
             drop(manager);
 
+            let options = ServerOptions {
+                auto_update: auto_update.map(|minutes| AutoUpdateOptions {
+                    interval: Duration::from_secs(minutes * 60),
+                    warning: Duration::from_secs(warn_secs),
+                    warn_command,
+                    warn_url,
+                }),
+                watchdog: watchdog.map(|max_restarts| WatchdogOptions {
+                    max_restarts,
+                    log_lines: 100,
+                }),
+            };
+
             let handle = app.to_owned();
             tauri::async_runtime::spawn(async move {
-                if let Err(err) = install_local_mod(path, &handle).await {
-                    error!("failed to install mod from cli: {:#}", err)
+                let result = server::run(options, &handle).await;
+
+                if let Err(err) = &result {
+                    error!("server supervision stopped: {:#}", err);
                 }
 
-                let manager = handle.lock_manager();
-                if let Err(err) = handle_launch_and_no_gui(launch, no_gui, &manager, &handle) {
-                    error!("{:#}", err);
+                if no_gui {
+                    process::exit(if result.is_ok() { 0 } else { 1 });
                 }
             });
-        } else {
-            handle_launch_and_no_gui(launch, no_gui, &manager, app)?;
+
+            return Ok(());
         }
 
-        debug!("cli finished");
-        return Ok(());
-
-        fn handle_launch_and_no_gui(
-            launch: bool,
-            no_gui: bool,
-            manager: &ModManager,
-            app: &AppHandle,
-        ) -> Result<()> {
+        let profile_id = manager.active_profile().id;
+        drop(manager);
+
+        let handle = app.to_owned();
+        tauri::async_runtime::spawn(async move {
+            if locked {
+                if let Err(err) = lock::install_locked(profile_id, None, &handle).await {
+                    error!("failed to install from lockfile: {:#}", err)
+                }
+            }
+
+            if let Some(path) = install {
+                if let Err(err) = install_local_mod(path, &handle).await {
+                    error!("failed to install mod from cli: {:#}", err)
+                }
+            }
+
             if launch {
-                manager
-                    .active_game()
-                    .launch(&app.lock_prefs(), app)
-                    .context("failed to launch game")?;
+                if let Err(err) = profile::launch::launch(&handle).await {
+                    if no_gui {
+                        error!("failed to launch game: {:#}", err);
+                        process::exit(1);
+                    }
+
+                    logger::log_webview_err("Failed to launch game", err, &handle);
+                }
             }
 
             if no_gui {
                 process::exit(0);
             }
+        });
 
-            Ok(())
-        }
+        debug!("cli finished");
+        Ok(())
     }
 }
 
 async fn install_local_mod(path: PathBuf, app: &AppHandle) -> Result<()> {
-    profile::import::import_local_mod(path, None, app, InstallOptions::default()).await
+    profile::import::import_local_mod(path, None, None, app, InstallOptions::default()).await
 }
@@ -0,0 +1,175 @@
+//! Changing the same entry across many config files at once, for example
+//! setting every `LogLevel` to `Warning` in a pack with dozens of chatty mods.
+
+use std::path::{Path, PathBuf};
+
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{frontend, gd_weave, AnyFileKind, ConfigCache};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEdit {
+    /// Name of the entries to change, ignoring case.
+    pub entry: String,
+    /// If set, only entries in sections with this name are changed.
+    pub section: Option<String>,
+    /// Relative paths of the files to consider. If empty, every file is.
+    #[serde(default)]
+    pub files: Vec<PathBuf>,
+    /// The new value, interpreted according to the type of each entry.
+    pub value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchChange {
+    pub file: PathBuf,
+    pub section: String,
+    pub entry: String,
+    pub old_value: frontend::Value,
+    pub new_value: frontend::Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSkip {
+    pub file: PathBuf,
+    pub section: String,
+    pub entry: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEditResult {
+    pub changes: Vec<BatchChange>,
+    /// Matching entries that the value couldn't be applied to.
+    pub skipped: Vec<BatchSkip>,
+}
+
+impl BatchEditResult {
+    fn skip(&mut self, file: &Path, section: &str, entry: &str, err: eyre::Report) {
+        self.skipped.push(BatchSkip {
+            file: file.to_path_buf(),
+            section: section.to_owned(),
+            entry: entry.to_owned(),
+            reason: format!("{err:#}"),
+        });
+    }
+}
+
+/// The section name shown for GDWeave files, which don't have sections.
+const GD_WEAVE_SECTION: &str = "Default";
+
+impl ConfigCache {
+    /// Applies `edit` to every matching entry and writes the changed files.
+    ///
+    /// With `dry_run`, the changes are only computed and nothing is modified.
+    pub fn batch_edit(
+        &mut self,
+        edit: &BatchEdit,
+        dry_run: bool,
+        profile_dir: &Path,
+    ) -> Result<BatchEditResult> {
+        let mut result = BatchEditResult::default();
+
+        for file in &mut self.0 {
+            if !edit.files.is_empty() && !edit.files.contains(&file.relative_path) {
+                continue;
+            }
+
+            let change_count = result.changes.len();
+            let path = &file.relative_path;
+
+            match &mut file.kind {
+                AnyFileKind::BepInEx(config) => {
+                    for (section, entry) in
+                        config.entries_named_mut(edit.section.as_deref(), &edit.entry)
+                    {
+                        let new_value = match entry.value().parse_as(&edit.value) {
+                            Ok(value) => value,
+                            Err(err) => {
+                                result.skip(path, section, entry.name(), err);
+                                continue;
+                            }
+                        };
+
+                        if &new_value == entry.value() {
+                            continue;
+                        }
+
+                        result.changes.push(BatchChange {
+                            file: path.clone(),
+                            section: section.to_owned(),
+                            entry: entry.name().to_owned(),
+                            old_value: entry.value().clone().into(),
+                            new_value: new_value.clone().into(),
+                        });
+
+                        if !dry_run {
+                            entry.set_value(new_value);
+                        }
+                    }
+                }
+                AnyFileKind::GDWeave(config) => {
+                    let section = GD_WEAVE_SECTION;
+                    if edit
+                        .section
+                        .as_deref()
+                        .is_some_and(|name| !name.eq_ignore_ascii_case(section))
+                    {
+                        continue;
+                    }
+
+                    for (name, value) in config.entries_named_mut(&edit.entry) {
+                        let new_value = match gd_weave::parse_as(value, &edit.value) {
+                            Ok(new_value) => new_value,
+                            Err(err) => {
+                                result.skip(path, section, name, err);
+                                continue;
+                            }
+                        };
+
+                        if &new_value == value {
+                            continue;
+                        }
+
+                        let values: Result<(frontend::Value, frontend::Value)> = value
+                            .clone()
+                            .try_into()
+                            .and_then(|old_value| Ok((old_value, new_value.clone().try_into()?)));
+
+                        let (old_value, frontend_value) = match values {
+                            Ok(values) => values,
+                            Err(err) => {
+                                result.skip(path, section, name, err);
+                                continue;
+                            }
+                        };
+
+                        result.changes.push(BatchChange {
+                            file: path.clone(),
+                            section: section.to_owned(),
+                            entry: name.to_owned(),
+                            old_value,
+                            new_value: frontend_value,
+                        });
+
+                        if !dry_run {
+                            *value = new_value;
+                        }
+                    }
+                }
+                _ => continue,
+            }
+
+            if !dry_run && result.changes.len() > change_count {
+                file.write(profile_dir)?;
+            }
+        }
+
+        Ok(result)
+    }
+}
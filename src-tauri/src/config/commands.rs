@@ -1,10 +1,26 @@
-use std::path::Path;
+use std::{
+    fs,
+    io::{BufReader, BufWriter, Cursor},
+    path::{Path, PathBuf},
+};
 
 use eyre::{eyre, Context};
 use tauri::{command, AppHandle};
-
-use super::{frontend, AnyFileKind};
-use crate::{state::ManagerExt, util::cmd::Result};
+use uuid::Uuid;
+
+use super::{
+    batch::{BatchEdit, BatchEditResult},
+    frontend,
+    preset::{self, PresetData},
+    watch, AnyFile, AnyFileKind,
+};
+use crate::{
+    i18n,
+    prefs::access,
+    profile::{export, import, Profile},
+    state::ManagerExt,
+    util::{cmd::Result, error::IoResultExt},
+};
 
 #[command]
 pub fn get_config_files(app: AppHandle) -> Result<Vec<frontend::File>> {
@@ -27,7 +43,7 @@ pub fn set_config_entry(
     let mut manager = app.lock_manager();
 
     let profile = manager.active_profile_mut();
-    let file = profile.config_cache.find_file(file)?;
+    let file = find_fresh_file(profile, file)?;
 
     match &mut file.kind {
         AnyFileKind::BepInEx(file) => file.find_entry(section, entry)?.set(value),
@@ -49,7 +65,7 @@ pub fn reset_config_entry(
     let mut manager = app.lock_manager();
 
     let profile = manager.active_profile_mut();
-    let file = profile.config_cache.find_file(file)?;
+    let file = find_fresh_file(profile, file)?;
 
     let value = match &mut file.kind {
         AnyFileKind::BepInEx(file) => file.find_entry(section, entry)?.reset(),
@@ -60,14 +76,40 @@ pub fn reset_config_entry(
     Ok(value)
 }
 
+/// Sets every matching entry to the same value. With `dry_run`, only returns what would change.
+#[command]
+pub fn batch_edit_config(
+    edit: BatchEdit,
+    dry_run: bool,
+    app: AppHandle,
+) -> Result<BatchEditResult> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.refresh_config();
+
+    let result = profile
+        .config_cache
+        .batch_edit(&edit, dry_run, &profile.path)?;
+
+    Ok(result)
+}
+
 #[command]
 pub fn open_config_file(file: &Path, app: AppHandle) -> Result<()> {
+    let prefs = app.lock_prefs();
     let manager = app.lock_manager();
 
     let profile = manager.active_profile();
     let path = profile.path.join(file);
-    open::that(&path)
-        .with_context(|| format!("failed to open config file at {}", path.display()))?;
+
+    match &prefs.config_editor {
+        Some(editor) => open::with_detached(&path, editor.to_string_lossy()),
+        None => open::that(&path),
+    }
+    .with_context(|| format!("failed to open config file at {}", path.display()))?;
+
+    watch::watch(profile.id, &profile.path, file, &app);
 
     Ok(())
 }
@@ -94,3 +136,110 @@ pub fn delete_config_file(file: &Path, app: AppHandle) -> Result<()> {
 
     Ok(())
 }
+
+#[command]
+pub async fn export_config_preset_file(
+    name: String,
+    files: Vec<PathBuf>,
+    dir: PathBuf,
+    app: AppHandle,
+) -> Result<()> {
+    access::request(&dir, "config preset", &app).await?;
+
+    let manager = app.lock_manager();
+
+    let path = preset::file_path(&dir, &name)?;
+
+    let writer = fs::File::create(&path)
+        .map(BufWriter::new)
+        .fs_context("creating preset file", &path)?;
+
+    manager
+        .active_profile()
+        .export_config_preset(name, files, writer)?;
+
+    open::that(dir).ok();
+
+    Ok(())
+}
+
+#[command]
+pub async fn export_config_preset_code(
+    name: String,
+    files: Vec<PathBuf>,
+    app: AppHandle,
+) -> Result<Uuid> {
+    let data = {
+        let manager = app.lock_manager();
+
+        let mut data = Cursor::new(Vec::new());
+        manager
+            .active_profile()
+            .export_config_preset(name, files, &mut data)?;
+
+        data.into_inner()
+    };
+
+    let key = export::upload_code(data, &app).await?;
+
+    Ok(key)
+}
+
+#[command]
+pub fn read_config_preset_file(path: PathBuf, app: AppHandle) -> Result<PresetData> {
+    let manager = app.lock_manager();
+
+    let file = fs::File::open(&path).fs_context("opening preset", &path)?;
+    let data = manager
+        .active_profile()
+        .read_config_preset(BufReader::new(file))?;
+
+    Ok(data)
+}
+
+#[command]
+pub async fn read_config_preset_code(key: Uuid, app: AppHandle) -> Result<PresetData> {
+    let bytes = import::fetch_code(key, &app).await?;
+
+    let manager = app.lock_manager();
+    let data = manager
+        .active_profile()
+        .read_config_preset(Cursor::new(bytes))?;
+
+    Ok(data)
+}
+
+#[command]
+pub fn import_config_preset(id: Uuid, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    manager.active_profile_mut().apply_config_preset(id)?;
+
+    Ok(())
+}
+
+#[command]
+pub fn discard_config_preset(id: Uuid) {
+    preset::discard(id);
+}
+
+/// Finds a file in the config cache, refusing to edit it if it was changed on disk
+/// since Gale last read it, which would otherwise overwrite those changes.
+fn find_fresh_file<'a>(profile: &'a mut Profile, file: &Path) -> Result<&'a mut AnyFile> {
+    if profile
+        .config_cache
+        .find_file(file)?
+        .is_stale(&profile.path)
+    {
+        profile.refresh_config();
+
+        return Err(eyre!(i18n::t(
+            "config.changed_externally",
+            &[("file", &file.display())]
+        ))
+        .into());
+    }
+
+    let file = profile.config_cache.find_file(file)?;
+    Ok(file)
+}
@@ -8,6 +8,7 @@ use std::{
 };
 
 use eyre::{Context, OptionExt, Result};
+use gale_core::config::{bepinex, frontend, gd_weave};
 use itertools::Itertools;
 use rayon::prelude::*;
 use tracing::debug;
@@ -16,13 +17,13 @@ use walkdir::WalkDir;
 use crate::{
     game::mod_loader::{ModLoader, ModLoaderKind},
     profile::Profile,
-    util::error::IoResultExt,
+    util::{error::IoResultExt, fs::PathExt},
 };
 
-mod bepinex;
+mod batch;
 pub mod commands;
-mod frontend;
-mod gd_weave;
+mod preset;
+mod watch;
 
 #[derive(Debug, Default)]
 pub struct ConfigCache(Vec<AnyFile>);
@@ -51,7 +52,14 @@ impl AnyFile {
             .to_string_lossy()
     }
 
-    fn write(&self, profile_dir: &Path) -> Result<()> {
+    /// Whether the file was changed on disk after it was last read or written.
+    fn is_stale(&self, profile_dir: &Path) -> bool {
+        fs::metadata(profile_dir.join(&self.relative_path))
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified > self.read_time)
+    }
+
+    fn write(&mut self, profile_dir: &Path) -> Result<()> {
         debug!("writing config file to {}", self.relative_path.display());
 
         let path = profile_dir.join(&self.relative_path);
@@ -64,7 +72,10 @@ impl AnyFile {
             AnyFileKind::GDWeave(file) => file.write(writer),
             AnyFileKind::Err(_) => Ok(()),
             AnyFileKind::Unsupported => Ok(()),
-        }
+        }?;
+
+        self.read_time = SystemTime::now();
+        Ok(())
     }
 }
 
@@ -77,6 +88,57 @@ impl AnyFileKind {
     }
 }
 
+/// Adds settings that only the newly installed file at `defaults` has to the config
+/// file at `path`, keeping everything the user has changed. The old file is backed up
+/// with a `.bak` extension first.
+///
+/// Returns whether anything was added. Formats other than BepInEx configs and JSON
+/// aren't understood, so those files are kept as they are.
+pub fn merge_defaults(path: &Path, defaults: &Path) -> Result<bool> {
+    fn read<T>(path: &Path, f: impl FnOnce(BufReader<fs::File>) -> Result<T>) -> Result<T> {
+        let file = fs::File::open(path).fs_context("opening file", path)?;
+        f(BufReader::new(file)).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    let extension = path.extension().and_then(|ext| ext.to_str());
+
+    let (added, kind) = match extension {
+        Some("cfg") => {
+            let mut file = read(path, bepinex::File::read)?;
+            let added = file.merge_missing(read(defaults, bepinex::File::read)?);
+            (added, AnyFileKind::BepInEx(file))
+        }
+        Some("json") => {
+            let mut file = read(path, gd_weave::File::read)?;
+            let added = file.merge_missing(read(defaults, gd_weave::File::read)?);
+            (added, AnyFileKind::GDWeave(file))
+        }
+        _ => return Ok(false),
+    };
+
+    if added == 0 {
+        return Ok(false);
+    }
+
+    let mut backup = path.to_path_buf();
+    backup.add_ext("bak");
+    fs::copy(path, &backup).fs_context("backing up config file", path)?;
+
+    let writer = fs::File::create(path)
+        .map(BufWriter::new)
+        .fs_context("opening file", path)?;
+
+    match kind {
+        AnyFileKind::BepInEx(file) => file.write(writer),
+        AnyFileKind::GDWeave(file) => file.write(writer),
+        AnyFileKind::Err(_) | AnyFileKind::Unsupported => Ok(()),
+    }?;
+
+    debug!("added {} new settings to {}", added, path.display());
+
+    Ok(true)
+}
+
 impl Profile {
     pub fn refresh_config(&mut self) {
         self.config_cache.refresh(&self.path, &self.game.mod_loader);
@@ -113,6 +175,11 @@ impl Profile {
 }
 
 impl ConfigCache {
+    /// The number of parsed config files held in memory.
+    pub fn file_count(&self) -> usize {
+        self.0.len()
+    }
+
     pub fn refresh(&mut self, root: &Path, mod_loader: &ModLoader) {
         let files = mod_loader
             .mod_config_dirs()
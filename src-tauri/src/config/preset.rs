@@ -0,0 +1,210 @@
+//! Config presets: a named set of config files that can be shared on their own,
+//! without the rest of the profile.
+//!
+//! Presets are zips with the config files and a `preset.yml` manifest, and can be
+//! shared as files or as codes, the same way as profiles.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{self, BufReader, Read, Seek, Write},
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+};
+
+use eyre::{ensure, Context, OptionExt, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tempfile::{tempdir, TempDir};
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::{
+    profile::{
+        export::{self, IncludeExtensions, IncludeGenerated},
+        Profile,
+    },
+    util::{self, error::IoResultExt},
+};
+
+pub const FILE_EXTENSION: &str = "gcp";
+const MANIFEST_NAME: &str = "preset.yml";
+
+/// Presets that have been read but not imported yet. Their directories are
+/// deleted when they're removed from here.
+static PENDING: LazyLock<Mutex<HashMap<Uuid, (TempDir, PresetManifest)>>> =
+    LazyLock::new(Default::default);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetManifest {
+    pub name: String,
+    /// The mods whose config is included, by full name.
+    pub mods: Vec<String>,
+    /// Relative paths of the included files.
+    pub files: Vec<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetData {
+    pub manifest: PresetManifest,
+    /// Refers to the extracted preset, which stays in the backend.
+    pub id: Uuid,
+    /// Mods in the preset that the active profile doesn't have.
+    pub missing_mods: Vec<String>,
+}
+
+/// Where a preset named `name` is exported to in `dir`.
+pub fn file_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    // the name is used as the file name, so it can't point anywhere else
+    ensure!(
+        Profile::is_valid_name(name) && !name.chars().all(|char| char == '.'),
+        "invalid preset name '{}'",
+        name
+    );
+
+    let mut path = dir.join(name);
+    path.set_extension(FILE_EXTENSION);
+
+    Ok(path)
+}
+
+impl Profile {
+    /// Writes a preset with the given config files, relative to the profile directory.
+    pub fn export_config_preset(
+        &self,
+        name: String,
+        files: Vec<PathBuf>,
+        writer: impl Write + Seek,
+    ) -> Result<()> {
+        ensure!(!files.is_empty(), "no config files selected");
+
+        let mods = self
+            .mods
+            .iter()
+            .filter(|profile_mod| {
+                self.linked_config
+                    .get(&profile_mod.uuid())
+                    .is_some_and(|path| files.contains(path))
+            })
+            .map(|profile_mod| profile_mod.full_name().into_owned())
+            .collect();
+
+        let manifest = PresetManifest { name, mods, files };
+
+        let mut zip = ZipWriter::new(writer);
+
+        zip.start_file(MANIFEST_NAME, SimpleFileOptions::default())?;
+        serde_yaml::to_writer(&mut zip, &manifest).context("failed to write preset manifest")?;
+
+        for file in &manifest.files {
+            ensure!(util::fs::is_enclosed(file), "invalid config path");
+
+            let path = self.path.join(file);
+            let mut reader = File::open(&path).fs_context("opening config file", &path)?;
+
+            let name = file.to_string_lossy().replace('\\', "/");
+            zip.start_file(name, SimpleFileOptions::default())?;
+            io::copy(&mut reader, &mut zip)?;
+        }
+
+        zip.finish()?;
+        Ok(())
+    }
+
+    /// Extracts a preset and checks which of its mods are missing from the profile.
+    pub fn read_config_preset(&self, source: impl Read + Seek) -> Result<PresetData> {
+        let temp_dir = tempdir().context("failed to create temporary directory")?;
+        util::zip::extract(source, temp_dir.path())?;
+
+        let reader = File::open(temp_dir.path().join(MANIFEST_NAME))
+            .map(BufReader::new)
+            .context("not a config preset")?;
+
+        let manifest: PresetManifest =
+            serde_yaml::from_reader(reader).context("failed to read preset manifest")?;
+
+        self.check_preset_files(&manifest.files, temp_dir.path())?;
+
+        let missing_mods = manifest
+            .mods
+            .iter()
+            .filter(|name| {
+                !self
+                    .mods
+                    .iter()
+                    .any(|profile_mod| profile_mod.full_name() == name.as_str())
+            })
+            .cloned()
+            .collect_vec();
+
+        let id = Uuid::new_v4();
+        PENDING
+            .lock()
+            .unwrap()
+            .insert(id, (temp_dir, manifest.clone()));
+
+        Ok(PresetData {
+            manifest,
+            id,
+            missing_mods,
+        })
+    }
+
+    /// Makes sure a preset only contains config, the same way profile imports do.
+    /// Anything else, like a plugin DLL, could run code as soon as the game starts.
+    fn check_preset_files(&self, files: &[PathBuf], root: &Path) -> Result<()> {
+        let config_dirs = self.game.mod_loader.mod_config_dirs();
+
+        let allowed: HashSet<_> =
+            export::find_config(root, IncludeExtensions::Default, IncludeGenerated::No)
+                .filter(|path| {
+                    config_dirs
+                        .iter()
+                        .any(|dir| *dir == "." || path.starts_with(dir))
+                })
+                .collect();
+
+        for file in files {
+            ensure!(
+                util::fs::is_enclosed(file) && allowed.contains(file),
+                "preset contains a file that isn't config: {}",
+                file.display()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Copies the files of a read preset into the profile, replacing existing ones.
+    pub fn apply_config_preset(&mut self, id: Uuid) -> Result<()> {
+        let (temp_dir, manifest) = PENDING
+            .lock()
+            .unwrap()
+            .remove(&id)
+            .ok_or_eyre("preset not found, try reading it again")?;
+
+        // the active profile might be a different game by now
+        self.check_preset_files(&manifest.files, temp_dir.path())?;
+
+        for file in &manifest.files {
+            let source = temp_dir.path().join(file);
+            let target = self.path.join(file);
+
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).fs_context("creating config directory", parent)?;
+            }
+
+            fs::copy(&source, &target).fs_context("copying config file", &target)?;
+        }
+
+        self.refresh_config();
+        Ok(())
+    }
+}
+
+/// Deletes a preset that was read but won't be imported.
+pub fn discard(id: Uuid) {
+    PENDING.lock().unwrap().remove(&id);
+}
@@ -0,0 +1,123 @@
+//! Picks up changes to config files that were opened in an external editor.
+//!
+//! Watched files are polled for modification, since they only need to be picked up
+//! while the user has them open. A file stops being watched once it's deleted or
+//! hasn't changed in a while.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+use eyre::Result;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, warn};
+
+use crate::state::ManagerExt;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Absolute paths of the files that are currently being watched.
+static WATCHED: LazyLock<Mutex<HashSet<PathBuf>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ConfigFileChanged {
+    profile_id: i64,
+    relative_path: PathBuf,
+}
+
+/// Starts watching a config file, unless it's already being watched.
+pub fn watch(profile_id: i64, profile_dir: &Path, relative_path: &Path, app: &AppHandle) {
+    let path = profile_dir.join(relative_path);
+    if !WATCHED.lock().unwrap().insert(path.clone()) {
+        return;
+    }
+
+    debug!("watching config file at {}", path.display());
+
+    let relative_path = relative_path.to_path_buf();
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        watch_loop(profile_id, &path, &relative_path, &app).await;
+
+        debug!("stopped watching config file at {}", path.display());
+        WATCHED.lock().unwrap().remove(&path);
+    });
+}
+
+async fn watch_loop(profile_id: i64, path: &Path, relative_path: &Path, app: &AppHandle) {
+    let mut last_modified = modified_time(path);
+    let mut last_change = Instant::now();
+
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        let Some(modified) = modified_time(path) else {
+            break;
+        };
+
+        if last_modified == Some(modified) {
+            if last_change.elapsed() > IDLE_TIMEOUT {
+                break;
+            }
+
+            continue;
+        }
+
+        last_modified = Some(modified);
+        last_change = Instant::now();
+
+        match reload(profile_id, relative_path, modified, app) {
+            Ok(true) => {
+                app.emit(
+                    "config_file_changed",
+                    ConfigFileChanged {
+                        profile_id,
+                        relative_path: relative_path.to_path_buf(),
+                    },
+                )
+                .ok();
+            }
+            Ok(false) => (),
+            Err(err) => {
+                warn!("failed to reload config file: {:#}", err);
+                break;
+            }
+        }
+    }
+}
+
+/// Re-reads the profile's config if the file was changed by something other than Gale.
+fn reload(
+    profile_id: i64,
+    relative_path: &Path,
+    modified: SystemTime,
+    app: &AppHandle,
+) -> Result<bool> {
+    let mut manager = app.lock_manager();
+    let (_, profile) = manager.profile_by_id_mut(profile_id)?;
+
+    let changed_externally = profile
+        .config_cache
+        .find_file(relative_path)
+        .map_or(true, |file| modified > file.read_time);
+
+    if changed_externally {
+        profile.refresh_config();
+    }
+
+    Ok(changed_externally)
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
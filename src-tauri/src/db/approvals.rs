@@ -0,0 +1,29 @@
+use eyre::Result;
+use rusqlite::OptionalExtension;
+use uuid::Uuid;
+
+use super::Db;
+
+impl Db {
+    /// Whether the user has reviewed and approved the files of a package before.
+    pub fn is_package_approved(&self, package_uuid: Uuid) -> Result<bool> {
+        let conn = self.conn();
+
+        let approved = conn
+            .prepare("SELECT 1 FROM approved_packages WHERE package_uuid = ?")?
+            .query_row([package_uuid], |_| Ok(()))
+            .optional()?
+            .is_some();
+
+        Ok(approved)
+    }
+
+    pub fn approve_package(&self, package_uuid: Uuid) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare("INSERT OR IGNORE INTO approved_packages (package_uuid) VALUES (?)")?
+            .execute([package_uuid])?;
+
+        Ok(())
+    }
+}
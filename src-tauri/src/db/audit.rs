@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use super::Db;
+
+/// A state-changing command, as recorded in the audit log.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub at: DateTime<Utc>,
+    /// The OS account Gale was running under.
+    pub user: String,
+    pub command: String,
+    /// The active game and profile when the command was invoked, if known.
+    pub game_slug: Option<String>,
+    pub profile_name: Option<String>,
+    /// The command's arguments as JSON, shortened if they're long.
+    pub args: String,
+    /// Unset while the command is running, or if its result never came back.
+    pub succeeded: Option<bool>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditFilter {
+    pub command: Option<String>,
+    pub profile_name: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+const DEFAULT_LIMIT: usize = 500;
+
+impl Db {
+    pub fn record_audit(&self, entry: &AuditEntry, request_id: &str) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare(
+            "INSERT INTO audit_log
+            (at, user, command, game_slug, profile_name, args, request_id, succeeded, error)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )?
+        .execute(params![
+            entry.at.timestamp(),
+            entry.user,
+            entry.command,
+            entry.game_slug,
+            entry.profile_name,
+            entry.args,
+            request_id,
+            entry.succeeded,
+            entry.error
+        ])?;
+
+        Ok(())
+    }
+
+    /// Fills in the outcome of the entry recorded with `request_id`.
+    pub fn finish_audit(&self, request_id: &str, error: Option<&str>) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare(
+            "UPDATE audit_log SET succeeded = ?, error = ?
+            WHERE request_id = ? AND succeeded IS NULL",
+        )?
+        .execute(params![error.is_none(), error, request_id])?;
+
+        Ok(())
+    }
+
+    /// Returns matching entries, newest first.
+    pub fn query_audit(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>> {
+        let conn = self.conn();
+
+        let entries = conn
+            .prepare(
+                "SELECT at, user, command, game_slug, profile_name, args, succeeded, error
+                FROM audit_log
+                WHERE (?1 IS NULL OR command = ?1)
+                AND (?2 IS NULL OR profile_name = ?2)
+                AND at >= ?3
+                ORDER BY at DESC, id DESC
+                LIMIT ?4",
+            )?
+            .query_map(
+                params![
+                    filter.command,
+                    filter.profile_name,
+                    filter.since.map_or(0, |since| since.timestamp()),
+                    filter.limit.unwrap_or(DEFAULT_LIMIT) as i64
+                ],
+                |row| {
+                    Ok(AuditEntry {
+                        at: DateTime::from_timestamp(row.get(0)?, 0).unwrap_or_default(),
+                        user: row.get(1)?,
+                        command: row.get(2)?,
+                        game_slug: row.get(3)?,
+                        profile_name: row.get(4)?,
+                        args: row.get(5)?,
+                        succeeded: row.get(6)?,
+                        error: row.get(7)?,
+                    })
+                },
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(entries)
+    }
+
+    /// Removes entries recorded before `cutoff`.
+    pub fn prune_audit(&self, cutoff: DateTime<Utc>) -> Result<usize> {
+        let conn = self.conn();
+
+        let count = conn
+            .prepare("DELETE FROM audit_log WHERE at < ?")?
+            .execute([cutoff.timestamp()])?;
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,27 @@
+use eyre::Result;
+use rusqlite::{params, OptionalExtension};
+
+use super::Db;
+
+impl Db {
+    /// The build id a game had when it was last checked.
+    pub fn last_build_id(&self, game_slug: &str) -> Result<Option<u64>> {
+        let conn = self.conn();
+
+        let build_id: Option<i64> = conn
+            .prepare("SELECT build_id FROM game_builds WHERE game_slug = ?")?
+            .query_row([game_slug], |row| row.get(0))
+            .optional()?;
+
+        Ok(build_id.map(|id| id as u64))
+    }
+
+    pub fn record_build_id(&self, game_slug: &str, build_id: u64) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare("INSERT OR REPLACE INTO game_builds (game_slug, build_id) VALUES (?, ?)")?
+            .execute(params![game_slug, build_id as i64])?;
+
+        Ok(())
+    }
+}
@@ -10,7 +10,7 @@ use super::Db;
 
 const SIZE_THRESHOLD: i64 = 1000;
 const MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 30); // 30 days
-const CACHE_TABLES: &[&str] = &["readme_cache", "changelog_cache"];
+const CACHE_TABLES: &[&str] = &["readme_cache", "changelog_cache", "links_cache"];
 
 impl Db {
     pub fn evict_outdated_cache(&self) -> Result<()> {
@@ -0,0 +1,28 @@
+use eyre::Result;
+use rusqlite::{params, OptionalExtension};
+use uuid::Uuid;
+
+use super::Db;
+
+impl Db {
+    /// Gets the recorded hash of a package version's archive, if it has been downloaded before.
+    pub fn archive_hash(&self, version_uuid: Uuid) -> Result<Option<String>> {
+        let conn = self.conn();
+
+        let hash = conn
+            .prepare("SELECT hash FROM archive_hashes WHERE version_uuid = ?")?
+            .query_row([version_uuid], |row| row.get(0))
+            .optional()?;
+
+        Ok(hash)
+    }
+
+    pub fn record_archive_hash(&self, version_uuid: Uuid, hash: &str) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare("INSERT OR REPLACE INTO archive_hashes (version_uuid, hash) VALUES (?, ?)")?
+            .execute(params![version_uuid, hash])?;
+
+        Ok(())
+    }
+}
@@ -106,6 +106,10 @@ fn read_manager_data(prefs: &Prefs) -> Result<SaveData> {
                 sync_data: None,
                 custom_args: None,
                 custom_args_enabled: None,
+                workshop_items: None,
+                loader_files: None,
+                notes: None,
+                readme: None,
             });
 
             if data.active_profile_index == index {
@@ -152,6 +156,7 @@ impl From<legacy::GamePrefs> for GamePrefs {
             custom_args_enabled: legacy.custom_args.is_some(),
             launch_mode: legacy.launch_mode.into(),
             platform: legacy.platform.map(Into::into),
+            ..Default::default()
         }
     }
 }
@@ -188,6 +193,10 @@ impl From<legacy::ProfileMod> for ProfileMod {
         ProfileMod {
             enabled: legacy.enabled,
             install_time: legacy.install_time,
+            categories: Default::default(),
+            tags: Vec::new(),
+            note: None,
+            local_only: false,
             kind: legacy.kind.into(),
         }
     }
@@ -1,6 +1,7 @@
 use std::{
     collections::HashSet,
     iter,
+    path::PathBuf,
     sync::{Mutex, MutexGuard},
 };
 
@@ -18,8 +19,16 @@ use crate::{
     util,
 };
 
+pub mod approvals;
+pub mod audit;
+pub mod builds;
 pub mod cache;
+pub mod hashes;
 mod migrate;
+pub mod ratings;
+pub mod server;
+pub mod stats;
+pub mod visits;
 
 pub const FILE_NAME: &str = "data.sqlite3";
 pub const SHM_FILE_NAME: &str = "data.sqlite3-shm";
@@ -113,6 +122,10 @@ pub struct ProfileData {
     pub sync_data: Option<profile::sync::SyncProfileData>,
     pub custom_args: Option<Vec<String>>,
     pub custom_args_enabled: Option<bool>,
+    pub workshop_items: Option<Vec<profile::workshop::WorkshopItem>>,
+    pub loader_files: Option<Vec<PathBuf>>,
+    pub notes: Option<String>,
+    pub readme: Option<String>,
 }
 
 pub struct SaveData {
@@ -126,6 +139,13 @@ impl Db {
         self.0.lock().unwrap()
     }
 
+    /// Moves the write-ahead log into the main database file.
+    pub fn checkpoint(&self) -> Result<()> {
+        self.conn()
+            .execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+            .context("failed to checkpoint")
+    }
+
     fn with_transaction<F>(&self, f: F) -> Result<()>
     where
         F: FnOnce(&rusqlite::Transaction) -> Result<()>,
@@ -202,7 +222,7 @@ impl Db {
 
         let mut profiles = conn
             .prepare(
-                "SELECT id, name, path, game_slug, mods, modpack, ignored_updates, sync_data, custom_args, custom_args_enabled FROM profiles",
+                "SELECT id, name, path, game_slug, mods, modpack, ignored_updates, sync_data, custom_args, custom_args_enabled, workshop_items, loader_files, notes, readme FROM profiles",
             )?
             .query_map((), |row| {
                 Ok(ProfileData {
@@ -216,6 +236,10 @@ impl Db {
                     sync_data: map_json_option_row(row, 7)?,
                     custom_args: map_json_option_row(row, 8)?,
                     custom_args_enabled: row.get(9)?,
+                    workshop_items: map_json_option_row(row, 10)?,
+                    loader_files: map_json_option_row(row, 11)?,
+                    notes: row.get(12)?,
+                    readme: row.get(13)?,
                 })
             })?
             .collect::<rusqlite::Result<Vec<_>>>()?;
@@ -316,8 +340,8 @@ impl Db {
     ) -> Result<()> {
         let mut stmt = tx.prepare(
             "INSERT OR REPLACE INTO profiles 
-                (id, name, path, game_slug, mods, modpack, ignored_updates, sync_data, custom_args, custom_args_enabled) 
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                (id, name, path, game_slug, mods, modpack, ignored_updates, sync_data, custom_args, custom_args_enabled, workshop_items, loader_files, notes, readme) 
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )?;
 
         for profile in profiles {
@@ -334,6 +358,8 @@ impl Db {
                 .map(serde_json::to_string)
                 .transpose()?;
             let custom_args = serde_json::to_string(&profile.custom_args)?;
+            let workshop_items = serde_json::to_string(&profile.workshop_items)?;
+            let loader_files = serde_json::to_string(&profile.loader_files)?;
 
             stmt.execute(params![
                 profile.id,
@@ -345,7 +371,11 @@ impl Db {
                 ignored_updates,
                 sync_data,
                 custom_args,
-                profile.custom_args_enabled
+                profile.custom_args_enabled,
+                workshop_items,
+                loader_files,
+                profile.notes,
+                profile.readme
             ])?;
         }
 
@@ -0,0 +1,38 @@
+use eyre::Result;
+use rusqlite::params;
+use uuid::Uuid;
+
+use super::Db;
+
+impl Db {
+    /// Queues a rating to be sent later, replacing any previously queued one for the package.
+    pub fn queue_rating(&self, package_uuid: Uuid, rated: bool) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare("INSERT OR REPLACE INTO pending_ratings (package_uuid, rated) VALUES (?, ?)")?
+            .execute(params![package_uuid, rated])?;
+
+        Ok(())
+    }
+
+    /// Returns the queued ratings, oldest first.
+    pub fn pending_ratings(&self) -> Result<Vec<(Uuid, bool)>> {
+        let conn = self.conn();
+
+        let ratings = conn
+            .prepare("SELECT package_uuid, rated FROM pending_ratings ORDER BY queued_at")?
+            .query_map((), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(ratings)
+    }
+
+    pub fn remove_pending_rating(&self, package_uuid: Uuid) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare("DELETE FROM pending_ratings WHERE package_uuid = ?")?
+            .execute([package_uuid])?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use eyre::Result;
+use rusqlite::{params, types::Type as SqliteType};
+use serde::Serialize;
+
+use super::Db;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerIncident {
+    pub id: i64,
+    pub occurred_at: DateTime<Utc>,
+    pub exit_code: Option<i32>,
+    pub log_tail: Option<String>,
+    pub restarted: bool,
+}
+
+impl Db {
+    pub fn insert_incident(
+        &self,
+        profile_id: i64,
+        exit_code: Option<i32>,
+        log_tail: Option<&str>,
+        restarted: bool,
+    ) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare(
+            "INSERT INTO server_incidents (profile_id, exit_code, log_tail, restarted)
+            VALUES (?, ?, ?, ?)",
+        )?
+        .execute(params![profile_id, exit_code, log_tail, restarted])?;
+
+        Ok(())
+    }
+
+    pub fn get_incidents(&self, profile_id: i64) -> Result<Vec<ServerIncident>> {
+        let conn = self.conn();
+
+        let incidents = conn
+            .prepare(
+                "SELECT id, occurred_at, exit_code, log_tail, restarted
+                FROM server_incidents
+                WHERE profile_id = ?
+                ORDER BY occurred_at DESC",
+            )?
+            .query_map([profile_id], |row| {
+                Ok(ServerIncident {
+                    id: row.get(0)?,
+                    occurred_at: parse_timestamp(row.get(1)?)?,
+                    exit_code: row.get(2)?,
+                    log_tail: row.get(3)?,
+                    restarted: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(incidents)
+    }
+}
+
+/// Parses sqlite's `CURRENT_TIMESTAMP` format, which is always in UTC.
+fn parse_timestamp(value: String) -> rusqlite::Result<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(&value, "%Y-%m-%d %H:%M:%S")
+        .map(|naive| naive.and_utc())
+        .map_err(|err| {
+            rusqlite::Error::FromSqlConversionFailure(1, SqliteType::Text, Box::new(err))
+        })
+}
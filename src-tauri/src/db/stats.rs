@@ -0,0 +1,65 @@
+use chrono::NaiveDate;
+use eyre::Result;
+use rusqlite::{params, types::Type as SqliteType};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::Db;
+
+/// A daily snapshot of a package's statistics.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageStats {
+    pub date: NaiveDate,
+    pub downloads: u32,
+    pub rating: u32,
+}
+
+impl Db {
+    /// Records today's statistics of a package, overwriting any earlier snapshot from today.
+    pub fn record_package_stats(
+        &self,
+        package_uuid: Uuid,
+        downloads: u32,
+        rating: u32,
+    ) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare(
+            "INSERT OR REPLACE INTO package_stats (package_uuid, downloads, rating)
+            VALUES (?, ?, ?)",
+        )?
+        .execute(params![package_uuid, downloads, rating])?;
+
+        Ok(())
+    }
+
+    /// Returns the recorded statistics of a package, oldest first.
+    pub fn get_package_stats(&self, package_uuid: Uuid) -> Result<Vec<PackageStats>> {
+        let conn = self.conn();
+
+        let stats = conn
+            .prepare(
+                "SELECT recorded_on, downloads, rating
+                FROM package_stats
+                WHERE package_uuid = ?
+                ORDER BY recorded_on",
+            )?
+            .query_map([package_uuid], |row| {
+                Ok(PackageStats {
+                    date: parse_date(row.get(0)?)?,
+                    downloads: row.get(1)?,
+                    rating: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(stats)
+    }
+}
+
+fn parse_date(value: String) -> rusqlite::Result<NaiveDate> {
+    NaiveDate::parse_from_str(&value, "%Y-%m-%d").map_err(|err| {
+        rusqlite::Error::FromSqlConversionFailure(0, SqliteType::Text, Box::new(err))
+    })
+}
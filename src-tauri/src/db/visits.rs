@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use rusqlite::{params, OptionalExtension};
+
+use super::Db;
+
+impl Db {
+    /// When the user last looked at the recently updated feed of a game.
+    pub fn last_visit(&self, game_slug: &str) -> Result<Option<DateTime<Utc>>> {
+        let conn = self.conn();
+
+        let timestamp: Option<i64> = conn
+            .prepare("SELECT visited_at FROM game_visits WHERE game_slug = ?")?
+            .query_row([game_slug], |row| row.get(0))
+            .optional()?;
+
+        Ok(timestamp.and_then(|secs| DateTime::from_timestamp(secs, 0)))
+    }
+
+    pub fn record_visit(&self, game_slug: &str, at: DateTime<Utc>) -> Result<()> {
+        let conn = self.conn();
+
+        conn.prepare("INSERT OR REPLACE INTO game_visits (game_slug, visited_at) VALUES (?, ?)")?
+            .execute(params![game_slug, at.timestamp()])?;
+
+        Ok(())
+    }
+}
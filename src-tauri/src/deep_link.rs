@@ -25,6 +25,15 @@ pub fn handle(app: &AppHandle, args: Vec<String>) -> bool {
 
     let app = app.to_owned();
 
+    // anything else is a plain command line, which cli.rs checks on its own
+    let is_link =
+        url.starts_with("gale://") || url.starts_with("ror2mm://") || url.ends_with("r2z");
+
+    if is_link && app.lock_prefs().kiosk_mode && !url.starts_with("gale://auth/callback") {
+        warn!("ignoring deep link in kiosk mode: {}", url);
+        return true;
+    }
+
     if url.starts_with("ror2mm://") {
         handle_inner_task(app.clone(), handle_r2_install(url, app));
     } else if url.starts_with("gale://auth/callback") {
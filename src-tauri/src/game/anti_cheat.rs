@@ -0,0 +1,66 @@
+//! Metadata about games where mods can get players banned, or that need
+//! special arguments to run modded at all.
+
+use std::borrow::Cow;
+
+use eyre::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use super::Game;
+use crate::prefs::Prefs;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AntiCheat<'a> {
+    /// The anti-cheat system in use, if it has a name.
+    #[serde(default)]
+    pub system: Option<&'a str>,
+    pub risk: AntiCheatRisk,
+    /// Game-specific advice shown alongside the warning.
+    #[serde(default, borrow)]
+    pub note: Option<Cow<'a, str>>,
+    /// Arguments that disable the anti-cheat, which are added when launching.
+    /// Only used if the risk is [`AntiCheatRisk::RequiresBypass`].
+    #[serde(default, borrow)]
+    pub bypass_args: Vec<&'a str>,
+}
+
+impl AntiCheat<'_> {
+    /// The arguments to launch with, see [`AntiCheat::bypass_args`].
+    pub fn launch_args(&self) -> &[&str] {
+        match self.risk {
+            AntiCheatRisk::RequiresBypass => &self.bypass_args,
+            AntiCheatRisk::OnlineBannable => &[],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AntiCheatRisk {
+    /// Playing modded in public matches can result in a ban.
+    OnlineBannable,
+    /// The game only runs modded with the anti-cheat disabled.
+    RequiresBypass,
+}
+
+/// Fails if `game` has an anti-cheat warning the user hasn't acknowledged yet.
+/// Mods can't be installed or launched until then.
+pub fn check(game: Game, prefs: &Prefs) -> Result<()> {
+    if game.anti_cheat.is_none() {
+        return Ok(());
+    }
+
+    let acknowledged = prefs
+        .game_prefs
+        .get(&*game.slug)
+        .is_some_and(|prefs| prefs.anti_cheat_acknowledged);
+
+    ensure!(
+        acknowledged,
+        "the anti-cheat warning for {} has to be acknowledged first",
+        game.name
+    );
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use serde_json::Value;
+use tauri::command;
+
+use super::custom;
+use crate::util::cmd::Result;
+
+#[command]
+pub fn get_custom_games() -> Result<Vec<Value>> {
+    let definitions = custom::list()?;
+
+    Ok(definitions)
+}
+
+#[command]
+pub fn save_custom_game(definition: Value, replaces: Option<String>) -> Result<()> {
+    custom::save(definition, replaces.as_deref())?;
+
+    Ok(())
+}
+
+#[command]
+pub fn delete_custom_game(slug: String) -> Result<()> {
+    custom::delete(&slug)?;
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+//! Game definitions added by the user, for games Gale doesn't know about yet.
+//!
+//! They use the same format as the built-in `games.json` and live in a file of their
+//! own in the data directory. As with built-in games, the slug is also the game's
+//! Thunderstore community. The game list is only loaded once, so changes to the
+//! definitions apply after a restart.
+
+use std::path::PathBuf;
+
+use eyre::{bail, ensure, Context, OptionExt, Result};
+use serde_json::Value;
+use tracing::{info, warn};
+
+use super::GameData;
+use crate::util::{self, fs::JsonStyle};
+
+pub const FILE_NAME: &str = "custom_games.json";
+
+fn path() -> PathBuf {
+    util::path::default_app_data_dir().join(FILE_NAME)
+}
+
+/// Reads the definitions as they are stored, in their original order.
+fn read() -> Result<Vec<Value>> {
+    let path = path();
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    util::fs::read_json(&path).context("failed to read custom games")
+}
+
+/// Like [`read`], but with the slugs filled in where they were left out.
+pub fn list() -> Result<Vec<Value>> {
+    let mut definitions = read()?;
+
+    for definition in &mut definitions {
+        if let (Some(slug), Some(object)) = (slug_of(definition), definition.as_object_mut()) {
+            object.insert("slug".to_owned(), Value::String(slug));
+        }
+    }
+
+    Ok(definitions)
+}
+
+fn write(definitions: &[Value]) -> Result<()> {
+    util::fs::write_json(path(), definitions, JsonStyle::Pretty)
+        .context("failed to write custom games")
+}
+
+/// Adds the user's games to the list. Invalid definitions and ones that clash with
+/// a built-in game are skipped, so one mistake doesn't hide the rest.
+pub(super) fn merge(games: &mut Vec<GameData<'static>>) {
+    let definitions = match read() {
+        Ok(definitions) => definitions,
+        Err(err) => {
+            warn!("{:#}", err);
+            return;
+        }
+    };
+
+    for definition in definitions {
+        // the list lives for the rest of the program, like the built-in one
+        let game = match serde_json::from_str::<GameData>(definition.to_string().leak()) {
+            Ok(game) => game,
+            Err(err) => {
+                warn!("skipping invalid custom game: {}", err);
+                continue;
+            }
+        };
+
+        if games.iter().any(|existing| existing.slug == game.slug) {
+            warn!(
+                "custom game {} clashes with an existing game, skipping",
+                game.slug
+            );
+            continue;
+        }
+
+        info!("adding custom game {}", game.slug);
+
+        games.push(GameData {
+            custom: true,
+            ..game
+        });
+    }
+}
+
+/// Checks that a definition parses and returns its slug.
+fn validate(definition: &Value) -> Result<String> {
+    let text = definition.to_string();
+    let game = serde_json::from_str::<GameData>(&text).context("invalid game definition")?;
+
+    ensure!(!game.name.trim().is_empty(), "name cannot be empty");
+    ensure!(
+        !game.slug.is_empty()
+            && game
+                .slug
+                .chars()
+                .all(|char| char.is_ascii_lowercase() || char.is_ascii_digit() || char == '-'),
+        "slug can only contain lowercase letters, numbers and dashes"
+    );
+
+    if super::from_slug(&game.slug).is_some_and(|existing| !existing.custom) {
+        bail!("{} is already a built-in game", game.slug);
+    }
+
+    Ok(game.slug.into_owned())
+}
+
+/// Hand-written definitions might leave out the slug.
+fn slug_of(definition: &Value) -> Option<String> {
+    let text = definition.to_string();
+    let game = serde_json::from_str::<GameData>(&text).ok()?;
+
+    Some(game.slug.into_owned())
+}
+
+/// Adds a definition, or replaces the one with the slug in `replaces`. The slug
+/// of a definition without one is derived from its name, like for built-in games.
+pub fn save(mut definition: Value, replaces: Option<&str>) -> Result<()> {
+    let slug = validate(&definition)?;
+
+    // store the slug explicitly, so renaming the game doesn't move its profiles
+    definition
+        .as_object_mut()
+        .ok_or_eyre("game definition must be an object")?
+        .insert("slug".to_owned(), Value::String(slug.clone()));
+
+    let mut definitions = read()?;
+    let replaces = replaces.unwrap_or(&slug);
+
+    let existing = definitions
+        .iter()
+        .position(|other| slug_of(other).as_deref() == Some(replaces));
+
+    if replaces != slug {
+        ensure!(
+            definitions
+                .iter()
+                .all(|other| slug_of(other).as_deref() != Some(slug.as_str())),
+            "there is already a custom game with the slug {}",
+            slug
+        );
+    }
+
+    match existing {
+        Some(index) => definitions[index] = definition,
+        None => definitions.push(definition),
+    }
+
+    write(&definitions)
+}
+
+pub fn delete(slug: &str) -> Result<()> {
+    let mut definitions = read()?;
+
+    let index = definitions
+        .iter()
+        .position(|definition| slug_of(definition).as_deref() == Some(slug))
+        .ok_or_eyre("custom game not found")?;
+
+    definitions.remove(index);
+    write(&definitions)
+}
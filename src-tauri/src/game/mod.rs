@@ -5,33 +5,44 @@ use std::{
     sync::LazyLock,
 };
 
-use eyre::Result;
+use eyre::{Context, Result};
 use heck::{ToKebabCase, ToPascalCase};
 use serde::{Deserialize, Serialize};
 
+use anti_cheat::AntiCheat;
 use mod_loader::ModLoader;
 use platform::Platforms;
 use tracing::{info, warn};
 
-use crate::util::{self, fs::JsonStyle};
+use crate::{
+    profile::install::{PackageInstaller, ScriptInstaller},
+    util,
+};
 
+pub mod anti_cheat;
+pub mod commands;
+pub mod custom;
 pub mod mod_loader;
 pub mod platform;
+mod signature;
 
 pub const CACHE_FILE_NAME: &str = "games.json";
+pub const CACHE_SIGNATURE_FILE_NAME: &str = "games.json.sig";
 const URL: &str =
     "https://raw.githubusercontent.com/Kesomannen/gale/refs/heads/master/src-tauri/games.json";
 const FALLBACK_JSON: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", "games.json"));
 
 static GAMES: LazyLock<Vec<GameData<'static>>> = LazyLock::new(|| {
+    let mut games = get_builtin_games();
+    custom::merge(&mut games);
+    games
+});
+
+fn get_builtin_games() -> Vec<GameData<'static>> {
     match (get_remote_games(), get_cached_games()) {
         (Ok(remote), _) => {
             info!(count = remote.len(), "got new games from remote");
 
-            write_cache(&remote).unwrap_or_else(|err| {
-                warn!("failed to write games cache: {err}");
-            });
-
             remote
         }
         (Err(err), Ok(cached)) => {
@@ -45,24 +56,48 @@ static GAMES: LazyLock<Vec<GameData<'static>>> = LazyLock::new(|| {
             serde_json::from_str(FALLBACK_JSON).unwrap()
         }
     }
-});
+}
 
+/// Reads the games cached from the remote list. The cache is checked against the list's
+/// signature again, since anything that can write to the data dir could have changed it.
 fn get_cached_games() -> Result<Vec<GameData<'static>>> {
-    let path = util::path::default_app_data_dir().join(CACHE_FILE_NAME);
-    let str = fs::read_to_string(path)?;
+    let dir = util::path::default_app_data_dir();
+    let str = fs::read_to_string(dir.join(CACHE_FILE_NAME))?;
+    let signature = fs::read_to_string(dir.join(CACHE_SIGNATURE_FILE_NAME))?;
+
+    signature::verify(str.as_bytes(), &signature).context("invalid cached game definitions")?;
+
     let games = serde_json::from_str(str.leak())?;
 
     Ok(games)
 }
 
-fn write_cache(games: &Vec<GameData<'static>>) -> Result<()> {
-    let path = util::path::default_app_data_dir().join(CACHE_FILE_NAME);
-    util::fs::write_json(path, games, JsonStyle::Pretty)
+/// Caches the remote list exactly as it was fetched, so its signature stays valid.
+fn write_cache(str: &str, signature: &str) -> Result<()> {
+    let dir = util::path::default_app_data_dir();
+
+    fs::write(dir.join(CACHE_FILE_NAME), str).context("failed to write games cache")?;
+    fs::write(dir.join(CACHE_SIGNATURE_FILE_NAME), signature)
+        .context("failed to write games cache signature")?;
+
+    Ok(())
 }
 
 fn get_remote_games() -> Result<Vec<GameData<'static>>> {
-    let str = reqwest::blocking::get(URL)?.text()?;
-    let games = serde_json::from_str(str.leak())?;
+    let str = reqwest::blocking::get(URL)?.error_for_status()?.text()?;
+
+    let signature = reqwest::blocking::get(format!("{URL}.sig"))?
+        .error_for_status()?
+        .text()?;
+
+    signature::verify(str.as_bytes(), &signature).context("invalid game definitions")?;
+
+    let str = str.leak();
+    let games = serde_json::from_str(str)?;
+
+    write_cache(str, &signature).unwrap_or_else(|err| {
+        warn!("failed to write games cache: {err:#}");
+    });
 
     Ok(games)
 }
@@ -99,6 +134,9 @@ struct JsonGame<'a> {
 
     #[serde(borrow, default)]
     platforms: Platforms<'a>,
+
+    #[serde(borrow, default)]
+    anti_cheat: Option<AntiCheat<'a>>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -111,6 +149,10 @@ pub struct GameData<'a> {
     pub server: bool,
     pub mod_loader: ModLoader<'a>,
     pub platforms: Platforms<'a>,
+    pub anti_cheat: Option<AntiCheat<'a>>,
+    /// Defined by the user rather than shipped with Gale.
+    #[serde(skip)]
+    pub custom: bool,
 }
 
 impl<'a> From<JsonGame<'a>> for GameData<'a> {
@@ -123,6 +165,7 @@ impl<'a> From<JsonGame<'a>> for GameData<'a> {
             r2_dir_name,
             mod_loader,
             platforms,
+            anti_cheat,
         } = value;
 
         let slug = match slug {
@@ -143,6 +186,8 @@ impl<'a> From<JsonGame<'a>> for GameData<'a> {
             server,
             mod_loader,
             platforms,
+            anti_cheat,
+            custom: false,
         }
     }
 }
@@ -160,3 +205,14 @@ impl Hash for GameData<'_> {
         self.slug.hash(state);
     }
 }
+
+impl GameData<'static> {
+    /// Gets the installer for a package, preferring the game's installer script
+    /// over the mod loader's rules.
+    pub fn installer_for(&'static self, package_name: &str) -> Box<dyn PackageInstaller> {
+        match ScriptInstaller::for_package(&self.slug, package_name) {
+            Some(installer) => Box::new(installer),
+            None => self.mod_loader.installer_for(package_name),
+        }
+    }
+}
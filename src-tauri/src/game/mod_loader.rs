@@ -33,6 +33,11 @@ pub enum ModLoaderKind<'a> {
     ReturnOfModding {
         files: Vec<&'a str>,
     },
+    UE4SS {
+        /// The game's Unreal project folder, which contains the actual executable
+        /// under `Binaries/Win64`.
+        project: &'a str,
+    },
 }
 
 impl ModLoader<'_> {
@@ -46,11 +51,12 @@ impl ModLoader<'_> {
             ModLoaderKind::Shimloader {} => "Shimloader",
             ModLoaderKind::Lovely {} => "Lovely",
             ModLoaderKind::ReturnOfModding { .. } => "ReturnOfModding",
+            ModLoaderKind::UE4SS { .. } => "UE4SS",
         }
     }
 
     /// Checks for the mod loader's own package on Thunderstore.
-    fn is_loader_package(&self, full_name: &str) -> bool {
+    pub fn is_loader_package(&self, full_name: &str) -> bool {
         if let Some(package_name) = self.package_name {
             full_name == package_name
         } else {
@@ -68,6 +74,7 @@ impl ModLoader<'_> {
                 ModLoaderKind::ReturnOfModding { .. } => {
                     full_name == "ReturnOfModding-ReturnOfModding"
                 }
+                ModLoaderKind::UE4SS { .. } => full_name == "Thunderstore-ue4ss",
             }
         }
     }
@@ -82,6 +89,8 @@ impl ModLoader<'_> {
             ModLoaderKind::Shimloader {} => None,
             ModLoaderKind::Lovely {} => Some("mods/lovely/log"),
             ModLoaderKind::ReturnOfModding { .. } => None,
+            // copied back from the game directory when the game exits
+            ModLoaderKind::UE4SS { .. } => Some("ue4ss/UE4SS.log"),
         }
     }
 
@@ -95,6 +104,7 @@ impl ModLoader<'_> {
             ModLoaderKind::Shimloader {} => &["."],
             ModLoaderKind::Lovely {} => &["."],
             ModLoaderKind::ReturnOfModding { .. } => &["ReturnOfModding/config"],
+            ModLoaderKind::UE4SS { .. } => &["ue4ss/Mods"],
         }
     }
 }
@@ -231,6 +241,22 @@ impl ModLoader<'static> {
 
                 Box::new(SubdirInstaller::new(SUBDIRS).with_default(0))
             }
+
+            (true, ModLoaderKind::UE4SS { .. }) => Box::new(UE4SSInstaller),
+            (false, ModLoaderKind::UE4SS { .. }) => {
+                const SUBDIRS: &[Subdir] = &[
+                    Subdir::tracked("Mods", "ue4ss/Mods"),
+                    Subdir::tracked("LogicMods", "LogicMods").extension(".pak"),
+                ];
+                const IGNORED: &[&str] =
+                    &["manifest.json", "icon.png", "README.md", "CHANGELOG.md"];
+
+                Box::new(
+                    SubdirInstaller::new(SUBDIRS)
+                        .with_default(0)
+                        .with_ignored_files(IGNORED),
+                )
+            }
         }
     }
 
@@ -238,8 +264,23 @@ impl ModLoader<'static> {
         match &self.kind {
             ModLoaderKind::BepInEx { .. } => Some("winhttp"),
             ModLoaderKind::GDWeave {} => Some("winmm"),
+            ModLoaderKind::UE4SS { .. } => Some("dwmapi"),
             ModLoaderKind::ReturnOfModding { files } => Some(files[0]),
             _ => None,
         }
     }
+
+    /// The file name of [`ModLoader::proxy_dll`], including the extension.
+    pub fn proxy_dll_file(&'static self) -> Option<String> {
+        self.proxy_dll().map(|name| {
+            if name.ends_with(".dll") {
+                name.to_owned()
+            } else {
+                format!("{name}.dll")
+            }
+        })
+    }
 }
+
+#[cfg(test)]
+mod tests;
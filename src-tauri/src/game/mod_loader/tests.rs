@@ -0,0 +1,330 @@
+//! Golden-file tests for the installer of every [`ModLoaderKind`].
+//!
+//! Each case plans a typical package for a loader and compares the result with
+//! `tests/golden/installers/<case>.txt`. A missing golden file fails the test, run
+//! with `GALE_BLESS=1` to write it or to rewrite all of them after an intended change,
+//! and review the diff before committing.
+
+use std::{env, fmt::Write, fs, path::PathBuf};
+
+use super::ModLoader;
+
+struct Case {
+    name: &'static str,
+    loader: &'static str,
+    package: &'static str,
+    files: &'static [&'static str],
+}
+
+const MOD_FILES: &[&str] = &[
+    "manifest.json",
+    "icon.png",
+    "README.md",
+    "CHANGELOG.md",
+    "Example.dll",
+    "plugins/Example.Plugin.dll",
+    "plugins/assets/bundle",
+    "patchers/Example.Patcher.dll",
+    "monomod/Assembly-CSharp.Example.mm.dll",
+    "config/Example.cfg",
+    "Mods/Example.Mod.dll",
+    "UserLibs/Example.lib.dll",
+    "Renderer/Example.Renderer.dll",
+    "mods/Example.Mod/mod.json",
+];
+
+const CASES: &[Case] = &[
+    Case {
+        name: "bepinex-loader",
+        loader: r#"{ "name": "BepInEx" }"#,
+        package: "BepInEx-BepInExPack",
+        files: &[
+            "manifest.json",
+            "icon.png",
+            "BepInExPack/winhttp.dll",
+            "BepInExPack/doorstop_config.ini",
+            "BepInExPack/BepInEx/core/BepInEx.dll",
+            "BepInExPack/BepInEx/config/BepInEx.cfg",
+        ],
+    },
+    Case {
+        name: "bepinex-mod",
+        loader: r#"{ "name": "BepInEx" }"#,
+        package: "Example-Mod",
+        files: MOD_FILES,
+    },
+    Case {
+        name: "bepisloader-loader",
+        loader: r#"{ "name": "BepisLoader" }"#,
+        package: "ResoniteModding-BepisLoader",
+        files: &[
+            "manifest.json",
+            "BepisLoader/winhttp.dll",
+            "BepisLoader/BepInEx/core/BepInEx.Core.dll",
+            "BepisLoader/BepInEx/config/BepInEx.cfg",
+        ],
+    },
+    Case {
+        name: "bepisloader-mod",
+        loader: r#"{ "name": "BepisLoader" }"#,
+        package: "Example-Mod",
+        files: MOD_FILES,
+    },
+    Case {
+        name: "melonloader-loader",
+        loader: r#"{ "name": "MelonLoader" }"#,
+        package: "LavaGang-MelonLoader",
+        files: &[
+            "manifest.json",
+            "version.dll",
+            "dobby.dll",
+            "MelonLoader/net6/MelonLoader.dll",
+            "MelonLoader/net35/MelonLoader.dll",
+            "MelonLoader/Dependencies/Il2CppAssemblyGenerator/Cpp2IL.dll",
+            "MelonLoader/Documentation/README.md",
+            "NOTICE.txt",
+        ],
+    },
+    Case {
+        name: "melonloader-mod",
+        loader: r#"{ "name": "MelonLoader" }"#,
+        package: "Example-Mod",
+        files: MOD_FILES,
+    },
+    Case {
+        name: "gdweave-loader",
+        loader: r#"{ "name": "GDWeave" }"#,
+        package: "NotNet-GDWeave",
+        files: &[
+            "manifest.json",
+            "winmm.dll",
+            "GDWeave/core/GDWeave.dll",
+            "GDWeave/core/GDWeave.pdb",
+        ],
+    },
+    Case {
+        name: "gdweave-mod",
+        loader: r#"{ "name": "GDWeave" }"#,
+        package: "Example-Mod",
+        files: &[
+            "manifest.json",
+            "icon.png",
+            "GDWeave/mods/Example.Mod/manifest.json",
+            "GDWeave/mods/Example.Mod/Example.Mod.dll",
+            "GDWeave/mods/Example.Mod/assets/example.pck",
+        ],
+    },
+    Case {
+        name: "northstar-loader",
+        loader: r#"{ "name": "Northstar" }"#,
+        package: "northstar-Northstar",
+        files: &[
+            "manifest.json",
+            "Northstar/Northstar.dll",
+            "Northstar/NorthstarLauncher.exe",
+            "Northstar/bin/x64_retail/wsock32.dll",
+            "Northstar/R2Northstar/plugins/DiscordRPC.dll",
+            "Northstar/R2Northstar/mods/Northstar.Client/mod.json",
+            "Northstar/R2Northstar/mods/md5sum.text",
+        ],
+    },
+    Case {
+        name: "northstar-mod",
+        loader: r#"{ "name": "Northstar" }"#,
+        package: "Example-Mod",
+        files: MOD_FILES,
+    },
+    Case {
+        name: "shimloader-loader",
+        loader: r#"{ "name": "Shimloader" }"#,
+        package: "Thunderstore-unreal_shimloader",
+        files: &[
+            "manifest.json",
+            "dwmapi.dll",
+            "UE4SS/dwmapi.dll",
+            "UE4SS/UE4SS.dll",
+            "UE4SS/UE4SS-settings.ini",
+            "UE4SS/Mods/mods.txt",
+        ],
+    },
+    Case {
+        name: "shimloader-mod",
+        loader: r#"{ "name": "Shimloader" }"#,
+        package: "Example-Mod",
+        files: &[
+            "manifest.json",
+            "mod/Example/Scripts/main.lua",
+            "pak/Example.pak",
+            "cfg/Example.cfg",
+            "Example.pak",
+        ],
+    },
+    Case {
+        name: "lovely-loader",
+        loader: r#"{ "name": "Lovely" }"#,
+        package: "Thunderstore-lovely",
+        files: &["manifest.json", "version.dll", "README.md"],
+    },
+    Case {
+        name: "lovely-mod",
+        loader: r#"{ "name": "Lovely" }"#,
+        package: "Example-Mod",
+        files: &[
+            "manifest.json",
+            "icon.png",
+            "Example/Example.lua",
+            "Example/lovely/patches.toml",
+        ],
+    },
+    Case {
+        name: "returnofmodding-loader",
+        loader: r#"{ "name": "ReturnOfModding", "files": ["version.dll"] }"#,
+        package: "ReturnOfModding-ReturnOfModding",
+        files: &[
+            "manifest.json",
+            "ReturnOfModding/version.dll",
+            "ReturnOfModding/README.md",
+        ],
+    },
+    Case {
+        name: "returnofmodding-mod",
+        loader: r#"{ "name": "ReturnOfModding", "files": ["version.dll"] }"#,
+        package: "Example-Mod",
+        files: &[
+            "manifest.json",
+            "main.lua",
+            "plugins/helper.lua",
+            "plugins_data/sprites/example.png",
+            "config/Example.cfg",
+        ],
+    },
+    Case {
+        name: "ue4ss-loader",
+        loader: r#"{ "name": "UE4SS", "project": "Example" }"#,
+        package: "Thunderstore-ue4ss",
+        files: &[
+            "manifest.json",
+            "icon.png",
+            "UE4SS/dwmapi.dll",
+            "UE4SS/ue4ss/UE4SS.dll",
+            "UE4SS/ue4ss/UE4SS-settings.ini",
+            "UE4SS/ue4ss/Mods/mods.txt",
+            "UE4SS/ue4ss/Mods/BPModLoaderMod/Scripts/main.lua",
+        ],
+    },
+    Case {
+        name: "ue4ss-mod",
+        loader: r#"{ "name": "UE4SS", "project": "Example" }"#,
+        package: "Example-Mod",
+        files: &[
+            "manifest.json",
+            "icon.png",
+            "README.md",
+            "Mods/ExampleMod/enabled.txt",
+            "Mods/ExampleMod/Scripts/main.lua",
+            "LogicMods/ExampleMod.pak",
+            "Example.pak",
+        ],
+    },
+];
+
+fn golden_dir() -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "golden", "installers"]
+        .iter()
+        .collect()
+}
+
+fn render(case: &Case) -> String {
+    let loader: ModLoader<'static> =
+        serde_json::from_str(case.loader).expect("loader should deserialize");
+    let loader = Box::leak(Box::new(loader));
+
+    let files = case.files.iter().map(PathBuf::from).collect::<Vec<_>>();
+    let plan = loader
+        .installer_for(case.package)
+        .plan(&files, case.package)
+        .unwrap_or_else(|err| panic!("failed to plan {}: {:#}", case.name, err));
+
+    let mut out = format!("# {} ({})\n", case.package, loader.as_str());
+    for file in plan {
+        let source = file.source.to_string_lossy().replace('\\', "/");
+        match file.target {
+            Some(target) => {
+                let target = target.to_string_lossy().replace('\\', "/");
+                write!(out, "{source} -> {target}").unwrap();
+                if file.tracked {
+                    out.push_str(" [tracked]");
+                }
+                if file.mutable {
+                    out.push_str(" [mutable]");
+                }
+            }
+            None => write!(out, "{source} -> (skipped)").unwrap(),
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+#[test]
+fn installers_match_golden_files() {
+    let dir = golden_dir();
+    let bless = env::var_os("GALE_BLESS").is_some();
+    let mut mismatches = Vec::new();
+
+    for case in CASES {
+        let actual = render(case);
+        let path = dir.join(format!("{}.txt", case.name));
+
+        if bless {
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(&path, actual).unwrap();
+            continue;
+        }
+
+        match fs::read_to_string(&path) {
+            Ok(expected) => {
+                if expected.replace("\r\n", "\n") != actual {
+                    mismatches.push(format!(
+                        "{}:\n--- expected\n{expected}--- actual\n{actual}",
+                        case.name
+                    ));
+                }
+            }
+            Err(err) => mismatches.push(format!("{}: {} ({err})", case.name, path.display())),
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "installer output changed, rerun with GALE_BLESS=1 if this is intended\n\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn every_loader_kind_is_covered() {
+    let covered = CASES
+        .iter()
+        .map(|case| {
+            serde_json::from_str::<ModLoader>(case.loader)
+                .unwrap()
+                .as_str()
+        })
+        .collect::<std::collections::HashSet<_>>();
+
+    for kind in [
+        "BepInEx",
+        "BepisLoader",
+        "MelonLoader",
+        "Northstar",
+        "GDWeave",
+        "Shimloader",
+        "Lovely",
+        "ReturnOfModding",
+        "UE4SS",
+    ] {
+        assert!(covered.contains(kind), "no golden test for {kind}");
+    }
+}
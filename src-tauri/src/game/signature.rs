@@ -0,0 +1,67 @@
+//! Verifies that remotely fetched game definitions are signed by the maintainers.
+//!
+//! The definitions decide where loaders and proxy DLLs are installed, so without this
+//! a compromised endpoint could point those installs anywhere on the user's drive.
+//!
+//! `games.json` is signed with `scripts/sign_games.py` and the signature is committed
+//! next to it as `games.json.sig`, which is fetched together with the list.
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use eyre::{bail, Context, Result};
+use ring::signature::{UnparsedPublicKey, ED25519};
+
+/// The pinned Ed25519 public keys, see the file for its format.
+const PUBLIC_KEYS: &str = include_str!("../../keys/games.pub");
+
+fn keys() -> impl Iterator<Item = &'static str> {
+    PUBLIC_KEYS
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// Checks a detached, base64 encoded signature of `data` against the pinned keys.
+///
+/// Fails if no keys are pinned, so that a build without them never trusts the remote list.
+pub fn verify(data: &[u8], signature: &str) -> Result<()> {
+    let signature = BASE64_STANDARD
+        .decode(signature.trim())
+        .context("signature is not valid base64")?;
+
+    for key in keys() {
+        let key = BASE64_STANDARD
+            .decode(key)
+            .context("pinned key is not valid base64")?;
+
+        if UnparsedPublicKey::new(&ED25519, key)
+            .verify(data, &signature)
+            .is_ok()
+        {
+            return Ok(());
+        }
+    }
+
+    bail!("signature does not match any of the pinned keys")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_list_is_signed() {
+        let data = include_bytes!("../../games.json");
+        let signature = include_str!("../../games.json.sig");
+
+        verify(data, signature).unwrap();
+    }
+
+    #[test]
+    fn rejects_modified_list() {
+        let mut data = include_bytes!("../../games.json").to_vec();
+        data.push(b'\n');
+        let signature = include_str!("../../games.json.sig");
+
+        assert!(verify(&data, signature).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! Message catalogs for user-facing strings produced by the backend.
+//!
+//! Catalogs live in `locales/<locale>.json` and map message keys to templates,
+//! where `{name}` is replaced by the argument with the same name. Missing messages
+//! fall back to English, and then to the key itself.
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{LazyLock, RwLock},
+};
+
+use include_dir::{include_dir, Dir};
+use tracing::warn;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+static LOCALES_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/locales");
+
+type Catalog = HashMap<String, String>;
+
+static CATALOGS: LazyLock<HashMap<&'static str, Catalog>> = LazyLock::new(|| {
+    LOCALES_DIR
+        .files()
+        .filter_map(|file| {
+            let locale = file.path().file_stem()?.to_str()?;
+
+            match serde_json::from_slice(file.contents()) {
+                Ok(catalog) => Some((locale, catalog)),
+                Err(err) => {
+                    warn!("failed to parse message catalog for {}: {}", locale, err);
+                    None
+                }
+            }
+        })
+        .collect()
+});
+
+static LOCALE: RwLock<&'static str> = RwLock::new(DEFAULT_LOCALE);
+
+/// Locales that have a message catalog.
+pub fn locales() -> Vec<&'static str> {
+    let mut locales = CATALOGS.keys().copied().collect::<Vec<_>>();
+    locales.sort();
+    locales
+}
+
+/// Switches the locale used by [`t`]. Unknown locales fall back to English.
+pub fn set_locale(locale: &str) {
+    let locale = CATALOGS
+        .get_key_value(locale)
+        .map(|(key, _)| *key)
+        .unwrap_or_else(|| {
+            warn!("no message catalog for locale {}, using English", locale);
+            DEFAULT_LOCALE
+        });
+
+    *LOCALE.write().unwrap() = locale;
+}
+
+/// Looks up a message in the current locale and fills in its arguments.
+pub fn t(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let locale = *LOCALE.read().unwrap();
+
+    let template = [locale, DEFAULT_LOCALE]
+        .into_iter()
+        .find_map(|locale| CATALOGS.get(locale)?.get(key));
+
+    let Some(template) = template else {
+        warn!("missing message {}", key);
+        return key.to_owned();
+    };
+
+    args.iter()
+        .fold(template.clone(), |message, (name, value)| {
+            message.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+}
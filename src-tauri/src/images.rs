@@ -0,0 +1,487 @@
+//! A local cache for package icons and game artwork, served to the webview
+//! through the `gale-img` protocol.
+//!
+//! Requests look like `gale-img://localhost/<kind>/<name>/<size>`. The source
+//! image is downloaded once and each size variant is stored next to it as a PNG,
+//! so icons keep working offline. Least recently used files are evicted once the
+//! cache grows past [`MAX_CACHE_SIZE`].
+//!
+//! The special `thumb` size is a small WebP used by the mod lists. Thumbnails for
+//! the most popular packages are generated in the background by [`pregenerate`],
+//! so scrolling through a large community doesn't have to decode full icons.
+//!
+//! Images embedded in READMEs and changelogs are requested as
+//! `gale-img://localhost/media?url=<url>` and stored under a hash of the URL. They're
+//! served as-is, since converting them would break animated GIFs, and count towards
+//! the same size limit as everything else.
+
+use std::{
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::SystemTime,
+};
+
+use eyre::{bail, ensure, OptionExt, Result};
+use futures_util::StreamExt;
+use image::{imageops::FilterType, DynamicImage, ImageFormat};
+use itertools::Itertools;
+use tauri::{
+    http::{header, Request, Response, StatusCode},
+    AppHandle, Runtime, UriSchemeContext, UriSchemeResponder, Url,
+};
+use tracing::{debug, info, warn};
+use walkdir::WalkDir;
+
+use crate::state::ManagerExt;
+
+pub const PROTOCOL: &str = "gale-img";
+
+/// Requested sizes are rounded up to one of these, larger ones get the original.
+const SIZES: [u32; 3] = [64, 128, 256];
+const MAX_CACHE_SIZE: u64 = 200 * 1024 * 1024;
+const SOURCE_FILE_NAME: &str = "source";
+const MEDIA_DIR_NAME: &str = "media";
+/// Embedded images larger than this are not cached.
+const MAX_MEDIA_SIZE: usize = 16 * 1024 * 1024;
+
+/// Edge length of list thumbnails, enough for the 48px list icons on most displays.
+const THUMBNAIL_SIZE: u32 = 64;
+/// How many of the top rated packages get their thumbnails generated ahead of time.
+const PREGENERATE_COUNT: usize = 500;
+const PREGENERATE_CONCURRENCY: usize = 4;
+
+static PREGENERATING: AtomicBool = AtomicBool::new(false);
+
+/// The address the webview loads a cached copy of an embedded image from.
+pub fn media_url(url: &str) -> String {
+    // custom protocols are served over http on windows
+    let base = if cfg!(windows) {
+        format!("http://{PROTOCOL}.localhost/{MEDIA_DIR_NAME}")
+    } else {
+        format!("{PROTOCOL}://localhost/{MEDIA_DIR_NAME}")
+    };
+
+    let mut media = Url::parse(&base).expect("media base url should be valid");
+    media.query_pairs_mut().append_pair("url", url);
+    media.into()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ImageKind {
+    /// A Thunderstore package icon, named by its full version ident.
+    Icon,
+    /// A game's artwork, named by its slug.
+    Game,
+}
+
+impl ImageKind {
+    fn parse(str: &str) -> Option<Self> {
+        match str {
+            "icon" => Some(Self::Icon),
+            "game" => Some(Self::Game),
+            _ => None,
+        }
+    }
+
+    fn dir_name(self) -> &'static str {
+        match self {
+            Self::Icon => "icon",
+            Self::Game => "game",
+        }
+    }
+
+    fn source_url(self, name: &str) -> String {
+        match self {
+            Self::Icon => format!("https://gcdn.thunderstore.io/live/repository/icons/{name}.png"),
+            Self::Game => format!(
+                "https://raw.githubusercontent.com/Kesomannen/gale/refs/heads/master/images/games/{name}.webp"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Variant {
+    Source,
+    Resized(u32),
+    Thumbnail,
+}
+
+impl Variant {
+    fn parse(str: &str) -> Self {
+        match str {
+            "thumb" => Self::Thumbnail,
+            _ => str
+                .parse::<u32>()
+                .ok()
+                .and_then(snap_size)
+                .map_or(Self::Source, Self::Resized),
+        }
+    }
+
+    fn file_name(self) -> String {
+        match self {
+            Self::Source => SOURCE_FILE_NAME.to_owned(),
+            Self::Resized(size) => format!("{size}.png"),
+            Self::Thumbnail => "thumb.webp".to_owned(),
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Thumbnail => "image/webp",
+            _ => "image/png",
+        }
+    }
+}
+
+fn cache_dir<R: Runtime>(app: &AppHandle<R>) -> PathBuf {
+    app.lock_prefs().data_dir.join("images")
+}
+
+pub fn handle_request<R: Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = ctx.app_handle().to_owned();
+    let path = request.uri().path().trim_start_matches('/').to_owned();
+    let query = request.uri().query().map(str::to_owned);
+
+    tauri::async_runtime::spawn(async move {
+        let result = match path.as_str() {
+            MEDIA_DIR_NAME => serve_media(query.as_deref(), &app).await,
+            _ => serve(&path, &app).await,
+        };
+
+        let response = match result {
+            Ok((bytes, content_type)) => Response::builder()
+                .header(header::CONTENT_TYPE, content_type)
+                .header(header::CACHE_CONTROL, "max-age=604800")
+                .body(bytes),
+            Err(err) => {
+                debug!("failed to serve image {}: {:#}", path, err);
+
+                Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(Vec::new())
+            }
+        };
+
+        responder.respond(response.expect("image response should be valid"));
+    });
+}
+
+async fn serve<R: Runtime>(path: &str, app: &AppHandle<R>) -> Result<(Vec<u8>, &'static str)> {
+    let Some((kind, name, variant)) = path.split('/').collect_tuple() else {
+        bail!("malformed image path");
+    };
+
+    let kind = ImageKind::parse(kind).ok_or_eyre("unknown image kind")?;
+    // names end up in paths, so only allow what package idents and slugs use
+    ensure!(
+        !name.is_empty()
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|char| char.is_ascii_alphanumeric() || matches!(char, '-' | '_' | '.')),
+        "invalid image name"
+    );
+
+    let variant = Variant::parse(variant);
+    let bytes = load(kind, name, variant, &cache_dir(app), app).await?;
+
+    Ok((bytes, variant.content_type()))
+}
+
+async fn serve_media<R: Runtime>(
+    query: Option<&str>,
+    app: &AppHandle<R>,
+) -> Result<(Vec<u8>, &'static str)> {
+    let url = query
+        .and_then(|query| query_param(query, "url"))
+        .ok_or_eyre("missing media url")?;
+
+    ensure!(
+        url.starts_with("https://") || url.starts_with("http://"),
+        "unsupported media url"
+    );
+
+    let path = cache_dir(app)
+        .join(MEDIA_DIR_NAME)
+        .join(blake3::hash(url.as_bytes()).to_hex().as_str())
+        .join(SOURCE_FILE_NAME);
+
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => {
+            touch(&path);
+            bytes
+        }
+        Err(_) => {
+            let bytes = download_media(&url, app).await?;
+
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(&path, &bytes)?;
+
+            bytes
+        }
+    };
+
+    let content_type = media_type(&bytes);
+    Ok((bytes, content_type))
+}
+
+fn query_param(query: &str, key: &str) -> Option<String> {
+    Url::parse(&format!("{PROTOCOL}://localhost/?{query}"))
+        .ok()?
+        .query_pairs()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.into_owned())
+}
+
+async fn download_media<R: Runtime>(url: &str, app: &AppHandle<R>) -> Result<Vec<u8>> {
+    let response = app.http().get(url).send().await?.error_for_status()?;
+
+    let too_large = response
+        .content_length()
+        .is_some_and(|len| len > MAX_MEDIA_SIZE as u64);
+    ensure!(!too_large, "media is too large");
+
+    let mut stream = response.bytes_stream();
+    let mut bytes = Vec::new();
+
+    while let Some(chunk) = stream.next().await {
+        bytes.extend_from_slice(&chunk?);
+        ensure!(bytes.len() <= MAX_MEDIA_SIZE, "media is too large");
+    }
+
+    Ok(bytes)
+}
+
+fn media_type(bytes: &[u8]) -> &'static str {
+    match image::guess_format(bytes) {
+        Ok(ImageFormat::Png) => "image/png",
+        Ok(ImageFormat::Jpeg) => "image/jpeg",
+        Ok(ImageFormat::Gif) => "image/gif",
+        Ok(ImageFormat::WebP) => "image/webp",
+        Ok(ImageFormat::Bmp) => "image/bmp",
+        Ok(ImageFormat::Ico) => "image/x-icon",
+        Ok(ImageFormat::Avif) => "image/avif",
+        // shields.io badges and the like
+        _ if bytes.trim_ascii_start().starts_with(b"<") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Reads a variant from the cache, downloading and converting it if needed.
+async fn load<R: Runtime>(
+    kind: ImageKind,
+    name: &str,
+    variant: Variant,
+    cache_dir: &Path,
+    app: &AppHandle<R>,
+) -> Result<Vec<u8>> {
+    let dir = cache_dir.join(kind.dir_name()).join(name);
+    let variant_path = dir.join(variant.file_name());
+
+    if let Ok(bytes) = fs::read(&variant_path) {
+        touch(&variant_path);
+        return Ok(bytes);
+    }
+
+    let source_path = dir.join(SOURCE_FILE_NAME);
+    let source = match fs::read(&source_path) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            let bytes = app
+                .http()
+                .get(kind.source_url(name))
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await?
+                .to_vec();
+
+            fs::create_dir_all(&dir)?;
+            fs::write(&source_path, &bytes)?;
+
+            bytes
+        }
+    };
+
+    let bytes = match variant {
+        Variant::Source => to_png(&source)?,
+        Variant::Resized(size) => {
+            let bytes =
+                tauri::async_runtime::spawn_blocking(move || resize(&source, size)).await??;
+            fs::write(&variant_path, &bytes)?;
+            bytes
+        }
+        Variant::Thumbnail => {
+            let bytes = tauri::async_runtime::spawn_blocking(move || thumbnail(&source)).await??;
+            fs::write(&variant_path, &bytes)?;
+            bytes
+        }
+    };
+
+    Ok(bytes)
+}
+
+fn snap_size(requested: u32) -> Option<u32> {
+    SIZES.into_iter().find(|size| *size >= requested)
+}
+
+fn resize(source: &[u8], size: u32) -> Result<Vec<u8>> {
+    let image = downscale(image::load_from_memory(source)?, size);
+
+    let mut bytes = Vec::new();
+    image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+    Ok(bytes)
+}
+
+fn thumbnail(source: &[u8]) -> Result<Vec<u8>> {
+    let image = downscale(image::load_from_memory(source)?, THUMBNAIL_SIZE);
+    // the webp encoder only accepts 8-bit color
+    let image = DynamicImage::ImageRgba8(image.into_rgba8());
+
+    let mut bytes = Vec::new();
+    image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::WebP)?;
+    Ok(bytes)
+}
+
+fn downscale(image: DynamicImage, size: u32) -> DynamicImage {
+    match image.width() > size || image.height() > size {
+        true => image.resize(size, size, FilterType::Lanczos3),
+        false => image,
+    }
+}
+
+fn to_png(source: &[u8]) -> Result<Vec<u8>> {
+    match image::guess_format(source)? {
+        ImageFormat::Png => Ok(source.to_vec()),
+        _ => {
+            let mut bytes = Vec::new();
+            image::load_from_memory(source)?
+                .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+            Ok(bytes)
+        }
+    }
+}
+
+/// Marks a file as recently used, so it's evicted last.
+fn touch(path: &Path) {
+    if let Ok(file) = fs::File::options().append(true).open(path) {
+        file.set_modified(SystemTime::now()).ok();
+    }
+}
+
+/// Generates list thumbnails for the top rated packages of the current game
+/// in the background. Does nothing if a previous run is still going.
+pub fn pregenerate(app: AppHandle) {
+    if PREGENERATING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+
+    let cache_dir = cache_dir(&app);
+
+    let names = {
+        let thunderstore = app.lock_thunderstore();
+
+        thunderstore
+            .latest()
+            .filter(|borrowed| !borrowed.package.has_nsfw_content)
+            .sorted_by(|a, b| {
+                b.package
+                    .is_pinned
+                    .cmp(&a.package.is_pinned)
+                    .then(b.package.rating_score.cmp(&a.package.rating_score))
+            })
+            .take(PREGENERATE_COUNT)
+            .map(|borrowed| borrowed.ident().to_string())
+            .filter(|name| {
+                !cache_dir
+                    .join(ImageKind::Icon.dir_name())
+                    .join(name)
+                    .join(Variant::Thumbnail.file_name())
+                    .exists()
+            })
+            .collect_vec()
+    };
+
+    if names.is_empty() {
+        PREGENERATING.store(false, Ordering::Release);
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        debug!("generating {} icon thumbnails", names.len());
+
+        futures_util::stream::iter(names)
+            .for_each_concurrent(PREGENERATE_CONCURRENCY, |name| {
+                let app = &app;
+                let cache_dir = &cache_dir;
+
+                async move {
+                    if let Err(err) =
+                        load(ImageKind::Icon, &name, Variant::Thumbnail, cache_dir, app).await
+                    {
+                        debug!("failed to generate thumbnail for {}: {:#}", name, err);
+                    }
+                }
+            })
+            .await;
+
+        PREGENERATING.store(false, Ordering::Release);
+    });
+}
+
+/// Deletes the least recently used images until the cache is below [`MAX_CACHE_SIZE`].
+pub fn evict<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    let dir = cache_dir(app);
+
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    let mut files = WalkDir::new(&dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            Some((entry.into_path(), metadata.len(), modified))
+        })
+        .collect_vec();
+
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+
+    if total <= MAX_CACHE_SIZE {
+        return Ok(());
+    }
+
+    files.sort_by_key(|(_, _, modified)| *modified);
+
+    let mut count = 0;
+
+    for (path, len, _) in files {
+        if total <= MAX_CACHE_SIZE {
+            break;
+        }
+
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                total -= len;
+                count += 1;
+            }
+            Err(err) => warn!("failed to evict {}: {}", path.display(), err),
+        }
+    }
+
+    info!("evicted {} images from cache", count);
+
+    Ok(())
+}
@@ -0,0 +1,415 @@
+//! An optional local server that lets other programs control Gale, like Stream Deck
+//! plugins and game launchers. Enabled by [`Prefs::ipc_server`].
+//!
+//! Clients connect to the unix socket `gale.sock` in the data directory, or the named
+//! pipe `\\.\pipe\gale` on Windows, and send newline-delimited [JSON-RPC 2.0] requests.
+//! Each response is written on a line of its own.
+//!
+//! | Method         | Params                                   | Result                 |
+//! |----------------|------------------------------------------|------------------------|
+//! | `version`      |                                          | [`VersionInfo`]        |
+//! | `listProfiles` | `{ game?: string }`                      | [`ProfileInfo`] array  |
+//! | `install`      | `{ profileId?: number, mods: string[] }` | `null`                 |
+//! | `launch`       | `{ profileId?: number }`                 | `null`                 |
+//!
+//! Mods are given as `Owner-Name` for the latest version or `Owner-Name-1.0.0` for a
+//! specific one. Without a `profileId`, the active profile is used. [`API_VERSION`] is
+//! only bumped when existing methods change in incompatible ways.
+//!
+//! The socket is only accessible to the current user. Installs and launches are
+//! recorded in the audit log as `ipc_install` and `ipc_launch`.
+//!
+//! [JSON-RPC 2.0]: https://www.jsonrpc.org/specification
+//! [`Prefs::ipc_server`]: crate::prefs::Prefs::ipc_server
+
+use std::{future::Future, path::Path, sync::Mutex};
+
+use eyre::{ensure, eyre, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{async_runtime::JoinHandle, ipc::InvokeBody, AppHandle};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    audit,
+    profile::{
+        install::{InstallOptions, InstallResultExt, ModInstall},
+        launch, sync,
+    },
+    state::ManagerExt,
+    thunderstore::{self, BorrowedMod, Thunderstore, VersionIdent},
+};
+
+pub const API_VERSION: u32 = 1;
+
+#[cfg(unix)]
+const SOCKET_NAME: &str = "gale.sock";
+#[cfg(windows)]
+const PIPE_NAME: &str = r"\\.\pipe\gale";
+
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const SERVER_ERROR: i32 = -32000;
+
+#[derive(Default)]
+pub struct State {
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl State {
+    /// Starts listening for connections, replacing any previous server.
+    pub fn start(&self, data_dir: &Path, app: &AppHandle) {
+        self.stop();
+
+        let data_dir = data_dir.to_path_buf();
+        let app = app.to_owned();
+        let task = tauri::async_runtime::spawn(async move {
+            if let Err(err) = listen(&data_dir, app).await {
+                error!("ipc server stopped: {:#}", err);
+            }
+        });
+
+        *self.task.lock().unwrap() = Some(task);
+    }
+
+    pub fn stop(&self) {
+        if let Some(task) = self.task.lock().unwrap().take() {
+            task.abort();
+            info!("stopped ipc server");
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn listen(data_dir: &Path, app: AppHandle) -> Result<()> {
+    use std::{fs::Permissions, os::unix::fs::PermissionsExt};
+
+    use eyre::Context;
+    use tokio::net::UnixListener;
+
+    let path = data_dir.join(SOCKET_NAME);
+
+    // left behind if we didn't exit cleanly, and would make binding fail
+    std::fs::remove_file(&path).ok();
+
+    let listener = UnixListener::bind(&path).context("failed to bind socket")?;
+
+    // anyone who can connect can install mods, so don't rely on the umask
+    std::fs::set_permissions(&path, Permissions::from_mode(0o600))
+        .context("failed to restrict socket permissions")?;
+    info!("ipc server listening on {}", path.display());
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        tauri::async_runtime::spawn(handle_connection(stream, app.clone()));
+    }
+}
+
+#[cfg(windows)]
+async fn listen(_data_dir: &Path, app: AppHandle) -> Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(PIPE_NAME)?;
+
+    info!("ipc server listening on {}", PIPE_NAME);
+
+    loop {
+        server.connect().await?;
+
+        // create the next instance before handing this one off, so clients always find one
+        let client = std::mem::replace(&mut server, ServerOptions::new().create(PIPE_NAME)?);
+        tauri::async_runtime::spawn(handle_connection(client, app.clone()));
+    }
+}
+
+async fn handle_connection<S>(stream: S, app: AppHandle)
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    debug!("accepted ipc connection");
+
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Some(response) = handle_message(&line, &app).await else {
+            continue;
+        };
+
+        let mut json = serde_json::to_vec(&response).expect("response should be serializable");
+        json.push(b'\n');
+
+        if let Err(err) = writer.write_all(&json).await {
+            warn!("failed to write ipc response: {}", err);
+            break;
+        }
+    }
+
+    debug!("ipc connection closed");
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+#[derive(Serialize)]
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i32, message: impl ToString) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Returns `None` for notifications, which don't get a response.
+async fn handle_message(message: &str, app: &AppHandle) -> Option<Response> {
+    let request = match serde_json::from_str::<Value>(message) {
+        Ok(value) => serde_json::from_value::<Request>(value)
+            .map_err(|err| RpcError::new(INVALID_REQUEST, err)),
+        Err(err) => Err(RpcError::new(PARSE_ERROR, err)),
+    };
+
+    let (id, result) = match request {
+        Ok(Request { id, method, params }) => {
+            debug!("handling ipc request {}", method);
+            let result = dispatch(&method, params, app).await;
+            (id?, result)
+        }
+        Err(err) => (Value::Null, Err(err)),
+    };
+
+    let (result, error) = match result {
+        Ok(result) => (Some(result), None),
+        Err(err) => (None, Some(err)),
+    };
+
+    Some(Response {
+        jsonrpc: "2.0",
+        id,
+        result,
+        error,
+    })
+}
+
+async fn dispatch(method: &str, params: Value, app: &AppHandle) -> Result<Value, RpcError> {
+    let result = match method {
+        "version" => Ok(to_value(VersionInfo::current())),
+        "listProfiles" => list_profiles(parse(params)?, app).map(to_value),
+        "install" => audited(method, &params, install(parse(params.clone())?, app), app).await,
+        "launch" => audited(method, &params, launch(parse(params.clone())?, app), app).await,
+        _ => return Err(RpcError::new(METHOD_NOT_FOUND, "method not found")),
+    };
+
+    result.map_err(|err| RpcError::new(SERVER_ERROR, format!("{err:#}")))
+}
+
+/// Runs a method that changes state, recording it in the audit log the same way as
+/// commands invoked from the frontend.
+async fn audited<T: Serialize>(
+    method: &str,
+    params: &Value,
+    run: impl Future<Output = Result<T>>,
+    app: &AppHandle,
+) -> Result<Value> {
+    let request_id = Uuid::new_v4().to_string();
+    let payload = InvokeBody::Json(params.clone());
+    audit::record(&format!("ipc_{method}"), &payload, &request_id, app);
+
+    let result = run.await;
+
+    let error = result.as_ref().err().map(|err| format!("{err:#}"));
+    audit::finish(&request_id, error.as_deref(), app);
+
+    result.map(to_value)
+}
+
+fn parse<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    // methods without params still get an empty object
+    let params = match params {
+        Value::Null => Value::Object(Default::default()),
+        params => params,
+    };
+
+    serde_json::from_value(params).map_err(|err| RpcError::new(INVALID_PARAMS, err))
+}
+
+fn to_value(value: impl Serialize) -> Value {
+    serde_json::to_value(value).expect("result should be serializable")
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+    pub api_version: u32,
+    pub app_version: &'static str,
+}
+
+impl VersionInfo {
+    fn current() -> Self {
+        Self {
+            api_version: API_VERSION,
+            app_version: env!("CARGO_PKG_VERSION"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ListProfilesParams {
+    #[serde(default)]
+    game: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    pub id: i64,
+    pub name: String,
+    pub game: String,
+    pub mod_count: usize,
+    pub active: bool,
+}
+
+fn list_profiles(params: ListProfilesParams, app: &AppHandle) -> Result<Vec<ProfileInfo>> {
+    let manager = app.lock_manager();
+
+    let profiles = manager
+        .games
+        .iter()
+        .filter(|(game, _)| params.game.as_ref().map_or(true, |slug| game.slug == *slug))
+        .flat_map(|(game, managed_game)| {
+            let is_active_game = *game == manager.active_game;
+
+            managed_game
+                .profiles
+                .iter()
+                .map(move |profile| ProfileInfo {
+                    id: profile.id,
+                    name: profile.name.clone(),
+                    game: game.slug.to_string(),
+                    mod_count: profile.mods.len(),
+                    active: is_active_game && profile.id == managed_game.active_profile_id,
+                })
+        })
+        .collect();
+
+    Ok(profiles)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstallParams {
+    #[serde(default)]
+    profile_id: Option<i64>,
+    mods: Vec<String>,
+}
+
+async fn install(params: InstallParams, app: &AppHandle) -> Result<()> {
+    ensure!(
+        !app.lock_prefs().kiosk_mode,
+        "installing mods is disabled in kiosk mode"
+    );
+
+    let profile_id = {
+        let manager = app.lock_manager();
+        match params.profile_id {
+            Some(id) => {
+                let (game, _) = manager.profile_by_id(id)?;
+                ensure!(
+                    game == manager.active_game,
+                    "mods can only be installed to profiles of the selected game"
+                );
+                id
+            }
+            None => manager.active_profile().id,
+        }
+    };
+
+    thunderstore::wait_for_fetch(app).await;
+
+    let mods = {
+        let thunderstore = app.lock_thunderstore();
+        params
+            .mods
+            .iter()
+            .map(|name| find_mod(name, &thunderstore).map(ModInstall::new))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    app.install_queue()
+        .install_with_deps(mods, profile_id, InstallOptions::default(), false, app)?
+        .await
+        .ignore_cancel()?;
+
+    Ok(())
+}
+
+fn find_mod<'a>(name: &str, thunderstore: &'a Thunderstore) -> Result<BorrowedMod<'a>> {
+    if let Ok(package) = thunderstore.find_package(name) {
+        return Ok((package, package.latest()).into());
+    }
+
+    let ident = name
+        .parse::<VersionIdent>()
+        .map_err(|_| eyre!("invalid mod {}", name))?;
+
+    thunderstore.find_ident(&ident)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LaunchParams {
+    #[serde(default)]
+    profile_id: Option<i64>,
+}
+
+async fn launch(params: LaunchParams, app: &AppHandle) -> Result<()> {
+    if let Some(profile_id) = params.profile_id {
+        let mut manager = app.lock_manager();
+
+        let (game, _) = manager.profile_by_id(profile_id)?;
+        manager.set_active_game(game, app)?;
+
+        let managed_game = manager.active_game_mut();
+        let index = managed_game.index_of(profile_id)?;
+        managed_game.set_active_profile(index)?;
+
+        manager.save_all(app)?;
+    }
+
+    if app.lock_prefs().pull_before_launch {
+        sync::pull_profile(false, app).await?;
+    }
+
+    launch::launch(app).await
+}
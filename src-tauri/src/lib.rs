@@ -2,7 +2,7 @@ use std::env;
 
 use itertools::Itertools;
 use state::ManagerExt;
-use tauri::{App, AppHandle, RunEvent};
+use tauri::{App, AppHandle, Manager, RunEvent};
 use tauri_plugin_deep_link::DeepLinkExt;
 use tauri_plugin_dialog::DialogExt;
 use tracing::{error, info, warn};
@@ -10,15 +10,23 @@ use tracing::{error, info, warn};
 #[cfg(target_os = "linux")]
 extern crate webkit2gtk;
 
+mod audit;
 mod cli;
 mod config;
 mod db;
 mod deep_link;
 mod game;
+mod i18n;
+mod images;
+mod ipc;
 mod logger;
+mod memory;
+mod notify;
 mod prefs;
 mod profile;
+mod schema;
 mod state;
+mod theme;
 mod thunderstore;
 mod util;
 
@@ -33,18 +41,23 @@ fn setup(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
         error!("setup error: {:?}", err);
 
         app.dialog()
-            .message(format!("Failed to launch Gale: {err:?}"))
+            .message(i18n::t("setup.failed", &[("error", &format!("{err:?}"))]))
             .blocking_show();
 
         return Err(err.into());
     }
 
-    if let Err(err) = app.deep_link().register("ror2mm") {
-        warn!("failed to register ror2mm deep link protocol: {:#}", err);
-    }
+    if let Some(dir) = util::path::portable_dir() {
+        // don't leave protocol handlers behind on machines we're only visiting
+        info!("running in portable mode from {}", dir.display());
+    } else {
+        if let Err(err) = app.deep_link().register("ror2mm") {
+            warn!("failed to register ror2mm deep link protocol: {:#}", err);
+        }
 
-    if let Err(err) = app.deep_link().register("gale") {
-        warn!("failed to register gale deep link protocol: {:#}", err);
+        if let Err(err) = app.deep_link().register("gale") {
+            warn!("failed to register gale deep link protocol: {:#}", err);
+        }
     }
 
     let args = env::args().collect_vec();
@@ -58,28 +71,65 @@ fn setup(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
             handle
                 .db()
                 .evict_outdated_cache()
-                .unwrap_or_else(|err| warn!("failed to evict outdated cache: {err:#}"))
+                .unwrap_or_else(|err| warn!("failed to evict outdated cache: {err:#}"));
+
+            audit::prune(&handle);
+
+            images::evict(&handle)
+                .unwrap_or_else(|err| warn!("failed to evict image cache: {err:#}"));
         })
         .await
     });
 
+    profile::health::start(app.handle());
+    profile::launch::game_update::start(app.handle());
+
+    let prefs = app.lock_prefs();
+    if prefs.ipc_server {
+        app.ipc_server().start(&prefs.data_dir, app.handle());
+    }
+    drop(prefs);
+    tauri::async_runtime::spawn(profile::install::interrupted::resume(
+        app.handle().to_owned(),
+    ));
+
     info!("setup done");
 
     Ok(())
 }
 
 fn event_handler(app: &AppHandle, event: RunEvent) {
-    if let RunEvent::ExitRequested { api, .. } = event {
-        if !app.install_queue().handle().is_processing() {
-            return;
-        }
+    match event {
+        // a code means we're exiting on purpose, possibly after the prompt below
+        RunEvent::ExitRequested {
+            api, code: None, ..
+        } => {
+            if !app.install_queue().handle().is_processing() {
+                return;
+            }
 
-        api.prevent_exit();
+            api.prevent_exit();
 
-        tauri::async_runtime::spawn(profile::install::handle_exit(app.to_owned()));
+            tauri::async_runtime::spawn(profile::install::handle_exit(app.to_owned()));
+        }
+        RunEvent::Exit => shutdown(app),
+        _ => (),
     }
 }
 
+/// Stops background work and flushes the database before the process exits.
+fn shutdown(app: &AppHandle) {
+    info!("shutting down");
+
+    app.lock_thunderstore().stop_fetching();
+    app.lan_share().stop();
+    app.ipc_server().stop();
+
+    app.db()
+        .checkpoint()
+        .unwrap_or_else(|err| warn!("failed to flush database: {:#}", err));
+}
+
 fn handle_single_instance(app: &AppHandle, args: Vec<String>, _cwd: String) {
     if !deep_link::handle(app, args.clone()) {
         cli::run(args, app);
@@ -91,93 +141,212 @@ pub fn run() {
         eprintln!("failed to set up logger: {err:#}");
     });
 
+    let handler: fn(tauri::ipc::Invoke) -> bool = tauri::generate_handler![
+        logger::open_gale_log,
+        logger::log_err,
+        logger::get_logs,
+        logger::export_trace,
+        memory::get_memory_report,
+        audit::get_audit_log,
+        util::net::cancel_requests,
+        notify::should_notify,
+        state::is_first_run,
+        state::is_portable,
+        schema::check_schema_version,
+        thunderstore::commands::query_thunderstore,
+        thunderstore::commands::stop_querying_thunderstore,
+        thunderstore::commands::get_index_status,
+        thunderstore::commands::refresh_index,
+        thunderstore::commands::get_markdown,
+        thunderstore::commands::render_markdown,
+        thunderstore::commands::get_package_links,
+        thunderstore::commands::search_all_games,
+        thunderstore::commands::get_package_contents,
+        thunderstore::commands::get_recently_updated,
+        thunderstore::commands::mark_feed_visited,
+        thunderstore::commands::rate_package,
+        thunderstore::commands::get_author_dashboard,
+        thunderstore::commands::set_thunderstore_token,
+        thunderstore::commands::has_thunderstore_token,
+        thunderstore::commands::clear_thunderstore_token,
+        thunderstore::commands::trigger_mod_fetch,
+        prefs::commands::get_prefs,
+        prefs::commands::export_settings,
+        prefs::commands::import_settings,
+        prefs::commands::get_dir_overrides,
+        prefs::commands::set_prefs,
+        prefs::commands::revoke_path_access,
+        prefs::commands::zoom_window,
+        prefs::commands::get_system_fonts,
+        prefs::commands::get_languages,
+        theme::commands::get_themes,
+        theme::commands::get_theme,
+        theme::commands::import_theme,
+        prefs::commands::acknowledge_anti_cheat,
+        prefs::commands::enable_kiosk_mode,
+        prefs::commands::disable_kiosk_mode,
+        profile::commands::get_game_info,
+        profile::commands::favorite_game,
+        profile::commands::set_active_game,
+        game::commands::get_custom_games,
+        game::commands::save_custom_game,
+        game::commands::delete_custom_game,
+        profile::commands::get_profile_info,
+        profile::commands::set_active_profile,
+        profile::commands::is_mod_installed,
+        profile::commands::query_profile,
+        profile::commands::get_dependants,
+        profile::commands::get_required_by,
+        profile::commands::create_profile,
+        profile::commands::delete_profile,
+        profile::commands::rename_profile,
+        profile::commands::move_profile,
+        profile::commands::duplicate_profile,
+        profile::commands::remove_mod,
+        profile::commands::force_remove_mods,
+        profile::commands::toggle_mod,
+        profile::commands::force_toggle_mods,
+        profile::commands::export_toggle_set,
+        profile::commands::import_toggle_set,
+        profile::commands::get_health_report,
+        profile::commands::run_health_check,
+        profile::commands::get_prune_suggestions,
+        profile::commands::apply_prune_suggestions,
+        profile::commands::set_all_mods_state,
+        profile::commands::remove_disabled_mods,
+        profile::commands::remove_all_mods,
+        profile::commands::run_bulk_action,
+        profile::commands::set_mod_tags,
+        profile::commands::set_mod_note,
+        profile::commands::set_mod_local_only,
+        profile::commands::set_profile_notes,
+        profile::commands::open_profile_dir,
+        profile::commands::open_mod_dir,
+        profile::commands::open_game_log,
+        profile::commands::open_game_log_dir,
+        profile::commands::report_mod,
+        profile::commands::create_desktop_shortcut,
+        profile::commands::get_local_markdown,
+        profile::commands::set_custom_args,
+        profile::commands::add_workshop_item,
+        profile::commands::remove_workshop_item,
+        profile::commands::open_workshop_item,
+        profile::launch::commands::launch_game,
+        profile::launch::commands::get_launch_args,
+        profile::launch::commands::scan_installed_games,
+        profile::launch::commands::use_gog_install,
+        profile::launch::commands::open_game_dir,
+        profile::launch::commands::clear_steam_launch_options,
+        profile::launch::commands::get_missing_loader_files,
+        profile::launch::commands::repair_loader_files,
+        profile::launch::commands::test_injection,
+        profile::server::commands::get_server_incidents,
+        profile::server::commands::install_dedicated_server,
+        profile::install::commands::install_all_mods,
+        profile::install::commands::install_mod,
+        profile::install::commands::preview_dependencies,
+        profile::install::commands::install_mod_excluding,
+        profile::install::commands::install_from_url,
+        profile::install::commands::preview_author_bundle,
+        profile::install::commands::install_author_bundle,
+        profile::install::commands::reinstall_mod,
+        profile::install::commands::cancel_all_installs,
+        profile::install::commands::get_install_queue,
+        profile::install::commands::cancel_install_batch,
+        profile::install::commands::prioritize_install_batch,
+        profile::install::commands::resolve_quarantine,
+        profile::install::commands::has_pending_installations,
+        profile::install::commands::clear_download_cache,
+        profile::install::commands::deduplicate_cache,
+        profile::install::commands::get_download_size,
+        profile::install::commands::get_install_plan,
+        profile::install::commands::get_foreign_files,
+        profile::install::commands::trash_foreign_files,
+        profile::install::commands::write_lock_file,
+        profile::install::commands::install_locked,
+        profile::update::commands::change_mod_version,
+        profile::update::commands::update_mods,
+        profile::update::commands::update_mods_safely,
+        profile::update::commands::ignore_update,
+        profile::update::commands::get_loader_info,
+        profile::update::commands::update_loader,
+        profile::import::commands::import_profile,
+        profile::import::commands::read_profile_code,
+        profile::import::commands::read_profile_file,
+        profile::import::commands::read_profile_base64,
+        profile::import::commands::preview_local_mod,
+        profile::import::commands::preview_local_mod_base64,
+        profile::import::commands::import_local_mod,
+        profile::import::commands::import_local_mod_base64,
+        profile::import::commands::get_r2modman_info,
+        profile::import::commands::import_r2modman,
+        profile::import::commands::resolve_mod_list,
+        profile::import::commands::install_mod_list,
+        profile::export::commands::export_code,
+        profile::export::commands::preview_client_profile,
+        profile::export::commands::export_client_code,
+        profile::export::commands::export_file,
+        profile::export::commands::export_pack,
+        profile::export::commands::upload_pack,
+        profile::export::commands::get_pack_args,
+        profile::export::commands::set_pack_args,
+        profile::export::commands::generate_changelog,
+        profile::export::commands::get_modpack_updates,
+        profile::export::commands::apply_modpack_updates,
+        profile::export::commands::copy_dependency_strings,
+        profile::export::commands::copy_debug_info,
+        profile::lan::commands::start_lan_share,
+        profile::lan::commands::stop_lan_share,
+        profile::lan::commands::get_lan_share_status,
+        profile::lan::commands::discover_lan_profiles,
+        profile::lan::commands::import_lan_profile,
+        profile::sync::commands::read_sync_profile,
+        profile::sync::commands::create_sync_profile,
+        profile::sync::commands::disconnect_sync_profile,
+        profile::sync::commands::delete_sync_profile,
+        profile::sync::commands::push_sync_profile,
+        profile::sync::commands::get_sync_revisions,
+        profile::sync::commands::get_mod_compatibility,
+        profile::sync::commands::report_mod_compatibility,
+        profile::sync::commands::restore_sync_revision,
+        profile::sync::commands::clone_sync_profile,
+        profile::sync::commands::pull_sync_profile,
+        profile::sync::commands::fetch_sync_profile,
+        profile::sync::commands::get_owned_sync_profiles,
+        profile::sync::commands::set_profile_readme,
+        profile::sync::commands::set_config_enforced,
+        profile::sync::commands::login,
+        profile::sync::commands::logout,
+        profile::sync::commands::logout_everywhere,
+        profile::sync::commands::delete_account,
+        profile::sync::commands::get_user,
+        config::commands::get_config_files,
+        config::commands::set_config_entry,
+        config::commands::reset_config_entry,
+        config::commands::batch_edit_config,
+        config::commands::export_config_preset_file,
+        config::commands::export_config_preset_code,
+        config::commands::read_config_preset_file,
+        config::commands::read_config_preset_code,
+        config::commands::import_config_preset,
+        config::commands::discard_config_preset,
+        config::commands::open_config_file,
+        config::commands::delete_config_file,
+    ];
+
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![
-            logger::open_gale_log,
-            logger::log_err,
-            state::is_first_run,
-            thunderstore::commands::query_thunderstore,
-            thunderstore::commands::stop_querying_thunderstore,
-            thunderstore::commands::get_markdown,
-            thunderstore::commands::set_thunderstore_token,
-            thunderstore::commands::has_thunderstore_token,
-            thunderstore::commands::clear_thunderstore_token,
-            thunderstore::commands::trigger_mod_fetch,
-            prefs::commands::get_prefs,
-            prefs::commands::set_prefs,
-            prefs::commands::zoom_window,
-            prefs::commands::get_system_fonts,
-            profile::commands::get_game_info,
-            profile::commands::favorite_game,
-            profile::commands::set_active_game,
-            profile::commands::get_profile_info,
-            profile::commands::set_active_profile,
-            profile::commands::is_mod_installed,
-            profile::commands::query_profile,
-            profile::commands::get_dependants,
-            profile::commands::create_profile,
-            profile::commands::delete_profile,
-            profile::commands::rename_profile,
-            profile::commands::duplicate_profile,
-            profile::commands::remove_mod,
-            profile::commands::force_remove_mods,
-            profile::commands::toggle_mod,
-            profile::commands::force_toggle_mods,
-            profile::commands::set_all_mods_state,
-            profile::commands::remove_disabled_mods,
-            profile::commands::open_profile_dir,
-            profile::commands::open_mod_dir,
-            profile::commands::open_game_log,
-            profile::commands::create_desktop_shortcut,
-            profile::commands::get_local_markdown,
-            profile::commands::set_custom_args,
-            profile::launch::commands::launch_game,
-            profile::launch::commands::get_launch_args,
-            profile::launch::commands::open_game_dir,
-            profile::install::commands::install_all_mods,
-            profile::install::commands::install_mod,
-            profile::install::commands::cancel_all_installs,
-            profile::install::commands::has_pending_installations,
-            profile::install::commands::clear_download_cache,
-            profile::install::commands::get_download_size,
-            profile::update::commands::change_mod_version,
-            profile::update::commands::update_mods,
-            profile::update::commands::ignore_update,
-            profile::import::commands::import_profile,
-            profile::import::commands::read_profile_code,
-            profile::import::commands::read_profile_file,
-            profile::import::commands::read_profile_base64,
-            profile::import::commands::import_local_mod,
-            profile::import::commands::import_local_mod_base64,
-            profile::import::commands::get_r2modman_info,
-            profile::import::commands::import_r2modman,
-            profile::export::commands::export_code,
-            profile::export::commands::export_file,
-            profile::export::commands::export_pack,
-            profile::export::commands::upload_pack,
-            profile::export::commands::get_pack_args,
-            profile::export::commands::set_pack_args,
-            profile::export::commands::generate_changelog,
-            profile::export::commands::copy_dependency_strings,
-            profile::export::commands::copy_debug_info,
-            profile::sync::commands::read_sync_profile,
-            profile::sync::commands::create_sync_profile,
-            profile::sync::commands::disconnect_sync_profile,
-            profile::sync::commands::delete_sync_profile,
-            profile::sync::commands::push_sync_profile,
-            profile::sync::commands::clone_sync_profile,
-            profile::sync::commands::pull_sync_profile,
-            profile::sync::commands::fetch_sync_profile,
-            profile::sync::commands::get_owned_sync_profiles,
-            profile::sync::commands::login,
-            profile::sync::commands::logout,
-            profile::sync::commands::get_user,
-            config::commands::get_config_files,
-            config::commands::set_config_entry,
-            config::commands::reset_config_entry,
-            config::commands::open_config_file,
-            config::commands::delete_config_file,
-        ])
+        .invoke_handler(move |invoke| {
+            let app = invoke.message.webview_ref().app_handle();
+
+            // checked up front so that new commands are blocked by default
+            if let Err(err) = prefs::kiosk::check(invoke.message.command(), app) {
+                invoke.resolver.reject(format!("{err:#}"));
+                return true;
+            }
+
+            audit::handle(invoke, handler)
+        })
+        .register_asynchronous_uri_scheme_protocol(images::PROTOCOL, images::handle_request)
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_shell::init())
@@ -0,0 +1,334 @@
+//! Logs go to the terminal, to `latest.log` in the app data directory and to an
+//! in-memory buffer of recent events that the app can query.
+//!
+//! The file and the buffer log at [`Level::INFO`] by default, which can be changed per
+//! subsystem with [`Prefs::log_levels`]. Logs from previous runs are kept in
+//! [`ARCHIVE_DIR_NAME`], up to [`MAX_ARCHIVED`] of them. Span timings are recorded
+//! separately by [`trace`].
+//!
+//! [`Prefs::log_levels`]: crate::prefs::Prefs::log_levels
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{self, Write},
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+use chrono::{DateTime, Utc};
+use eyre::{Context, OptionExt, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter};
+use tracing::{
+    field::{Field, Visit},
+    level_filters::LevelFilter,
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{filter::Targets, layer, prelude::*, reload, Layer, Registry};
+
+use crate::{
+    notify::{self, Category},
+    util::{self, error::IoResultExt, fs::PathExt},
+};
+
+pub mod trace;
+
+pub const FILE_NAME: &str = "latest.log";
+pub const ARCHIVE_DIR_NAME: &str = "logs";
+pub const MAX_ARCHIVED: usize = 5;
+
+/// How many events are kept in memory for [`get_logs`].
+const MAX_RECENT: usize = 2000;
+const CRATE_NAME: &str = env!("CARGO_CRATE_NAME");
+
+static RECENT: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+static FILTER: OnceLock<reload::Handle<Targets, Registry>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl From<Level> for LogLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::TRACE => LogLevel::Trace,
+            Level::DEBUG => LogLevel::Debug,
+            Level::INFO => LogLevel::Info,
+            Level::WARN => LogLevel::Warn,
+            Level::ERROR => LogLevel::Error,
+        }
+    }
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Trace => LevelFilter::TRACE,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Error => LevelFilter::ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    timestamp: DateTime<Utc>,
+    level: LogLevel,
+    /// The module that logged the event, like `gale::profile::install`.
+    target: String,
+    /// The message, followed by any other fields as `key=value`.
+    message: String,
+}
+
+impl LogEntry {
+    fn is_in(&self, subsystem: &str) -> bool {
+        let target = self
+            .target
+            .strip_prefix(CRATE_NAME)
+            .and_then(|rest| rest.strip_prefix("::"))
+            .unwrap_or(&self.target);
+
+        target
+            .strip_prefix(subsystem)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    }
+}
+
+/// Keeps the most recent events in [`RECENT`].
+struct RecentLogs;
+
+impl<S: Subscriber> Layer<S> for RecentLogs {
+    fn on_event(&self, event: &Event<'_>, _ctx: layer::Context<'_, S>) {
+        let mut message = String::new();
+        event.record(&mut MessageVisitor(&mut message));
+
+        let metadata = event.metadata();
+        let entry = LogEntry {
+            timestamp: Utc::now(),
+            level: (*metadata.level()).into(),
+            target: metadata.target().to_owned(),
+            message,
+        };
+
+        let mut recent = RECENT.lock().unwrap();
+        if recent.len() >= MAX_RECENT {
+            recent.pop_front();
+        }
+        recent.push_back(entry);
+    }
+}
+
+struct MessageVisitor<'a>(&'a mut String);
+
+impl MessageVisitor<'_> {
+    fn separate(&mut self) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+    }
+}
+
+impl Visit for MessageVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.separate();
+        match field.name() {
+            "message" => self.0.push_str(value),
+            name => write!(self.0, "{name}={value}").unwrap(),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.separate();
+        match field.name() {
+            "message" => write!(self.0, "{value:?}").unwrap(),
+            name => write!(self.0, "{name}={value:?}").unwrap(),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct WebviewError<'a> {
+    name: &'a str,
+    message: String,
+}
+
+/// Emits an error to the webview, causing it to show an error toast and
+/// log the message properly to the log file/terminal.
+pub fn log_webview_err(name: impl AsRef<str>, error: eyre::Error, app: &AppHandle) {
+    if !notify::allows(Category::Errors) {
+        tracing::error!("{}: {:#}", name.as_ref(), error);
+        return;
+    }
+
+    app.emit(
+        "error",
+        WebviewError {
+            name: name.as_ref(),
+            message: format!("{error:#}"),
+        },
+    )
+    .unwrap_or_else(|err| {
+        tracing::warn!("failed to log error to webview:");
+        tracing::error!("{:#}", err)
+    })
+}
+
+fn log_path() -> PathBuf {
+    util::path::default_app_data_dir().join(FILE_NAME)
+}
+
+/// Builds the filter for the log file and buffer.
+///
+/// The keys of `levels` are module paths relative to the crate, like `profile::install`,
+/// or the names of dependencies, like `reqwest`.
+fn targets(levels: &HashMap<String, LogLevel>) -> Targets {
+    let targets = levels.iter().flat_map(|(subsystem, level)| {
+        [
+            (
+                format!("{CRATE_NAME}::{subsystem}"),
+                LevelFilter::from(*level),
+            ),
+            (subsystem.clone(), LevelFilter::from(*level)),
+        ]
+    });
+
+    Targets::new()
+        .with_default(LevelFilter::INFO)
+        .with_targets(targets)
+}
+
+/// Moves the previous log into the archive directory, shifting the older ones
+/// and dropping the oldest one.
+fn rotate(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let dir = path.with_file_name(ARCHIVE_DIR_NAME);
+    fs::create_dir_all(&dir).fs_context("creating log archive directory", &dir)?;
+
+    let archived = |index: usize| dir.join(format!("gale.{index}.log"));
+
+    for index in (1..MAX_ARCHIVED).rev() {
+        let from = archived(index);
+        if from.exists() {
+            fs::rename(&from, archived(index + 1)).fs_context("archiving log", &from)?;
+        }
+    }
+
+    fs::rename(path, archived(1)).fs_context("archiving log", path)?;
+
+    Ok(())
+}
+
+pub fn setup() -> Result<()> {
+    let path = log_path();
+    fs::create_dir_all(path.parent().unwrap()).context("failed to create log directory")?;
+
+    rotate(&path).unwrap_or_else(|err| eprintln!("failed to archive previous log: {err:#}"));
+
+    let log_file = File::create(path).context("failed to create log file")?;
+
+    let (filter, handle) = reload::Layer::new(targets(&HashMap::new()));
+    FILTER.set(handle).ok();
+
+    let subscriber = Registry::default()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .compact()
+                .with_ansi(false)
+                .with_writer(log_file)
+                .and_then(RecentLogs)
+                .with_filter(filter),
+        )
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(true)
+                .with_filter(LevelFilter::from_level(Level::DEBUG)),
+        )
+        .with(trace::SpanTimings.with_filter(LevelFilter::from_level(Level::INFO)));
+
+    tracing::subscriber::set_global_default(subscriber).context("failed to register subscriber")?;
+
+    Ok(())
+}
+
+/// Applies new per-subsystem levels to the log file and buffer.
+pub fn set_levels(levels: &HashMap<String, LogLevel>) -> Result<()> {
+    FILTER
+        .get()
+        .ok_or_eyre("logger is not set up")?
+        .reload(targets(levels))
+        .context("failed to update log levels")
+}
+
+#[command]
+pub fn open_gale_log() -> util::cmd::Result<()> {
+    let path = log_path()
+        .exists_or_none()
+        .ok_or_eyre("no log file found")?;
+
+    open::that_detached(&path).context("failed to open log file")?;
+
+    Ok(())
+}
+
+#[command]
+pub fn export_trace(dir: PathBuf, minutes: u64) -> util::cmd::Result<()> {
+    let path = dir.join(format!(
+        "gale-trace-{}.json",
+        chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+    ));
+
+    let count = trace::export(minutes, &path)?;
+    tracing::info!("exported {} spans to {}", count, path.display());
+
+    open::that_detached(&dir).ok();
+
+    Ok(())
+}
+
+#[command]
+pub fn log_err(msg: String) {
+    tracing::error!("{}", msg);
+}
+
+/// Returns the most recent events, newest last.
+///
+/// `subsystem` works like the keys of [`Prefs::log_levels`] and includes nested modules.
+///
+/// [`Prefs::log_levels`]: crate::prefs::Prefs::log_levels
+#[command]
+pub fn get_logs(
+    subsystem: Option<String>,
+    min_level: Option<LogLevel>,
+    limit: Option<usize>,
+) -> Vec<LogEntry> {
+    let recent = RECENT.lock().unwrap();
+
+    let mut entries = recent
+        .iter()
+        .rev()
+        .filter(|entry| min_level.map_or(true, |level| entry.level >= level))
+        .filter(|entry| {
+            subsystem
+                .as_ref()
+                .map_or(true, |subsystem| entry.is_in(subsystem))
+        })
+        .take(limit.unwrap_or(MAX_RECENT))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    entries.reverse();
+    entries
+}
@@ -0,0 +1,208 @@
+//! Records how long spans take, so that slow operations can be exported as a
+//! [Chrome trace] and opened in `chrome://tracing` or [Perfetto].
+//!
+//! Spans are kept for [`RETENTION`] after they close. Each root span gets its own
+//! track in the trace, which its children share.
+//!
+//! [Chrome trace]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+//! [Perfetto]: https://ui.perfetto.dev
+
+use std::{
+    collections::VecDeque,
+    path::Path,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use eyre::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+use crate::util::{self, fs::JsonStyle};
+
+const RETENTION: Duration = Duration::from_secs(30 * 60);
+const MAX_SPANS: usize = 50_000;
+
+static START: OnceLock<Instant> = OnceLock::new();
+static SPANS: Mutex<VecDeque<SpanRecord>> = Mutex::new(VecDeque::new());
+
+struct SpanRecord {
+    name: &'static str,
+    target: &'static str,
+    track: u64,
+    fields: Map<String, Value>,
+    start: Instant,
+    end: Instant,
+}
+
+/// Stored in the extensions of open spans.
+struct Timing {
+    track: u64,
+    fields: Map<String, Value>,
+    start: Instant,
+}
+
+fn start_time() -> Instant {
+    *START.get_or_init(Instant::now)
+}
+
+pub struct SpanTimings;
+
+impl<S> Layer<S> for SpanTimings
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let track = span
+            .parent()
+            .and_then(|parent| {
+                parent
+                    .extensions()
+                    .get::<Timing>()
+                    .map(|timing| timing.track)
+            })
+            .unwrap_or_else(|| id.into_u64());
+
+        let mut fields = Map::new();
+        attrs.record(&mut JsonVisitor(&mut fields));
+
+        span.extensions_mut().insert(Timing {
+            track,
+            fields,
+            start: Instant::now(),
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        if let Some(timing) = span.extensions_mut().get_mut::<Timing>() {
+            values.record(&mut JsonVisitor(&mut timing.fields));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+
+        let Some(timing) = span.extensions_mut().remove::<Timing>() else {
+            return;
+        };
+
+        let metadata = span.metadata();
+        let record = SpanRecord {
+            name: metadata.name(),
+            target: metadata.target(),
+            track: timing.track,
+            fields: timing.fields,
+            start: timing.start,
+            end: Instant::now(),
+        };
+
+        let mut spans = SPANS.lock().unwrap();
+        spans.push_back(record);
+
+        while spans
+            .front()
+            .is_some_and(|span| spans.len() > MAX_SPANS || span.end.elapsed() > RETENTION)
+        {
+            spans.pop_front();
+        }
+    }
+}
+
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_owned(), format!("{value:?}").into());
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Trace<'a> {
+    trace_events: Vec<TraceEvent<'a>>,
+    display_time_unit: &'static str,
+}
+
+/// A complete event in the trace event format, with times in microseconds.
+#[derive(Serialize)]
+struct TraceEvent<'a> {
+    name: &'static str,
+    cat: &'static str,
+    ph: &'static str,
+    ts: u128,
+    dur: u128,
+    pid: u32,
+    tid: u64,
+    args: &'a Map<String, Value>,
+}
+
+/// Writes the spans that ended in the last `minutes` to `path`.
+///
+/// Returns the number of spans in the trace.
+pub fn export(minutes: u64, path: &Path) -> Result<usize> {
+    let start = start_time();
+    let cutoff = Instant::now()
+        .checked_sub(Duration::from_secs(minutes * 60))
+        .unwrap_or(start);
+
+    let spans = SPANS.lock().unwrap();
+
+    let trace_events = spans
+        .iter()
+        .filter(|span| span.end >= cutoff)
+        .map(|span| TraceEvent {
+            name: span.name,
+            cat: span.target,
+            ph: "X",
+            ts: span.start.saturating_duration_since(start).as_micros(),
+            dur: (span.end - span.start).as_micros(),
+            pid: 1,
+            tid: span.track,
+            args: &span.fields,
+        })
+        .collect::<Vec<_>>();
+
+    let count = trace_events.len();
+
+    let trace = Trace {
+        trace_events,
+        display_time_unit: "ms",
+    };
+
+    util::fs::write_json(path, &trace, JsonStyle::Compact)?;
+
+    Ok(count)
+}
@@ -0,0 +1,158 @@
+//! Rough estimates of how much memory the app's state takes up, to help track down
+//! high memory usage with large communities.
+//!
+//! The numbers only cover heap allocations owned by the state itself and are meant
+//! for comparing the parts against each other, not for exact accounting.
+
+use std::mem;
+
+use serde::Serialize;
+use tauri::{command, AppHandle};
+
+use crate::{
+    profile::{ManagedGame, Profile, ProfileMod},
+    state::ManagerExt,
+    thunderstore::{PackageListing, PackageVersion, VersionIdent},
+};
+
+/// Estimates the number of bytes a value owns on the heap.
+pub trait HeapSize {
+    fn heap_size(&self) -> usize;
+}
+
+impl HeapSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, HeapSize::heap_size)
+    }
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * mem::size_of::<T>() + self.iter().map(HeapSize::heap_size).sum::<usize>()
+    }
+}
+
+impl HeapSize for VersionIdent {
+    fn heap_size(&self) -> usize {
+        // interned, but version identifiers are practically never shared
+        self.as_str().len()
+    }
+}
+
+impl HeapSize for PackageVersion {
+    fn heap_size(&self) -> usize {
+        self.ident.heap_size() + self.dependencies.heap_size() + self.description.len()
+    }
+}
+
+impl HeapSize for PackageListing {
+    fn heap_size(&self) -> usize {
+        self.ident.as_str().len()
+            + self.categories.capacity() * mem::size_of::<usize>()
+            + self.donation_link.heap_size()
+            + self.package_url.heap_size()
+            + self.versions.heap_size()
+    }
+}
+
+impl HeapSize for Profile {
+    fn heap_size(&self) -> usize {
+        // mods are counted shallowly, their identifiers point into the package index
+        self.name.heap_size()
+            + self.mods.capacity() * mem::size_of::<ProfileMod>()
+            + self.custom_args.heap_size()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryReport {
+    /// Resident memory of the whole process, where the platform reports it.
+    process: Option<u64>,
+    thunderstore: IndexMemory,
+    games: Vec<GameMemory>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexMemory {
+    packages: usize,
+    versions: usize,
+    bytes: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GameMemory {
+    slug: String,
+    profiles: usize,
+    mods: usize,
+    config_files: usize,
+    bytes: usize,
+}
+
+impl GameMemory {
+    fn new(game: &ManagedGame) -> Self {
+        Self {
+            slug: game.game.slug.to_string(),
+            profiles: game.profiles.len(),
+            mods: game.profiles.iter().map(|profile| profile.mods.len()).sum(),
+            config_files: game
+                .profiles
+                .iter()
+                .map(|profile| profile.config_cache.file_count())
+                .sum(),
+            bytes: game.profiles.iter().map(HeapSize::heap_size).sum(),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn process_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+
+    Some(kilobytes * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_memory() -> Option<u64> {
+    None
+}
+
+#[command]
+pub fn get_memory_report(app: AppHandle) -> MemoryReport {
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    let thunderstore = IndexMemory {
+        packages: thunderstore.latest().count(),
+        versions: thunderstore
+            .latest()
+            .map(|borrowed| borrowed.package.versions.len())
+            .sum(),
+        bytes: thunderstore.heap_size(),
+    };
+
+    let games = manager.games.values().map(GameMemory::new).collect();
+
+    MemoryReport {
+        process: process_memory(),
+        thunderstore,
+        games,
+    }
+}
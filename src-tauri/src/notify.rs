@@ -0,0 +1,145 @@
+//! Decides which notifications reach the user.
+//!
+//! Each [`Category`] can be turned off in [`Prefs::notifications`], and with
+//! `quiet_while_playing` set, all of them are held back while a game launched by
+//! Gale is running. Notifications from the backend go through [`send`], while the
+//! frontend asks [`should_notify`] before showing its own.
+//!
+//! [`Prefs::notifications`]: crate::prefs::Prefs::notifications
+
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter};
+use tracing::debug;
+
+use crate::profile::launch::hooks;
+
+/// How many launched games are still running.
+static PLAYING: AtomicUsize = AtomicUsize::new(0);
+
+/// A copy of the prefs, so that checking doesn't need to lock them.
+static PREFS: RwLock<NotificationPrefs> = RwLock::new(NotificationPrefs::new());
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Category {
+    /// New versions of Gale and mod updates.
+    Updates,
+    /// Changes to synced profiles made elsewhere.
+    Sync,
+    /// Errors and problems found in the background, like failed fetches.
+    Errors,
+    /// Dedicated server crashes and restarts.
+    Server,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NotificationPrefs {
+    pub updates: bool,
+    pub sync: bool,
+    pub errors: bool,
+    pub server: bool,
+    /// Whether notifications are held back while a game is running.
+    pub quiet_while_playing: bool,
+}
+
+impl NotificationPrefs {
+    const fn new() -> Self {
+        Self {
+            updates: true,
+            sync: true,
+            errors: true,
+            server: true,
+            quiet_while_playing: true,
+        }
+    }
+
+    fn is_enabled(&self, category: Category) -> bool {
+        match category {
+            Category::Updates => self.updates,
+            Category::Sync => self.sync,
+            Category::Errors => self.errors,
+            Category::Server => self.server,
+        }
+    }
+}
+
+impl Default for NotificationPrefs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Called whenever the prefs are loaded or changed.
+pub fn configure(prefs: &NotificationPrefs) {
+    *PREFS.write().unwrap() = prefs.clone();
+}
+
+pub fn allows(category: Category) -> bool {
+    let prefs = PREFS.read().unwrap();
+
+    prefs.is_enabled(category)
+        && !(prefs.quiet_while_playing && PLAYING.load(Ordering::Acquire) > 0)
+}
+
+#[derive(Serialize, Clone)]
+struct Notification<'a> {
+    category: Category,
+    message: &'a str,
+}
+
+/// Shows `message` as a toast, unless its category is turned off or a game is running.
+pub fn send(category: Category, message: &str, app: &AppHandle) {
+    if !allows(category) {
+        debug!("holding back notification: {}", message);
+        return;
+    }
+
+    app.emit("notification", Notification { category, message })
+        .ok();
+}
+
+/// Counts the game as running until its process exits.
+pub fn watch_game(exe_path: &Path, app: &AppHandle) {
+    let Some(exe_name) = exe_path.file_name() else {
+        return;
+    };
+
+    let exe_name = exe_name.to_string_lossy().into_owned();
+    let app = app.clone();
+
+    set_playing(true, &app);
+
+    thread::spawn(move || {
+        hooks::wait_for_exit(&exe_name);
+        set_playing(false, &app);
+    });
+}
+
+fn set_playing(playing: bool, app: &AppHandle) {
+    let previous = if playing {
+        PLAYING.fetch_add(1, Ordering::AcqRel)
+    } else {
+        PLAYING.fetch_sub(1, Ordering::AcqRel)
+    };
+
+    // only the first game starting and the last one closing change anything
+    if (playing && previous == 0) || (!playing && previous == 1) {
+        debug!("game running: {}", playing);
+        app.emit("game_running", playing).ok();
+    }
+}
+
+#[command]
+pub fn should_notify(category: Category) -> bool {
+    allows(category)
+}
@@ -0,0 +1,137 @@
+//! User approval for writing outside of the directories Gale manages.
+//!
+//! Gale writes freely to its data directory and the game directory. Any other location
+//! has to be approved once, after which it's remembered in [`Prefs::allowed_paths`]
+//! and can be revoked from the settings.
+//!
+//! Paths are normalized before they're compared, so a `..` component or a symlink
+//! can't make a location look like it's inside an allowed directory.
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use eyre::{ensure, Result};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+use tokio::sync::oneshot;
+use tracing::info;
+
+use super::Prefs;
+use crate::{game::Game, i18n, profile::launch, state::ManagerExt};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowedPath {
+    pub path: PathBuf,
+    /// What the location was approved for.
+    pub reason: String,
+    pub approved_at: DateTime<Utc>,
+}
+
+impl Prefs {
+    /// Whether Gale may write to `path` without asking the user first.
+    pub fn is_write_allowed(&self, path: &Path, game: Game) -> bool {
+        let path = normalize(path);
+        let contains = |dir: &Path| path.starts_with(normalize(dir));
+
+        contains(&self.data_dir)
+            || self
+                .allowed_paths
+                .iter()
+                .any(|allowed| contains(&allowed.path))
+            || launch::locate_game_dir(game, self).is_ok_and(|dir| contains(&dir))
+    }
+
+    pub fn revoke_access(&mut self, path: &Path) {
+        self.allowed_paths.retain(|allowed| allowed.path != path);
+    }
+}
+
+/// Resolves `.`, `..` and symlinks in `path`. Symlinks are resolved one component at
+/// a time, so that `..` goes to the parent of where a link actually points. The parts
+/// that don't exist yet are normalized lexically.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => continue,
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+
+        if let Ok(canonical) = fs::canonicalize(&normalized) {
+            normalized = canonical;
+        }
+    }
+
+    normalized
+}
+
+/// Whether `path` is inside `dir` once both are normalized.
+pub fn is_within(path: &Path, dir: &Path) -> bool {
+    normalize(path).starts_with(normalize(dir))
+}
+
+/// Fails unless writing to `path` is already allowed. This is for places that can't
+/// wait for the user to answer, like while the prefs are locked.
+pub fn check(path: &Path, game: Game, prefs: &Prefs) -> Result<()> {
+    ensure!(
+        prefs.is_write_allowed(path, game),
+        i18n::t("access.not_allowed", &[("path", &path.display())])
+    );
+
+    Ok(())
+}
+
+/// Asks the user for permission to write to `path`, unless it's already allowed.
+///
+/// `reason` is shown to the user and stored alongside the approval.
+pub async fn request(path: &Path, reason: &str, app: &AppHandle) -> Result<()> {
+    {
+        let prefs = app.lock_prefs();
+        let game = app.lock_manager().active_game;
+
+        if prefs.is_write_allowed(path, game) {
+            return Ok(());
+        }
+    }
+
+    let (tx, rx) = oneshot::channel();
+
+    app.dialog()
+        .message(i18n::t(
+            "access.message",
+            &[("path", &path.display()), ("reason", &reason)],
+        ))
+        .title(i18n::t("access.title", &[]))
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            i18n::t("access.allow", &[]),
+            i18n::t("access.deny", &[]),
+        ))
+        .show(move |result| {
+            tx.send(result).ok();
+        });
+
+    let approved = rx.await.unwrap_or(false);
+    ensure!(
+        approved,
+        i18n::t("access.denied", &[("path", &path.display())])
+    );
+
+    info!("allowed writing to {} ({})", path.display(), reason);
+
+    let mut prefs = app.lock_prefs();
+    prefs.allowed_paths.push(AllowedPath {
+        path: normalize(path),
+        reason: reason.to_owned(),
+        approved_at: Utc::now(),
+    });
+    prefs.save(app.db())
+}
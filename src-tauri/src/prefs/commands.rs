@@ -1,26 +1,129 @@
+use std::path::PathBuf;
+
 use eyre::anyhow;
 use font_kit::source::SystemSource;
 use serde::Deserialize;
+use serde_json::{Map, Value};
 use tauri::{command, AppHandle, Manager, Window};
 
-use super::Prefs;
+use super::{
+    access,
+    transfer::{self, ImportReport},
+    Prefs,
+};
 use crate::{
+    i18n,
     state::ManagerExt,
-    util::{cmd::Result, window::WindowExt},
+    util::{self, cmd::Result, path::DirOverrides, window::WindowExt},
 };
 
 #[command]
 pub fn get_prefs(app: AppHandle) -> Prefs {
-    app.lock_prefs().clone()
+    let mut prefs = app.lock_prefs().clone();
+
+    if prefs.kiosk_mode {
+        super::kiosk::redact(&mut prefs);
+    }
+
+    prefs
 }
 
 #[command]
-pub fn set_prefs(value: Prefs, app: AppHandle) -> Result<()> {
+pub async fn set_prefs(value: Prefs, app: AppHandle) -> Result<()> {
+    let (data_dir_changed, archive_dir_changed, cache_dir_changed, temp_dir_changed) = {
+        let prefs = app.lock_prefs();
+        (
+            prefs.data_dir != value.data_dir,
+            prefs.archive_dir != value.archive_dir,
+            prefs.cache_dir != value.cache_dir,
+            prefs.temp_dir != value.temp_dir,
+        )
+    };
+
+    if data_dir_changed {
+        access::request(&value.data_dir, "data folder", &app).await?;
+    }
+
+    if let (true, Some(dir)) = (archive_dir_changed, &value.archive_dir) {
+        access::request(dir, "mod archive", &app).await?;
+    }
+
+    if let (true, Some(dir)) = (cache_dir_changed, &value.cache_dir) {
+        access::request(dir, "cache folder", &app).await?;
+    }
+
+    if let (true, Some(dir)) = (temp_dir_changed, &value.temp_dir) {
+        access::request(dir, "temp folder", &app).await?;
+    }
+
     let mut prefs = app.lock_prefs();
     prefs.set(value, &app)?;
     Ok(())
 }
 
+#[command]
+pub fn export_settings(
+    dir: PathBuf,
+    include_paths: bool,
+    frontend: Map<String, Value>,
+    app: AppHandle,
+) -> Result<PathBuf> {
+    let prefs = app.lock_prefs();
+    let path = transfer::export(&prefs, include_paths, frontend, &dir)?;
+    Ok(path)
+}
+
+#[command]
+pub async fn import_settings(path: PathBuf, app: AppHandle) -> Result<ImportReport> {
+    let (prefs, report) = transfer::import(&app.lock_prefs(), &path)?;
+    set_prefs(prefs, app).await?;
+    Ok(report)
+}
+
+#[command]
+pub fn get_dir_overrides() -> DirOverrides {
+    util::path::dir_overrides().clone()
+}
+
+#[command]
+pub fn acknowledge_anti_cheat(app: AppHandle) -> Result<()> {
+    let game = app.lock_manager().active_game;
+
+    let mut prefs = app.lock_prefs();
+    prefs
+        .game_prefs
+        .entry(game.slug.to_string())
+        .or_default()
+        .anti_cheat_acknowledged = true;
+    prefs.save(app.db())?;
+
+    Ok(())
+}
+
+#[command]
+pub fn revoke_path_access(path: PathBuf, app: AppHandle) -> Result<()> {
+    let mut prefs = app.lock_prefs();
+    prefs.revoke_access(&path);
+    prefs.save(app.db())?;
+    Ok(())
+}
+
+#[command]
+pub fn enable_kiosk_mode(pin: Option<String>, app: AppHandle) -> Result<()> {
+    let mut prefs = app.lock_prefs();
+    prefs.enable_kiosk_mode(pin.as_deref())?;
+    prefs.save(app.db())?;
+    Ok(())
+}
+
+#[command]
+pub fn disable_kiosk_mode(pin: Option<String>, app: AppHandle) -> Result<()> {
+    let mut prefs = app.lock_prefs();
+    prefs.disable_kiosk_mode(pin.as_deref())?;
+    prefs.save(app.db())?;
+    Ok(())
+}
+
 #[derive(Deserialize)]
 #[serde(untagged)]
 pub enum Zoom {
@@ -48,6 +151,11 @@ pub fn zoom_window(value: Zoom, window: Window, app: AppHandle) -> Result<()> {
     Ok(())
 }
 
+#[command]
+pub fn get_languages() -> Vec<&'static str> {
+    i18n::locales()
+}
+
 #[command]
 pub fn get_system_fonts() -> Result<Vec<String>> {
     let fonts = SystemSource::new().all_families().unwrap();
@@ -0,0 +1,150 @@
+//! A locked down mode for shared machines, where one person maintains the profiles
+//! and everyone else should only be able to launch them.
+//!
+//! While kiosk mode is on, only the commands in [`ALLOWED_COMMANDS`] can be invoked.
+//! Anything not listed is rejected, so new commands are locked by default.
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use eyre::{bail, ensure, eyre, Result};
+use tauri::AppHandle;
+
+use super::Prefs;
+use crate::{i18n, state::ManagerExt};
+
+/// Commands that don't modify profiles, configs or prefs.
+const ALLOWED_COMMANDS: &[&str] = &[
+    "open_gale_log",
+    "log_err",
+    "get_logs",
+    "get_memory_report",
+    "get_audit_log",
+    "cancel_requests",
+    "should_notify",
+    "is_first_run",
+    "is_portable",
+    "check_schema_version",
+    "scan_installed_games",
+    "query_thunderstore",
+    "stop_querying_thunderstore",
+    "get_index_status",
+    "get_markdown",
+    "render_markdown",
+    "get_package_links",
+    "search_all_games",
+    "get_package_contents",
+    "get_recently_updated",
+    "has_thunderstore_token",
+    "trigger_mod_fetch",
+    "refresh_index",
+    "get_prefs",
+    "get_dir_overrides",
+    "zoom_window",
+    "get_system_fonts",
+    "get_languages",
+    "get_themes",
+    "get_theme",
+    "disable_kiosk_mode",
+    "get_game_info",
+    "set_active_game",
+    "get_custom_games",
+    "get_profile_info",
+    "set_active_profile",
+    "is_mod_installed",
+    "query_profile",
+    "get_dependants",
+    "get_required_by",
+    "open_profile_dir",
+    "open_game_log",
+    "open_game_log_dir",
+    "get_local_markdown",
+    "launch_game",
+    "get_launch_args",
+    "open_game_dir",
+    "get_missing_loader_files",
+    "get_loader_info",
+    "open_workshop_item",
+    "get_server_incidents",
+    "has_pending_installations",
+    "get_install_queue",
+    "preview_dependencies",
+    "get_download_size",
+    "preview_author_bundle",
+    "get_install_plan",
+    "get_foreign_files",
+    "preview_local_mod",
+    "preview_local_mod_base64",
+    "get_lan_share_status",
+    "get_user",
+    "get_sync_revisions",
+    "get_mod_compatibility",
+    "get_health_report",
+    "get_prune_suggestions",
+    "get_config_files",
+];
+
+/// Whether `command` is left alone by kiosk mode, i.e. can't change anything.
+pub fn is_read_only(command: &str) -> bool {
+    ALLOWED_COMMANDS.contains(&command)
+}
+
+/// Rejects `command` if kiosk mode is on and the command isn't allowed.
+pub fn check(command: &str, app: &AppHandle) -> Result<()> {
+    if app.lock_prefs().kiosk_mode && !is_read_only(command) {
+        bail!(i18n::t("kiosk.blocked", &[]));
+    }
+
+    Ok(())
+}
+
+impl Prefs {
+    pub fn enable_kiosk_mode(&mut self, pin: Option<&str>) -> Result<()> {
+        self.kiosk_pin = pin
+            .filter(|pin| !pin.is_empty())
+            .map(hash_pin)
+            .transpose()?;
+        self.kiosk_mode = true;
+
+        Ok(())
+    }
+
+    pub fn disable_kiosk_mode(&mut self, pin: Option<&str>) -> Result<()> {
+        if let Some(expected) = &self.kiosk_pin {
+            ensure!(
+                pin.is_some_and(|pin| verify_pin(pin, expected)),
+                i18n::t("kiosk.incorrect_pin", &[])
+            );
+        }
+
+        self.kiosk_mode = false;
+        self.kiosk_pin = None;
+
+        Ok(())
+    }
+}
+
+/// PINs are short, so they're hashed with a salt and a deliberately slow function.
+fn hash_pin(pin: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map_err(|err| eyre!("failed to hash PIN: {}", err))?;
+
+    Ok(hash.to_string())
+}
+
+fn verify_pin(pin: &str, expected: &str) -> bool {
+    PasswordHash::new(expected).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(pin.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// Strips secrets from prefs that are sent to the frontend while kiosk mode is on.
+pub fn redact(prefs: &mut Prefs) {
+    prefs.kiosk_pin = None;
+    prefs.webhooks.clear();
+}
@@ -7,14 +7,24 @@ use std::{
 
 use eyre::{bail, ensure, Context, Result};
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, Url};
 use tracing::{debug, info, warn};
 
 use crate::{
     db::{self, Db},
     game::{self, platform::Platform},
-    logger,
-    profile::launch::LaunchMode,
+    i18n,
+    logger::{self, LogLevel},
+    notify::{self, NotificationPrefs},
+    profile::{
+        install::{
+            cache,
+            deploy::{self, DeployMode},
+            load_scripts, tuning,
+        },
+        launch::{hooks::LaunchHooks, process::ProcessOptions, LaunchMode},
+        webhook::Webhook,
+    },
     state::ManagerExt,
     util::{
         self,
@@ -24,7 +34,10 @@ use crate::{
     },
 };
 
+pub mod access;
 pub mod commands;
+pub mod kiosk;
+pub mod transfer;
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq)]
 #[serde(transparent)]
@@ -187,6 +200,89 @@ pub struct Prefs {
     pub zoom_factor: f32,
     pub pull_before_launch: bool,
 
+    /// Locale of the messages generated by the backend.
+    pub language: String,
+    /// File name of the selected theme in the themes directory.
+    pub theme: Option<String>,
+
+    /// If set, downloaded mod zips are kept in this directory and
+    /// reused instead of downloading them again.
+    pub archive_dir: Option<PathBuf>,
+
+    /// Where extracted mods are cached, instead of the `cache` folder in the data directory.
+    pub cache_dir: Option<PathBuf>,
+    /// Whether downloads are shared with the other users of the machine, see [`cache`].
+    pub shared_cache: bool,
+    /// Whether newly cached mods are compressed, which saves disk space at the cost of
+    /// copying instead of linking files into profiles.
+    pub compress_cache: bool,
+    /// Whether files that are identical between cached versions of a package
+    /// are only stored once.
+    pub deduplicate_cache: bool,
+    /// Where temporary files are written, instead of the system's temp folder.
+    /// Only takes effect after a restart.
+    pub temp_dir: Option<PathBuf>,
+
+    /// Program used to open config files, instead of the system default.
+    pub config_editor: Option<PathBuf>,
+
+    /// Threads used to write extracted files, or one per CPU core if unset.
+    pub extract_threads: Option<usize>,
+    /// How many mods are downloaded at once while installing.
+    pub simultaneous_downloads: usize,
+    /// Size of the buffer used when writing extracted files, in KiB.
+    pub write_buffer_size: usize,
+    /// A caching proxy for Thunderstore's CDN, like an Nginx cache on a LAN, that mods are
+    /// downloaded from first. Downloads that fail there, or that are corrupted or don't
+    /// match a recorded hash, are retried from Thunderstore.
+    pub download_mirror: Option<String>,
+
+    pub webhooks: Vec<Webhook>,
+
+    /// Thunderstore namespaces the user publishes packages under.
+    pub owned_namespaces: Vec<String>,
+
+    /// Whether newly installed packages with suspicious files need to be approved first.
+    pub quarantine_new_mods: bool,
+
+    /// Whether notes are included when exporting or syncing profiles.
+    pub share_notes: bool,
+
+    /// Whether community reports of which mods work on the installed game version are shown.
+    pub compatibility_notes: bool,
+
+    /// Whether profile health checks run automatically in the background, see [`crate::profile::health`].
+    pub scheduled_health_checks: bool,
+
+    /// Whether files that mods create while the game runs are recorded,
+    /// see [`crate::profile::launch::runtime_files`].
+    pub track_runtime_files: bool,
+    /// Whether uninstalling a mod also removes the files it was recorded creating.
+    pub remove_runtime_files: bool,
+
+    /// Whether other programs can control Gale over a local socket, see [`crate::ipc`].
+    pub ipc_server: bool,
+
+    /// Log levels by subsystem, see [`logger::set_levels`].
+    pub log_levels: HashMap<String, LogLevel>,
+
+    /// Which notifications are shown, see [`notify`].
+    pub notifications: NotificationPrefs,
+
+    /// Blocks anything other than launching and browsing, see [`kiosk`].
+    pub kiosk_mode: bool,
+    /// Hash of the PIN required to leave kiosk mode.
+    pub kiosk_pin: Option<String>,
+
+    /// Locations outside of the managed directories that the user has approved.
+    pub allowed_paths: Vec<access::AllowedPath>,
+
+    /// Game executables Gale has set a Windows GPU preference for, so that turning the
+    /// option off only ever clears those, see [`ProcessOptions::apply_gpu_preference`].
+    ///
+    /// [`ProcessOptions::apply_gpu_preference`]: crate::profile::launch::process::ProcessOptions::apply_gpu_preference
+    pub gpu_preference_exes: Vec<PathBuf>,
+
     pub game_prefs: HashMap<String, GamePrefs>,
 }
 
@@ -198,6 +294,22 @@ pub struct GamePrefs {
     pub custom_args_enabled: bool,
     pub launch_mode: LaunchMode,
     pub platform: Option<Platform>,
+    /// How mod files are placed into profiles, see [`deploy`].
+    pub deploy_mode: DeployMode,
+    /// Whether to stage files next to the game directory and swap them in at once,
+    /// for games that lock or scan their files at awkward times.
+    pub stage_game_files: bool,
+    /// Whether the user has seen and accepted the game's anti-cheat warning.
+    pub anti_cheat_acknowledged: bool,
+    /// Whether to write the launch options into Steam's config when launching,
+    /// so that starting the game from Steam also loads the profile.
+    pub inject_steam_launch_options: bool,
+    /// Priority, affinity and GPU settings, by profile id.
+    pub process_options: HashMap<i64, ProcessOptions>,
+    /// Commands to run around launching the game, by profile id.
+    pub launch_hooks: HashMap<i64, LaunchHooks>,
+    /// Minutes between automatic fetches of the package index, 15 if unset.
+    pub fetch_interval: Option<u32>,
 }
 
 impl Default for Prefs {
@@ -205,15 +317,52 @@ impl Default for Prefs {
         Self {
             data_dir: DirPref::new(util::path::default_app_data_dir())
                 .keep(logger::FILE_NAME)
+                .keep(logger::ARCHIVE_DIR_NAME)
                 .keep(db::FILE_NAME)
                 .keep(db::SHM_FILE_NAME)
                 .keep(db::WAL_FILE_NAME)
-                .keep(game::CACHE_FILE_NAME),
+                .keep(game::CACHE_FILE_NAME)
+                .keep(game::CACHE_SIGNATURE_FILE_NAME)
+                .keep(game::custom::FILE_NAME),
 
             fetch_mods_automatically: true,
             pull_before_launch: true,
 
             zoom_factor: 1.0,
+            language: i18n::DEFAULT_LOCALE.to_owned(),
+            theme: None,
+
+            archive_dir: None,
+            cache_dir: None,
+            shared_cache: false,
+            compress_cache: false,
+            deduplicate_cache: true,
+            temp_dir: None,
+            config_editor: None,
+
+            extract_threads: None,
+            simultaneous_downloads: tuning::DEFAULT_SIMULTANEOUS_DOWNLOADS,
+            write_buffer_size: tuning::DEFAULT_WRITE_BUFFER_SIZE,
+            download_mirror: None,
+
+            webhooks: Vec::new(),
+
+            owned_namespaces: Vec::new(),
+            quarantine_new_mods: false,
+            share_notes: false,
+            compatibility_notes: true,
+            scheduled_health_checks: false,
+            track_runtime_files: false,
+            remove_runtime_files: false,
+            ipc_server: false,
+            log_levels: HashMap::new(),
+            notifications: NotificationPrefs::default(),
+
+            kiosk_mode: false,
+            kiosk_pin: None,
+
+            allowed_paths: Vec::new(),
+            gpu_preference_exes: Vec::new(),
 
             game_prefs: HashMap::new(),
         }
@@ -224,29 +373,66 @@ impl Prefs {
     pub fn init(&mut self, db: &Db, app: &AppHandle) -> Result<()> {
         self.data_dir.keep_files.extend(&[
             logger::FILE_NAME,
+            logger::ARCHIVE_DIR_NAME,
             db::FILE_NAME,
             db::SHM_FILE_NAME,
             db::WAL_FILE_NAME,
             game::CACHE_FILE_NAME,
+            game::CACHE_SIGNATURE_FILE_NAME,
+            game::custom::FILE_NAME,
         ]);
 
         let window = app.get_webview_window("main").unwrap();
         window.zoom(self.zoom_factor as f64).ok();
 
+        i18n::set_locale(&self.language);
+
+        if let Some(dir) = self.temp_dir() {
+            fs::create_dir_all(&dir).fs_context("creating temp directory", &dir)?;
+            // this can only be set once per process
+            tempfile::env::override_temp_dir(&dir).ok();
+        }
+
+        logger::set_levels(&self.log_levels)
+            .unwrap_or_else(|err| warn!("failed to apply log levels: {:#}", err));
+
+        notify::configure(&self.notifications);
+
+        tuning::apply(self)
+            .unwrap_or_else(|err| warn!("failed to apply extraction settings: {:#}", err));
+
+        load_scripts(&self.data_dir);
+
         self.save(db)?;
 
         Ok(())
     }
 
-    fn save(&self, db: &Db) -> Result<()> {
+    pub fn save(&self, db: &Db) -> Result<()> {
         db.save_prefs(self)
     }
 
     fn set(&mut self, value: Self, app: &AppHandle) -> Result<()> {
+        let redeploy = value
+            .game_prefs
+            .iter()
+            .filter(|(slug, prefs)| {
+                let old = self.game_prefs.get(*slug).map(|prefs| prefs.deploy_mode);
+                old.unwrap_or_default() != prefs.deploy_mode
+            })
+            .filter_map(|(slug, prefs)| Some((game::from_slug(slug)?, prefs.deploy_mode)))
+            .collect::<Vec<_>>();
+
         self.game_prefs = value.game_prefs;
         self.validate_game_prefs()?;
 
-        if self.data_dir != value.data_dir {
+        for (game, mode) in redeploy {
+            deploy::migrate(game, mode, self, app)
+                .with_context(|| format!("failed to switch {} to {:?}", game.slug, mode))?;
+        }
+
+        let data_dir_changed = self.data_dir != value.data_dir;
+        if data_dir_changed {
             // move profile paths
             let mut manager = app.lock_manager();
 
@@ -254,12 +440,16 @@ impl Prefs {
             for (key, game) in &mut manager.games {
                 path.push(&*key.slug);
 
+                let old_profiles_dir = game.path.join("profiles");
                 game.path = path.clone();
 
                 path.push("profiles");
 
                 for profile in &mut game.profiles {
-                    profile.path = path.join(&profile.name);
+                    // profiles in custom locations stay where they are
+                    if profile.path.parent() == Some(old_profiles_dir.as_path()) {
+                        profile.path = path.join(&profile.name);
+                    }
                 }
 
                 path.pop();
@@ -271,6 +461,10 @@ impl Prefs {
 
         self.data_dir.set(value.data_dir.value)?;
 
+        if data_dir_changed {
+            load_scripts(&self.data_dir);
+        }
+
         if self.zoom_factor != value.zoom_factor {
             let window = app.get_webview_window("main").unwrap();
             window
@@ -279,9 +473,102 @@ impl Prefs {
         }
         self.zoom_factor = value.zoom_factor;
 
+        if self.language != value.language {
+            ensure!(
+                i18n::locales().contains(&value.language.as_str()),
+                "unsupported language"
+            );
+            i18n::set_locale(&value.language);
+        }
+        self.language = value.language;
+        self.theme = value.theme;
+
         self.fetch_mods_automatically = value.fetch_mods_automatically;
         self.pull_before_launch = value.pull_before_launch;
 
+        if let Some(dir) = &value.archive_dir {
+            ensure!(dir.is_dir(), "archive directory does not exist");
+        }
+        self.archive_dir = value.archive_dir;
+
+        let old_cache_dir = self.cache_dir();
+        if let Some(dir) = &value.cache_dir {
+            ensure!(dir.is_dir(), "cache directory does not exist");
+        }
+        self.cache_dir = value.cache_dir;
+
+        let was_shared = self.shared_cache;
+        self.shared_cache = value.shared_cache;
+        self.compress_cache = value.compress_cache;
+        self.deduplicate_cache = value.deduplicate_cache;
+
+        let new_cache_dir = self.cache_dir();
+        if old_cache_dir != new_cache_dir {
+            ensure!(
+                !app.install_queue().handle().is_processing(),
+                "cannot move the cache while mods are being installed"
+            );
+            move_cache(&old_cache_dir, &new_cache_dir)?;
+        }
+
+        if self.shared_cache && !was_shared {
+            cache::share(&util::path::shared_cache_dir())?;
+        }
+
+        if let Some(dir) = &value.temp_dir {
+            ensure!(dir.is_dir(), "temp directory does not exist");
+        }
+        self.temp_dir = value.temp_dir;
+
+        if let Some(editor) = &value.config_editor {
+            ensure!(editor.is_file(), "config editor does not exist");
+        }
+        self.config_editor = value.config_editor;
+
+        let tuning_changed = self.extract_threads != value.extract_threads
+            || self.write_buffer_size != value.write_buffer_size;
+        self.extract_threads = value.extract_threads;
+        self.simultaneous_downloads = value.simultaneous_downloads.max(1);
+        self.write_buffer_size = value.write_buffer_size;
+
+        if tuning_changed {
+            tuning::apply(self)?;
+        }
+
+        if let Some(mirror) = &value.download_mirror {
+            let url = Url::parse(mirror).context("invalid mirror URL")?;
+            ensure!(
+                matches!(url.scheme(), "http" | "https"),
+                "mirror URL must start with http:// or https://"
+            );
+        }
+        self.download_mirror = value.download_mirror;
+
+        self.webhooks = value.webhooks;
+        self.owned_namespaces = value.owned_namespaces;
+        self.quarantine_new_mods = value.quarantine_new_mods;
+        self.share_notes = value.share_notes;
+        self.compatibility_notes = value.compatibility_notes;
+        self.scheduled_health_checks = value.scheduled_health_checks;
+        self.track_runtime_files = value.track_runtime_files;
+        self.remove_runtime_files = value.remove_runtime_files;
+
+        if !value.ipc_server {
+            app.ipc_server().stop();
+        } else if !self.ipc_server || data_dir_changed {
+            // the socket lives in the data directory
+            app.ipc_server().start(&self.data_dir, app);
+        }
+        self.ipc_server = value.ipc_server;
+
+        if self.log_levels != value.log_levels {
+            logger::set_levels(&value.log_levels)?;
+        }
+        self.log_levels = value.log_levels;
+
+        notify::configure(&value.notifications);
+        self.notifications = value.notifications;
+
         self.save(app.db()).context("failed save prefs")
     }
 
@@ -317,12 +604,67 @@ impl Prefs {
                     slug
                 );
             }
+
+            value
+                .process_options
+                .retain(|_, options| *options != ProcessOptions::default());
+            for options in value.process_options.values() {
+                options.validate()?;
+            }
+
+            value.launch_hooks.retain(|_, hooks| {
+                for hook in [&mut hooks.pre_launch, &mut hooks.post_exit] {
+                    if hook.as_ref().is_some_and(|hook| hook.trim().is_empty()) {
+                        *hook = None;
+                    }
+                }
+
+                *hooks != LaunchHooks::default()
+            });
         }
 
         Ok(())
     }
 
+    /// The download cache. The command line and environment take precedence over
+    /// the pref, which defaults to the data directory.
     pub fn cache_dir(&self) -> PathBuf {
-        self.data_dir.join("cache")
+        util::path::dir_overrides()
+            .cache_dir
+            .clone()
+            .or_else(|| self.cache_dir.clone())
+            .unwrap_or_else(|| self.data_dir.join("cache"))
+    }
+
+    /// The temp directory override in effect, if any.
+    pub fn temp_dir(&self) -> Option<PathBuf> {
+        util::path::dir_overrides()
+            .temp_dir
+            .clone()
+            .or_else(|| self.temp_dir.clone())
     }
 }
+
+/// Moves the existing cache into a new location. If there's already something
+/// there, it's used as is and the old cache is left behind.
+fn move_cache(from: &Path, to: &Path) -> Result<()> {
+    if !from.exists() {
+        return Ok(());
+    }
+
+    fs::create_dir_all(to).fs_context("creating cache directory", to)?;
+
+    if to.read_dir()?.next().is_some() {
+        info!(
+            "{} is not empty, leaving the old cache in place",
+            to.display()
+        );
+        return Ok(());
+    }
+
+    DirPref::new(from.to_path_buf())
+        .set(to.to_path_buf())
+        .context("failed to move cache")?;
+
+    Ok(())
+}
@@ -0,0 +1,250 @@
+//! Exporting Gale's own settings to a file, to set up another machine the same way
+//! without touching any profiles.
+//!
+//! Only settings for how Gale looks and behaves are transferred. Anything that talks to
+//! other services, runs programs or relaxes a safety check is left out, as are settings
+//! tied to profile ids, since ids differ between installs. Both sides check against the
+//! same allowlist, so a crafted file can't sneak anything else in.
+//!
+//! Paths are only included when asked for, and are written relative to the home folder
+//! where possible. On import, paths that don't exist on the new machine are skipped.
+
+use std::path::{Component, Path, PathBuf};
+
+use eyre::{ensure, OptionExt, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::Prefs;
+use crate::util::{self, fs::JsonStyle};
+
+pub const FILE_NAME: &str = "gale-settings.json";
+const FORMAT_VERSION: u32 = 1;
+const HOME_PREFIX: &str = "~/";
+
+const PREFS: &[&str] = &[
+    "fetchModsAutomatically",
+    "zoomFactor",
+    "pullBeforeLaunch",
+    "language",
+    "theme",
+    "compressCache",
+    "deduplicateCache",
+    "extractThreads",
+    "simultaneousDownloads",
+    "writeBufferSize",
+    "ownedNamespaces",
+    "compatibilityNotes",
+    "scheduledHealthChecks",
+    "trackRuntimeFiles",
+    "logLevels",
+    "notifications",
+];
+const PATH_PREFS: &[&str] = &["archiveDir", "cacheDir", "tempDir"];
+const GAME_PREFS: &[&str] = &[
+    "launchMode",
+    "platform",
+    "deployMode",
+    "stageGameFiles",
+    "fetchInterval",
+];
+const PATH_GAME_PREFS: &[&str] = &["dirOverride"];
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsFile {
+    version: u32,
+    prefs: Map<String, Value>,
+    /// Settings kept by the frontend, like colors and the font.
+    #[serde(default)]
+    frontend: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    /// The frontend's part of the file, for it to apply itself.
+    pub frontend: Map<String, Value>,
+    /// Path settings that were skipped because the path doesn't exist here.
+    pub skipped: Vec<String>,
+}
+
+/// Writes the settings file to `dir` and returns its path.
+pub fn export(
+    prefs: &Prefs,
+    include_paths: bool,
+    frontend: Map<String, Value>,
+    dir: &Path,
+) -> Result<PathBuf> {
+    let Value::Object(mut map) = serde_json::to_value(prefs)? else {
+        unreachable!("prefs should serialize to an object");
+    };
+
+    let games = map.remove("gamePrefs");
+    strip(&mut map, PREFS, PATH_PREFS, include_paths);
+
+    if let Some(Value::Object(mut games)) = games {
+        for game in games.values_mut() {
+            if let Value::Object(game) = game {
+                strip(game, GAME_PREFS, PATH_GAME_PREFS, include_paths);
+            }
+        }
+
+        map.insert("gamePrefs".to_owned(), Value::Object(games));
+    }
+
+    let file = SettingsFile {
+        version: FORMAT_VERSION,
+        prefs: map,
+        frontend,
+    };
+
+    let path = dir.join(FILE_NAME);
+    util::fs::write_json(&path, &file, JsonStyle::Pretty)?;
+
+    Ok(path)
+}
+
+/// Removes everything that isn't in `allowed`, or in `paths` if those are included.
+fn strip(map: &mut Map<String, Value>, allowed: &[&str], paths: &[&str], include_paths: bool) {
+    map.retain(|key, _| {
+        allowed.contains(&key.as_str()) || (include_paths && paths.contains(&key.as_str()))
+    });
+
+    for key in paths {
+        if let Some(Value::String(path)) = map.get_mut(*key) {
+            *path = to_portable(Path::new(path));
+        }
+    }
+}
+
+fn to_portable(path: &Path) -> String {
+    let relative = dirs_next::home_dir().and_then(|home| path.strip_prefix(home).ok());
+
+    match relative {
+        Some(relative) => {
+            let parts = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>();
+
+            format!("{HOME_PREFIX}{}", parts.join("/"))
+        }
+        None => path.to_string_lossy().into_owned(),
+    }
+}
+
+fn from_portable(path: &str) -> Option<PathBuf> {
+    match path.strip_prefix(HOME_PREFIX) {
+        Some(relative) => {
+            let relative = Path::new(relative);
+            // don't let a crafted file point outside of the home folder
+            let is_normal = relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+
+            if !is_normal {
+                return None;
+            }
+
+            dirs_next::home_dir().map(|home| home.join(relative))
+        }
+        None => Some(PathBuf::from(path)),
+    }
+}
+
+/// Reads a settings file and merges it into `current`. The result still has to go
+/// through [`Prefs::set`] to be validated and applied.
+pub fn import(current: &Prefs, path: &Path) -> Result<(Prefs, ImportReport)> {
+    let file: SettingsFile = util::fs::read_json(path)?;
+
+    ensure!(
+        file.version <= FORMAT_VERSION,
+        "settings file is from a newer version of Gale"
+    );
+
+    let Value::Object(mut prefs) = serde_json::to_value(current)? else {
+        unreachable!("prefs should serialize to an object");
+    };
+
+    let mut skipped = Vec::new();
+
+    for (key, value) in file.prefs {
+        if key == "gamePrefs" {
+            let Value::Object(games) = value else {
+                continue;
+            };
+
+            let current_games = prefs
+                .entry("gamePrefs")
+                .or_insert_with(|| Value::Object(Map::new()))
+                .as_object_mut()
+                .ok_or_eyre("invalid game prefs")?;
+
+            for (slug, game) in games {
+                let Value::Object(game) = game else {
+                    continue;
+                };
+
+                let current_game = current_games
+                    .entry(slug.clone())
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .ok_or_eyre("invalid game prefs")?;
+
+                for (key, value) in game {
+                    let is_path = PATH_GAME_PREFS.contains(&key.as_str());
+                    if !is_path && !GAME_PREFS.contains(&key.as_str()) {
+                        continue;
+                    }
+
+                    let name = format!("{slug}.{key}");
+                    if let Some(value) = resolve(value, is_path, name, &mut skipped) {
+                        current_game.insert(key, value);
+                    }
+                }
+            }
+
+            continue;
+        }
+
+        let is_path = PATH_PREFS.contains(&key.as_str());
+        if !is_path && !PREFS.contains(&key.as_str()) {
+            continue;
+        }
+
+        if let Some(value) = resolve(value, is_path, key.clone(), &mut skipped) {
+            prefs.insert(key, value);
+        }
+    }
+
+    let mut merged: Prefs = serde_json::from_value(Value::Object(prefs))?;
+    // the files to keep when moving it aren't serialized
+    merged.data_dir = current.data_dir.clone();
+
+    let report = ImportReport {
+        frontend: file.frontend,
+        skipped,
+    };
+
+    Ok((merged, report))
+}
+
+fn resolve(value: Value, is_path: bool, name: String, skipped: &mut Vec<String>) -> Option<Value> {
+    if !is_path {
+        return Some(value);
+    }
+
+    // null just clears the setting
+    let Value::String(path) = &value else {
+        return Some(value);
+    };
+
+    match from_portable(path).filter(|path| path.exists()) {
+        Some(path) => Some(Value::String(path.to_string_lossy().into_owned())),
+        None => {
+            skipped.push(name);
+            None
+        }
+    }
+}
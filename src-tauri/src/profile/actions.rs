@@ -8,19 +8,20 @@ use eyre::{anyhow, bail, ensure, Context, OptionExt, Result};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Listener};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use super::{
     export::{IncludeExtensions, IncludeGenerated},
     import,
     install::PackageInstaller,
-    Dependant, ManagedGame, Profile, ProfileMod,
+    Dependant, ManagedGame, ModManager, Profile, ProfileMod,
 };
 use crate::{
     config::ConfigCache,
     db::Db,
-    logger,
+    game::Game,
+    i18n, logger,
     state::ManagerExt,
     thunderstore::Thunderstore,
     util::{
@@ -101,9 +102,15 @@ impl Profile {
     pub fn force_remove_mod(&mut self, uuid: Uuid) -> Result<()> {
         let index = self.index_of(uuid)?;
         let profile_mod = &self.mods[index];
+        let mut installer = self.installer_for(profile_mod);
 
-        self.installer_for(profile_mod)
-            .uninstall(profile_mod, self)?;
+        // profiles from before loader files were recorded fall back to the installer
+        if self.is_loader(profile_mod) && !self.loader_files.is_empty() {
+            self.strip_loader_files(&*installer)?;
+            self.loader_files.clear();
+        } else {
+            installer.uninstall(profile_mod, self)?;
+        }
 
         self.mods.remove(index);
 
@@ -200,12 +207,15 @@ impl Profile {
             open::that(path)?;
             Ok(())
         } else {
-            Err(anyhow!("unsupported"))
+            Err(anyhow!(
+                "mod folder is unsupported for {}",
+                self.game.mod_loader.as_str()
+            ))
         }
     }
 
     fn installer_for(&self, profile_mod: &ProfileMod) -> Box<dyn PackageInstaller> {
-        self.game.mod_loader.installer_for(&profile_mod.full_name())
+        self.game.installer_for(&profile_mod.full_name())
     }
 
     fn reorder_mod(&mut self, uuid: Uuid, delta: i32) -> Result<()> {
@@ -320,6 +330,10 @@ impl ManagedGame {
             sync: None,
             custom_args: Vec::new(),
             custom_args_enabled: false,
+            workshop_items: Vec::new(),
+            loader_files: Vec::new(),
+            notes: None,
+            readme: None,
         };
 
         let index = self.target_profile_index(&profile.name);
@@ -331,7 +345,7 @@ impl ManagedGame {
     pub fn create_default_profile(&mut self, db: &Db) -> Result<()> {
         info!("creating default profile for {}", self.game.slug);
 
-        let res = self.create_profile("Default".to_owned(), None, db);
+        let res = self.create_profile(i18n::t("profile.default_name", &[]), None, db);
 
         match res.map(|profile| profile.id) {
             Ok(id) => {
@@ -342,6 +356,55 @@ impl ManagedGame {
         }
     }
 
+    /// Moves a profile into `dir`, or back into the data directory if `dir` is `None`.
+    ///
+    /// If the profile can't simply be renamed (e.g. because it's moved to another drive),
+    /// it's copied instead, after making sure there's enough space for it.
+    /// Moves a profile into `dir`, or back into the game's directory if `dir` is `None`.
+    ///
+    /// This is a rename when both are on the same drive. Otherwise the profile has to be
+    /// copied, which is left to the returned [`ProfileMove`] so that the manager doesn't
+    /// have to stay locked for as long as that takes.
+    pub fn move_profile(&mut self, id: i64, dir: Option<PathBuf>) -> Result<Option<ProfileMove>> {
+        let dir = dir.unwrap_or_else(|| self.path.join("profiles"));
+        let profile = self.profile_mut(id)?;
+
+        let target = dir.join(&profile.name);
+
+        if target == profile.path {
+            return Ok(None);
+        }
+
+        // if the directory is empty, remove and replace it
+        fs::remove_dir(&target).ok();
+
+        ensure!(
+            !target.exists(),
+            "{} already exists and is not empty",
+            target.display()
+        );
+
+        fs::create_dir_all(&dir).fs_context("creating directory", &dir)?;
+
+        info!(
+            "moving profile {} from {} to {}",
+            profile.name,
+            profile.path.display(),
+            target.display()
+        );
+
+        if fs::rename(&profile.path, &target).is_ok() {
+            profile.path = target;
+            return Ok(None);
+        }
+
+        Ok(Some(ProfileMove {
+            id,
+            from: profile.path.clone(),
+            to: target,
+        }))
+    }
+
     pub fn delete_profile(&mut self, index: usize, allow_delete_last: bool, db: &Db) -> Result<()> {
         ensure!(
             allow_delete_last || self.profiles.len() > 1,
@@ -382,6 +445,7 @@ impl ManagedGame {
             &old_profile.path,
             IncludeExtensions::Default,
             IncludeGenerated::Yes,
+            &[],
         )
         .context("failed to copy config files")?;
 
@@ -397,12 +461,20 @@ impl ManagedGame {
         let ignored_updates = old_profile.ignored_updates.clone();
         let custom_args = old_profile.custom_args.clone();
         let custom_args_enabled = old_profile.custom_args_enabled;
+        let workshop_items = old_profile.workshop_items.clone();
+        let loader_files = old_profile.loader_files.clone();
+        let notes = old_profile.notes.clone();
+        let readme = old_profile.readme.clone();
 
         let new_profile = self.active_profile_mut();
         new_profile.mods = mods;
         new_profile.ignored_updates = ignored_updates;
         new_profile.custom_args = custom_args;
         new_profile.custom_args_enabled = custom_args_enabled;
+        new_profile.workshop_items = workshop_items;
+        new_profile.loader_files = loader_files;
+        new_profile.notes = notes;
+        new_profile.readme = readme;
 
         Ok(new_profile)
     }
@@ -488,3 +560,50 @@ impl ManagedGame {
         Ok(())
     }
 }
+
+/// A profile that has to be copied to another drive, see [`ManagedGame::move_profile`].
+pub struct ProfileMove {
+    id: i64,
+    from: PathBuf,
+    to: PathBuf,
+}
+
+impl ProfileMove {
+    /// Copies the profile to its new location. Doesn't need the manager to be locked.
+    pub fn copy(&self) -> Result<()> {
+        let size = util::fs::get_directory_size(&self.from);
+        util::fs::ensure_free_space(&self.to, size)?;
+
+        if let Err(err) = util::fs::copy_dir(&self.from, &self.to, Overwrite::Yes, UseLinks::No) {
+            // don't leave a half-copied profile behind, the original is still intact
+            self.clean_up();
+            return Err(err.wrap_err("failed to copy profile"));
+        }
+
+        Ok(())
+    }
+
+    /// Points the profile at the copy, unless it was removed or moved while being copied.
+    /// Returns the profile's game and its old directory, which can be removed afterwards.
+    pub fn finish(self, manager: &mut ModManager) -> Result<(Game, PathBuf)> {
+        let found = manager
+            .profile_by_id_mut(self.id)
+            .ok()
+            .filter(|(_, profile)| profile.path == self.from);
+
+        let Some((game, profile)) = found else {
+            self.clean_up();
+            bail!("the profile was changed while it was being moved");
+        };
+
+        profile.path = self.to;
+
+        Ok((game, self.from))
+    }
+
+    fn clean_up(&self) {
+        if let Err(err) = fs::remove_dir_all(&self.to) {
+            warn!("failed to clean up {}: {}", self.to.display(), err);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Runs an action on many mods at once, reporting progress through the status bar
+//! and collecting the result of each mod instead of stopping at the first failure.
+
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tracing::warn;
+use uuid::Uuid;
+
+use super::{update, Profile};
+use crate::{i18n, state::ManagerExt};
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum BulkAction {
+    Update,
+    Remove,
+    Toggle,
+}
+
+impl BulkAction {
+    fn status_key(self) -> &'static str {
+        match self {
+            BulkAction::Update => "bulk.updating",
+            BulkAction::Remove => "bulk.removing",
+            BulkAction::Toggle => "bulk.toggling",
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkReport {
+    pub succeeded: Vec<Uuid>,
+    pub failed: Vec<BulkFailure>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkFailure {
+    pub uuid: Uuid,
+    pub name: Option<String>,
+    pub error: String,
+}
+
+impl BulkReport {
+    fn record(&mut self, uuid: Uuid, name: Option<String>, result: Result<()>) {
+        match result {
+            Ok(()) => self.succeeded.push(uuid),
+            Err(err) => {
+                warn!("bulk action failed for {}: {:#}", uuid, err);
+                self.failed.push(BulkFailure {
+                    uuid,
+                    name,
+                    error: format!("{err:#}"),
+                });
+            }
+        }
+    }
+}
+
+/// Runs `action` on each of `uuids` in the active profile.
+///
+/// Dependants and dependencies are not checked, like with the `force_*` commands.
+pub async fn run(action: BulkAction, uuids: Vec<Uuid>, app: &AppHandle) -> Result<BulkReport> {
+    let report = match action {
+        BulkAction::Update => run_updates(&uuids, app).await,
+        BulkAction::Remove | BulkAction::Toggle => run_local(action, &uuids, app)?,
+    };
+
+    app.emit("status_update", None::<String>).ok();
+
+    Ok(report)
+}
+
+/// Updates mods one at a time, so that a failed download only affects its own mod.
+async fn run_updates(uuids: &[Uuid], app: &AppHandle) -> BulkReport {
+    let mut report = BulkReport::default();
+
+    for (i, &uuid) in uuids.iter().enumerate() {
+        emit_progress(BulkAction::Update, i, uuids.len(), app);
+
+        let name = mod_name(app.lock_manager().active_profile(), uuid);
+        let result = update::update_mods(vec![uuid], false, app).await;
+        report.record(uuid, name, result);
+    }
+
+    report
+}
+
+fn run_local(action: BulkAction, uuids: &[Uuid], app: &AppHandle) -> Result<BulkReport> {
+    let mut manager = app.lock_manager();
+    let profile = manager.active_profile_mut();
+
+    let mut report = BulkReport::default();
+
+    for (i, &uuid) in uuids.iter().enumerate() {
+        emit_progress(action, i, uuids.len(), app);
+
+        let name = mod_name(profile, uuid);
+        let result = match action {
+            BulkAction::Remove => profile.force_remove_mod(uuid),
+            BulkAction::Toggle => profile.force_toggle_mod(uuid),
+            BulkAction::Update => unreachable!("updates are run separately"),
+        };
+
+        report.record(uuid, name, result);
+    }
+
+    profile.save(app, true)?;
+
+    Ok(report)
+}
+
+fn mod_name(profile: &Profile, uuid: Uuid) -> Option<String> {
+    profile
+        .get_mod(uuid)
+        .ok()
+        .map(|profile_mod| profile_mod.full_name().into_owned())
+}
+
+fn emit_progress(action: BulkAction, done: usize, total: usize, app: &AppHandle) {
+    let message = i18n::t(action.status_key(), &[("done", &done), ("total", &total)]);
+    app.emit("status_update", Some(message)).ok();
+}
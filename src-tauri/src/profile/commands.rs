@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{fs, path::PathBuf};
 
 use eyre::{Context, OptionExt};
 use itertools::Itertools;
@@ -7,13 +7,24 @@ use tauri::{command, AppHandle};
 use tracing::warn;
 use uuid::Uuid;
 
-use super::{actions::ActionResult, Dependant, Profile};
+use super::{
+    actions::ActionResult,
+    bulk::{BulkAction, BulkReport},
+    health::{self, HealthReport},
+    prune::{self, Suggestion},
+    report::ModReport,
+    toggles::{self, ToggleSetReport},
+    workshop::{self, WorkshopItem},
+    Dependant, Profile,
+};
 use crate::{
-    game::{self, platform::Platform, Game},
+    game::{self, anti_cheat::AntiCheat, platform::Platform, Game},
+    prefs,
     profile::FrontendManagedGame,
     state::ManagerExt,
     thunderstore::{
-        cache::MarkdownKind, query::QueryModsArgs, FrontendProfileMod, Thunderstore, VersionIdent,
+        cache::MarkdownKind, markdown, query::QueryModsArgs, FrontendProfileMod, Thunderstore,
+        VersionIdent,
     },
     util::cmd::Result,
 };
@@ -24,8 +35,10 @@ pub struct FrontendGame {
     name: &'static str,
     slug: &'static str,
     popular: bool,
+    server: bool,
     mod_loader: &'static str,
     platforms: Vec<Platform>,
+    anti_cheat: Option<&'static AntiCheat<'static>>,
 }
 
 impl From<Game> for FrontendGame {
@@ -36,8 +49,10 @@ impl From<Game> for FrontendGame {
             name: value.name,
             slug: &*value.slug,
             popular: value.popular,
+            server: value.server,
             mod_loader: value.mod_loader.as_str(),
             platforms,
+            anti_cheat: value.anti_cheat.as_ref(),
         }
     }
 }
@@ -145,6 +160,8 @@ pub struct ProfileQuery {
     total_mod_count: usize,
     updates: Vec<FrontendAvailableUpdate>,
     unknown_mods: Vec<Dependant>,
+    /// Every tag used in the profile, sorted alphabetically.
+    tags: Vec<String>,
 }
 
 #[command]
@@ -157,6 +174,7 @@ pub fn query_profile(args: QueryModsArgs, app: AppHandle) -> Result<ProfileQuery
 
     let (mods, unknown_mods) = profile.query_mods(&args, &thunderstore);
     let total_mod_count = profile.mods.len();
+    let tags = profile.tags();
 
     let updates = profile
         .mods
@@ -189,6 +207,7 @@ pub fn query_profile(args: QueryModsArgs, app: AppHandle) -> Result<ProfileQuery
         total_mod_count,
         updates,
         unknown_mods,
+        tags,
     })
 }
 
@@ -245,6 +264,57 @@ pub fn rename_profile(name: String, app: AppHandle) -> Result<()> {
     Ok(())
 }
 
+/// Moves the active profile into `dir`, or back into the data directory if `dir` is `None`.
+#[command]
+pub async fn move_profile(dir: Option<PathBuf>, app: AppHandle) -> Result<()> {
+    if let Some(dir) = &dir {
+        prefs::access::request(dir, "profile location", &app).await?;
+    }
+
+    tauri::async_runtime::spawn_blocking(move || -> eyre::Result<()> {
+        let pending = {
+            let mut manager = app.lock_manager();
+            let game = manager.active_game_mut();
+
+            let id = game.active_profile_id;
+            let pending = game.move_profile(id, dir)?;
+            game.save(&app)?;
+
+            pending
+        };
+
+        let Some(pending) = pending else {
+            return Ok(());
+        };
+
+        // copying to another drive can take a while, don't block everything else meanwhile
+        pending.copy()?;
+
+        let old_path = {
+            let mut manager = app.lock_manager();
+            let (game, old_path) = pending.finish(&mut manager)?;
+            manager.games[&game].save(&app)?;
+
+            old_path
+        };
+
+        // the copy is complete at this point, so a partially removed original
+        // shouldn't fail the move
+        if let Err(err) = fs::remove_dir_all(&old_path) {
+            warn!(
+                "failed to remove old profile directory {}: {}",
+                old_path.display(),
+                err
+            );
+        }
+
+        Ok(())
+    })
+    .await??;
+
+    Ok(())
+}
+
 #[command]
 pub fn duplicate_profile(name: String, app: AppHandle) -> Result<()> {
     let mut manager = app.lock_manager();
@@ -262,11 +332,29 @@ pub fn duplicate_profile(name: String, app: AppHandle) -> Result<()> {
 
 #[command]
 pub fn remove_mod(uuid: Uuid, app: AppHandle) -> Result<ActionResult> {
+    let remove_runtime_files = app.lock_prefs().remove_runtime_files;
+
     mod_action_command(app, |profile, thunderstore| {
-        profile.remove_mod(uuid, thunderstore)
+        let name = profile.get_mod(uuid)?.full_name().into_owned();
+        let result = profile.remove_mod(uuid, thunderstore)?;
+
+        if remove_runtime_files && matches!(result, ActionResult::Done) {
+            trash_runtime_files(profile, &name);
+        }
+
+        Ok(result)
     })
 }
 
+fn trash_runtime_files(profile: &Profile, package_name: &str) {
+    if let Err(err) = profile.trash_runtime_files(package_name) {
+        warn!(
+            "failed to remove runtime files of {}: {:#}",
+            package_name, err
+        );
+    }
+}
+
 #[command]
 pub fn toggle_mod(uuid: Uuid, app: AppHandle) -> Result<ActionResult> {
     mod_action_command(app, |profile, thunderstore| {
@@ -293,11 +381,17 @@ where
 
 #[command]
 pub fn force_remove_mods(uuids: Vec<Uuid>, app: AppHandle) -> Result<()> {
+    let remove_runtime_files = app.lock_prefs().remove_runtime_files;
     let mut manager = app.lock_manager();
 
     let profile = manager.active_profile_mut();
     for package_uuid in uuids {
+        let name = profile.get_mod(package_uuid)?.full_name().into_owned();
         profile.force_remove_mod(package_uuid)?;
+
+        if remove_runtime_files {
+            trash_runtime_files(profile, &name);
+        }
     }
 
     profile.save(&app, true)?;
@@ -351,6 +445,52 @@ pub fn remove_disabled_mods(app: AppHandle) -> Result<usize> {
     Ok(len)
 }
 
+#[command]
+pub fn set_mod_tags(uuid: Uuid, tags: Vec<String>, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    profile.set_mod_tags(uuid, tags)?;
+    profile.save(&app, true)?;
+
+    Ok(())
+}
+
+#[command]
+pub async fn run_bulk_action(
+    action: BulkAction,
+    uuids: Vec<Uuid>,
+    app: AppHandle,
+) -> Result<BulkReport> {
+    let report = super::bulk::run(action, uuids, &app).await?;
+
+    Ok(report)
+}
+
+/// Uninstalls every mod in the active profile, optionally keeping the mod loader.
+#[command]
+pub fn remove_all_mods(keep_loader: bool, app: AppHandle) -> Result<usize> {
+    let mut manager = app.lock_manager();
+
+    let profile = manager.active_profile_mut();
+    let uuids = profile
+        .mods
+        .iter()
+        .filter(|profile_mod| !(keep_loader && profile.is_loader(profile_mod)))
+        .map(|profile_mod| profile_mod.uuid())
+        .collect_vec();
+
+    let len = uuids.len();
+
+    for uuid in uuids {
+        profile.force_remove_mod(uuid)?;
+    }
+
+    profile.save(&app, true)?;
+
+    Ok(len)
+}
+
 #[command]
 pub fn force_toggle_mods(uuids: Vec<Uuid>, app: AppHandle) -> Result<()> {
     let mut manager = app.lock_manager();
@@ -365,6 +505,56 @@ pub fn force_toggle_mods(uuids: Vec<Uuid>, app: AppHandle) -> Result<()> {
     Ok(())
 }
 
+#[command]
+pub fn export_toggle_set(dir: PathBuf, app: AppHandle) -> Result<()> {
+    let manager = app.lock_manager();
+
+    let path = manager.active_profile().export_toggle_set(&dir)?;
+    open::that(path.parent().unwrap()).ok();
+
+    Ok(())
+}
+
+#[command]
+pub fn import_toggle_set(path: PathBuf, app: AppHandle) -> Result<ToggleSetReport> {
+    let set = toggles::read_file(&path)?;
+
+    let mut manager = app.lock_manager();
+    let profile = manager.active_profile_mut();
+
+    let report = profile.apply_toggle_set(&set)?;
+    profile.save(&app, true)?;
+
+    Ok(report)
+}
+
+#[command]
+pub fn get_health_report(app: AppHandle) -> Option<HealthReport> {
+    health::last_report(&app)
+}
+
+#[command]
+pub async fn run_health_check(app: AppHandle) -> Result<HealthReport> {
+    let report = tauri::async_runtime::spawn_blocking(move || health::run(&app)).await??;
+
+    Ok(report)
+}
+
+#[command]
+pub async fn get_prune_suggestions(app: AppHandle) -> Result<Vec<Suggestion>> {
+    let suggestions = tauri::async_runtime::spawn_blocking(move || prune::analyze(&app)).await??;
+
+    Ok(suggestions)
+}
+
+#[command]
+pub async fn apply_prune_suggestions(suggestions: Vec<Suggestion>, app: AppHandle) -> Result<u64> {
+    let freed =
+        tauri::async_runtime::spawn_blocking(move || prune::apply(suggestions, &app)).await??;
+
+    Ok(freed)
+}
+
 #[command]
 pub fn get_dependants(uuid: Uuid, app: AppHandle) -> Result<Vec<VersionIdent>> {
     let manager = app.lock_manager();
@@ -379,6 +569,35 @@ pub fn get_dependants(uuid: Uuid, app: AppHandle) -> Result<Vec<VersionIdent>> {
     Ok(dependants)
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequiredBy {
+    /// Mods in the active profile that depend on the package, directly or not.
+    installed: usize,
+    /// Packages in the community whose latest version depends on the package directly.
+    community: usize,
+}
+
+#[command]
+pub fn get_required_by(uuid: Uuid, app: AppHandle) -> Result<RequiredBy> {
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    let installed = manager
+        .active_profile()
+        .dependants(uuid, &thunderstore)
+        .count();
+
+    let community = thunderstore
+        .get_package(uuid)
+        .map_or(0, |package| thunderstore.dependant_count(package));
+
+    Ok(RequiredBy {
+        installed,
+        community,
+    })
+}
+
 #[command]
 pub fn open_profile_dir(app: AppHandle) -> Result<()> {
     let manager = app.lock_manager();
@@ -408,6 +627,33 @@ pub fn open_game_log(app: AppHandle) -> Result<()> {
     Ok(())
 }
 
+#[command]
+pub fn open_game_log_dir(app: AppHandle) -> Result<()> {
+    let manager = app.lock_manager();
+
+    let path = manager.active_profile().log_dir()?;
+    open::that(path).context("failed to open directory")?;
+
+    Ok(())
+}
+
+/// Collects a bug report for a mod and opens the page to file it on.
+#[command]
+pub fn report_mod(uuid: Uuid, app: AppHandle) -> Result<ModReport> {
+    let prefs = app.lock_prefs();
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+    let queue = app.install_queue().handle();
+
+    let report = manager
+        .active_profile()
+        .report_mod(uuid, &prefs, &thunderstore, &queue)?;
+
+    open::that_detached(&report.url).context("failed to open report page")?;
+
+    Ok(report)
+}
+
 #[command]
 pub fn create_desktop_shortcut(app: AppHandle) -> Result<()> {
     let manager = app.lock_manager();
@@ -438,7 +684,7 @@ pub async fn get_local_markdown(
         MarkdownKind::Changelog => &local_mod.changelog,
     };
 
-    Ok(str.clone())
+    Ok(str.as_deref().map(|source| markdown::render(source, None)))
 }
 
 #[command]
@@ -451,3 +697,63 @@ pub fn set_custom_args(custom_args: Vec<String>, enabled: bool, app: AppHandle)
     manager.save_active_game(&app)?;
     Ok(())
 }
+
+#[command]
+pub fn set_profile_notes(notes: Option<String>, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+    let profile = manager.active_profile_mut();
+    profile.notes = super::normalize_note(notes);
+    profile.save(&app, true)?;
+    Ok(())
+}
+
+#[command]
+pub fn set_mod_note(uuid: Uuid, note: Option<String>, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+    let profile = manager.active_profile_mut();
+    profile.set_mod_note(uuid, note)?;
+    profile.save(&app, true)?;
+    Ok(())
+}
+
+#[command]
+pub fn set_mod_local_only(uuid: Uuid, local_only: bool, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+    let profile = manager.active_profile_mut();
+    profile.set_mod_local_only(uuid, local_only)?;
+    profile.save(&app, true)?;
+    Ok(())
+}
+
+/// Adds a Workshop item by ID or page URL and opens it in Steam so it can be subscribed to.
+#[command]
+pub fn add_workshop_item(input: String, name: Option<String>, app: AppHandle) -> Result<()> {
+    let id = workshop::parse_id(&input)?;
+
+    let mut manager = app.lock_manager();
+    let profile = manager.active_profile_mut();
+    profile.add_workshop_item(WorkshopItem { id, name })?;
+    profile.save(&app, true)?;
+
+    workshop::open_in_steam(id)?;
+
+    Ok(())
+}
+
+/// Stops tracking a Workshop item. The user stays subscribed to it in Steam.
+#[command]
+pub fn remove_workshop_item(id: u64, app: AppHandle) -> Result<()> {
+    let mut manager = app.lock_manager();
+    let profile = manager.active_profile_mut();
+    profile.remove_workshop_item(id)?;
+    profile.save(&app, true)?;
+
+    Ok(())
+}
+
+#[command]
+pub fn open_workshop_item(id: u64) -> Result<()> {
+    workshop::open_in_steam(id)?;
+
+    Ok(())
+}
@@ -183,7 +183,9 @@ impl Profile {
         )
     }
 
-    fn find_snapshots(&self) -> Result<Box<dyn Iterator<Item = (DirEntry, semver::Version)>>> {
+    pub(super) fn find_snapshots(
+        &self,
+    ) -> Result<Box<dyn Iterator<Item = (DirEntry, semver::Version)>>> {
         let path = self.path.join("snapshots");
 
         if !path.exists() {
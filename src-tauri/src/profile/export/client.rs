@@ -0,0 +1,128 @@
+//! Derives a client profile from a server profile, using the client/server
+//! categories mods are tagged with on Thunderstore.
+
+use std::{collections::HashSet, io::Cursor};
+
+use serde::Serialize;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use super::{export_zip_with, upload_code, ExportOptions};
+use crate::{
+    profile::{Dependant, Profile, Result},
+    state::ManagerExt,
+    thunderstore::{PackageListing, Thunderstore},
+};
+
+const SERVER_CATEGORIES: &[&str] = &["server-side", "server side", "server-only", "server only"];
+const CLIENT_CATEGORIES: &[&str] = &[
+    "client-side",
+    "client side",
+    "client-only",
+    "client only",
+    "all clients",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModSide {
+    Client,
+    Server,
+    Both,
+    Unknown,
+}
+
+impl ModSide {
+    pub fn of(package: &PackageListing) -> Self {
+        let has_any = |names: &[&str]| {
+            package
+                .categories
+                .iter()
+                .any(|category| names.contains(&category.to_lowercase().as_str()))
+        };
+
+        match (has_any(CLIENT_CATEGORIES), has_any(SERVER_CATEGORIES)) {
+            (true, true) => ModSide::Both,
+            (true, false) => ModSide::Client,
+            (false, true) => ModSide::Server,
+            (false, false) => ModSide::Unknown,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientProfilePreview {
+    included: Vec<Dependant>,
+    excluded: Vec<Dependant>,
+}
+
+impl Profile {
+    /// Finds the mods of this profile that players need on their clients.
+    ///
+    /// Server-only mods are dropped, unless another included mod depends on them.
+    /// Mods without any side metadata are kept to be on the safe side.
+    pub fn client_mods(&self, thunderstore: &Thunderstore) -> HashSet<Uuid> {
+        let mut included = self
+            .thunderstore_mods()
+            .filter(|(ts_mod, _)| {
+                thunderstore
+                    .get_package(ts_mod.id.package_uuid)
+                    .map(|package| ModSide::of(package) != ModSide::Server)
+                    .unwrap_or(true)
+            })
+            .map(|(ts_mod, _)| ts_mod.id.package_uuid)
+            .collect::<HashSet<_>>();
+
+        let dependencies = self
+            .mods
+            .iter()
+            .filter(|profile_mod| included.contains(&profile_mod.uuid()))
+            .flat_map(|profile_mod| profile_mod.dependencies(thunderstore))
+            .map(|dep| dep.package.uuid)
+            .filter(|uuid| self.has_mod(*uuid))
+            .collect::<Vec<_>>();
+
+        included.extend(dependencies);
+        included
+    }
+
+    pub fn preview_client_profile(&self, thunderstore: &Thunderstore) -> ClientProfilePreview {
+        let client_mods = self.client_mods(thunderstore);
+
+        let (included, excluded) = self
+            .mods
+            .iter()
+            .filter(|profile_mod| profile_mod.kind.as_thunderstore().is_some())
+            .partition::<Vec<_>, _>(|profile_mod| client_mods.contains(&profile_mod.uuid()));
+
+        ClientProfilePreview {
+            included: included.into_iter().map(Dependant::from).collect(),
+            excluded: excluded.into_iter().map(Dependant::from).collect(),
+        }
+    }
+}
+
+/// Exports the client part of the active profile as a code players can import.
+pub async fn export_client_code(app: &AppHandle) -> Result<Uuid> {
+    let options = ExportOptions::from_prefs(app);
+
+    let data = {
+        let manager = app.lock_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        let game = manager.active_game().game;
+        let profile = manager.active_profile();
+
+        let client_mods = profile.client_mods(&thunderstore);
+
+        let mut data = Cursor::new(Vec::new());
+        export_zip_with(profile, &mut data, game, options, |uuid| {
+            client_mods.contains(&uuid)
+        })?;
+
+        data.into_inner()
+    };
+
+    upload_code(data, app).await
+}
@@ -13,7 +13,9 @@ use uuid::Uuid;
 
 use super::{
     changelog,
+    client::{self, ClientProfilePreview},
     modpack::{self, ModpackArgs},
+    updates::{self, ModpackUpdates},
 };
 use crate::{
     profile::ProfileModKind,
@@ -29,8 +31,28 @@ pub async fn export_code(app: AppHandle) -> Result<Uuid> {
     Ok(key)
 }
 
+#[command]
+pub fn preview_client_profile(app: AppHandle) -> Result<ClientProfilePreview> {
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    let preview = manager
+        .active_profile()
+        .preview_client_profile(&thunderstore);
+
+    Ok(preview)
+}
+
+#[command]
+pub async fn export_client_code(app: AppHandle) -> Result<Uuid> {
+    let key = client::export_client_code(&app).await?;
+
+    Ok(key)
+}
+
 #[command]
 pub fn export_file(dir: PathBuf, app: AppHandle) -> Result<()> {
+    let options = super::ExportOptions::from_prefs(&app);
     let manager = app.lock_manager();
 
     let game = manager.active_game().game;
@@ -42,7 +64,7 @@ pub fn export_file(dir: PathBuf, app: AppHandle) -> Result<()> {
 
     let file = fs::File::create(&path).map_err(|err| anyhow!(err))?;
     let writer = BufWriter::new(file);
-    super::export_zip(manager.active_profile(), writer, game)?;
+    super::export_zip(manager.active_profile(), writer, game, options)?;
 
     open::that(path.parent().unwrap()).ok();
 
@@ -184,6 +206,25 @@ pub fn copy_debug_info(app: AppHandle) -> Result<()> {
     Ok(())
 }
 
+#[command]
+pub fn get_modpack_updates(app: AppHandle) -> Result<ModpackUpdates> {
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+    let install_queue = app.install_queue().handle();
+
+    let updates = manager
+        .active_profile()
+        .modpack_updates(&thunderstore, &install_queue)?;
+
+    Ok(updates)
+}
+
+#[command]
+pub async fn apply_modpack_updates(app: AppHandle) -> Result<ModpackArgs> {
+    let args = updates::apply(&app).await?;
+    Ok(args)
+}
+
 #[command]
 pub fn generate_changelog(mut args: ModpackArgs, all: bool, app: AppHandle) -> Result<String> {
     let manager = app.lock_manager();
@@ -13,16 +13,18 @@ use uuid::Uuid;
 use walkdir::WalkDir;
 use zip::{write::SimpleFileOptions, ZipWriter};
 
-use super::{install::ModInstall, Profile, Result};
+use super::{install::ModInstall, workshop::WorkshopItem, Profile, Result};
 use crate::{
     game::Game,
     state::ManagerExt,
-    thunderstore::{LegacyProfileCreateResponse, PackageIdent, Thunderstore, VersionIdent},
+    thunderstore::{self, LegacyProfileCreateResponse, PackageIdent, Thunderstore, VersionIdent},
 };
 
 mod changelog;
+pub mod client;
 pub mod commands;
 pub mod modpack;
+pub mod updates;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -34,6 +36,16 @@ pub struct ProfileManifest {
     pub game: Option<String>,
     #[serde(default)]
     pub ignored_updates: Vec<Uuid>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workshop_items: Vec<WorkshopItem>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Instructions written by the profile's owner, always shared unlike `notes`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readme: Option<String>,
+    /// Config files subscribers of a synced profile can't keep local edits to.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enforced_config: Vec<PathBuf>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -43,6 +55,8 @@ pub struct R2Mod {
     #[serde(alias = "versionNumber")]
     pub version: R2Version,
     pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
 }
 
 impl R2Mod {
@@ -53,7 +67,9 @@ impl R2Mod {
     pub fn into_install(&self, thunderstore: &Thunderstore) -> Result<ModInstall> {
         let borrowed_mod = thunderstore.find_ident(&self.version_ident())?;
 
-        Ok(ModInstall::new(borrowed_mod).with_state(self.enabled))
+        Ok(ModInstall::new(borrowed_mod)
+            .with_state(self.enabled)
+            .with_note(self.note.clone()))
     }
 }
 
@@ -83,12 +99,63 @@ impl From<semver::Version> for R2Version {
 
 pub const PROFILE_DATA_PREFIX: &str = "#r2modman\n";
 
-pub(super) fn export_zip(profile: &Profile, writer: impl Write + Seek, game: Game) -> Result<()> {
+/// Options for [`export_zip`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportOptions {
+    /// Whether to include the profile's and mods' notes.
+    pub include_notes: bool,
+    /// Whether to leave out local only mods and their config.
+    pub exclude_local_only: bool,
+}
+
+impl ExportOptions {
+    pub fn from_prefs(app: &AppHandle) -> Self {
+        Self {
+            include_notes: app.lock_prefs().share_notes,
+            exclude_local_only: false,
+        }
+    }
+
+    /// Options for pushing to a synced profile.
+    pub fn for_sync(app: &AppHandle) -> Self {
+        Self {
+            exclude_local_only: true,
+            ..Self::from_prefs(app)
+        }
+    }
+}
+
+pub(super) fn export_zip(
+    profile: &Profile,
+    writer: impl Write + Seek,
+    game: Game,
+    options: ExportOptions,
+) -> Result<()> {
+    export_zip_with(profile, writer, game, options, |_| true)
+}
+
+/// Like [`export_zip`], but only includes mods for which `include` returns `true`.
+fn export_zip_with(
+    profile: &Profile,
+    writer: impl Write + Seek,
+    game: Game,
+    options: ExportOptions,
+    include: impl Fn(Uuid) -> bool,
+) -> Result<()> {
     let mut zip = ZipWriter::new(writer);
 
     let mods = profile
-        .thunderstore_mods()
-        .map(|(ts_mod, enabled)| {
+        .mods
+        .iter()
+        .filter_map(|profile_mod| {
+            let ts_mod = profile_mod.kind.as_thunderstore()?;
+            Some((ts_mod, profile_mod))
+        })
+        .filter(|(ts_mod, profile_mod)| {
+            include(ts_mod.id.package_uuid)
+                && !(options.exclude_local_only && profile_mod.local_only)
+        })
+        .map(|(ts_mod, profile_mod)| {
             let ident = ts_mod.ident.without_version();
             let version = ts_mod
                 .ident
@@ -100,7 +167,8 @@ pub(super) fn export_zip(profile: &Profile, writer: impl Write + Seek, game: Gam
             R2Mod {
                 ident,
                 version,
-                enabled,
+                enabled: profile_mod.enabled,
+                note: profile_mod.note.clone().filter(|_| options.include_notes),
             }
         })
         .collect();
@@ -108,19 +176,33 @@ pub(super) fn export_zip(profile: &Profile, writer: impl Write + Seek, game: Gam
     let manifest = ProfileManifest {
         name: profile.name.clone(),
         ignored_updates: profile.ignored_updates.iter().cloned().collect(),
+        workshop_items: profile.workshop_items.clone(),
         game: Some(game.slug.to_string()),
+        notes: profile.notes.clone().filter(|_| options.include_notes),
+        readme: profile.readme.clone(),
+        enforced_config: profile
+            .sync
+            .as_ref()
+            .map(|sync| sync.enforced_config().to_vec())
+            .unwrap_or_default(),
         mods,
     };
 
     zip.start_file("export.r2x", SimpleFileOptions::default())?;
     serde_yaml::to_writer(&mut zip, &manifest).context("failed to write profile manifest")?;
 
+    let excluded_config = match options.exclude_local_only {
+        true => profile.local_only_config(),
+        false => Vec::new(),
+    };
+
     write_config(
         find_config(
             &profile.path,
             IncludeExtensions::Default,
             IncludeGenerated::No,
-        ),
+        )
+        .filter(|file| !excluded_config.contains(file)),
         &profile.path,
         &mut zip,
     )?;
@@ -129,26 +211,36 @@ pub(super) fn export_zip(profile: &Profile, writer: impl Write + Seek, game: Gam
 }
 
 async fn export_code(app: &AppHandle) -> Result<Uuid> {
-    let base64 = {
-        let mut manager = app.lock_manager();
+    let options = ExportOptions::from_prefs(app);
+
+    let data = {
+        let manager = app.lock_manager();
 
         let game = manager.active_game().game;
-        let profile = manager.active_profile_mut();
+        let profile = manager.active_profile();
 
         let mut data = Cursor::new(Vec::new());
-        export_zip(profile, &mut data, game)?;
-
-        let mut base64 = String::from(PROFILE_DATA_PREFIX);
-        base64.push_str(&BASE64_STANDARD.encode(data.get_ref()));
+        export_zip(profile, &mut data, game, options)?;
 
-        base64
+        data.into_inner()
     };
 
-    const URL: &str = "https://thunderstore.io/api/experimental/legacyprofile/create/";
+    upload_code(data, app).await
+}
+
+/// Uploads an exported profile to Thunderstore, returning the profile code.
+pub async fn upload_code(data: Vec<u8>, app: &AppHandle) -> Result<Uuid> {
+    let mut base64 = String::from(PROFILE_DATA_PREFIX);
+    base64.push_str(&BASE64_STANDARD.encode(data));
+
+    let url = format!(
+        "{}/api/experimental/legacyprofile/create/",
+        *thunderstore::API_URL
+    );
 
     let response = app
         .http()
-        .post(URL)
+        .post(url)
         .header("Content-Type", "application/octet-stream")
         .body(base64)
         .send()
@@ -187,7 +279,7 @@ const GENERATED_FILES: &[&str] = &[
     "doorstop_config.ini",
     "snapshots",
     "_state",
-    "MelonLoader/Dependencies/Il2CppAssemblyGenerator/Config.cfg"
+    "MelonLoader/Dependencies/Il2CppAssemblyGenerator/Config.cfg",
 ];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -159,7 +159,7 @@ fn base_request(
     token: impl Display,
     client: &reqwest::Client,
 ) -> reqwest::RequestBuilder {
-    let url = format!("https://thunderstore.io/api/experimental/{tail}/");
+    let url = format!("{}/api/experimental/{tail}/", *API_URL);
 
     client.post(url).bearer_auth(token)
 }
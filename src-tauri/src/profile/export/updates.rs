@@ -0,0 +1,131 @@
+//! Suggests dependency updates for profiles used as a modpack source,
+//! that is profiles with [`ModpackArgs`].
+
+use eyre::{Context, OptionExt};
+use itertools::Itertools;
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::warn;
+use uuid::Uuid;
+
+use super::{changelog, modpack::ModpackArgs};
+use crate::{
+    profile::{install::queue::InstallQueueHandle, update, Profile, Result},
+    state::ManagerExt,
+    thunderstore::{Thunderstore, VersionIdent},
+};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyUpdate {
+    pub uuid: Uuid,
+    pub current: VersionIdent,
+    pub latest: VersionIdent,
+    pub changelog_url: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackUpdates {
+    pub version_number: String,
+    /// The version the modpack will be bumped to when the updates are applied.
+    pub next_version_number: String,
+    pub updates: Vec<DependencyUpdate>,
+}
+
+impl Profile {
+    pub fn modpack_updates(
+        &self,
+        thunderstore: &Thunderstore,
+        install_queue: &InstallQueueHandle,
+    ) -> Result<ModpackUpdates> {
+        let args = self
+            .modpack
+            .as_ref()
+            .ok_or_eyre("profile is not a modpack source")?;
+
+        let updates = self
+            .mods_to_pack(args)
+            .filter_map(|mod_id| {
+                self.check_update(mod_id.package_uuid, true, thunderstore, install_queue)
+                    .ok()
+                    .flatten()
+            })
+            .map(|update| DependencyUpdate {
+                uuid: update.package.uuid,
+                current: update.current.ident.clone(),
+                latest: update.latest.ident.clone(),
+                changelog_url: format!("{}changelog/", update.package.url(self.game)),
+            })
+            .collect();
+
+        Ok(ModpackUpdates {
+            version_number: args.version_number.clone(),
+            next_version_number: self.next_pack_version(args)?.to_string(),
+            updates,
+        })
+    }
+
+    /// Bumps the patch version, unless the current version hasn't been exported yet.
+    fn next_pack_version(&self, args: &ModpackArgs) -> Result<semver::Version> {
+        let mut version: semver::Version = args
+            .version_number
+            .parse()
+            .context("invalid version number")?;
+
+        let released = self
+            .find_snapshots()?
+            .any(|(_, snapshot)| snapshot == version);
+
+        if released {
+            version.patch += 1;
+            version.pre = semver::Prerelease::EMPTY;
+        }
+
+        Ok(version)
+    }
+}
+
+/// Applies all dependency updates to the active profile, then bumps its
+/// modpack version and adds the changes to the changelog.
+pub async fn apply(app: &AppHandle) -> Result<ModpackArgs> {
+    let uuids = {
+        let manager = app.lock_manager();
+        let thunderstore = app.lock_thunderstore();
+        let install_queue = app.install_queue().handle();
+
+        manager
+            .active_profile()
+            .modpack_updates(&thunderstore, &install_queue)?
+            .updates
+            .into_iter()
+            .map(|update| update.uuid)
+            .collect_vec()
+    };
+
+    if !uuids.is_empty() {
+        update::update_mods(uuids, true, app).await?;
+    }
+
+    let mut manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    let game = manager.active_game().game;
+    let profile = manager.active_profile_mut();
+
+    let mut args = profile
+        .modpack
+        .clone()
+        .ok_or_eyre("profile is not a modpack source")?;
+
+    args.version_number = profile.next_pack_version(&args)?.to_string();
+
+    if let Err(err) = changelog::generate_latest(&mut args, profile, game, &thunderstore) {
+        warn!("failed to generate changelog for modpack update: {:#}", err);
+    }
+
+    profile.modpack = Some(args.clone());
+    profile.save(app, true)?;
+
+    Ok(args)
+}
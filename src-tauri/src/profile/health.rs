@@ -0,0 +1,216 @@
+//! Health checks look for problems in the active game's profiles and the download
+//! cache: mod files that went missing, dependencies that aren't installed, broken
+//! cache entries and cached mods that no profile uses anymore.
+//!
+//! Checks can be run on demand, or scheduled with [`Prefs::scheduled_health_checks`]
+//! to run about once a day while nothing is being installed. The latest report is
+//! stored in the data directory.
+//!
+//! [`Prefs::scheduled_health_checks`]: crate::prefs::Prefs::scheduled_health_checks
+
+use std::{fs, path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, info, warn};
+
+use super::{install::cache, Profile};
+use crate::{
+    prefs::Prefs,
+    state::ManagerExt,
+    thunderstore::Thunderstore,
+    util::{self, fs::JsonStyle},
+};
+
+const FILE_NAME: &str = "health.json";
+/// How often the scheduler wakes up to see if a check is due.
+const POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const CHECK_INTERVAL: chrono::TimeDelta = chrono::TimeDelta::hours(24);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub checked_at: DateTime<Utc>,
+    /// Slug of the game whose profiles were checked.
+    pub game: String,
+    pub issues: Vec<HealthIssue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthIssue {
+    pub kind: HealthIssueKind,
+    /// Name of the affected profile, if the issue is tied to one.
+    pub profile: Option<String>,
+    /// The mod or file the issue is about.
+    pub subject: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthIssueKind {
+    /// An installed mod has no files in the profile.
+    MissingFiles,
+    /// A dependency of an installed mod isn't in the profile.
+    MissingDependency,
+    /// An installed mod's cache entry exists, but is empty.
+    EmptyCacheEntry,
+    /// Cached mod versions that no profile uses, which can be cleared.
+    UnusedCache,
+    /// The active profile's loader files are missing from the game directory.
+    MissingLoaderFiles,
+}
+
+fn report_path(prefs: &Prefs) -> PathBuf {
+    prefs.data_dir.join(FILE_NAME)
+}
+
+/// Reads the latest stored report, if any.
+pub fn last_report(app: &AppHandle) -> Option<HealthReport> {
+    let path = report_path(&app.lock_prefs());
+
+    if !path.exists() {
+        return None;
+    }
+
+    util::fs::read_json(&path)
+        .inspect_err(|err| warn!("failed to read health report: {:#}", err))
+        .ok()
+}
+
+/// Runs all checks and stores the report.
+pub fn run(app: &AppHandle) -> Result<HealthReport> {
+    debug!("running health check");
+
+    // this takes all the locks itself, so it has to happen first
+    let unused_cache = cache::prepare_soft_clear(app.clone())?;
+
+    let prefs = app.lock_prefs();
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    let managed_game = manager.active_game();
+    let mut issues = Vec::new();
+
+    for profile in &managed_game.profiles {
+        check_profile(profile, &prefs, &thunderstore, &mut issues);
+    }
+
+    for file in managed_game.missing_loader_files(&prefs)? {
+        issues.push(HealthIssue {
+            kind: HealthIssueKind::MissingLoaderFiles,
+            profile: Some(managed_game.active_profile().name.clone()),
+            subject: file,
+        });
+    }
+
+    if !unused_cache.is_empty() {
+        issues.push(HealthIssue {
+            kind: HealthIssueKind::UnusedCache,
+            profile: None,
+            subject: format!("{} cached mod versions", unused_cache.len()),
+        });
+    }
+
+    let report = HealthReport {
+        checked_at: Utc::now(),
+        game: managed_game.game.slug.to_string(),
+        issues,
+    };
+
+    util::fs::write_json(report_path(&prefs), &report, JsonStyle::Pretty)?;
+
+    info!("health check found {} issues", report.issues.len());
+
+    Ok(report)
+}
+
+fn check_profile(
+    profile: &Profile,
+    prefs: &Prefs,
+    thunderstore: &Thunderstore,
+    issues: &mut Vec<HealthIssue>,
+) {
+    let mut push = |kind, subject: String| {
+        issues.push(HealthIssue {
+            kind,
+            profile: Some(profile.name.clone()),
+            subject,
+        })
+    };
+
+    for profile_mod in &profile.mods {
+        let Some((ts_mod, _)) = profile_mod.as_thunderstore() else {
+            continue;
+        };
+
+        let ident = &ts_mod.ident;
+
+        let mod_dir = profile
+            .game
+            .installer_for(ident.full_name())
+            .mod_dir(ident.full_name(), profile);
+
+        if mod_dir.is_some_and(|dir| !dir.exists()) {
+            push(HealthIssueKind::MissingFiles, ident.to_string());
+        }
+
+        let cache_path = cache::path(ident, profile.game, prefs);
+        let empty_cache = fs::read_dir(&cache_path).is_ok_and(|mut dir| dir.next().is_none());
+
+        if empty_cache {
+            push(HealthIssueKind::EmptyCacheEntry, ident.to_string());
+        }
+
+        let missing_deps = profile_mod
+            .dependencies(thunderstore)
+            .filter(|dep| !profile.has_mod(dep.package.uuid));
+
+        for dep in missing_deps {
+            push(
+                HealthIssueKind::MissingDependency,
+                format!("{} (needed by {})", dep.ident(), ident.full_name()),
+            );
+        }
+    }
+}
+
+/// Starts the background scheduler, which runs a check whenever one is due and
+/// the app isn't busy installing anything.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            if !is_due(&app) || app.install_queue().handle().is_processing() {
+                continue;
+            }
+
+            let handle = app.clone();
+            let result = tauri::async_runtime::spawn_blocking(move || run(&handle)).await;
+
+            match result {
+                Ok(Ok(report)) if !report.issues.is_empty() => {
+                    app.emit("health_report", report).ok();
+                }
+                Ok(Ok(_)) => (),
+                Ok(Err(err)) => warn!("scheduled health check failed: {:#}", err),
+                Err(err) => warn!("failed to run scheduled health check: {}", err),
+            }
+        }
+    });
+}
+
+fn is_due(app: &AppHandle) -> bool {
+    if !app.lock_prefs().scheduled_health_checks {
+        return false;
+    }
+
+    last_report(app).map_or(true, |report| {
+        Utc::now() - report.checked_at > CHECK_INTERVAL
+    })
+}
@@ -8,13 +8,14 @@ use uuid::Uuid;
 use crate::{
     profile::{import::ImportOptions, install::InstallOptions},
     state::ManagerExt,
-    thunderstore::{self, VersionIdent},
+    thunderstore::{self, ModId, VersionIdent},
     util::cmd::Result,
 };
 
 use super::{
+    list,
     r2modman::{self},
-    ImportData,
+    ImportData, LocalModMetadata, LocalModPreview,
 };
 
 #[derive(Debug, Serialize, Clone)]
@@ -85,19 +86,41 @@ pub async fn read_profile_base64(base64: String, app: AppHandle) -> Result<Front
 }
 
 #[command]
-pub async fn import_local_mod(path: PathBuf, app: AppHandle) -> Result<()> {
+pub fn preview_local_mod(path: PathBuf, app: AppHandle) -> Result<LocalModPreview> {
+    let preview = super::preview_local_mod(&path, &app)?;
+
+    Ok(preview)
+}
+
+#[command]
+pub fn preview_local_mod_base64(base64: String, app: AppHandle) -> Result<LocalModPreview> {
+    let preview = super::preview_local_mod_base64(&base64, &app)?;
+
+    Ok(preview)
+}
+
+#[command]
+pub async fn import_local_mod(
+    path: PathBuf,
+    metadata: Option<LocalModMetadata>,
+    app: AppHandle,
+) -> Result<()> {
     thunderstore::wait_for_fetch(&app).await;
 
-    super::import_local_mod(path, None, &app, InstallOptions::default()).await?;
+    super::import_local_mod(path, None, metadata, &app, InstallOptions::default()).await?;
 
     Ok(())
 }
 
 #[command]
-pub async fn import_local_mod_base64(base64: String, app: AppHandle) -> Result<()> {
+pub async fn import_local_mod_base64(
+    base64: String,
+    metadata: Option<LocalModMetadata>,
+    app: AppHandle,
+) -> Result<()> {
     thunderstore::wait_for_fetch(&app).await;
 
-    super::import_local_mod_base64(base64, &app, InstallOptions::default()).await?;
+    super::import_local_mod_base64(base64, metadata, &app, InstallOptions::default()).await?;
 
     Ok(())
 }
@@ -118,3 +141,20 @@ pub async fn import_r2modman(path: PathBuf, include: Vec<bool>, app: AppHandle)
 
     Ok(())
 }
+
+#[command]
+pub async fn resolve_mod_list(text: String, app: AppHandle) -> Result<Vec<list::ListMatch>> {
+    thunderstore::wait_for_fetch(&app).await;
+
+    let thunderstore = app.lock_thunderstore();
+    let matches = list::resolve(&text, &thunderstore);
+
+    Ok(matches)
+}
+
+#[command]
+pub async fn install_mod_list(ids: Vec<ModId>, app: AppHandle) -> Result<()> {
+    list::install(ids, &app).await?;
+
+    Ok(())
+}
@@ -0,0 +1,242 @@
+//! Imports mods from a pasted list, either one name or dependency string per line
+//! or CSV rows of `name,version` or `owner,name,version`.
+//!
+//! Lines are first resolved against the package index with [`resolve`], so the
+//! user can review and correct the matches before anything is installed.
+
+use std::collections::HashSet;
+
+use eyre::Result;
+use itertools::Itertools;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::{
+    profile::install::{InstallOptions, InstallResultExt, ModInstall},
+    state::ManagerExt,
+    thunderstore::{BorrowedMod, ModId, PackageListing, Thunderstore},
+};
+
+/// Matches scoring below this aren't suggested at all.
+const MIN_SCORE: f32 = 0.5;
+const MAX_CANDIDATES: usize = 5;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListMatch {
+    /// The line as it was written.
+    input: String,
+    /// Possible packages for the line, best match first.
+    candidates: Vec<ListCandidate>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCandidate {
+    id: ModId,
+    full_name: String,
+    /// Whether the owner and name matched exactly.
+    exact: bool,
+}
+
+#[derive(Debug)]
+struct ListEntry<'a> {
+    owner: Option<&'a str>,
+    name: &'a str,
+    version: Option<&'a str>,
+}
+
+pub fn resolve(text: &str, thunderstore: &Thunderstore) -> Vec<ListMatch> {
+    text.lines()
+        .filter_map(|line| {
+            let entry = parse_line(line)?;
+            let candidates = find_candidates(&entry, thunderstore);
+
+            Some(ListMatch {
+                input: line.trim().to_owned(),
+                candidates,
+            })
+        })
+        .collect()
+}
+
+fn parse_line(line: &str) -> Option<ListEntry<'_>> {
+    let line = line.trim();
+
+    if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+        return None;
+    }
+
+    let fields = line
+        .split([',', ';', '\t'])
+        .map(|field| field.trim().trim_matches('"').trim())
+        .filter(|field| !field.is_empty())
+        .collect_vec();
+
+    match *fields.as_slice() {
+        // skip csv headers
+        [first, ..] if ["name", "mod", "owner", "author"].contains(&&*first.to_lowercase()) => None,
+        [owner, name, version, ..] => Some(ListEntry {
+            owner: Some(owner),
+            name,
+            version: Some(version),
+        }),
+        [name, version] => Some(ListEntry {
+            version: Some(version),
+            ..parse_dependency_string(name)
+        }),
+        [name] => Some(parse_dependency_string(name)),
+        [] => None,
+    }
+}
+
+/// Parses `owner-name-version`, `owner-name` or just `name`.
+fn parse_dependency_string(str: &str) -> ListEntry<'_> {
+    let mut parts = str.splitn(3, '-');
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(owner), Some(name), Some(version)) if semver::Version::parse(version).is_ok() => {
+            ListEntry {
+                owner: Some(owner),
+                name,
+                version: Some(version),
+            }
+        }
+        (Some(owner), Some(name), None) if !name.contains(' ') => ListEntry {
+            owner: Some(owner),
+            name,
+            version: None,
+        },
+        _ => ListEntry {
+            owner: None,
+            name: str,
+            version: None,
+        },
+    }
+}
+
+fn find_candidates(entry: &ListEntry, thunderstore: &Thunderstore) -> Vec<ListCandidate> {
+    let name = normalize(entry.name);
+    let owner = entry.owner.map(normalize);
+
+    thunderstore
+        .latest()
+        .filter_map(|borrowed| {
+            let package = borrowed.package;
+
+            let exact = owner
+                .as_ref()
+                .is_some_and(|owner| *owner == normalize(package.owner()))
+                && name == normalize(package.name());
+
+            let score = match exact {
+                true => 2.0,
+                false => fuzzy_score(&name, owner.as_deref(), package),
+            };
+
+            (score >= MIN_SCORE).then_some((borrowed, score, exact))
+        })
+        .sorted_by(|(a, a_score, _), (b, b_score, _)| {
+            b_score.total_cmp(a_score).then(
+                b.package
+                    .total_downloads()
+                    .cmp(&a.package.total_downloads()),
+            )
+        })
+        .take(MAX_CANDIDATES)
+        .map(|(borrowed, _, exact)| {
+            let borrowed = with_version(borrowed, entry.version);
+
+            ListCandidate {
+                id: borrowed.into(),
+                full_name: borrowed.ident().to_string(),
+                exact,
+            }
+        })
+        .collect()
+}
+
+/// Picks the requested version of a package, or its latest one if it's missing.
+fn with_version<'a>(borrowed: BorrowedMod<'a>, version: Option<&str>) -> BorrowedMod<'a> {
+    version
+        .and_then(|version| borrowed.package.get_version_with_num(version))
+        .map_or(borrowed, |version| (borrowed.package, version).into())
+}
+
+fn fuzzy_score(name: &str, owner: Option<&str>, package: &PackageListing) -> f32 {
+    let package_name = normalize(package.name());
+
+    let mut score = if package_name == name {
+        1.0
+    } else {
+        similarity(name, &package_name)
+    };
+
+    if owner.is_some_and(|owner| owner == normalize(package.owner())) {
+        score += 0.25;
+    }
+
+    if package.is_deprecated {
+        score -= 0.1;
+    }
+
+    score
+}
+
+/// Lowercases and strips everything except letters and digits, so
+/// `More Suits`, `MoreSuits` and `more_suits` are treated the same.
+fn normalize(str: &str) -> String {
+    str.chars()
+        .filter(|char| char.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Dice coefficient of the character bigrams of `a` and `b`, between 0 and 1.
+fn similarity(a: &str, b: &str) -> f32 {
+    fn bigrams(str: &str) -> HashSet<(char, char)> {
+        str.chars().tuple_windows().collect()
+    }
+
+    let a = bigrams(a);
+    let b = bigrams(b);
+
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let shared = a.intersection(&b).count();
+    (2 * shared) as f32 / (a.len() + b.len()) as f32
+}
+
+/// Installs the reviewed mods and their dependencies into the active profile.
+pub async fn install(ids: Vec<ModId>, app: &AppHandle) -> Result<()> {
+    let (profile_id, installs) = {
+        let manager = app.lock_manager();
+        let thunderstore = app.lock_thunderstore();
+        let profile = manager.active_profile();
+
+        let installs = ids
+            .into_iter()
+            .unique_by(|id| id.package_uuid)
+            .map(|id| ModInstall::try_from_id(id, &thunderstore))
+            .collect::<Result<Vec<_>>>()?;
+
+        (profile.id, installs)
+    };
+
+    if installs.is_empty() {
+        return Ok(());
+    }
+
+    app.install_queue()
+        .install_with_deps(
+            installs,
+            profile_id,
+            InstallOptions::default().cancel_individually(),
+            true,
+            app,
+        )?
+        .await
+        .ignore_cancel()
+}
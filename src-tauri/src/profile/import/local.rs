@@ -6,46 +6,178 @@ use std::{
 
 use base64::{prelude::BASE64_STANDARD, Engine};
 use eyre::{bail, ensure, Context, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 use tempfile::NamedTempFile;
 use uuid::Uuid;
 use zip::ZipArchive;
 
 use crate::{
-    game::mod_loader::{ModLoader, ModLoaderKind},
+    game::mod_loader::ModLoaderKind,
     prefs::Prefs,
-    profile::{install::InstallOptions, LocalMod, Profile, ProfileMod},
+    profile::{
+        install::{deploy::DeployMode, InstallOptions, PlannedFile},
+        LocalMod, Profile, ProfileMod,
+    },
     state::ManagerExt,
     thunderstore::PackageManifest,
     util::{self, fs::PathExt},
 };
 
+/// Metadata entered by the user for archives that don't have a manifest.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModMetadata {
+    pub name: String,
+    pub author: Option<String>,
+    pub version: Option<semver::Version>,
+    pub description: Option<String>,
+}
+
+/// What importing an archive would do, shown before importing it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModPreview {
+    /// Whether the archive has a manifest. If not, the metadata below is guessed
+    /// from the file names and should be confirmed by the user.
+    has_manifest: bool,
+    name: String,
+    author: Option<String>,
+    version: Option<semver::Version>,
+    /// Where each file would end up in the profile.
+    plan: Vec<PlannedFile>,
+}
+
+fn write_temp_file(base64: &str) -> Result<NamedTempFile> {
+    let data = BASE64_STANDARD.decode(base64)?;
+
+    let mut file = NamedTempFile::new().context("failed to create temp file")?;
+    file.write_all(&data).context("failed to write temp file")?;
+
+    Ok(file)
+}
+
 pub async fn import_local_mod_base64(
     base64: String,
+    metadata: Option<LocalModMetadata>,
     app: &AppHandle,
     options: InstallOptions,
 ) -> Result<()> {
-    let data = BASE64_STANDARD.decode(base64)?;
-
-    let mut file = NamedTempFile::new().context("failed to create temp file")?;
-    file.write_all(&data).context("failed to write temp file")?;
+    let file = write_temp_file(&base64)?;
 
     import_local_mod(
         file.path().to_owned(),
         Some(LocalModKind::Zip),
+        metadata,
         app,
         options,
     )
     .await
 }
 
+pub fn preview_local_mod_base64(base64: &str, app: &AppHandle) -> Result<LocalModPreview> {
+    let file = write_temp_file(base64)?;
+
+    preview_local_mod(file.path(), app)
+}
+
+/// Inspects a zip archive to find out where its files would go, and which name and
+/// version it'd be imported with.
+pub fn preview_local_mod(path: &Path, app: &AppHandle) -> Result<LocalModPreview> {
+    let files = util::fs::open_zip(path)
+        .context("failed to open zip archive")?
+        .file_names()
+        .filter(|name| !name.ends_with('/'))
+        .map(PathBuf::from)
+        .collect_vec();
+
+    let (has_manifest, name, author, version) = match read_zip_manifest(path)? {
+        Some(manifest) => (
+            true,
+            manifest.name,
+            manifest.author,
+            Some(manifest.version_number),
+        ),
+        None => {
+            let (name, version) = guess_metadata(path, &files);
+            (false, name, None, version)
+        }
+    };
+
+    let game = app.lock_manager().active_game;
+    let plan = game.installer_for(&name).plan(&files, &name)?;
+
+    Ok(LocalModPreview {
+        has_manifest,
+        name,
+        author,
+        version,
+        plan,
+    })
+}
+
+/// Guesses a name and version from file names like `MyMod-1.2.0.zip` or `MyMod v1.2.zip`.
+/// If the archive has a single dll, its name is preferred, since zip names are often
+/// made up by whoever uploaded them.
+fn guess_metadata(path: &Path, files: &[PathBuf]) -> (String, Option<semver::Version>) {
+    let stem = util::fs::file_name_owned(path.with_extension(""));
+
+    let (name, version) = match stem.rsplit_once(['-', '_', ' ']) {
+        Some((name, version)) => match parse_loose_version(version) {
+            Some(version) => (name.to_owned(), Some(version)),
+            None => (stem.clone(), None),
+        },
+        None => (stem.clone(), None),
+    };
+
+    let dlls = files
+        .iter()
+        .filter(|file| file.extension().is_some_and(|ext| ext == "dll"))
+        .collect_vec();
+
+    let name = match dlls.as_slice() {
+        [dll] => util::fs::file_name_owned(dll.with_extension("")),
+        _ => name,
+    };
+
+    // thunderstore names only allow these characters, which keeps the mod's folder tidy
+    let name = name
+        .trim()
+        .chars()
+        .map(|char| {
+            if char.is_ascii_alphanumeric() {
+                char
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    (name, version)
+}
+
+/// Accepts a leading `v` and versions with fewer than three parts, like `v1.2`.
+fn parse_loose_version(str: &str) -> Option<semver::Version> {
+    let str = str.trim_start_matches(['v', 'V']);
+    let parts = str.split('.').count();
+
+    if !(1..=3).contains(&parts) || str.split('.').any(|part| part.parse::<u64>().is_err()) {
+        return None;
+    }
+
+    let padded = format!("{}{}", str, ".0".repeat(3 - parts));
+    semver::Version::parse(&padded).ok()
+}
+
 pub async fn import_local_mod(
     path: PathBuf,
     override_kind: Option<LocalModKind>,
+    metadata: Option<LocalModMetadata>,
     app: &AppHandle,
     options: InstallOptions,
 ) -> Result<()> {
-    let (mut local_mod, kind) = read_local_mod(&path, override_kind)?;
+    let (mut local_mod, kind) = read_local_mod(&path, override_kind, metadata)?;
 
     if let Some(deps) = &local_mod.dependencies {
         let (profile_id, mods) = {
@@ -88,7 +220,7 @@ pub async fn import_local_mod(
 
     match kind {
         LocalModKind::Zip => {
-            let mod_dir = install_from_zip(&path, profile, &local_mod.name, mod_loader, &prefs)
+            let mod_dir = install_from_zip(&path, profile, &local_mod.name, &prefs)
                 .context("install error")?;
 
             if let Some(mod_dir) = mod_dir {
@@ -130,6 +262,7 @@ pub enum LocalModKind {
 fn read_local_mod(
     path: &Path,
     override_kind: Option<LocalModKind>,
+    metadata: Option<LocalModMetadata>,
 ) -> Result<(LocalMod, LocalModKind)> {
     ensure!(path.is_file(), "path is not a file");
 
@@ -148,8 +281,8 @@ fn read_local_mod(
     let uuid = Uuid::new_v4();
     let file_size = path.metadata()?.len();
 
-    let local_mod = match manifest {
-        Some(manifest) => LocalMod {
+    let local_mod = match (manifest, metadata) {
+        (Some(manifest), _) => LocalMod {
             uuid,
             file_size,
             name: manifest.name,
@@ -159,7 +292,20 @@ fn read_local_mod(
             dependencies: Some(manifest.dependencies),
             ..Default::default()
         },
-        None => LocalMod {
+        (None, Some(metadata)) => {
+            ensure!(!metadata.name.trim().is_empty(), "name cannot be empty");
+
+            LocalMod {
+                uuid,
+                file_size,
+                name: metadata.name.trim().to_owned(),
+                author: metadata.author.filter(|author| !author.trim().is_empty()),
+                description: metadata.description.filter(|desc| !desc.trim().is_empty()),
+                version: metadata.version,
+                ..Default::default()
+            }
+        }
+        (None, None) => LocalMod {
             uuid,
             file_size,
             name: util::fs::file_name_owned(path.with_extension("")),
@@ -176,29 +322,33 @@ fn read_zip_manifest(path: &Path) -> Result<Option<PackageManifest>> {
     let manifest = zip.by_name("manifest.json");
 
     match manifest {
-        Ok(mut file) => {
-            let mut str = String::with_capacity(file.size() as usize);
-            file.read_to_string(&mut str)
+        Ok(file) => {
+            // the size is whatever the archive claims, so cap what we reserve
+            let mut str = String::with_capacity(file.size().min(MAX_MANIFEST_SIZE) as usize);
+            file.take(MAX_MANIFEST_SIZE)
+                .read_to_string(&mut str)
                 .context("failed to read manifest")?;
 
-            // remove BOM
-            if str.starts_with("\u{feff}") {
-                str.replace_range(0..3, "");
-            }
-
-            serde_json::from_str(&str)
-                .context("failed to parse manifest")
-                .map(Some)
+            parse_manifest(&str).map(Some)
         }
         Err(_) => Ok(None),
     }
 }
 
+/// Manifests are a few hundred bytes, anything bigger than this is not a real one.
+const MAX_MANIFEST_SIZE: u64 = 1024 * 1024;
+
+fn parse_manifest(text: &str) -> Result<PackageManifest> {
+    // some tools write a BOM, which serde_json doesn't accept
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+
+    serde_json::from_str(text).context("failed to parse manifest")
+}
+
 fn install_from_zip(
     src: &Path,
     profile: &Profile,
     package_name: &str,
-    mod_loader: &'static ModLoader,
     prefs: &Prefs,
 ) -> Result<Option<PathBuf>> {
     // dont use tempdir since we need the files on the same drive as the destination
@@ -212,9 +362,16 @@ fn install_from_zip(
         .context("failed to read file")?;
     let archive = ZipArchive::new(reader).context("failed to read archive")?;
 
-    let mut installer = mod_loader.installer_for(package_name);
+    // the extracted files are removed afterwards, so symlinks to them would break
+    let deployer = match prefs.deploy_mode(profile.game) {
+        DeployMode::Symlink => DeployMode::HardLink,
+        mode => mode,
+    }
+    .deployer();
+
+    let mut installer = profile.game.installer_for(package_name);
     installer.extract(archive, package_name, temp_path.clone())?;
-    installer.install(&temp_path, package_name, profile)?;
+    installer.install(&temp_path, package_name, profile, deployer)?;
 
     fs::remove_dir_all(temp_path).context("failed to remove temporary directory")?;
 
@@ -230,3 +387,54 @@ fn read_text_file(mod_dir: &Path, file: &str) -> Result<Option<String>> {
         .map(|path| fs::read_to_string(path).with_context(|| format!("failed to read {file}")))
         .transpose()
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn manifest_json(name: &str, version: &str, description: &str) -> String {
+        serde_json::json!({
+            "name": name,
+            "version_number": version,
+            "description": description,
+            "website_url": "",
+            "dependencies": ["BepInEx-BepInExPack-5.4.2100"],
+        })
+        .to_string()
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(text in any::<String>()) {
+            let _ = parse_manifest(&text);
+        }
+
+        #[test]
+        fn never_panics_on_json_like_input(
+            text in r#"\{("[a-z_]{0,16}": ?("[^"]{0,16}"|[0-9.]{0,8}|\[\]|null),? ?){0,6}\}"#
+        ) {
+            let _ = parse_manifest(&text);
+        }
+
+        #[test]
+        fn bom_is_ignored(
+            name in "[A-Za-z0-9_]{1,32}",
+            version in (0u64..100, 0u64..100, 0u64..10000),
+            description in any::<String>(),
+        ) {
+            let version = format!("{}.{}.{}", version.0, version.1, version.2);
+            let json = manifest_json(&name, &version, &description);
+
+            let plain = parse_manifest(&json).unwrap();
+            let with_bom = parse_manifest(&format!("\u{feff}{json}")).unwrap();
+
+            prop_assert_eq!(&plain.name, &name);
+            prop_assert_eq!(plain.version_number.to_string(), version);
+            prop_assert_eq!(plain.name, with_bom.name);
+            prop_assert_eq!(plain.description, with_bom.description);
+            prop_assert_eq!(plain.dependencies, with_bom.dependencies);
+        }
+    }
+}
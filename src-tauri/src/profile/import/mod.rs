@@ -6,7 +6,7 @@ use std::{
 };
 
 use base64::{prelude::BASE64_STANDARD, Engine};
-use eyre::{eyre, Context, Result};
+use eyre::{eyre, Context, OptionExt, Result};
 use itertools::Itertools;
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
@@ -21,15 +21,19 @@ use crate::{
         install::{InstallOptions, ModInstall},
     },
     state::ManagerExt,
-    thunderstore::ModId,
+    thunderstore::{self, ModId},
     util::{self, error::IoResultExt},
 };
 
 pub mod commands;
+mod list;
 mod local;
 mod r2modman;
 
-pub use local::{import_local_mod, import_local_mod_base64};
+pub use local::{
+    import_local_mod, import_local_mod_base64, preview_local_mod, preview_local_mod_base64,
+    LocalModMetadata, LocalModPreview,
+};
 
 use super::{
     export::{self, IncludeExtensions, IncludeGenerated},
@@ -83,10 +87,18 @@ fn read_base64(base64: &str) -> Result<ImportData> {
 }
 
 pub async fn read_code(key: Uuid, app: &AppHandle) -> Result<ImportData> {
+    let bytes = fetch_code(key, app).await?;
+
+    read_file(Cursor::new(bytes))
+}
+
+/// Downloads the zip behind a profile code.
+pub async fn fetch_code(key: Uuid, app: &AppHandle) -> Result<Vec<u8>> {
     let response = app
         .http()
         .get(format!(
-            "https://thunderstore.io/api/experimental/legacyprofile/get/{key}/"
+            "{}/api/experimental/legacyprofile/get/{key}/",
+            *thunderstore::API_URL
         ))
         .send()
         .await?
@@ -100,10 +112,15 @@ pub async fn read_code(key: Uuid, app: &AppHandle) -> Result<ImportData> {
         .text()
         .await?;
 
-    match response.strip_prefix(PROFILE_DATA_PREFIX) {
-        Some(str) => read_base64(str),
-        None => Err(eyre!("invalid profile data")),
-    }
+    let base64 = response
+        .strip_prefix(PROFILE_DATA_PREFIX)
+        .ok_or_eyre("invalid profile data")?;
+
+    let bytes = BASE64_STANDARD
+        .decode(base64)
+        .context("failed to decode base64 data")?;
+
+    Ok(bytes)
 }
 
 #[derive(Debug, Default)]
@@ -141,11 +158,20 @@ pub(super) async fn import_profile(
 
     let result = match result {
         Ok(()) => {
+            let local_only_config = {
+                let mut manager = app.lock_manager();
+                let (_, profile) = manager.profile_by_id_mut(profile_id)?;
+
+                profile.refresh_config();
+                profile.local_only_config()
+            };
+
             import_config(
                 &profile_path,
                 &data.path,
                 options.included_extensions(),
                 IncludeGenerated::No,
+                &local_only_config,
             )
             .context("error importing config")?;
 
@@ -180,6 +206,9 @@ fn prepare_import(
         name,
         mods,
         ignored_updates,
+        workshop_items,
+        notes,
+        readme,
         ..
     } = manifest;
 
@@ -209,6 +238,11 @@ fn prepare_import(
     };
 
     profile.ignored_updates = ignored_updates.into_iter().collect();
+    profile.workshop_items = workshop_items;
+    if notes.is_some() {
+        profile.notes = notes;
+    }
+    profile.readme = readme;
 
     let id = profile.id;
     let path = profile.path.clone();
@@ -218,6 +252,38 @@ fn prepare_import(
     Ok((id, path, to_install))
 }
 
+/// Overwrites `files` in `dest` with their copies from an extracted profile at `src`,
+/// returning how many were changed.
+///
+/// Only files that would be imported as config are considered, so a manifest
+/// can't be used to write anywhere else.
+pub fn restore_config_files(dest: &Path, src: &Path, files: &[PathBuf]) -> Result<usize> {
+    let mut count = 0;
+
+    for file in export::find_config(src, IncludeExtensions::Default, IncludeGenerated::No) {
+        if !files.contains(&file) {
+            continue;
+        }
+
+        let src_path = src.join(&file);
+        let dest_path = dest.join(&file);
+
+        let unchanged =
+            dest_path.exists() && util::fs::checksum(&src_path)? == util::fs::checksum(&dest_path)?;
+
+        if unchanged {
+            continue;
+        }
+
+        trace!("restore {}", file.display());
+        fs::create_dir_all(dest_path.parent().unwrap())?;
+        fs::copy(src_path, dest_path)?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
 fn cleanup_failed_profile(profile_id: i64, app: &AppHandle) -> Result<()> {
     let mut manager = app.lock_manager();
 
@@ -232,19 +298,35 @@ fn cleanup_failed_profile(profile_id: i64, app: &AppHandle) -> Result<()> {
     Ok(())
 }
 
-fn incremental_update(
+pub(super) fn incremental_update(
     installs: impl IntoIterator<Item = ModInstall>,
     profile: &mut Profile,
 ) -> Result<impl Iterator<Item = ModInstall>> {
-    let current_mods: HashMap<ModId, bool> = profile
-        .thunderstore_mods()
-        .map(|(ts_mod, enabled)| (ts_mod.id.clone(), enabled))
+    // local only mods are left as they are, whether or not the imported profile has them
+    let (local_only, current_mods): (Vec<_>, Vec<_>) = profile
+        .mods
+        .iter()
+        .filter_map(|profile_mod| {
+            let (ts_mod, enabled) = profile_mod.as_thunderstore()?;
+            Some((profile_mod.local_only, ts_mod.id.clone(), enabled))
+        })
+        .partition(|(local_only, _, _)| *local_only);
+
+    let local_only: HashSet<Uuid> = local_only
+        .into_iter()
+        .map(|(_, id, _)| id.package_uuid)
+        .collect();
+
+    let current_mods: HashMap<ModId, bool> = current_mods
+        .into_iter()
+        .map(|(_, id, enabled)| (id, enabled))
         .collect();
 
     let current_ids: HashSet<&ModId> = current_mods.keys().collect();
 
     let mut new_mods: HashMap<ModId, ModInstall> = installs
         .into_iter()
+        .filter(|install| !local_only.contains(&install.uuid()))
         .map(|install| (install.mod_id().clone(), install))
         .collect();
 
@@ -277,11 +359,16 @@ fn incremental_update(
     Ok(to_install)
 }
 
+/// Copies config files from `src` to `dest`.
+///
+/// Unless all extensions are included, files missing from `src` are removed,
+/// except for those in `keep`.
 pub fn import_config(
     dest: &Path,
     src: &Path,
     extensions: IncludeExtensions,
     generated: IncludeGenerated,
+    keep: &[PathBuf],
 ) -> Result<()> {
     let existing_files = export::find_config(dest, extensions, generated);
     let source_files = export::find_config(src, extensions, generated);
@@ -293,7 +380,7 @@ pub fn import_config(
                     .strip_prefix("BepInEx/config")
                     .is_ok_and(|suffix| src.join("config").join(suffix).exists());
 
-            if !exists {
+            if !exists && !keep.contains(&file) {
                 trace!("remove {}", file.display());
                 fs::remove_file(dest.join(&file))?;
             }
@@ -162,6 +162,10 @@ fn prepare_import(mut profile_dir: PathBuf, app: &AppHandle) -> Result<Option<Im
             mods,
             game: None,
             ignored_updates: Vec::new(),
+            workshop_items: Vec::new(),
+            notes: None,
+            readme: None,
+            enforced_config: Vec::new(),
         },
         path: profile_dir,
         delete_after_import: false,
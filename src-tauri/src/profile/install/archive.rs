@@ -0,0 +1,62 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+use tracing::{debug, warn};
+
+use crate::{prefs::Prefs, thunderstore::VersionIdent, util::error::IoResultExt};
+
+/// Gets the path of a mod's original zip in the user's archive directory,
+/// or `None` if archiving is disabled.
+pub fn path(ident: &VersionIdent, prefs: &Prefs) -> Option<PathBuf> {
+    prefs
+        .archive_dir
+        .as_ref()
+        .map(|dir| dir.join(format!("{ident}.zip")))
+}
+
+/// Reads a mod's zip from the archive, if it exists.
+pub(super) fn read(ident: &VersionIdent, prefs: &Prefs) -> Result<Option<Vec<u8>>> {
+    match path(ident, prefs) {
+        Some(path) => read_path(ident, &path),
+        None => Ok(None),
+    }
+}
+
+/// Reads a mod's zip from a folder laid out like the archive.
+pub(super) fn read_in(dir: &Path, ident: &VersionIdent) -> Result<Option<Vec<u8>>> {
+    read_path(ident, &dir.join(format!("{ident}.zip")))
+}
+
+fn read_path(ident: &VersionIdent, path: &Path) -> Result<Option<Vec<u8>>> {
+    match fs::read(path) {
+        Ok(data) => {
+            debug!("found {} in archive", ident);
+            Ok(Some(data))
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).fs_context("reading archived mod", path),
+    }
+}
+
+/// Copies a freshly downloaded zip to the archive. Failures are only logged,
+/// since the archive is a convenience and shouldn't break installation.
+pub(super) fn write(ident: &VersionIdent, data: &[u8], prefs: &Prefs) {
+    let Some(path) = path(ident, prefs) else {
+        return;
+    };
+
+    if path.exists() {
+        return;
+    }
+
+    let result = fs::create_dir_all(path.parent().unwrap())
+        .and_then(|_| fs::write(&path, data))
+        .fs_context("writing archived mod", &path);
+
+    if let Err(err) = result {
+        warn!("failed to archive {}: {:#}", ident, err);
+    }
+}
@@ -0,0 +1,128 @@
+//! Installs every package by one author in a single batch, which is handy for
+//! cosmetic and emote collections that are split up into many small packages.
+
+use std::collections::HashSet;
+
+use eyre::{ensure, Result};
+use itertools::Itertools;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use super::{cache, InstallOptions, InstallResultExt, ModInstall};
+use crate::{
+    prefs::Prefs,
+    profile::Profile,
+    state::ManagerExt,
+    thunderstore::{BorrowedMod, Thunderstore, VersionIdent},
+};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorBundle {
+    /// Packages by the author that aren't installed yet.
+    packages: Vec<VersionIdent>,
+    /// How many of the author's packages the profile already has.
+    installed: usize,
+    /// Missing dependencies from other authors that would be installed as well.
+    dependencies: usize,
+    /// Combined size of everything that isn't downloaded yet, in bytes.
+    download_size: u64,
+}
+
+/// The latest versions of the author's packages, leaving out deprecated ones and modpacks.
+fn bundle_packages<'a>(
+    owner: &'a str,
+    thunderstore: &'a Thunderstore,
+) -> impl Iterator<Item = BorrowedMod<'a>> + 'a {
+    thunderstore.latest().filter(move |borrowed| {
+        borrowed.package.owner().eq_ignore_ascii_case(owner)
+            && !borrowed.package.is_deprecated
+            && !borrowed.package.is_modpack()
+    })
+}
+
+fn preview_bundle(
+    owner: &str,
+    profile: &Profile,
+    prefs: &Prefs,
+    thunderstore: &Thunderstore,
+) -> AuthorBundle {
+    let (installed, missing): (Vec<_>, Vec<_>) = bundle_packages(owner, thunderstore)
+        .partition(|borrowed| profile.has_mod(borrowed.package.uuid));
+
+    let bundle_uuids = missing
+        .iter()
+        .map(|borrowed| borrowed.package.uuid)
+        .collect::<HashSet<_>>();
+
+    let dependencies = missing
+        .iter()
+        .flat_map(|borrowed| profile.missing_deps(borrowed.dependencies(), thunderstore))
+        .filter(|dep| !bundle_uuids.contains(&dep.package.uuid))
+        .unique_by(|dep| dep.package.uuid)
+        .collect_vec();
+
+    let download_size = missing
+        .iter()
+        .chain(&dependencies)
+        .filter(|borrowed| !cache::exists(&cache::path(borrowed.ident(), profile.game, prefs)))
+        .map(|borrowed| borrowed.version.file_size)
+        .sum();
+
+    AuthorBundle {
+        packages: missing
+            .iter()
+            .map(|borrowed| borrowed.ident().clone())
+            .collect(),
+        installed: installed.len(),
+        dependencies: dependencies.len(),
+        download_size,
+    }
+}
+
+/// Shows what installing all of an author's packages would do to the active profile.
+pub fn preview(owner: &str, app: &AppHandle) -> Result<AuthorBundle> {
+    let prefs = app.lock_prefs();
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    Ok(preview_bundle(
+        owner,
+        manager.active_profile(),
+        &prefs,
+        &thunderstore,
+    ))
+}
+
+/// Installs all of the author's packages that the active profile doesn't have yet.
+pub async fn install(owner: &str, app: &AppHandle) -> Result<()> {
+    let (profile_id, installs) = {
+        let manager = app.lock_manager();
+        let thunderstore = app.lock_thunderstore();
+        let profile = manager.active_profile();
+
+        let installs = bundle_packages(owner, &thunderstore)
+            .filter(|borrowed| !profile.has_mod(borrowed.package.uuid))
+            .map(ModInstall::new)
+            .collect_vec();
+
+        (profile.id, installs)
+    };
+
+    ensure!(
+        !installs.is_empty(),
+        "all packages by {} are already installed",
+        owner
+    );
+
+    app.install_queue()
+        .install_with_deps(
+            installs,
+            profile_id,
+            InstallOptions::default().cancel_individually(),
+            true,
+            app,
+        )?
+        .await
+        .ignore_cancel()
+}
@@ -1,20 +1,257 @@
-use std::{collections::HashSet, fs, path::PathBuf};
+//! The download cache holds extracted mods, which are installed into profiles by
+//! linking or copying their files.
+//!
+//! Downloads can be shared between the users of a machine (see [`Prefs::shared_cache`]).
+//! Only the original zips are shared, each user still extracts them into their own
+//! cache. That way nobody can tamper with the files another user installs from.
+//!
+//! A zip is only shared once its hash matches the one recorded from Thunderstore, and
+//! that hash is shared next to it. Both files can only be changed by the user who
+//! shared them, so the zip is used as long as it still matches. Someone could still
+//! share a zip they changed themselves before anyone else downloads that version,
+//! so turning sharing on means trusting the other users.
+
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Cursor, Write},
+    path::{Path, PathBuf},
+};
 
 use eyre::{Context, Result};
-use tracing::info;
 use tauri::AppHandle;
+use tempfile::NamedTempFile;
+use tracing::{debug, info, instrument, warn};
+use zip::ZipArchive;
 
-use crate::{prefs::Prefs, state::ManagerExt, thunderstore::VersionIdent, util};
+use super::{compress, installers::ScriptInstaller};
+use crate::{
+    game::Game,
+    prefs::Prefs,
+    state::ManagerExt,
+    thunderstore::VersionIdent,
+    util::{self, error::IoResultExt},
+};
 
-pub(super) fn path(ident: &VersionIdent, prefs: &Prefs) -> PathBuf {
+/// Gets where a mod is extracted to for `game`. Mods extracted by the game's installer
+/// script get their own entry for each version of the script (see [`ScriptInstaller::cache_key`]).
+pub fn path(ident: &VersionIdent, game: Game, prefs: &Prefs) -> PathBuf {
     let mut path = prefs.cache_dir();
 
     path.push(ident.full_name());
-    path.push(ident.version());
+    match ScriptInstaller::cache_key(&game.slug, ident.full_name()) {
+        Some(key) => path.push(format!("{}+{}", ident.version(), key)),
+        None => path.push(ident.version()),
+    }
 
     path
 }
 
+/// Whether a usable entry exists at `cache_path`. Entries that were left half
+/// compressed count as missing, see [`compress::is_partial`].
+pub fn exists(cache_path: &Path) -> bool {
+    cache_path.exists() && !compress::is_partial(cache_path)
+}
+
+/// Extracts a mod archive into `cache_path` (see [`path`]) using the game's installer,
+/// then compresses it if `compressed` is set.
+#[instrument(name = "extract", skip_all, fields(package = %ident))]
+pub fn insert(
+    data: Vec<u8>,
+    ident: &VersionIdent,
+    game: Game,
+    cache_path: &Path,
+    compressed: bool,
+) -> Result<()> {
+    let package_name = ident.full_name();
+
+    if compress::is_partial(cache_path) {
+        fs::remove_dir_all(cache_path).fs_context("removing partial cache entry", cache_path)?;
+    }
+
+    fs::create_dir_all(cache_path).fs_context("creating mod cache dir", cache_path)?;
+
+    let mut installer = game.installer_for(package_name);
+    let archive = ZipArchive::new(Cursor::new(data)).context("failed to open archive")?;
+
+    let result = installer
+        .extract(archive, package_name, cache_path.to_path_buf())
+        .context("error while extracting")
+        .and_then(|()| {
+            if compressed {
+                compress::compress_entry(cache_path).context("error while compressing")
+            } else {
+                Ok(())
+            }
+        });
+
+    if result.is_err() {
+        // the cached mod is probably in an invalid state, so remove it
+        fs::remove_dir_all(cache_path).unwrap_or_else(|err| {
+            warn!(
+                "failed to clean up after failed extraction of {}: {:#}",
+                ident, err
+            );
+        });
+    }
+
+    result
+}
+
+/// Gets the path of a mod's zip in the shared cache, or `None` if the cache isn't shared.
+pub fn shared_path(ident: &VersionIdent, prefs: &Prefs) -> Option<PathBuf> {
+    prefs
+        .shared_cache
+        .then(|| util::path::shared_cache_dir().join(format!("{ident}.zip")))
+}
+
+/// Where the hash of a shared zip is kept.
+fn shared_hash_path(zip_path: &Path) -> PathBuf {
+    zip_path.with_extension("zip.blake3")
+}
+
+/// Reads a mod's zip from the shared cache, if another user has downloaded it.
+///
+/// The zip is only returned if it matches the hash its sharer recorded, and neither
+/// file can have been changed by anyone else since.
+pub(super) fn read_shared(ident: &VersionIdent, prefs: &Prefs) -> Result<Option<Vec<u8>>> {
+    let Some(path) = shared_path(ident, prefs) else {
+        return Ok(None);
+    };
+
+    let hash_path = shared_hash_path(&path);
+
+    let (Some(data), Some(expected)) = (read_optional(&path)?, read_optional(&hash_path)?) else {
+        return Ok(None);
+    };
+
+    if !is_trusted(&path, &hash_path)? {
+        warn!(
+            "shared copy of {} could have been tampered with, ignoring it",
+            ident
+        );
+        return Ok(None);
+    }
+
+    if blake3::hash(&data).to_hex().as_bytes() != expected.trim_ascii() {
+        warn!(
+            "shared copy of {} doesn't match its hash, ignoring it",
+            ident
+        );
+        return Ok(None);
+    }
+
+    debug!("found {} in shared cache", ident);
+    Ok(Some(data))
+}
+
+fn read_optional(path: &Path) -> Result<Option<Vec<u8>>> {
+    match fs::read(path) {
+        Ok(data) => Ok(Some(data)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).fs_context("reading shared mod", path),
+    }
+}
+
+/// Whether a shared zip and its hash were both written by the same user, and nobody
+/// else can write to them.
+#[cfg(unix)]
+fn is_trusted(zip_path: &Path, hash_path: &Path) -> Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let zip = fs::symlink_metadata(zip_path).fs_context("reading shared mod", zip_path)?;
+    let hash = fs::symlink_metadata(hash_path).fs_context("reading shared mod", hash_path)?;
+
+    let is_private = |metadata: &fs::Metadata| metadata.is_file() && metadata.mode() & 0o022 == 0;
+
+    Ok(zip.uid() == hash.uid() && is_private(&zip) && is_private(&hash))
+}
+
+/// Files created in `ProgramData` can only be changed by their creator by default.
+#[cfg(not(unix))]
+fn is_trusted(zip_path: &Path, hash_path: &Path) -> Result<bool> {
+    let is_file = |path: &Path| fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_file());
+
+    Ok(is_file(zip_path) && is_file(hash_path))
+}
+
+/// Adds a download to the shared cache. Only call this once `data` is known to match
+/// the hash recorded from Thunderstore. Failures are only logged, like for the archive.
+pub(super) fn write_shared(ident: &VersionIdent, data: &[u8], prefs: &Prefs) {
+    let Some(path) = shared_path(ident, prefs) else {
+        return;
+    };
+
+    if path.exists() {
+        return;
+    }
+
+    let hash = blake3::hash(data).to_hex();
+
+    // the hash goes second, so a zip is never used before it's complete
+    let result = write_shared_file(&path, data)
+        .and_then(|()| write_shared_file(&shared_hash_path(&path), hash.as_bytes()));
+
+    if let Err(err) = result {
+        warn!("failed to share {}: {:#}", ident, err);
+    }
+}
+
+fn write_shared_file(path: &Path, data: &[u8]) -> Result<()> {
+    let dir = path.parent().unwrap();
+    share(dir)?;
+
+    // write to a temporary file first, so others never read a partial file
+    let mut file = NamedTempFile::new_in(dir).fs_context("creating shared mod", dir)?;
+    file.write_all(data)
+        .fs_context("writing shared mod", path)?;
+    make_readable(file.path());
+
+    // another user may have gotten there first, in which case theirs is kept
+    file.persist_noclobber(path)
+        .map_err(|err| err.error)
+        .fs_context("saving shared mod", path)?;
+
+    Ok(())
+}
+
+/// Prepares `dir` to be used as a shared cache, letting every user add zips to it.
+pub fn share(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir).fs_context("creating shared cache", dir)?;
+    open_to_others(dir);
+
+    Ok(())
+}
+
+/// Lets other users create files in `dir`, but only remove their own.
+///
+/// Fails silently, since directories created by other users can't be changed
+/// and have already been opened up by them anyway.
+#[cfg(unix)]
+fn open_to_others(dir: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o1777))
+        .unwrap_or_else(|err| debug!("failed to open up {}: {}", dir.display(), err));
+}
+
+/// Users can already create folders in `ProgramData`, which is where the shared
+/// cache goes by default.
+#[cfg(not(unix))]
+fn open_to_others(_dir: &Path) {}
+
+/// Temporary files are only readable by their owner by default.
+#[cfg(unix)]
+fn make_readable(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o644))
+        .unwrap_or_else(|err| debug!("failed to set permissions of {}: {}", path.display(), err));
+}
+
+#[cfg(not(unix))]
+fn make_readable(_path: &Path) {}
+
 pub(super) fn clear(path: PathBuf) -> Result<()> {
     if path.exists() {
         fs::remove_dir_all(&path).context("failed to delete cache directory")?;
@@ -24,22 +261,21 @@ pub(super) fn clear(path: PathBuf) -> Result<()> {
     Ok(())
 }
 
-pub(super) fn prepare_soft_clear(app: AppHandle) -> Result<Vec<PathBuf>> {
+pub fn prepare_soft_clear(app: AppHandle) -> Result<Vec<PathBuf>> {
     let prefs = app.lock_prefs();
     let manager = app.lock_manager();
     let thunderstore = app.lock_thunderstore();
 
-    let installed_mods = manager
-        .active_game()
+    // compare whole paths, so entries left behind by an older installer script go too
+    let active_game = manager.active_game();
+    let installed_mods = active_game
         .installed_mods(&thunderstore)
-        .map(|borrowed| {
-            let ident = borrowed.ident();
-            (ident.full_name(), ident.version())
-        })
+        .map(|borrowed| path(borrowed.ident(), active_game.game, &prefs))
         .collect::<HashSet<_>>();
 
-    let packages = prefs
-        .cache_dir()
+    let cache_dir = prefs.cache_dir();
+
+    let packages = cache_dir
         .read_dir()
         .context("failed to read cache directory")?
         .filter_map(Result::ok);
@@ -66,9 +302,8 @@ pub(super) fn prepare_soft_clear(app: AppHandle) -> Result<Vec<PathBuf>> {
 
         for entry in versions {
             let path = entry.path();
-            let version = util::fs::file_name_owned(&path);
 
-            if installed_mods.contains(&(&package_name, &version)) {
+            if installed_mods.contains(&path) {
                 // package is installed, skip
                 continue;
             }
@@ -84,7 +319,7 @@ pub(super) fn do_soft_clear(paths: Vec<PathBuf>) -> Result<()> {
     let count = paths.len();
 
     for path in paths {
-        fs::remove_dir_all(path)?;
+        fs::remove_dir_all(&path).fs_context("removing cached mod", &path)?;
     }
 
     info!("cleared {} mods from cache", count);
@@ -0,0 +1,82 @@
+//! Catches truncated or corrupted downloads before they are extracted.
+//!
+//! Thunderstore doesn't publish hashes of its archives, so the hash of the first
+//! good download of each version is recorded and compared against on later downloads.
+//! Only downloads from Thunderstore itself are recorded. Zips from anywhere else, like
+//! LAN peers, are only used if they match a hash recorded that way.
+
+use std::io::Cursor;
+
+use eyre::{ensure, Context, Result};
+use tauri::AppHandle;
+use tracing::warn;
+use zip::ZipArchive;
+
+use super::ModInstall;
+use crate::state::ManagerExt;
+
+/// The result of checking a downloaded archive.
+pub(super) enum Verdict {
+    Valid,
+    /// The archive is well-formed, but no hash has been recorded for the version yet.
+    Unrecorded {
+        actual: String,
+    },
+    /// The archive is well-formed, but its hash doesn't match an earlier download.
+    Mismatch {
+        expected: String,
+        actual: String,
+    },
+}
+
+fn hash(data: &[u8]) -> String {
+    blake3::hash(data).to_hex().to_string()
+}
+
+/// Checks that `data` is a complete zip archive and compares it against the recorded hash.
+/// Nothing is recorded, so this is safe to use on data from anywhere.
+pub(super) fn check(install: &ModInstall, data: &[u8], app: &AppHandle) -> Result<Verdict> {
+    if install.file_size > 0 {
+        ensure!(
+            data.len() as u64 == install.file_size,
+            "expected {} bytes, got {}",
+            install.file_size,
+            data.len()
+        );
+    }
+
+    ZipArchive::new(Cursor::new(data)).context("archive is corrupted")?;
+
+    let actual = hash(data);
+
+    match app.db().archive_hash(install.id.version_uuid)? {
+        Some(expected) if expected != actual => Ok(Verdict::Mismatch { expected, actual }),
+        Some(_) => Ok(Verdict::Valid),
+        None => Ok(Verdict::Unrecorded { actual }),
+    }
+}
+
+/// Like [`check`], but records the hash if this is the first time the version is
+/// downloaded. Only use this on data that came straight from Thunderstore.
+pub(super) fn verify(install: &ModInstall, data: &[u8], app: &AppHandle) -> Result<Verdict> {
+    match check(install, data, app)? {
+        Verdict::Unrecorded { actual } => {
+            record(install, &actual, app);
+            Ok(Verdict::Valid)
+        }
+        verdict => Ok(verdict),
+    }
+}
+
+/// Whether `data` matches the hash recorded for the version. Unlike [`verify`],
+/// nothing is trusted on first sight, since the data might not come from Thunderstore.
+pub(super) fn matches_recorded(install: &ModInstall, data: &[u8], app: &AppHandle) -> Result<bool> {
+    Ok(matches!(check(install, data, app)?, Verdict::Valid))
+}
+
+/// Overwrites the recorded hash of a version.
+pub(super) fn record(install: &ModInstall, hash: &str, app: &AppHandle) {
+    app.db()
+        .record_archive_hash(install.id.version_uuid, hash)
+        .unwrap_or_else(|err| warn!("failed to record hash of {}: {:#}", install.ident, err));
+}
@@ -1,14 +1,21 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use eyre::eyre;
 use itertools::Itertools;
 use tauri::{command, AppHandle};
+use uuid::Uuid;
 
 use crate::{
     profile::install::InstallResultExt,
     state::ManagerExt,
-    thunderstore::ModId,
+    thunderstore::{self, ModId},
     util::{self, cmd::Result},
 };
 
-use super::{InstallOptions, ModInstall};
+use super::{
+    bundle::AuthorBundle, foreign::ForeignFile, preview::DependencyPreview, queue::QueueItem,
+    InstallOptions, ModInstall, PlannedFile,
+};
 
 #[command]
 pub async fn install_all_mods(app: AppHandle) -> Result<()> {
@@ -52,6 +59,53 @@ pub async fn install_mod(id: ModId, app: AppHandle) -> Result<()> {
     Ok(())
 }
 
+#[command]
+pub async fn preview_dependencies(mod_ref: ModId, app: AppHandle) -> Result<DependencyPreview> {
+    let preview = super::preview::preview(&mod_ref, &app).await?;
+
+    Ok(preview)
+}
+
+#[command]
+pub async fn install_mod_excluding(
+    id: ModId,
+    excluded: HashSet<Uuid>,
+    optional: Vec<ModId>,
+    app: AppHandle,
+) -> Result<()> {
+    super::preview::install(&id, &excluded, &optional, &app).await?;
+
+    Ok(())
+}
+
+#[command]
+pub async fn install_from_url(url: String, app: AppHandle) -> Result<()> {
+    super::url::install(&url, &app).await?;
+
+    Ok(())
+}
+
+#[command]
+pub fn preview_author_bundle(owner: String, app: AppHandle) -> Result<AuthorBundle> {
+    let bundle = super::bundle::preview(&owner, &app)?;
+
+    Ok(bundle)
+}
+
+#[command]
+pub async fn install_author_bundle(owner: String, app: AppHandle) -> Result<()> {
+    super::bundle::install(&owner, &app).await?;
+
+    Ok(())
+}
+
+#[command]
+pub async fn reinstall_mod(uuid: Uuid, app: AppHandle) -> Result<()> {
+    super::reinstall(uuid, &app).await?;
+
+    Ok(())
+}
+
 #[command]
 pub fn cancel_all_installs(app: AppHandle) -> Result<()> {
     app.app_state().install_queue.cancel_all();
@@ -59,6 +113,37 @@ pub fn cancel_all_installs(app: AppHandle) -> Result<()> {
     Ok(())
 }
 
+#[command]
+pub fn get_install_queue(app: AppHandle) -> Vec<QueueItem> {
+    app.install_queue().handle().items()
+}
+
+#[command]
+pub fn cancel_install_batch(batch_id: u64, app: AppHandle) -> Result<()> {
+    app.install_queue().handle().cancel_batch(batch_id, &app)?;
+
+    Ok(())
+}
+
+#[command]
+pub fn prioritize_install_batch(batch_id: u64, app: AppHandle) -> Result<()> {
+    app.install_queue()
+        .handle()
+        .prioritize_batch(batch_id, &app)?;
+
+    Ok(())
+}
+
+/// Approves or rejects a package that is being held for review.
+#[command]
+pub fn resolve_quarantine(package_uuid: Uuid, approve: bool) -> Result<()> {
+    if !super::quarantine::resolve(package_uuid, approve) {
+        return Err(eyre!("no review is pending for this package").into());
+    }
+
+    Ok(())
+}
+
 #[command]
 pub fn has_pending_installations(app: AppHandle) -> Result<bool> {
     let profile_id = app.lock_manager().active_profile().id;
@@ -79,7 +164,13 @@ pub async fn clear_download_cache(soft: bool, app: AppHandle) -> Result<u64> {
 
         Ok(size)
     } else {
-        let path = app.lock_prefs().cache_dir();
+        let path = {
+            let prefs = app.lock_prefs();
+            let manager = app.lock_manager();
+
+            super::deploy::ensure_not_linked(None, None, &manager, &prefs)?;
+            prefs.cache_dir()
+        };
 
         let size = util::fs::get_directory_size(&path);
 
@@ -89,6 +180,16 @@ pub async fn clear_download_cache(soft: bool, app: AppHandle) -> Result<u64> {
     }
 }
 
+/// Links identical files between cached versions of each package, returning the bytes saved.
+#[command]
+pub async fn deduplicate_cache(app: AppHandle) -> Result<u64> {
+    let path = app.lock_prefs().cache_dir();
+    let saved =
+        tauri::async_runtime::spawn_blocking(move || super::dedup::dedup_all(&path)).await??;
+
+    Ok(saved)
+}
+
 #[command]
 pub fn get_download_size(mod_ref: ModId, app: AppHandle) -> Result<u64> {
     let prefs = app.lock_prefs();
@@ -106,3 +207,58 @@ pub fn get_download_size(mod_ref: ModId, app: AppHandle) -> Result<u64> {
 
     Ok(size)
 }
+
+/// Shows where the files of a package would be placed in the active profile, without installing it.
+#[command]
+pub async fn get_install_plan(mod_ref: ModId, app: AppHandle) -> Result<Vec<PlannedFile>> {
+    let ident = mod_ref.borrow(&app.lock_thunderstore())?.ident().clone();
+
+    let files = thunderstore::contents::list(&ident, &app)
+        .await?
+        .into_iter()
+        .filter(|file| !file.is_dir)
+        .map(|file| PathBuf::from(file.path))
+        .collect_vec();
+
+    let game = app.lock_manager().active_game;
+    let package_name = ident.full_name();
+
+    let plan = game
+        .installer_for(package_name)
+        .plan(&files, package_name)?;
+
+    Ok(plan)
+}
+
+#[command]
+pub fn get_foreign_files(app: AppHandle) -> Result<Vec<ForeignFile>> {
+    let files = super::foreign::find(app.lock_manager().active_profile())?;
+
+    Ok(files)
+}
+
+#[command]
+pub fn trash_foreign_files(paths: Vec<PathBuf>, app: AppHandle) -> Result<()> {
+    super::foreign::trash(&paths, app.lock_manager().active_profile())?;
+
+    Ok(())
+}
+
+#[command]
+pub fn write_lock_file(app: AppHandle) -> Result<PathBuf> {
+    let prefs = app.lock_prefs();
+    let manager = app.lock_manager();
+
+    let path = manager.active_profile().write_lock(&prefs)?;
+
+    Ok(path)
+}
+
+#[command]
+pub async fn install_locked(path: Option<PathBuf>, app: AppHandle) -> Result<()> {
+    let profile_id = app.lock_manager().active_profile().id;
+
+    super::lock::install_locked(profile_id, path, &app).await?;
+
+    Ok(())
+}
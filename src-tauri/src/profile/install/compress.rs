@@ -0,0 +1,129 @@
+//! Optional zstd compression of cache entries, see [`Prefs::compress_cache`].
+//!
+//! Files are compressed in place and keep their names, so an entry is still laid out
+//! like the profile. A marker file in the entry's root tells compressed entries apart.
+//! While an entry is being compressed it has a different marker instead, so an entry
+//! left half compressed by a crash is recognized and extracted again.
+//! Since compressed files can't be linked, they're always decompressed into profiles
+//! as copies, regardless of the deploy mode.
+//!
+//! [`Prefs::compress_cache`]: crate::prefs::Prefs::compress_cache
+
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use eyre::{Context, Result};
+use itertools::Itertools;
+use tempfile::NamedTempFile;
+use tracing::debug;
+use walkdir::WalkDir;
+
+use super::deploy::Deployer;
+use crate::util::error::IoResultExt;
+
+const MARKER: &str = ".gale_zstd";
+const PARTIAL_MARKER: &str = ".gale_zstd_partial";
+const LEVEL: i32 = 3;
+
+/// Whether the cache entry at `entry` has been compressed.
+pub fn is_compressed(entry: &Path) -> bool {
+    entry.join(MARKER).exists()
+}
+
+/// Whether the compression of the cache entry at `entry` was interrupted, leaving
+/// a mix of compressed and plain files. Such an entry can't be used.
+pub fn is_partial(entry: &Path) -> bool {
+    entry.join(PARTIAL_MARKER).exists()
+}
+
+/// Whether `relative_path`, relative to an entry's root, is one of the compression
+/// markers, which aren't part of the package.
+pub fn is_marker(relative_path: &Path) -> bool {
+    relative_path == Path::new(MARKER) || relative_path == Path::new(PARTIAL_MARKER)
+}
+
+/// Compresses every file of the cache entry at `entry`.
+pub fn compress_entry(entry: &Path) -> Result<()> {
+    let partial_marker = entry.join(PARTIAL_MARKER);
+    File::create(&partial_marker).context("failed to write compression marker")?;
+
+    let files = WalkDir::new(entry)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|file| {
+            file.file_type().is_file() && !file.path().strip_prefix(entry).is_ok_and(is_marker)
+        })
+        .map(|file| file.into_path())
+        .collect_vec();
+
+    for path in &files {
+        // next to the file so that it can be renamed over it, and with a name
+        // that can't collide with another file of the package
+        let dir = path.parent().unwrap_or(entry);
+        let temp = NamedTempFile::new_in(dir).fs_context("creating temporary file", dir)?;
+
+        compress_file(path, temp.path()).fs_context("compressing file", path)?;
+
+        temp.persist(path)
+            .map_err(|err| err.error)
+            .fs_context("replacing file", path)?;
+    }
+
+    fs::rename(&partial_marker, entry.join(MARKER))
+        .context("failed to write compression marker")?;
+
+    debug!("compressed {} files in {}", files.len(), entry.display());
+
+    Ok(())
+}
+
+fn compress_file(src: &Path, target: &Path) -> io::Result<()> {
+    let reader = BufReader::new(File::open(src)?);
+    let mut writer = BufWriter::new(File::create(target)?);
+    zstd::stream::copy_encode(reader, &mut writer, LEVEL)?;
+    writer.flush()?;
+
+    // keep the executable bit and such
+    fs::set_permissions(target, fs::metadata(src)?.permissions())
+}
+
+/// Writes the decompressed contents of `src` to `target`.
+pub fn decompress_to(src: &Path, target: &Path) -> io::Result<()> {
+    let reader = BufReader::new(File::open(src)?);
+    let mut writer = BufWriter::new(File::create(target)?);
+    zstd::stream::copy_decode(reader, &mut writer)?;
+    writer.flush()?;
+
+    fs::set_permissions(target, fs::metadata(src)?.permissions())
+}
+
+/// Decompresses `src` into a temporary file, for code that needs to read it from disk.
+pub fn decompress_temp(src: &Path) -> Result<NamedTempFile> {
+    let file = NamedTempFile::new().context("failed to create temporary file")?;
+    decompress_to(src, file.path()).fs_context("decompressing file", src)?;
+
+    Ok(file)
+}
+
+/// Deploys files from compressed cache entries by decompressing them.
+pub struct Decompressor;
+
+impl Deployer for Decompressor {
+    fn deploy(&self, src: &Path, target: &Path) -> io::Result<()> {
+        decompress_to(src, target)
+    }
+}
+
+/// Opens a file for reading, decompressing it if it's from a compressed entry.
+pub fn reader(path: &Path, compressed: bool) -> io::Result<Box<dyn Read>> {
+    let file = BufReader::new(File::open(path)?);
+
+    if compressed {
+        Ok(Box::new(zstd::stream::Decoder::with_buffer(file)?))
+    } else {
+        Ok(Box::new(file))
+    }
+}
@@ -0,0 +1,174 @@
+//! Deduplicates files between cached versions of the same package.
+//!
+//! Updates often change a single DLL and leave the rest of the package alone. When a
+//! version is cached, each of its files that is identical to the file at the same path in
+//! another cached version is replaced with a hard link to it, so the unchanged files are
+//! only stored once. Removing a version only removes its links, so nothing else needs
+//! to keep track of which files are shared.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Context, Result};
+use itertools::Itertools;
+use tracing::{debug, warn};
+use walkdir::WalkDir;
+
+use crate::util::{self, fs::PathExt};
+
+/// Links the files of the cache entry at `entry` to identical files in other cached
+/// versions of the package. Returns the number of bytes saved.
+pub fn dedup_entry(entry: &Path) -> Result<u64> {
+    let Some(package_dir) = entry.parent() else {
+        return Ok(0);
+    };
+
+    let others = fs::read_dir(package_dir)
+        .context("failed to read package cache")?
+        .filter_map(Result::ok)
+        .map(|other| other.path())
+        .filter(|other| other != entry && other.is_dir())
+        .collect_vec();
+
+    Ok(dedup_against(entry, &others))
+}
+
+fn dedup_against(entry: &Path, others: &[PathBuf]) -> u64 {
+    if others.is_empty() {
+        return 0;
+    }
+
+    let files = WalkDir::new(entry)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|file| file.file_type().is_file());
+
+    let mut saved = 0;
+
+    for file in files {
+        let len = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        if len == 0 {
+            continue;
+        }
+
+        let relative_path = file
+            .path()
+            .strip_prefix(entry)
+            .expect("WalkDir should only return full paths inside of the root");
+
+        let Some(original) = find_identical(file.path(), relative_path, len, others) else {
+            continue;
+        };
+
+        match replace_with_link(file.path(), &original) {
+            Ok(()) => saved += len,
+            Err(err) => {
+                // likely a file system without hard links, so don't bother with the rest
+                warn!(
+                    "failed to link {} to {}: {:#}",
+                    file.path().display(),
+                    original.display(),
+                    err
+                );
+                break;
+            }
+        }
+    }
+
+    saved
+}
+
+fn find_identical(
+    path: &Path,
+    relative_path: &Path,
+    len: u64,
+    others: &[PathBuf],
+) -> Option<PathBuf> {
+    let mut hash = None;
+
+    for other in others {
+        let candidate = other.join(relative_path);
+
+        let same_len = candidate
+            .metadata()
+            .is_ok_and(|metadata| metadata.is_file() && metadata.len() == len);
+
+        if !same_len || is_same_file(path, &candidate) {
+            continue;
+        }
+
+        if hash.is_none() {
+            hash = Some(util::fs::checksum(path).ok()?);
+        }
+
+        if util::fs::checksum(&candidate).is_ok_and(|other_hash| Some(other_hash) == hash) {
+            return Some(candidate);
+        }
+    }
+
+    None
+}
+
+fn replace_with_link(path: &Path, original: &Path) -> Result<()> {
+    let mut temp_path = path.to_path_buf();
+    temp_path.add_ext("dedup");
+
+    fs::hard_link(original, &temp_path).context("failed to create link")?;
+    fs::rename(&temp_path, path)
+        .inspect_err(|_| {
+            fs::remove_file(&temp_path).ok();
+        })
+        .context("failed to replace file")?;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (a.metadata(), b.metadata()) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_a: &Path, _b: &Path) -> bool {
+    false
+}
+
+/// Deduplicates every package in the cache at `cache_dir`, comparing each version
+/// with the ones that sort before it. Returns the number of bytes saved.
+pub fn dedup_all(cache_dir: &Path) -> Result<u64> {
+    let packages = fs::read_dir(cache_dir)
+        .context("failed to read cache directory")?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir());
+
+    let mut saved = 0;
+
+    for package in packages {
+        let Ok(versions) = fs::read_dir(&package) else {
+            continue;
+        };
+
+        let versions = versions
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .sorted()
+            .collect_vec();
+
+        for (i, version) in versions.iter().enumerate().skip(1) {
+            saved += dedup_against(version, &versions[..i]);
+        }
+    }
+
+    debug!("deduplicating the cache saved {} bytes", saved);
+
+    Ok(saved)
+}
@@ -0,0 +1,249 @@
+//! Strategies for placing files from the mod cache into profiles.
+//!
+//! Hard links are the default, since they take no extra space and survive the cache being
+//! cleared. Some file systems and anti-cheat setups don't handle links well though,
+//! so the strategy can be changed per game, after which existing profiles are migrated.
+
+use std::{fs, io, path::Path};
+
+use eyre::{ensure, Result};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{info, instrument, warn};
+use walkdir::WalkDir;
+
+use super::{cache, compress, PackageInstaller};
+use crate::{
+    game::Game,
+    prefs::Prefs,
+    profile::{ModManager, Profile},
+    state::ManagerExt,
+    thunderstore::VersionIdent,
+    util::{error::IoResultExt, fs::PathExt},
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DeployMode {
+    #[default]
+    HardLink,
+    Symlink,
+    Copy,
+}
+
+impl DeployMode {
+    pub fn deployer(self) -> &'static dyn Deployer {
+        match self {
+            DeployMode::HardLink => &HardLinkDeployer,
+            DeployMode::Symlink => &SymlinkDeployer,
+            DeployMode::Copy => &CopyDeployer,
+        }
+    }
+}
+
+pub trait Deployer: Send + Sync {
+    /// Places the file at `src` at `target`, which must not exist.
+    fn deploy(&self, src: &Path, target: &Path) -> io::Result<()>;
+}
+
+struct HardLinkDeployer;
+
+impl Deployer for HardLinkDeployer {
+    fn deploy(&self, src: &Path, target: &Path) -> io::Result<()> {
+        match fs::hard_link(src, target) {
+            // hard links can't cross drives, which happens when
+            // the profile is in a custom location
+            Err(err) if is_cross_device(&err) => {
+                warn!(
+                    "can't hard link {} from another drive, copying instead",
+                    target.display()
+                );
+                copy(src, target)
+            }
+            result => result,
+        }
+    }
+}
+
+/// Whether `err` is from linking across drives, i.e. `EXDEV` or `ERROR_NOT_SAME_DEVICE`.
+fn is_cross_device(err: &io::Error) -> bool {
+    #[cfg(unix)]
+    const CODE: i32 = 18;
+    #[cfg(windows)]
+    const CODE: i32 = 17;
+
+    err.raw_os_error() == Some(CODE)
+}
+
+struct SymlinkDeployer;
+
+impl Deployer for SymlinkDeployer {
+    fn deploy(&self, src: &Path, target: &Path) -> io::Result<()> {
+        #[cfg(unix)]
+        let result = std::os::unix::fs::symlink(src, target);
+        #[cfg(windows)]
+        let result = std::os::windows::fs::symlink_file(src, target);
+
+        // windows only allows symlinks in developer mode or as an administrator
+        result.or_else(|err| {
+            warn!(
+                "failed to symlink {}, copying instead: {}",
+                target.display(),
+                err
+            );
+            copy(src, target)
+        })
+    }
+}
+
+struct CopyDeployer;
+
+impl Deployer for CopyDeployer {
+    fn deploy(&self, src: &Path, target: &Path) -> io::Result<()> {
+        copy(src, target)
+    }
+}
+
+fn copy(src: &Path, target: &Path) -> io::Result<()> {
+    fs::copy(src, target).map(|_| ())
+}
+
+impl Prefs {
+    pub fn deploy_mode(&self, game: Game) -> DeployMode {
+        self.game_prefs
+            .get(&*game.slug)
+            .map(|prefs| prefs.deploy_mode)
+            .unwrap_or_default()
+    }
+}
+
+/// Fails if a profile deployed with symlinks uses the cache entry of `ident`, or any
+/// entry if it's `None`, since removing it would leave the profile's files dangling.
+/// The profile with the id `except` is skipped.
+pub fn ensure_not_linked(
+    ident: Option<&VersionIdent>,
+    except: Option<i64>,
+    manager: &ModManager,
+    prefs: &Prefs,
+) -> Result<()> {
+    let linked = manager
+        .games
+        .values()
+        .filter(|managed_game| prefs.deploy_mode(managed_game.game) == DeployMode::Symlink);
+
+    for managed_game in linked {
+        for profile in &managed_game.profiles {
+            if Some(profile.id) == except {
+                continue;
+            }
+
+            let uses_entry = profile.thunderstore_mods().any(|(ts_mod, _)| match ident {
+                Some(ident) => ts_mod.ident == *ident,
+                None => true,
+            });
+
+            ensure!(
+                !uses_entry,
+                "{} links to files in the cache, switch {} to copies or hard links first",
+                profile.name,
+                managed_game.game.name
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Re-deploys the mod files in all of `game`'s profiles with `mode`.
+///
+/// Files that are expected to change, like configs, are always copies and are left alone.
+#[instrument(skip_all, fields(game = %game.slug, ?mode))]
+pub fn migrate(game: Game, mode: DeployMode, prefs: &Prefs, app: &AppHandle) -> Result<()> {
+    let manager = app.lock_manager();
+    let Some(managed_game) = manager.games.get(&game) else {
+        return Ok(());
+    };
+
+    let deployer = mode.deployer();
+    let mut count = 0;
+
+    for profile in &managed_game.profiles {
+        for profile_mod in &profile.mods {
+            let Some(ts_mod) = profile_mod.kind.as_thunderstore() else {
+                continue;
+            };
+
+            let cache_path = cache::path(&ts_mod.ident, game, prefs);
+            if !cache::exists(&cache_path) {
+                warn!("{} is not in the cache, skipping", ts_mod.ident);
+                continue;
+            }
+
+            let installer = game.installer_for(ts_mod.ident.full_name());
+            count += redeploy(&cache_path, profile, &*installer, deployer)?;
+        }
+    }
+
+    info!(
+        "redeployed {} files of {} with {:?}",
+        count, game.slug, mode
+    );
+
+    Ok(())
+}
+
+fn redeploy(
+    cache_path: &Path,
+    profile: &Profile,
+    installer: &dyn PackageInstaller,
+    deployer: &dyn Deployer,
+) -> Result<usize> {
+    let mut count = 0;
+
+    let compressed = compress::is_compressed(cache_path);
+    let deployer: &dyn Deployer = if compressed {
+        &compress::Decompressor
+    } else {
+        deployer
+    };
+
+    let files = WalkDir::new(cache_path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file());
+
+    for entry in files {
+        let relative_path = entry
+            .path()
+            .strip_prefix(cache_path)
+            .expect("WalkDir should only return full paths inside of the root");
+
+        if installer.is_mutable(relative_path) || compress::is_marker(relative_path) {
+            continue;
+        }
+
+        let mut target = profile.path.join(relative_path);
+        if !exists(&target) {
+            // the mod might be disabled
+            target.add_ext("old");
+
+            if !exists(&target) {
+                continue;
+            }
+        }
+
+        fs::remove_file(&target).fs_context("removing file", &target)?;
+        deployer
+            .deploy(entry.path(), &target)
+            .fs_context("deploying file", &target)?;
+
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Like [`Path::exists`], but also true for broken symlinks.
+pub(super) fn exists(path: &Path) -> bool {
+    path.symlink_metadata().is_ok()
+}
@@ -0,0 +1,86 @@
+//! Finds files in the directories managed by the mod loader that no installed package
+//! claims, which are usually leftovers from installing mods by hand.
+
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, Context, Result};
+use serde::Serialize;
+use tracing::info;
+
+use crate::{profile::Profile, util};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForeignFile {
+    /// Relative to the profile directory.
+    path: PathBuf,
+    is_dir: bool,
+    /// In bytes, including everything inside directories.
+    size: u64,
+}
+
+/// Lists the unclaimed files in the profile.
+pub fn find(profile: &Profile) -> Result<Vec<ForeignFile>> {
+    let files = foreign_paths(profile)?
+        .into_iter()
+        .map(|path| {
+            let full_path = profile.path.join(&path);
+            let is_dir = full_path.is_dir();
+            let size = if is_dir {
+                util::fs::get_directory_size(&full_path)
+            } else {
+                full_path.metadata().map_or(0, |metadata| metadata.len())
+            };
+
+            ForeignFile { path, is_dir, size }
+        })
+        .collect();
+
+    Ok(files)
+}
+
+fn foreign_paths(profile: &Profile) -> Result<Vec<PathBuf>> {
+    let mod_loader = &profile.game.mod_loader;
+    // any name other than the loader's gives the installer used for regular mods
+    let installer = profile.game.installer_for("");
+
+    let log_path = mod_loader.log_path().map(Path::new);
+
+    let paths = installer
+        .foreign_files(profile)?
+        .into_iter()
+        .filter(|path| {
+            let path = super::fs::without_old_ext(path);
+
+            // the loader's own files often live next to the mods
+            let is_loader_file = profile
+                .loader_files
+                .iter()
+                .any(|loader_file| loader_file.starts_with(&path));
+
+            !is_loader_file && !log_path.is_some_and(|log_path| log_path.starts_with(&path))
+        })
+        .collect();
+
+    Ok(paths)
+}
+
+/// Moves the given files to the recycle bin. Paths that aren't foreign anymore are refused,
+/// so this can't be used to remove files that belong to a mod.
+pub fn trash(paths: &[PathBuf], profile: &Profile) -> Result<()> {
+    let foreign = foreign_paths(profile)?;
+
+    if let Some(path) = paths.iter().find(|path| !foreign.contains(path)) {
+        bail!("{} is not a foreign file", path.display());
+    }
+
+    for path in paths {
+        let full_path = profile.path.join(path);
+        trash::delete(&full_path)
+            .with_context(|| format!("failed to move {} to recycle bin", path.display()))?;
+    }
+
+    info!("trashed {} foreign files in {}", paths.len(), profile.name);
+
+    Ok(())
+}
@@ -1,81 +1,157 @@
 use std::{
     borrow::Cow,
     fs::{self, File},
-    io::{self, Read, Seek},
+    io::{self, BufWriter, Read, Seek, Write},
     path::{Path, PathBuf},
+    sync::Mutex,
 };
 
 use eyre::{Context, Result};
-use tracing::warn;
+use gale_core::archive::entry_path;
+use tracing::{instrument, warn};
 use walkdir::WalkDir;
 use zip::ZipArchive;
 
+use super::{compress, deploy::Deployer, tuning, PlannedFile};
 use crate::{
     profile::Profile,
     util::{self, error::IoResultExt, fs::PathExt},
 };
 
+/// The most memory reserved up front for a file read from an archive.
+const MAX_PREALLOC: u64 = 64 * 1024 * 1024;
+
 /// Extract a package archive to `dest`, mapping files using `map_file`.
 ///
 /// `map_file` is called with each file's relative path. It should return
 /// the (relative) output path where the file should be copied. `Ok(None)`
 /// skips the file entirely.
 ///
-/// Directories are created as needed.
+/// Directories are created as needed. If more than one extraction thread is
+/// configured (see [`tuning`]), files are written in parallel.
 pub(super) fn extract<S, M>(
     mut archive: ZipArchive<S>,
     dest: PathBuf,
     mut map_file: M,
 ) -> Result<()>
 where
-    S: Read + Seek,
+    S: Read + Seek + Send,
     M: FnMut(&Path) -> Result<Option<Cow<Path>>>,
 {
+    let mut entries = Vec::new();
+
     for i in 0..archive.len() {
-        let mut source_file = archive.by_index(i)?;
+        let source_file = archive.by_index(i)?;
 
         if source_file.is_dir() {
             continue; // we create the necessary dirs when copying files instead
         }
 
-        let name = source_file.name();
-        let relative_path: Cow<'_, Path> = if cfg!(unix) && name.contains('\\') {
-            PathBuf::from(name.replace('\\', "/")).into()
-        } else {
-            Path::new(name).into()
-        };
-
-        if !util::fs::is_enclosed(&relative_path) {
+        let Some(relative_path) = entry_path(source_file.name()) else {
             warn!(
                 "file {} escapes the archive root, skipping",
-                relative_path.display()
+                source_file.name()
             );
             continue;
-        }
+        };
 
         let Some(relative_target) = map_file(&relative_path)? else {
             continue;
         };
 
-        let target_path = dest.join(relative_target);
+        entries.push((i, dest.join(relative_target)));
+    }
+
+    let buffer_size = tuning::write_buffer_size();
+
+    let Some(pool) = tuning::extract_pool() else {
+        for (i, target_path) in entries {
+            let mut source_file = archive.by_index(i)?;
+            let mode = source_file.unix_mode();
+            write_file(&mut source_file, mode, &target_path, buffer_size)?;
+        }
+
+        return Ok(());
+    };
+
+    // decompressing needs the archive, so only the writing is spread out
+    let failed = Mutex::new(None);
+
+    pool.scope(|scope| -> Result<()> {
+        for (i, target_path) in entries {
+            let mut source_file = archive.by_index(i)?;
+            let mode = source_file.unix_mode();
 
-        fs::create_dir_all(target_path.parent().unwrap())?;
+            // the size comes from the archive, so don't trust it with a huge allocation
+            let capacity = source_file.size().min(MAX_PREALLOC) as usize;
+            let mut data = Vec::with_capacity(capacity);
+            source_file.read_to_end(&mut data)?;
+
+            let failed = &failed;
+            scope.spawn(move |_| {
+                if let Err(err) = write_file(&mut data.as_slice(), mode, &target_path, buffer_size)
+                {
+                    failed.lock().unwrap().get_or_insert(err);
+                }
+            });
+        }
 
-        let mut target_file = File::create(&target_path)?;
-        io::copy(&mut source_file, &mut target_file)?;
+        Ok(())
+    })?;
 
-        #[cfg(unix)]
-        set_unix_mode(&source_file, &target_path)?;
+    match failed.into_inner().unwrap() {
+        Some(err) => Err(err),
+        None => Ok(()),
     }
+}
+
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn write_file(
+    source: &mut impl Read,
+    mode: Option<u32>,
+    target_path: &Path,
+    buffer_size: usize,
+) -> Result<()> {
+    fs::create_dir_all(target_path.parent().unwrap())?;
+
+    let mut writer = BufWriter::with_capacity(buffer_size, File::create(target_path)?);
+    io::copy(source, &mut writer)?;
+    writer.flush()?;
+
+    #[cfg(unix)]
+    set_unix_mode(mode, target_path)?;
 
     Ok(())
 }
 
+/// Maps `files` using `map_file` in the same way as [`extract`], without extracting anything.
+///
+/// The returned files are neither tracked nor mutable.
+pub(super) fn plan<M>(files: &[PathBuf], mut map_file: M) -> Result<Vec<PlannedFile>>
+where
+    M: FnMut(&Path) -> Result<Option<Cow<Path>>>,
+{
+    files
+        .iter()
+        .filter(|source| util::fs::is_enclosed(source))
+        .map(|source| {
+            let target = map_file(source)?.map(Cow::into_owned);
+
+            Ok(PlannedFile {
+                source: source.clone(),
+                target,
+                tracked: false,
+                mutable: false,
+            })
+        })
+        .collect()
+}
+
 #[cfg(unix)]
-fn set_unix_mode(file: &zip::read::ZipFile, target_path: &Path) -> io::Result<()> {
+fn set_unix_mode(mode: Option<u32>, target_path: &Path) -> io::Result<()> {
     use std::os::unix::fs::PermissionsExt;
 
-    let mut mode = match file.unix_mode() {
+    let mut mode = match mode {
         Some(mode) => mode,
         None => fs::metadata(target_path)?.permissions().mode(),
     };
@@ -92,7 +168,7 @@ fn set_unix_mode(file: &zip::read::ZipFile, target_path: &Path) -> io::Result<()
 
 #[derive(Debug, Clone, Copy)]
 pub enum FileInstallMethod {
-    /// Use a hard link.
+    /// Use the game's deployment strategy, see [`super::deploy`].
     Link,
     /// Copy the file.
     Copy,
@@ -104,6 +180,9 @@ pub enum ConflictResolution {
     Skip,
     /// Overwrite the current file.
     Overwrite,
+    /// Keep the current file, but add any settings that only the new one has.
+    /// Used for config files, see [`crate::config::merge_defaults`].
+    Merge,
 }
 
 /// Install from a well structured mod directory.
@@ -112,10 +191,23 @@ pub enum ConflictResolution {
 ///
 /// `before_install` is called each time a file is encountered,
 /// with the file's relative path and whether the target file already exists.
-pub(super) fn install<F>(src: &Path, profile: &Profile, mut before_install: F) -> Result<()>
+#[instrument(name = "install_files", skip_all)]
+pub(super) fn install<F>(
+    src: &Path,
+    profile: &Profile,
+    deployer: &dyn Deployer,
+    mut before_install: F,
+) -> Result<()>
 where
     F: FnMut(&Path, bool) -> Result<(FileInstallMethod, ConflictResolution)>,
 {
+    let compressed = compress::is_compressed(src);
+    let deployer: &dyn Deployer = if compressed {
+        &compress::Decompressor
+    } else {
+        deployer
+    };
+
     for entry in WalkDir::new(src) {
         let entry = entry?;
 
@@ -124,6 +216,10 @@ where
             .strip_prefix(src)
             .expect("WalkDir should only return full paths inside of the root");
 
+        if compress::is_marker(relative_path) {
+            continue;
+        }
+
         let target = profile.path.join(relative_path);
         if entry.file_type().is_dir() {
             if target.exists() {
@@ -134,21 +230,31 @@ where
                 format!("failed to create directory {}", relative_path.display())
             })?;
         } else {
-            let target_exists = target.exists();
+            let target_exists = super::deploy::exists(&target);
             let (method, conflict) = before_install(relative_path, target_exists)?;
 
             if target_exists {
-                match (conflict, method) {
-                    (ConflictResolution::Skip, _) => {
+                match conflict {
+                    ConflictResolution::Skip => {
                         warn!(
                             "skipping file {} since it already exists",
                             relative_path.display()
                         );
                         continue;
                     }
-                    // fs::copy already overwrites the target, no need to remove it
-                    (ConflictResolution::Overwrite, FileInstallMethod::Copy) => (),
-                    (ConflictResolution::Overwrite, FileInstallMethod::Link) => {
+                    ConflictResolution::Merge => {
+                        if let Err(err) = merge_defaults(&target, entry.path(), compressed) {
+                            warn!(
+                                "failed to merge {}, keeping the current file: {:#}",
+                                relative_path.display(),
+                                err
+                            );
+                        }
+                        continue;
+                    }
+                    // always remove the old file, since writing to it could
+                    // modify the cache through a link
+                    ConflictResolution::Overwrite => {
                         fs::remove_file(&target).with_context(|| {
                             format!(
                                 "failed to remove existing file at {}",
@@ -161,12 +267,18 @@ where
 
             match method {
                 FileInstallMethod::Link => {
-                    fs::hard_link(entry.path(), target).with_context(|| {
+                    deployer.deploy(entry.path(), &target).with_context(|| {
                         format!("failed to link file at {}", relative_path.display())
                     })?;
                 }
                 FileInstallMethod::Copy => {
-                    fs::copy(entry.path(), target).with_context(|| {
+                    let result = if compressed {
+                        compress::decompress_to(entry.path(), &target)
+                    } else {
+                        fs::copy(entry.path(), &target).map(|_| ())
+                    };
+
+                    result.with_context(|| {
                         format!("failed to copy file at {}", relative_path.display())
                     })?;
                 }
@@ -177,6 +289,15 @@ where
     Ok(())
 }
 
+fn merge_defaults(target: &Path, defaults: &Path, compressed: bool) -> Result<bool> {
+    if compressed {
+        let temp = compress::decompress_temp(defaults)?;
+        crate::config::merge_defaults(target, temp.path())
+    } else {
+        crate::config::merge_defaults(target, defaults)
+    }
+}
+
 /// Removes either a directory or file at `path`. Also accounts for any
 /// `.old` extensions that may exist.
 pub(super) fn uninstall_any(path: impl AsRef<Path>) -> Result<()> {
@@ -219,6 +340,17 @@ where
     }
 }
 
+/// Returns `path` without any `.old` extensions added by disabling it.
+pub(super) fn without_old_ext(path: &Path) -> PathBuf {
+    let mut path = path.to_path_buf();
+
+    while let Some("old") = path.extension().and_then(|ext| ext.to_str()) {
+        path.set_extension("");
+    }
+
+    path
+}
+
 /// Toggles a file by adding/removing a `.old` extension to it.
 pub(super) fn toggle_file(path: impl AsRef<Path>, enabled: bool) -> Result<()> {
     let path = path.as_ref();
@@ -6,7 +6,7 @@ use std::{
 
 use eyre::Result;
 
-use super::{FileInstallMethod, PackageInstaller, PackageZip};
+use super::{Deployer, FileInstallMethod, PackageInstaller, PackageZip, PlannedFile};
 use crate::profile::{
     install::{self, fs::ConflictResolution},
     Profile, ProfileMod,
@@ -32,32 +32,58 @@ fn scan(profile: &Profile, package_name: &str) -> Result<impl Iterator<Item = Pa
         .map(|entry| entry.path()))
 }
 
+fn map_file(relative_path: &Path) -> Option<Cow<'_, Path>> {
+    let mut components = relative_path.components();
+    if components.clone().count() == 1 {
+        // ignore top-level files, such as manifest.json and icon.png
+        return None;
+    }
+
+    // remove the top-level dir (usually called BepInExPack)
+    components.next();
+
+    Some(Cow::Borrowed(components.as_path()))
+}
+
+fn is_mutable(relative_path: &Path) -> bool {
+    relative_path.extension().is_some_and(|ext| ext == "cfg")
+}
+
 impl PackageInstaller for BepinexInstaller {
     fn extract(&mut self, archive: PackageZip, _package_name: &str, dest: PathBuf) -> Result<()> {
-        install::fs::extract(archive, dest, |relative_path| {
-            let mut components = relative_path.components();
-            if components.clone().count() == 1 {
-                // ignore top-level files, such as manifest.json and icon.png
-                return Ok(None);
-            }
+        install::fs::extract(archive, dest, |relative_path| Ok(map_file(relative_path)))
+    }
 
-            // remove the top-level dir (usually called BepInExPack)
-            components.next();
+    fn plan(&self, files: &[PathBuf], _package_name: &str) -> Result<Vec<PlannedFile>> {
+        let mut plan = install::fs::plan(files, |relative_path| Ok(map_file(relative_path)))?;
 
-            Ok(Some(Cow::Borrowed(components.as_path())))
-        })
+        for file in &mut plan {
+            file.mutable = file.target.as_deref().is_some_and(is_mutable);
+        }
+
+        Ok(plan)
     }
 
-    fn install(&mut self, src: &Path, _package_name: &str, profile: &Profile) -> Result<()> {
-        install::fs::install(src, profile, |relative_path, _| {
-            if relative_path.extension().is_some_and(|ext| ext == "cfg") {
-                Ok((FileInstallMethod::Copy, ConflictResolution::Skip))
+    fn install(
+        &mut self,
+        src: &Path,
+        _package_name: &str,
+        profile: &Profile,
+        deployer: &dyn Deployer,
+    ) -> Result<()> {
+        install::fs::install(src, profile, deployer, |relative_path, _| {
+            if is_mutable(relative_path) {
+                Ok((FileInstallMethod::Copy, ConflictResolution::Merge))
             } else {
                 Ok((FileInstallMethod::Link, ConflictResolution::Overwrite))
             }
         })
     }
 
+    fn is_mutable(&self, relative_path: &Path) -> bool {
+        is_mutable(relative_path)
+    }
+
     fn toggle(&mut self, enabled: bool, profile_mod: &ProfileMod, profile: &Profile) -> Result<()> {
         for file in scan(profile, &profile_mod.full_name())? {
             install::fs::toggle_file(file, enabled)?;
@@ -1,8 +1,11 @@
-use std::{borrow::Cow, path::PathBuf};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
 
 use eyre::Result;
 
-use super::{PackageInstaller, PackageZip};
+use super::{PackageInstaller, PackageZip, PlannedFile};
 use crate::profile::{install, Profile, ProfileMod};
 
 pub enum FlattenTopLevel {
@@ -27,6 +30,21 @@ impl<'a> ExtractInstaller<'a> {
         }
     }
 
+    fn map_file<'p>(&self, relative_path: &'p Path) -> Option<Cow<'p, Path>> {
+        let mut components = relative_path.components();
+
+        if matches!(self.flatten_top_level, FlattenTopLevel::Yes) {
+            components.next();
+        }
+
+        let path = components.as_path();
+
+        self.files
+            .iter()
+            .any(|file| path.starts_with(file))
+            .then_some(Cow::Borrowed(path))
+    }
+
     fn scan_mod<'b>(&'b self, profile: &'b Profile) -> impl Iterator<Item = PathBuf> + 'b {
         self.files.iter().map(|file| profile.path.join(file))
     }
@@ -35,22 +53,14 @@ impl<'a> ExtractInstaller<'a> {
 impl PackageInstaller for ExtractInstaller<'_> {
     fn extract(&mut self, archive: PackageZip, _package_name: &str, dest: PathBuf) -> Result<()> {
         install::fs::extract(archive, dest, |relative_path| {
-            let mut components = relative_path.components();
-
-            if matches!(self.flatten_top_level, FlattenTopLevel::Yes) {
-                components.next();
-            }
-
-            let path = components.as_path();
-
-            Ok(self
-                .files
-                .iter()
-                .any(|file| path.starts_with(file))
-                .then_some(Cow::Borrowed(path)))
+            Ok(self.map_file(relative_path))
         })
     }
 
+    fn plan(&self, files: &[PathBuf], _package_name: &str) -> Result<Vec<PlannedFile>> {
+        install::fs::plan(files, |relative_path| Ok(self.map_file(relative_path)))
+    }
+
     fn toggle(
         &mut self,
         enabled: bool,
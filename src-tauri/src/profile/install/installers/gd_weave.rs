@@ -1,12 +1,12 @@
 use std::{
     borrow::Cow,
     fs,
-    path::{self, PathBuf},
+    path::{self, Path, PathBuf},
 };
 
 use eyre::{bail, Result};
 
-use super::{PackageInstaller, PackageZip};
+use super::{PackageInstaller, PackageZip, PlannedFile};
 use crate::profile::{
     install::{self},
     Profile, ProfileMod,
@@ -22,6 +22,40 @@ fn profile_mod_dir(package_name: &str, profile: &Profile) -> PathBuf {
     profile.path.join(relative_mod_dir(package_name))
 }
 
+/// Finds the directory with a manifest.json file in it,
+/// except the top level one since that has thunderstore's manifest.
+fn find_root<'a>(paths: impl Iterator<Item = &'a Path>) -> Result<PathBuf> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+
+    for path in paths {
+        let mut components = path.components();
+
+        match components.next_back() {
+            Some(path::Component::Normal(name))
+                if name == "manifest.json" && components.clone().count() > 0 =>
+            {
+                roots.push(components.collect());
+            }
+            _ => (),
+        }
+    }
+
+    match roots.len() {
+        0 => bail!("no mod root found"),
+        1 => Ok(roots.into_iter().next().unwrap()),
+        _ => bail!("multiple mod roots found"),
+    }
+}
+
+fn map_file<'p>(relative_path: &Path, root: &Path, package_name: &str) -> Option<Cow<'p, Path>> {
+    let relative_to_root = relative_path.strip_prefix(root).ok()?;
+
+    let mut path = relative_mod_dir(package_name);
+    path.push(relative_to_root);
+
+    Some(Cow::Owned(path))
+}
+
 impl PackageInstaller for GDWeaveModInstaller {
     fn extract(
         &mut self,
@@ -29,44 +63,26 @@ impl PackageInstaller for GDWeaveModInstaller {
         package_name: &str,
         dest: PathBuf,
     ) -> Result<()> {
-        // find a directory with a manifest.json file in it
-        // except the top level one since that has thunderstore's manifest
-
-        let mut roots: Vec<PathBuf> = Vec::new();
+        let mut paths = Vec::new();
 
         for i in 0..archive.len() {
-            let file = archive.by_index(i)?;
-            let Some(path) = file.enclosed_name() else {
-                continue;
-            };
-
-            let mut components = path.components();
-
-            match components.next_back() {
-                Some(path::Component::Normal(name))
-                    if name == "manifest.json" && components.clone().count() > 0 =>
-                {
-                    roots.push(components.collect());
-                }
-                _ => (),
+            if let Some(path) = archive.by_index(i)?.enclosed_name() {
+                paths.push(path);
             }
         }
 
-        let root = match roots.len() {
-            0 => bail!("no mod root found"),
-            1 => roots.into_iter().next().unwrap(),
-            _ => bail!("multiple mod roots found"),
-        };
+        let root = find_root(paths.iter().map(PathBuf::as_path))?;
 
         install::fs::extract(archive, dest, |relative_path| {
-            if let Ok(relative_to_root) = relative_path.strip_prefix(&root) {
-                let mut path = relative_mod_dir(package_name);
-                path.push(relative_to_root);
+            Ok(map_file(relative_path, &root, package_name))
+        })
+    }
 
-                Ok(Some(Cow::Owned(path)))
-            } else {
-                Ok(None)
-            }
+    fn plan(&self, files: &[PathBuf], package_name: &str) -> Result<Vec<PlannedFile>> {
+        let root = find_root(files.iter().map(PathBuf::as_path))?;
+
+        install::fs::plan(files, |relative_path| {
+            Ok(map_file(relative_path, &root, package_name))
         })
     }
 
@@ -82,4 +98,8 @@ impl PackageInstaller for GDWeaveModInstaller {
     fn mod_dir(&self, package_name: &str, profile: &Profile) -> Option<PathBuf> {
         Some(profile_mod_dir(package_name, profile))
     }
+
+    fn foreign_files(&self, profile: &Profile) -> Result<Vec<PathBuf>> {
+        super::unclaimed_entries(Path::new("GDWeave/mods"), profile)
+    }
 }
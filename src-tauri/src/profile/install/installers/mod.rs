@@ -1,43 +1,120 @@
 use std::{
+    collections::HashSet,
+    fs,
     io::Cursor,
     path::{Path, PathBuf},
 };
 
 use eyre::Result;
+use serde::Serialize;
 use zip::ZipArchive;
 
-use super::fs::{ConflictResolution, FileInstallMethod};
+use super::{
+    deploy::Deployer,
+    fs::{ConflictResolution, FileInstallMethod},
+};
 use crate::profile::{Profile, ProfileMod};
 
 mod bepinex;
 mod extract;
 mod gd_weave;
+mod script;
 mod shimloader;
 mod subdir;
+mod ue4ss;
 
 pub use self::{
     bepinex::BepinexInstaller,
     extract::{ExtractInstaller, FlattenTopLevel},
     gd_weave::GDWeaveModInstaller,
+    script::{load_scripts, ScriptInstaller},
     shimloader::ShimloaderInstaller,
     subdir::{Subdir, SubdirInstaller},
+    ue4ss::UE4SSInstaller,
 };
 
 pub type PackageZip = ZipArchive<Cursor<Vec<u8>>>;
 
+/// Where a file from a package archive would end up when installed.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedFile {
+    /// The path of the file inside the package archive.
+    pub source: PathBuf,
+    /// The path relative to the profile directory, or `None` if the file is skipped.
+    pub target: Option<PathBuf>,
+    /// Whether the file's owner is recorded in the profile's state files.
+    pub tracked: bool,
+    /// Whether the file is copied instead of hard linked, since it's expected to change.
+    pub mutable: bool,
+}
+
 pub trait PackageInstaller {
     fn extract(&mut self, archive: PackageZip, package_name: &str, dest: PathBuf) -> Result<()>;
 
-    fn install(&mut self, src: &Path, _package_name: &str, profile: &Profile) -> Result<()> {
-        super::fs::install(src, profile, |_, _| {
+    /// Maps the files of a package archive like [`PackageInstaller::extract`] would,
+    /// without touching the disk.
+    fn plan(&self, files: &[PathBuf], package_name: &str) -> Result<Vec<PlannedFile>>;
+
+    fn install(
+        &mut self,
+        src: &Path,
+        _package_name: &str,
+        profile: &Profile,
+        deployer: &dyn Deployer,
+    ) -> Result<()> {
+        super::fs::install(src, profile, deployer, |_, _| {
             Ok((FileInstallMethod::Link, ConflictResolution::Overwrite))
         })
     }
 
+    /// Whether the file at `relative_path` is copied rather than deployed,
+    /// since it's expected to change.
+    fn is_mutable(&self, _relative_path: &Path) -> bool {
+        false
+    }
+
     fn toggle(&mut self, enabled: bool, profile_mod: &ProfileMod, profile: &Profile) -> Result<()>;
     fn uninstall(&mut self, profile_mod: &ProfileMod, profile: &Profile) -> Result<()>;
 
     fn mod_dir(&self, _package_name: &str, _profile: &Profile) -> Option<PathBuf> {
         None
     }
+
+    /// Lists files and directories in the directories this installer manages that no
+    /// installed package claims, relative to the profile directory.
+    fn foreign_files(&self, _profile: &Profile) -> Result<Vec<PathBuf>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Lists the entries of `target` that aren't named after an installed mod, for
+/// installers that give each mod a directory of its own.
+fn unclaimed_entries(target: &Path, profile: &Profile) -> Result<Vec<PathBuf>> {
+    let dir = profile.path.join(target);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let installed = profile
+        .mods
+        .iter()
+        .map(|profile_mod| profile_mod.full_name())
+        .collect::<HashSet<_>>();
+
+    let mut entries = Vec::new();
+
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+
+        let claimed = entry.file_type()?.is_dir()
+            && name.to_str().is_some_and(|name| installed.contains(name));
+
+        if !claimed {
+            entries.push(target.join(name));
+        }
+    }
+
+    Ok(entries)
 }
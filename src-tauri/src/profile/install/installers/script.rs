@@ -0,0 +1,328 @@
+//! Installers defined by [Rhai](https://rhai.rs) scripts, for packages that don't fit
+//! any of the built-in rules.
+//!
+//! A script is loaded from `<data dir>/installers/<game slug>.rhai` and replaces the
+//! game's installer. It may define the following functions:
+//!
+//! - `map_file(path, package)` (required): returns where the file at `path` in the
+//!   archive goes, relative to the profile directory, or `()` to leave it out.
+//! - `is_mutable(path)`: whether an installed file is expected to change, like a config.
+//!   These are copied instead of linked, merged on updates and kept on uninstall.
+//! - `handles(package)`: whether the script installs `package` at all. Others fall
+//!   back to the game's built-in installer.
+//!
+//! Paths use forward slashes and can't leave the profile directory, even through a
+//! symlink. Every other file the script installs is recorded in the profile's state
+//! files, the same way as files in tracked subdirs.
+//!
+//! Packages a script handles are extracted differently, so their cache entries are
+//! keyed by the script's contents (see [`ScriptInstaller::cache_key`]). Editing a
+//! script makes Gale extract those packages again instead of reusing the old layout.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use eyre::{bail, ensure, eyre, Context, Result};
+use rhai::{Dynamic, Engine, Scope, AST};
+use tracing::{info, warn};
+
+use super::{
+    subdir::{PackageStateHandle, ProfileStateHandle},
+    Deployer, PackageInstaller, PackageZip, PlannedFile,
+};
+use crate::{
+    prefs::access,
+    profile::{
+        install::{
+            self,
+            fs::{ConflictResolution, FileInstallMethod},
+        },
+        Profile, ProfileMod,
+    },
+};
+
+const DIR_NAME: &str = "installers";
+const EXTENSION: &str = "rhai";
+
+/// Stops scripts that run away, e.g. with an infinite loop.
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// Compiled scripts, keyed by game slug.
+static SCRIPTS: RwLock<Option<HashMap<String, Arc<InstallerScript>>>> = RwLock::new(None);
+
+struct InstallerScript {
+    engine: Engine,
+    ast: AST,
+    /// Hash of the script's source, identifying this version of it.
+    digest: blake3::Hash,
+    /// Results of `handles`, since it's asked about the same packages over and over.
+    handled: Mutex<HashMap<String, bool>>,
+}
+
+impl InstallerScript {
+    fn compile(path: &Path) -> Result<Self> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+
+        let source = fs::read_to_string(path).context("failed to read script")?;
+        let ast = engine.compile(&source).map_err(|err| eyre!("{err}"))?;
+
+        let digest = blake3::hash(source.as_bytes());
+        let script = Self {
+            engine,
+            ast,
+            digest,
+            handled: Mutex::new(HashMap::new()),
+        };
+        if !script.has_fn("map_file") {
+            bail!("script does not define map_file");
+        }
+
+        Ok(script)
+    }
+
+    fn has_fn(&self, name: &str) -> bool {
+        self.ast.iter_functions().any(|func| func.name == name)
+    }
+
+    fn call(&self, name: &str, args: impl rhai::FuncArgs) -> Result<Dynamic> {
+        self.engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &self.ast, name, args)
+            .map_err(|err| eyre!("{err}"))
+            .with_context(|| format!("error in {name}"))
+    }
+
+    fn handles(&self, package_name: &str) -> bool {
+        if !self.has_fn("handles") {
+            return true;
+        }
+
+        if let Some(&handled) = self.handled.lock().unwrap().get(package_name) {
+            return handled;
+        }
+
+        let handled = match self.call("handles", (package_name.to_owned(),)) {
+            Ok(result) => result.as_bool().unwrap_or(false),
+            Err(err) => {
+                warn!("installer script failed for {}: {:#}", package_name, err);
+                false
+            }
+        };
+
+        self.handled
+            .lock()
+            .unwrap()
+            .insert(package_name.to_owned(), handled);
+
+        handled
+    }
+
+    fn map_file(&self, relative_path: &Path, package_name: &str) -> Result<Option<PathBuf>> {
+        let args = (path_to_string(relative_path), package_name.to_owned());
+        let result = self.call("map_file", args)?;
+
+        if result.is_unit() {
+            return Ok(None);
+        }
+
+        let Ok(target) = result.into_string() else {
+            bail!("map_file must return a string or ()");
+        };
+
+        let target = PathBuf::from(target);
+
+        // don't let scripts write outside of the profile
+        let is_safe = target
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+
+        if !is_safe || target.as_os_str().is_empty() {
+            bail!("map_file returned invalid path {}", target.display());
+        }
+
+        Ok(Some(target))
+    }
+
+    fn is_mutable(&self, relative_path: &Path) -> bool {
+        if !self.has_fn("is_mutable") {
+            return false;
+        }
+
+        self.call("is_mutable", (path_to_string(relative_path),))
+            .ok()
+            .and_then(|result| result.as_bool().ok())
+            .unwrap_or(false)
+    }
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Compiles the installer scripts in `data_dir`, replacing any loaded before.
+/// Scripts that fail to compile are skipped.
+pub fn load_scripts(data_dir: &Path) {
+    let mut scripts = HashMap::new();
+
+    if let Ok(entries) = fs::read_dir(data_dir.join(DIR_NAME)) {
+        for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+            if path.extension().map_or(true, |ext| ext != EXTENSION) {
+                continue;
+            }
+
+            let Some(slug) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            match InstallerScript::compile(&path) {
+                Ok(script) => {
+                    info!("loaded installer script for {}", slug);
+                    scripts.insert(slug.to_owned(), Arc::new(script));
+                }
+                Err(err) => {
+                    warn!(
+                        "failed to load installer script {}: {:#}",
+                        path.display(),
+                        err
+                    );
+                }
+            }
+        }
+    }
+
+    *SCRIPTS.write().unwrap() = Some(scripts);
+}
+
+/// Installs packages by asking a game's script where each file goes.
+pub struct ScriptInstaller {
+    script: Arc<InstallerScript>,
+}
+
+impl ScriptInstaller {
+    /// Returns the installer for `package_name` if the game has a script that handles it.
+    pub fn for_package(game_slug: &str, package_name: &str) -> Option<Self> {
+        let script = SCRIPTS.read().unwrap().as_ref()?.get(game_slug)?.clone();
+
+        script.handles(package_name).then_some(Self { script })
+    }
+
+    /// Identifies the version of `game_slug`'s script that would extract `package_name`,
+    /// or `None` if the built-in installer does.
+    pub fn cache_key(game_slug: &str, package_name: &str) -> Option<String> {
+        Self::for_package(game_slug, package_name)
+            .map(|installer| installer.script.digest.to_hex()[..16].to_owned())
+    }
+}
+
+impl PackageInstaller for ScriptInstaller {
+    fn extract(&mut self, archive: PackageZip, package_name: &str, dest: PathBuf) -> Result<()> {
+        install::fs::extract(archive, dest, |relative_path| {
+            Ok(self
+                .script
+                .map_file(relative_path, package_name)?
+                .map(Into::into))
+        })
+    }
+
+    fn plan(&self, files: &[PathBuf], package_name: &str) -> Result<Vec<PlannedFile>> {
+        let mut plan = install::fs::plan(files, |relative_path| {
+            Ok(self
+                .script
+                .map_file(relative_path, package_name)?
+                .map(Into::into))
+        })?;
+
+        for file in &mut plan {
+            if let Some(target) = &file.target {
+                file.mutable = self.script.is_mutable(target);
+                file.tracked = !file.mutable;
+            }
+        }
+
+        Ok(plan)
+    }
+
+    fn install(
+        &mut self,
+        src: &Path,
+        package_name: &str,
+        profile: &Profile,
+        deployer: &dyn Deployer,
+    ) -> Result<()> {
+        let mut state = PackageStateHandle::new(package_name, profile);
+        let mut profile_state = ProfileStateHandle::new(profile);
+
+        install::fs::install(src, profile, deployer, |relative_path, _| {
+            // the script only returns relative paths, but a symlink in the profile
+            // could still lead somewhere else
+            ensure!(
+                access::is_within(&profile.path.join(relative_path), &profile.path),
+                "{} resolves to outside of the profile",
+                relative_path.display()
+            );
+
+            if self.script.is_mutable(relative_path) {
+                return Ok((FileInstallMethod::Copy, ConflictResolution::Merge));
+            }
+
+            if !state.files().iter().any(|file| file == relative_path) {
+                state.files().push(relative_path.to_owned());
+            }
+
+            profile_state
+                .file_map()
+                .insert(relative_path.to_owned(), package_name.to_owned());
+
+            Ok((FileInstallMethod::Link, ConflictResolution::Overwrite))
+        })?;
+
+        state.commit().context("failed to write state")?;
+        profile_state
+            .commit()
+            .context("failed to write profile state")?;
+
+        Ok(())
+    }
+
+    fn is_mutable(&self, relative_path: &Path) -> bool {
+        self.script.is_mutable(relative_path)
+    }
+
+    fn toggle(&mut self, enabled: bool, profile_mod: &ProfileMod, profile: &Profile) -> Result<()> {
+        let mut state = PackageStateHandle::from_profile_mod(profile_mod, profile);
+        for file in state.files() {
+            install::fs::toggle_any(profile.path.join(file), enabled)?;
+        }
+
+        Ok(())
+    }
+
+    fn uninstall(&mut self, profile_mod: &ProfileMod, profile: &Profile) -> Result<()> {
+        let mut state = PackageStateHandle::from_profile_mod(profile_mod, profile);
+        for file in state.files() {
+            install::fs::uninstall_any(profile.path.join(file))?;
+        }
+
+        if let Err(err) = state.delete() {
+            warn!(
+                "failed to delete state file for {}: {:#}",
+                profile_mod.full_name(),
+                err
+            );
+        }
+
+        let mut profile_state = ProfileStateHandle::new(profile);
+        profile_state
+            .file_map()
+            .retain(|_, package| *package != profile_mod.full_name());
+        profile_state
+            .commit()
+            .context("failed to write profile state")?;
+
+        Ok(())
+    }
+}
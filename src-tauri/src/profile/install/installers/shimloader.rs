@@ -1,12 +1,12 @@
 use std::{
     borrow::Cow,
     fs,
-    path::{Component, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
 use eyre::{Context, Result};
 
-use super::{PackageInstaller, PackageZip};
+use super::{PackageInstaller, PackageZip, PlannedFile};
 use crate::profile::{
     install::{self},
     Profile, ProfileMod,
@@ -14,46 +14,50 @@ use crate::profile::{
 
 pub struct ShimloaderInstaller;
 
+fn map_file(relative_path: &Path) -> Option<Cow<'_, Path>> {
+    let mut components = relative_path.components();
+    let in_ue4ss = relative_path.starts_with("UE4SS");
+
+    if in_ue4ss {
+        components.next();
+    }
+
+    let Some(Component::Normal(next)) = components.clone().next() else {
+        return None;
+    };
+
+    match next.to_str() {
+        Some("dwmapi.dll") => {
+            // The Shimloader package has 2 dwmapi.dll files, the one inside "UE4SS" doesn't seem to work.
+            if in_ue4ss {
+                return None;
+            }
+
+            Some(Cow::Borrowed(components.as_path()))
+        }
+        Some("UE4SS.dll" | "UE4SS-settings.ini") => Some(Cow::Borrowed(components.as_path())),
+        Some("Mods") => {
+            components.next();
+
+            let mut path: PathBuf = ["shimloader", "mod"].iter().collect();
+            path.push(components);
+
+            Some(Cow::Owned(path))
+        }
+        _ => None,
+    }
+}
+
 impl PackageInstaller for ShimloaderInstaller {
     fn extract(&mut self, archive: PackageZip, _package_name: &str, dest: PathBuf) -> Result<()> {
         fs::create_dir_all(dest.join("shimloader").join("cfg"))
             .context("failed to create cfg directory")?;
 
-        install::fs::extract(archive, dest, |relative_path| {
-            let mut components = relative_path.components();
-            let in_ue4ss = relative_path.starts_with("UE4SS");
-
-            if in_ue4ss {
-                components.next();
-            }
+        install::fs::extract(archive, dest, |relative_path| Ok(map_file(relative_path)))
+    }
 
-            let Some(Component::Normal(next)) = components.clone().next() else {
-                return Ok(None);
-            };
-
-            Ok(match next.to_str() {
-                Some("dwmapi.dll") => {
-                    // The Shimloader package has 2 dwmapi.dll files, the one inside "UE4SS" doesn't seem to work.
-                    if in_ue4ss {
-                        return Ok(None);
-                    }
-
-                    Some(Cow::Borrowed(components.as_path()))
-                }
-                Some("UE4SS.dll" | "UE4SS-settings.ini") => {
-                    Some(Cow::Borrowed(components.as_path()))
-                }
-                Some("Mods") => {
-                    components.next();
-
-                    let mut path: PathBuf = ["shimloader", "mod"].iter().collect();
-                    path.push(components);
-
-                    Some(Cow::Owned(path))
-                }
-                _ => None,
-            })
-        })
+    fn plan(&self, files: &[PathBuf], _package_name: &str) -> Result<Vec<PlannedFile>> {
+        install::fs::plan(files, |relative_path| Ok(map_file(relative_path)))
     }
 
     fn toggle(
@@ -6,10 +6,12 @@ use std::{
 };
 
 use eyre::{Context, OptionExt, Result};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use tracing::warn;
+use walkdir::WalkDir;
 
-use super::{PackageInstaller, PackageZip};
+use super::{Deployer, PackageInstaller, PackageZip, PlannedFile};
 use crate::{
     profile::{
         install::{
@@ -144,6 +146,14 @@ impl<'a> SubdirInstaller<'a> {
         self.extra_subdirs.iter().chain(self.subdirs.iter())
     }
 
+    /// Finds the most specific subdir that `relative_path` is in, since some
+    /// subdirs are nested inside others.
+    fn subdir_of(&'_ self, relative_path: &Path) -> Option<&'_ Subdir<'_>> {
+        self.subdirs()
+            .filter(|subdir| relative_path.starts_with(subdir.target))
+            .max_by_key(|subdir| subdir.target.len())
+    }
+
     /// Determines if a path component matches any subdir names/extensions.
     fn match_subdir(&'_ self, name: &str) -> Option<&'_ Subdir<'_>> {
         self.subdirs().find(|subdir| {
@@ -357,7 +367,7 @@ fn state_file_path(name: &str, profile: &Profile) -> PathBuf {
 }
 
 /// A handle to an opened state file for one mod/package.
-struct PackageStateHandle {
+pub(super) struct PackageStateHandle {
     path: PathBuf,
     state: PackageState,
 }
@@ -369,33 +379,33 @@ struct PackageState {
 }
 
 impl PackageStateHandle {
-    fn new(package_name: &str, profile: &Profile) -> Self {
+    pub(super) fn new(package_name: &str, profile: &Profile) -> Self {
         let path = state_file_path(package_name, profile);
         let state = util::fs::read_json(&path).unwrap_or_default();
         Self { path, state }
     }
 
-    fn from_profile_mod(profile_mod: &ProfileMod, profile: &Profile) -> Self {
+    pub(super) fn from_profile_mod(profile_mod: &ProfileMod, profile: &Profile) -> Self {
         Self::new(&profile_mod.full_name(), profile)
     }
 
-    fn files(&mut self) -> &mut Vec<PathBuf> {
+    pub(super) fn files(&mut self) -> &mut Vec<PathBuf> {
         &mut self.state.files
     }
 
-    fn commit(&self) -> Result<()> {
+    pub(super) fn commit(&self) -> Result<()> {
         fs::create_dir_all(self.path.parent().unwrap())?;
         util::fs::write_json(&self.path, &self.state, JsonStyle::Pretty)
     }
 
-    fn delete(self) -> Result<()> {
+    pub(super) fn delete(self) -> Result<()> {
         fs::remove_file(self.path)?;
         Ok(())
     }
 }
 
 /// A handle to an opened profile state file.
-struct ProfileStateHandle {
+pub(super) struct ProfileStateHandle {
     path: PathBuf,
     state: ProfileState,
 }
@@ -407,17 +417,17 @@ struct ProfileState {
 }
 
 impl ProfileStateHandle {
-    fn new(profile: &Profile) -> Self {
+    pub(super) fn new(profile: &Profile) -> Self {
         let path = state_file_path("profile", profile);
         let state = util::fs::read_json(&path).unwrap_or_default();
         Self { path, state }
     }
 
-    fn file_map(&mut self) -> &mut HashMap<PathBuf, String> {
+    pub(super) fn file_map(&mut self) -> &mut HashMap<PathBuf, String> {
         &mut self.state.file_map
     }
 
-    fn commit(&self) -> Result<()> {
+    pub(super) fn commit(&self) -> Result<()> {
         fs::create_dir_all(self.path.parent().unwrap())?;
         util::fs::write_json(&self.path, &self.state, JsonStyle::Pretty)
     }
@@ -430,11 +440,39 @@ impl PackageInstaller for SubdirInstaller<'_> {
         })
     }
 
-    fn install(&mut self, src: &Path, package_name: &str, profile: &Profile) -> Result<()> {
+    fn plan(&self, files: &[PathBuf], package_name: &str) -> Result<Vec<PlannedFile>> {
+        let mut plan = install::fs::plan(files, |relative_path| {
+            self.map_file(relative_path, package_name)
+        })?;
+
+        for file in &mut plan {
+            let Some(target) = &file.target else {
+                continue;
+            };
+
+            if let Some(subdir) = self
+                .subdirs()
+                .find(|subdir| target.starts_with(subdir.target))
+            {
+                file.tracked = subdir.mode == SubdirMode::Track;
+                file.mutable = subdir.mutable;
+            }
+        }
+
+        Ok(plan)
+    }
+
+    fn install(
+        &mut self,
+        src: &Path,
+        package_name: &str,
+        profile: &Profile,
+        deployer: &dyn Deployer,
+    ) -> Result<()> {
         let mut state: Option<PackageStateHandle> = None;
         let mut profile_state: Option<ProfileStateHandle> = None;
 
-        install::fs::install(src, profile, |relative_path, exists| {
+        install::fs::install(src, profile, deployer, |relative_path, exists| {
             let subdir = self
                 .subdirs()
                 .find(|subdir| relative_path.starts_with(subdir.target))
@@ -449,6 +487,8 @@ impl PackageInstaller for SubdirInstaller<'_> {
             let conflict = match subdir.mode {
                 // this should never happen
                 SubdirMode::Separate | SubdirMode::SeparateFlatten => ConflictResolution::Skip,
+                // keep the user's changes to configs, but pick up new settings
+                SubdirMode::None if subdir.mutable => ConflictResolution::Merge,
                 SubdirMode::None => ConflictResolution::Overwrite,
                 SubdirMode::Track => {
                     state
@@ -489,6 +529,12 @@ impl PackageInstaller for SubdirInstaller<'_> {
         Ok(())
     }
 
+    fn is_mutable(&self, relative_path: &Path) -> bool {
+        self.subdirs()
+            .find(|subdir| relative_path.starts_with(subdir.target))
+            .is_some_and(|subdir| subdir.mutable)
+    }
+
     fn toggle(&mut self, enabled: bool, profile_mod: &ProfileMod, profile: &Profile) -> Result<()> {
         self.scan_mod(profile_mod, profile, |path| {
             install::fs::toggle_any(path, enabled)
@@ -523,6 +569,58 @@ impl PackageInstaller for SubdirInstaller<'_> {
         Ok(())
     }
 
+    fn foreign_files(&self, profile: &Profile) -> Result<Vec<PathBuf>> {
+        let mut foreign = Vec::new();
+        let mut profile_state: Option<ProfileStateHandle> = None;
+
+        for subdir in self.subdirs().unique_by(|subdir| subdir.target) {
+            match subdir.mode {
+                SubdirMode::Separate | SubdirMode::SeparateFlatten => {
+                    let entries = super::unclaimed_entries(Path::new(subdir.target), profile)?;
+
+                    // leave alone directories that contain other subdirs
+                    foreign.extend(entries.into_iter().filter(|entry| {
+                        !self
+                            .subdirs()
+                            .any(|other| Path::new(other.target).starts_with(entry))
+                    }));
+                }
+                SubdirMode::Track => {
+                    let file_map = profile_state
+                        .get_or_insert_with(|| ProfileStateHandle::new(profile))
+                        .file_map();
+
+                    let files = WalkDir::new(profile.path.join(subdir.target))
+                        .into_iter()
+                        .filter_map(Result::ok)
+                        .filter(|entry| entry.file_type().is_file());
+
+                    for file in files {
+                        let Ok(relative_path) = file.path().strip_prefix(&profile.path) else {
+                            continue;
+                        };
+
+                        // files in nested subdirs are handled when we get to those
+                        let innermost = self.subdir_of(relative_path);
+                        if innermost.is_some_and(|other| other.target != subdir.target) {
+                            continue;
+                        }
+
+                        let enabled_path = install::fs::without_old_ext(relative_path);
+                        if !file_map.contains_key(&enabled_path) {
+                            foreign.push(relative_path.to_path_buf());
+                        }
+                    }
+                }
+                // we don't know which files belong to which mod, and these are
+                // usually configs anyway
+                SubdirMode::None => (),
+            }
+        }
+
+        Ok(foreign)
+    }
+
     fn mod_dir(&self, package_name: &str, profile: &Profile) -> Option<PathBuf> {
         self.default_subdir.map(|index| {
             let mut path = profile.path.to_path_buf();
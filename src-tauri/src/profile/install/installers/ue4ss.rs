@@ -0,0 +1,98 @@
+use std::{
+    borrow::Cow,
+    path::{Component, Components, Path, PathBuf},
+};
+
+use eyre::Result;
+
+use super::{PackageInstaller, PackageZip, PlannedFile};
+use crate::profile::{install, Profile, ProfileMod};
+
+/// Everything UE4SS needs goes into this folder of the profile, which is copied
+/// next to the game's executable on launch.
+const LOADER_DIR: &str = "ue4ss";
+
+/// Entries that older releases keep next to the proxy instead of in [`LOADER_DIR`].
+const ROOT_ENTRIES: &[&str] = &["dwmapi.dll", "UE4SS.dll", "UE4SS-settings.ini", "Mods"];
+
+/// Files that make up the loader itself, as opposed to the mods it ships with.
+const CORE_FILES: &[&str] = &["dwmapi.dll", "UE4SS.dll", "UE4SS-settings.ini"];
+
+pub struct UE4SSInstaller;
+
+fn map_file(relative_path: &Path) -> Option<Cow<'_, Path>> {
+    let mut components = relative_path.components();
+
+    // the package may wrap the loader in a folder of its own
+    if !next_is_loader_root(&components) {
+        components.next();
+    }
+
+    let Some(Component::Normal(first)) = components.next() else {
+        return None;
+    };
+
+    let mut path = PathBuf::from(LOADER_DIR);
+
+    if first != LOADER_DIR {
+        if !ROOT_ENTRIES.contains(&first.to_str()?) {
+            return None;
+        }
+
+        path.push(first);
+    }
+
+    path.push(components.as_path());
+    Some(Cow::Owned(path))
+}
+
+fn next_is_loader_root(components: &Components) -> bool {
+    match components.clone().next() {
+        Some(Component::Normal(name)) => {
+            // case matters, since some packages wrap the loader in an uppercase `UE4SS` folder
+            name == LOADER_DIR
+                || name
+                    .to_str()
+                    .is_some_and(|name| ROOT_ENTRIES.contains(&name))
+        }
+        _ => false,
+    }
+}
+
+fn core_files(profile: &Profile) -> impl Iterator<Item = PathBuf> + '_ {
+    CORE_FILES
+        .iter()
+        .map(|file| profile.path.join(LOADER_DIR).join(file))
+}
+
+impl PackageInstaller for UE4SSInstaller {
+    fn extract(&mut self, archive: PackageZip, _package_name: &str, dest: PathBuf) -> Result<()> {
+        install::fs::extract(archive, dest, |relative_path| Ok(map_file(relative_path)))
+    }
+
+    fn plan(&self, files: &[PathBuf], _package_name: &str) -> Result<Vec<PlannedFile>> {
+        install::fs::plan(files, |relative_path| Ok(map_file(relative_path)))
+    }
+
+    fn toggle(
+        &mut self,
+        enabled: bool,
+        _profile_mod: &ProfileMod,
+        profile: &Profile,
+    ) -> Result<()> {
+        // the bundled mods are left alone, they don't do anything without the loader
+        for path in core_files(profile) {
+            install::fs::toggle_any(path, enabled)?;
+        }
+
+        Ok(())
+    }
+
+    fn uninstall(&mut self, _profile_mod: &ProfileMod, profile: &Profile) -> Result<()> {
+        for path in core_files(profile) {
+            install::fs::uninstall_any(path)?;
+        }
+
+        Ok(())
+    }
+}
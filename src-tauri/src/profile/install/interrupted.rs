@@ -0,0 +1,141 @@
+//! Saves the install queue when the app is closed in the middle of installing,
+//! and queues the unfinished mods again on the next start.
+
+use std::{fs, path::PathBuf};
+
+use eyre::Result;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+use super::{InstallOptions, InstallResultExt, ModInstall};
+use crate::{
+    state::ManagerExt,
+    thunderstore::{self, VersionIdent},
+    util::{self, fs::JsonStyle},
+};
+
+const FILE_NAME: &str = "interrupted_installs.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InterruptedBatch {
+    profile_id: i64,
+    mods: Vec<VersionIdent>,
+}
+
+fn path(app: &AppHandle) -> PathBuf {
+    app.lock_prefs().data_dir.join(FILE_NAME)
+}
+
+/// Writes the current and pending batches of the install queue to disk.
+pub fn save(app: &AppHandle) -> Result<()> {
+    let batches = app
+        .install_queue()
+        .handle()
+        .unfinished()
+        .into_iter()
+        .map(|(profile_id, mods)| InterruptedBatch { profile_id, mods })
+        .collect_vec();
+
+    if batches.is_empty() {
+        return Ok(());
+    }
+
+    info!("saving {} unfinished install batches", batches.len());
+
+    util::fs::write_json(path(app), &batches, JsonStyle::Pretty)
+}
+
+/// Queues the batches that were interrupted last time the app was closed, if any.
+///
+/// Batches for profiles of other games are kept until that game is opened.
+pub async fn resume(app: AppHandle) {
+    let path = path(&app);
+
+    if !path.exists() {
+        return;
+    }
+
+    let batches: Vec<InterruptedBatch> = match util::fs::read_json(&path) {
+        Ok(batches) => batches,
+        Err(err) => {
+            warn!("failed to read interrupted installs: {:#}", err);
+            return;
+        }
+    };
+
+    // the package index only covers the active game, so the rest have to wait
+    let (batches, later): (Vec<_>, Vec<_>) = {
+        let manager = app.lock_manager();
+
+        batches.into_iter().partition(|batch| {
+            manager
+                .profile_by_id(batch.profile_id)
+                .is_ok_and(|(game, _)| game == manager.active_game)
+        })
+    };
+
+    let result = if later.is_empty() {
+        fs::remove_file(&path).map_err(Into::into)
+    } else {
+        util::fs::write_json(&path, &later, JsonStyle::Pretty)
+    };
+
+    if let Err(err) = result {
+        warn!("failed to update interrupted installs: {:#}", err);
+    }
+
+    thunderstore::wait_for_fetch(&app).await;
+
+    for batch in batches {
+        if let Err(err) = resume_batch(batch, &app).await {
+            warn!("failed to resume interrupted installs: {:#}", err);
+        }
+    }
+}
+
+async fn resume_batch(batch: InterruptedBatch, app: &AppHandle) -> Result<()> {
+    let installs = {
+        let manager = app.lock_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        let (_, profile) = manager.profile_by_id(batch.profile_id)?;
+
+        batch
+            .mods
+            .iter()
+            .filter_map(|ident| match thunderstore.find_ident(ident) {
+                Ok(borrowed) => Some(borrowed),
+                Err(_) => {
+                    warn!("{} is no longer available, skipping it", ident);
+                    None
+                }
+            })
+            .filter(|borrowed| !profile.has_mod(borrowed.package.uuid))
+            .map(ModInstall::new)
+            .collect_vec()
+    };
+
+    if installs.is_empty() {
+        return Ok(());
+    }
+
+    info!(
+        "resuming {} interrupted installs in profile {}",
+        installs.len(),
+        batch.profile_id
+    );
+
+    app.install_queue()
+        .install_with_deps(
+            installs,
+            batch.profile_id,
+            InstallOptions::default(),
+            true,
+            app,
+        )?
+        .await
+        .ignore_cancel()
+}
@@ -0,0 +1,102 @@
+//! Records which files in a profile were placed by the mod loader package, so that
+//! removing the loader takes exactly those files and nothing else.
+
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+use tracing::debug;
+use walkdir::WalkDir;
+
+use super::{compress, PackageInstaller};
+use crate::{
+    profile::{Profile, ProfileMod},
+    util::{error::IoResultExt, fs::PathExt},
+};
+
+impl Profile {
+    pub fn is_loader(&self, profile_mod: &ProfileMod) -> bool {
+        self.game
+            .mod_loader
+            .is_loader_package(&profile_mod.full_name())
+    }
+
+    /// Records the files of the loader package cached at `cache_path`, since the cache
+    /// is laid out like the profile. Other packages are ignored.
+    pub fn record_loader_files(&mut self, package_name: &str, cache_path: &Path) {
+        if !self.game.mod_loader.is_loader_package(package_name) {
+            return;
+        }
+
+        self.loader_files = WalkDir::new(cache_path)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| {
+                entry
+                    .path()
+                    .strip_prefix(cache_path)
+                    .ok()
+                    .map(Path::to_path_buf)
+            })
+            .filter(|relative_path| !compress::is_marker(relative_path))
+            .collect();
+
+        debug!(
+            "recorded {} files of {} in {}",
+            self.loader_files.len(),
+            package_name,
+            self.name
+        );
+    }
+
+    /// Removes the recorded loader files, except for ones the user may have edited.
+    ///
+    /// Directories left empty are removed as well.
+    pub fn strip_loader_files(&self, installer: &dyn PackageInstaller) -> Result<()> {
+        let mut dirs = Vec::new();
+
+        for relative_path in &self.loader_files {
+            if installer.is_mutable(relative_path) {
+                continue;
+            }
+
+            let mut path = self.path.join(relative_path);
+            if !path.exists() {
+                // the loader might be disabled
+                path.add_ext("old");
+            }
+
+            if path.exists() {
+                fs::remove_file(&path).fs_context("removing loader file", &path)?;
+            }
+
+            dirs.extend(relative_path.parent().map(|parent| self.path.join(parent)));
+        }
+
+        remove_empty_dirs(dirs, &self.path);
+
+        Ok(())
+    }
+}
+
+fn remove_empty_dirs(mut dirs: Vec<PathBuf>, root: &Path) {
+    // deepest first, so that parents are empty by the time we get to them
+    dirs.sort();
+    dirs.dedup();
+    dirs.sort_by_key(|dir| Reverse(dir.components().count()));
+
+    for mut dir in dirs {
+        while dir != root && dir.starts_with(root) {
+            // fails if the directory isn't empty, which is fine
+            if fs::remove_dir(&dir).is_err() {
+                break;
+            }
+
+            dir.pop();
+        }
+    }
+}
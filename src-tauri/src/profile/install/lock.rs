@@ -0,0 +1,296 @@
+//! Lockfiles pin the exact versions and contents of a profile's mods, so that the
+//! profile can later be reproduced exactly, for example on a dedicated server.
+//!
+//! The hash of each mod is computed over its extracted files in the download cache,
+//! since the original archives are not kept around after extraction.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use eyre::{bail, ensure, Context, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+use super::{cache, compress, deploy, InstallOptions, InstallResultExt, ModInstall};
+use crate::{
+    prefs::Prefs,
+    profile::{import, Profile},
+    state::ManagerExt,
+    thunderstore::{ModId, VersionIdent},
+    util::{self, error::IoResultExt, fs::JsonStyle},
+};
+
+pub const FILE_NAME: &str = "gale.lock";
+
+/// Bumped whenever the format of the lockfile changes in an incompatible way.
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockFile {
+    pub format_version: u32,
+    pub game: String,
+    pub mods: Vec<LockedMod>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedMod {
+    #[serde(rename = "fullName")]
+    pub ident: VersionIdent,
+    #[serde(flatten)]
+    pub id: ModId,
+    pub enabled: bool,
+    /// Blake3 hash of the mod's extracted files.
+    ///
+    /// This is `None` if the mod was no longer in the cache when the lockfile was generated.
+    pub hash: Option<String>,
+}
+
+impl Profile {
+    pub fn lock_path(&self) -> PathBuf {
+        self.path.join(FILE_NAME)
+    }
+
+    /// Generates a lockfile for the profile. Local mods are not included.
+    pub fn lock(&self, prefs: &Prefs) -> Result<LockFile> {
+        let mods = self
+            .thunderstore_mods()
+            .map(|(ts_mod, enabled)| {
+                let path = cache::path(&ts_mod.ident, self.game, prefs);
+                let hash = match cache::exists(&path) {
+                    true => Some(hash_dir(&path)?.to_string()),
+                    false => {
+                        warn!("{} is not cached, omitting its hash", ts_mod.ident);
+                        None
+                    }
+                };
+
+                Ok(LockedMod {
+                    ident: ts_mod.ident.clone(),
+                    id: ts_mod.id.clone(),
+                    enabled,
+                    hash,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(LockFile {
+            format_version: FORMAT_VERSION,
+            game: self.game.slug.to_string(),
+            mods,
+        })
+    }
+
+    /// Generates and writes a lockfile to `gale.lock` in the profile directory.
+    pub fn write_lock(&self, prefs: &Prefs) -> Result<PathBuf> {
+        let lock = self.lock(prefs)?;
+        let path = self.lock_path();
+
+        util::fs::write_json(&path, &lock, JsonStyle::Pretty)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+
+        info!(
+            "wrote lockfile for profile {} with {} mods",
+            self.name,
+            lock.mods.len()
+        );
+
+        Ok(path)
+    }
+}
+
+pub fn read(path: &Path) -> Result<LockFile> {
+    let lock: LockFile = util::fs::read_json(path)
+        .with_context(|| format!("failed to read lockfile at {}", path.display()))?;
+
+    ensure!(
+        lock.format_version <= FORMAT_VERSION,
+        "lockfile was created by a newer version of Gale"
+    );
+
+    Ok(lock)
+}
+
+/// Installs the mods specified in a lockfile into a profile.
+///
+/// If `path` is `None`, the profile's own `gale.lock` is used.
+pub async fn install_locked(profile_id: i64, path: Option<PathBuf>, app: &AppHandle) -> Result<()> {
+    let path = match path {
+        Some(path) => path,
+        None => app.lock_manager().profile_by_id(profile_id)?.1.lock_path(),
+    };
+
+    let lock = read(&path)?;
+    install_lock(profile_id, lock, InstallOptions::default(), app).await
+}
+
+/// Installs the exact mods specified in a lockfile into a profile, removing any
+/// other Thunderstore mods and verifying the contents of each mod against its hash.
+pub async fn install_lock(
+    profile_id: i64,
+    lock: LockFile,
+    options: InstallOptions,
+    app: &AppHandle,
+) -> Result<()> {
+    let to_install = {
+        let prefs = app.lock_prefs();
+        let mut manager = app.lock_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        let (game, _) = manager.profile_by_id(profile_id)?;
+
+        ensure!(
+            lock.game == *game.slug,
+            "lockfile is for {}, but the profile is for {}",
+            lock.game,
+            game.slug
+        );
+
+        let installs = lock
+            .mods
+            .iter()
+            .map(|locked| {
+                ModInstall::try_from_id(locked.id.clone(), &thunderstore)
+                    .map(|install| install.with_state(locked.enabled))
+                    .with_context(|| format!("{} is not available on Thunderstore", locked.ident))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // make sure we don't install from a cache entry that doesn't match the lock
+        let mut mismatched = Vec::new();
+        for locked in &lock.mods {
+            let cache_path = cache::path(&locked.ident, game, &prefs);
+
+            if cache::exists(&cache_path) && !verify(locked, &cache_path)? {
+                deploy::ensure_not_linked(Some(&locked.ident), Some(profile_id), &manager, &prefs)?;
+
+                mismatched.push((locked, cache_path));
+            }
+        }
+
+        let (_, profile) = manager.profile_by_id_mut(profile_id)?;
+
+        for (locked, cache_path) in mismatched {
+            warn!(
+                "cached files of {} do not match lockfile, redownloading",
+                locked.ident
+            );
+
+            fs::remove_dir_all(&cache_path).fs_context("removing cache entry", &cache_path)?;
+
+            // the installed files came from the bad cache entry, so reinstall the mod as well
+            if profile.has_mod(locked.id.package_uuid) {
+                profile.force_remove_mod(locked.id.package_uuid)?;
+            }
+        }
+
+        let to_install = import::incremental_update(installs, profile)?.collect_vec();
+        profile.save(app, true)?;
+
+        to_install
+    };
+
+    app.install_queue()
+        .install(to_install, profile_id, options, app)
+        .await
+        .ignore_cancel()?;
+
+    let prefs = app.lock_prefs();
+    let mut manager = app.lock_manager();
+    let (game, profile) = manager.profile_by_id_mut(profile_id)?;
+
+    for locked in &lock.mods {
+        let cache_path = cache::path(&locked.ident, game, &prefs);
+
+        if !cache_path.exists() {
+            warn!("unable to verify {}, since it is not cached", locked.ident);
+            continue;
+        }
+
+        if !verify(locked, &cache_path)? {
+            bail!("hash mismatch for {}", locked.ident);
+        }
+    }
+
+    // match the load order of the lockfile
+    profile.mods.sort_by_key(|profile_mod| {
+        lock.mods
+            .iter()
+            .position(|locked| locked.id.package_uuid == profile_mod.uuid())
+            .unwrap_or(usize::MAX)
+    });
+
+    profile.save(app, true)?;
+
+    info!(
+        "installed {} mods from lockfile into profile {}",
+        lock.mods.len(),
+        profile.name
+    );
+
+    Ok(())
+}
+
+/// Checks the cached files of a locked mod against its hash.
+///
+/// Mods without a hash always pass.
+fn verify(locked: &LockedMod, cache_path: &Path) -> Result<bool> {
+    let Some(expected) = &locked.hash else {
+        return Ok(true);
+    };
+
+    let actual = hash_dir(cache_path)?;
+    Ok(actual.to_string() == *expected)
+}
+
+/// Hashes the relative paths and contents of all files in a directory.
+///
+/// Entries are visited in a sorted order, so the hash is stable across platforms.
+fn hash_dir(path: &Path) -> Result<blake3::Hash> {
+    let mut hasher = blake3::Hasher::new();
+    // hash the original contents, so the result doesn't depend on compression
+    let compressed = compress::is_compressed(path);
+
+    for entry in WalkDir::new(path).sort_by_file_name() {
+        let entry = entry.context("failed to read directory entry")?;
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = entry
+            .path()
+            .strip_prefix(path)
+            .expect("walkdir should only yield paths inside the root");
+
+        if compress::is_marker(relative) {
+            continue;
+        }
+
+        // normalize separators so windows and unix agree
+        let relative = relative.to_string_lossy().replace('\\', "/");
+
+        // prefix every field with its length, so that bytes can't shift from the
+        // contents of one file to the path of the next without changing the hash
+        hasher.update(&(relative.len() as u64).to_le_bytes());
+        hasher.update(relative.as_bytes());
+
+        // the length of a compressed file's contents isn't known up front, so they
+        // are hashed separately and added as a fixed-size hash after their length
+        let mut file =
+            compress::reader(entry.path(), compressed).fs_context("opening file", entry.path())?;
+        let mut contents = blake3::Hasher::new();
+        let len = io::copy(&mut file, &mut contents).fs_context("hashing file", entry.path())?;
+
+        hasher.update(&len.to_le_bytes());
+        hasher.update(contents.finalize().as_bytes());
+    }
+
+    Ok(hasher.finalize())
+}
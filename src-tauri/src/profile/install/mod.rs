@@ -5,21 +5,34 @@
 /// 1) The mod ZIP is downloaded from Thunderstore and extracted according to the modloader-specific rules.
 /// This is handled by [`PackageInstaller::extract`], implemented in the respective `installers` submodules.
 /// 2) The extracted files are placed into `<data directory>/cache/<author-name>/<version>`.
-/// 3) The files are copied to the profile folder (at the same relative path). Most files are copied using
-/// hard links, but ones that are expected to change are properly cloned. This is also implemented in the
-/// respective `installers` submodules.
+/// 3) The files are copied to the profile folder (at the same relative path). Most files are placed using
+/// the game's deployment strategy (hard links by default), but ones that are expected to change are
+/// properly cloned. This is also implemented in the respective `installers` submodules.
 ///
 /// If the cache is hit, we skip directly to step 3). This process is orchestrated in the `queue` module.
 ///
 /// Modules:
+/// - `archive`: mirrors downloaded zips to a user-specified folder, used for offline installs
 /// - `cache`: functions related to locating and clearing the mod download cache
+/// - `checksum`: verifies downloaded archives before they are extracted
+/// - `compress`: optionally compresses cache entries with zstd
+/// - `dedup`: links identical files between cached versions of a package
+/// - `deploy`: the strategies for placing cached files into profiles
+/// - `foreign`: finds files in managed directories that no installed package claims
 /// - `fs`: utility file system functions for common installer tasks such as extraction
 /// - `queue`: handles the queue of mod installations, orchestrating the other modules
+/// - `reinstall`: installs a single mod again from a fresh copy
+/// - `tuning`: thread and buffer settings used during extraction
+/// - `quarantine`: holds back new packages with suspicious files until the user approves them
+/// - `lock`: generates and installs from `gale.lock` files
+/// - `loader_files`: keeps track of the files owned by the mod loader package
 /// - `installers`: contains installers handle the modloader-specific file placement
-use std::{fmt::Display, iter, process};
+/// - `interrupted`: saves unfinished installations on exit and resumes them on the next start
+use std::{collections::HashSet, fmt::Display, iter, path::PathBuf};
 
 use chrono::{DateTime, Utc};
 use eyre::Result;
+use internment::Intern;
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 use tauri_plugin_dialog::DialogExt;
@@ -29,18 +42,37 @@ use uuid::Uuid;
 
 use super::Profile;
 use crate::{
+    game::anti_cheat,
+    i18n,
     prefs::Prefs,
     profile::{ProfileMod, ProfileModKind, ThunderstoreMod},
     state::ManagerExt,
     thunderstore::{BorrowedMod, ModId, Thunderstore, VersionIdent},
+    util,
 };
 
-mod cache;
+pub mod archive;
+pub mod bundle;
+pub mod cache;
+mod checksum;
 pub mod commands;
+mod compress;
+mod dedup;
+pub mod deploy;
+pub mod foreign;
 mod fs;
 mod installers;
+pub mod interrupted;
 pub use installers::*;
+pub use reinstall::reinstall;
+mod loader_files;
+pub mod lock;
+pub mod preview;
+mod quarantine;
 pub mod queue;
+mod reinstall;
+pub mod tuning;
+mod url;
 
 type BeforeInstallHandler =
     Box<dyn Fn(&ModInstall, &mut Profile) -> Result<()> + 'static + Send + Sync>;
@@ -62,6 +94,9 @@ pub enum CancelBehavior {
 pub struct InstallOptions {
     cancel_behavior: CancelBehavior,
     before_install: Option<BeforeInstallHandler>,
+    /// A folder of zips to try before the archive or downloading, which are verified
+    /// just like downloads.
+    archives: Option<PathBuf>,
 }
 
 impl InstallOptions {
@@ -82,6 +117,11 @@ impl InstallOptions {
         self.before_install = Some(before_install);
         self
     }
+
+    pub fn archives_from(mut self, dir: PathBuf) -> Self {
+        self.archives = Some(dir);
+        self
+    }
 }
 
 /// A mod waiting to be installed via [`queue::InstallQueue`].
@@ -102,6 +142,14 @@ pub struct ModInstall {
     ///
     /// This is mainly used to retain the install date when updating mods.
     install_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    categories: HashSet<Intern<String>>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    note: Option<String>,
+    #[serde(default)]
+    local_only: bool,
 }
 
 impl ModInstall {
@@ -114,6 +162,10 @@ impl ModInstall {
             enabled: true,
             index: None,
             install_time: None,
+            categories: borrowed.package.categories.clone(),
+            tags: Vec::new(),
+            note: None,
+            local_only: false,
         }
     }
 
@@ -136,10 +188,29 @@ impl ModInstall {
         self
     }
 
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_note(mut self, note: Option<String>) -> Self {
+        self.note = note;
+        self
+    }
+
+    pub fn with_local_only(mut self, local_only: bool) -> Self {
+        self.local_only = local_only;
+        self
+    }
+
     pub fn mod_id(&self) -> &ModId {
         &self.id
     }
 
+    pub fn ident(&self) -> &VersionIdent {
+        &self.ident
+    }
+
     /// The uuid the resulting `ProfileMod` will get after the mod is installed.
     pub fn uuid(&self) -> Uuid {
         self.id.package_uuid
@@ -156,16 +227,26 @@ impl ModInstall {
             enabled,
             index,
             install_time,
+            categories,
+            tags,
+            note,
+            local_only,
             ..
         } = self;
 
         let uuid = id.package_uuid;
         let install_time = install_time.unwrap_or_else(Utc::now);
 
-        let profile_mod = ProfileMod::new_at(
-            install_time,
-            ProfileModKind::Thunderstore(ThunderstoreMod { ident, id }),
-        );
+        let profile_mod = ProfileMod {
+            categories,
+            tags,
+            note,
+            local_only,
+            ..ProfileMod::new_at(
+                install_time,
+                ProfileModKind::Thunderstore(ThunderstoreMod { ident, id }),
+            )
+        };
 
         match index {
             Some(index) if index < profile.mods.len() => {
@@ -243,13 +324,46 @@ fn total_download_size(
         .missing_deps(borrowed.dependencies(), thunderstore)
         .chain(iter::once(borrowed))
         .filter(|borrowed| {
-            !cache::path(borrowed.ident(), prefs).exists()
+            !cache::path(borrowed.ident(), profile.game, prefs).exists()
                 && !queue.has_mod(borrowed.package.uuid, profile.id)
         })
         .map(|borrowed| borrowed.version.file_size)
         .sum()
 }
 
+/// Extracted mods take up roughly this many times their download size.
+const EXTRACT_FACTOR: u64 = 3;
+
+/// Fails early if the game's anti-cheat warning hasn't been acknowledged, or if the
+/// cache or profile drive can't fit `mods` once they're downloaded, extracted and installed.
+///
+/// The space is only an estimate, but it beats running out of space halfway through
+/// a batch and leaving the profile partially installed.
+fn check_can_install(mods: &[ModInstall], profile_id: i64, app: &AppHandle) -> Result<()> {
+    let prefs = app.lock_prefs();
+    let manager = app.lock_manager();
+    let (game, profile) = manager.profile_by_id(profile_id)?;
+
+    anti_cheat::check(game, &prefs)?;
+
+    let uncached = mods
+        .iter()
+        .filter(|install| !cache::path(&install.ident, profile.game, &prefs).exists())
+        .map(|install| install.file_size)
+        .sum::<u64>();
+
+    util::fs::ensure_free_space(&prefs.cache_dir(), uncached * EXTRACT_FACTOR)?;
+
+    // files are hard linked from the cache, unless the profile lives somewhere else
+    // (possibly on another drive), in which case they have to be copied in full
+    if !profile.path.starts_with(&*prefs.data_dir) {
+        let total = mods.iter().map(|install| install.file_size).sum::<u64>();
+        util::fs::ensure_free_space(&profile.path, total * EXTRACT_FACTOR)?;
+    }
+
+    Ok(())
+}
+
 pub async fn handle_exit(app: AppHandle) {
     let install_queue = app.install_queue();
 
@@ -263,10 +377,10 @@ pub async fn handle_exit(app: AppHandle) {
     let wait_for_install = install_queue.wait_for_empty();
 
     app.dialog()
-        .message("Gale is busy installing mods.")
+        .message(i18n::t("exit.busy", &[]))
         .buttons(tauri_plugin_dialog::MessageDialogButtons::OkCancelCustom(
-            "Continue in background".to_string(),
-            "Cancel".to_string(),
+            i18n::t("exit.continue", &[]),
+            i18n::t("exit.cancel", &[]),
         ))
         .show(move |result| {
             dialog_tx
@@ -285,11 +399,15 @@ pub async fn handle_exit(app: AppHandle) {
             info!("waiting for installations to complete before exiting");
         }
         DialogDecision::Cancel => {
+            if let Err(err) = interrupted::save(&app) {
+                warn!("failed to save unfinished installations: {:#}", err);
+            }
+
             warn!("cancelling installations");
             install_queue.cancel_all();
         }
     }
 
     wait_for_install.await;
-    process::exit(0);
+    app.exit(0);
 }
@@ -0,0 +1,185 @@
+//! Shows everything a package would pull in before it's queued, so that large
+//! dependency trees, like those of modpacks, can be trimmed down first.
+//!
+//! Packages can also list optional dependencies in an `optional_dependencies` field
+//! of their manifest. That isn't part of Thunderstore's format, so the manifest is
+//! read straight from the archive. Optional dependencies are only installed if picked.
+
+use std::{collections::HashSet, iter};
+
+use eyre::{ensure, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::warn;
+use uuid::Uuid;
+
+use super::{cache, InstallOptions, InstallResultExt, ModInstall};
+use crate::{
+    prefs::Prefs,
+    profile::Profile,
+    state::ManagerExt,
+    thunderstore::{contents, BorrowedMod, ModId, VersionIdent},
+};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyPreview {
+    /// The package itself, followed by all of its dependencies.
+    entries: Vec<PreviewEntry>,
+    /// Extras the package works with, which aren't installed unless picked.
+    optional: Vec<PreviewEntry>,
+    /// Combined size of the missing packages that aren't downloaded yet, in bytes.
+    download_size: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewEntry {
+    /// Passed back to leave the package out, or to pick it if it's optional.
+    id: ModId,
+    ident: VersionIdent,
+    file_size: u64,
+    /// Whether the profile already has some version of the package.
+    installed: bool,
+    cached: bool,
+}
+
+impl PreviewEntry {
+    fn new(borrowed: BorrowedMod, profile: &Profile, prefs: &Prefs) -> Self {
+        Self {
+            id: borrowed.into(),
+            ident: borrowed.ident().clone(),
+            file_size: borrowed.version.file_size,
+            installed: profile.has_mod(borrowed.package.uuid),
+            cached: cache::path(borrowed.ident(), profile.game, prefs).exists(),
+        }
+    }
+}
+
+/// Only the part of the manifest we care about, so that otherwise broken
+/// manifests can still be read.
+#[derive(Deserialize)]
+struct OptionalManifest {
+    #[serde(default)]
+    optional_dependencies: Vec<VersionIdent>,
+}
+
+async fn optional_dependencies(ident: &VersionIdent, app: &AppHandle) -> Vec<VersionIdent> {
+    let result = async {
+        let Some(bytes) = contents::read(ident, "manifest.json", app).await? else {
+            return Ok(Vec::new());
+        };
+
+        // plenty of manifests are saved with a byte order mark
+        let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&bytes);
+        let manifest: OptionalManifest = serde_json::from_slice(bytes)?;
+
+        eyre::Ok(manifest.optional_dependencies)
+    }
+    .await;
+
+    result.unwrap_or_else(|err| {
+        warn!(
+            "failed to read optional dependencies of {}: {:#}",
+            ident, err
+        );
+        Vec::new()
+    })
+}
+
+/// Resolves the full dependency tree of a package for the active profile.
+pub async fn preview(id: &ModId, app: &AppHandle) -> Result<DependencyPreview> {
+    let ident = id.borrow(&app.lock_thunderstore())?.ident().clone();
+    let optional_idents = optional_dependencies(&ident, app).await;
+
+    let prefs = app.lock_prefs();
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    let profile = manager.active_profile();
+    let root = id.borrow(&thunderstore)?;
+
+    let entries = iter::once(root)
+        .chain(thunderstore.dependencies(root.dependencies()))
+        .unique_by(|borrowed| borrowed.package.uuid)
+        .map(|borrowed| PreviewEntry::new(borrowed, profile, &prefs))
+        .collect_vec();
+
+    let required = entries
+        .iter()
+        .map(|entry| entry.id.package_uuid)
+        .collect::<HashSet<_>>();
+
+    let optional = optional_idents
+        .iter()
+        .filter_map(|ident| thunderstore.find_ident(ident).ok())
+        .filter(|borrowed| !required.contains(&borrowed.package.uuid))
+        .unique_by(|borrowed| borrowed.package.uuid)
+        .map(|borrowed| PreviewEntry::new(borrowed, profile, &prefs))
+        .collect_vec();
+
+    let download_size = entries
+        .iter()
+        .filter(|entry| !entry.installed && !entry.cached)
+        .map(|entry| entry.file_size)
+        .sum();
+
+    Ok(DependencyPreview {
+        entries,
+        optional,
+        download_size,
+    })
+}
+
+/// Installs a package into the active profile along with its missing dependencies and
+/// the `optional` ones that were picked, leaving out the packages in `excluded`.
+pub async fn install(
+    id: &ModId,
+    excluded: &HashSet<Uuid>,
+    optional: &[ModId],
+    app: &AppHandle,
+) -> Result<()> {
+    let (profile_id, installs) = {
+        let manager = app.lock_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        let profile = manager.active_profile();
+        let root = id.borrow(&thunderstore)?;
+
+        ensure!(
+            !profile.has_mod(root.package.uuid),
+            "mod is already installed"
+        );
+
+        let roots = iter::once(Ok(root))
+            .chain(optional.iter().map(|id| id.borrow(&thunderstore)))
+            .filter_ok(|borrowed| !profile.has_mod(borrowed.package.uuid))
+            .collect::<Result<Vec<_>>>()?;
+
+        let dependencies = profile
+            .missing_deps(
+                roots.iter().flat_map(|borrowed| borrowed.dependencies()),
+                &thunderstore,
+            )
+            .filter(|dep| !excluded.contains(&dep.package.uuid));
+
+        let installs = roots
+            .iter()
+            .copied()
+            .chain(dependencies)
+            .map(ModInstall::new)
+            .unique_by(|install| install.uuid())
+            .collect_vec()
+            .into_iter()
+            .rev() // install dependencies first
+            .collect_vec();
+
+        (profile.id, installs)
+    };
+
+    app.install_queue()
+        .install(installs, profile_id, InstallOptions::default(), app)
+        .await
+        .ignore_cancel()
+}
@@ -0,0 +1,169 @@
+//! Optional review of newly installed packages before their files reach a profile.
+//!
+//! Packages are extracted into the cache before being linked into a profile, so the cache
+//! doubles as the quarantine area. If the package contains executables, scripts or files
+//! placed directly in the profile root (which usually ends up in the game directory),
+//! installation is paused until the user approves or rejects them.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicBool, LazyLock, Mutex},
+    time::Duration,
+};
+
+use eyre::eyre;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::{compress, queue, InstallError, InstallOptions, InstallResult, ModInstall};
+use crate::{i18n, state::ManagerExt, thunderstore::VersionIdent};
+
+const EXECUTABLE_EXTENSIONS: &[&str] = &["exe", "msi", "com", "scr", "jar"];
+const SCRIPT_EXTENSIONS: &[&str] = &["bat", "cmd", "ps1", "vbs", "sh", "py"];
+
+/// Reviews that are waiting for the user to respond, by package uuid.
+static PENDING: LazyLock<Mutex<HashMap<Uuid, oneshot::Sender<bool>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantineReview {
+    pub package_uuid: Uuid,
+    pub ident: VersionIdent,
+    pub flagged: Vec<FlaggedFile>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FlaggedFile {
+    /// The path relative to the profile directory.
+    pub path: PathBuf,
+    pub reason: FlagReason,
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum FlagReason {
+    Executable,
+    Script,
+    /// The file is placed directly in the profile root.
+    Root,
+}
+
+/// Waits for the user to approve a package's files, if quarantine is turned on
+/// and the package hasn't been approved before.
+///
+/// Rejected packages are removed from the cache and fail to install.
+pub(super) async fn review(
+    install: &ModInstall,
+    cache_path: &Path,
+    cancel: &AtomicBool,
+    options: &InstallOptions,
+    app: &AppHandle,
+) -> InstallResult<()> {
+    if !app.lock_prefs().quarantine_new_mods {
+        return Ok(());
+    }
+
+    let uuid = install.uuid();
+    if app.db().is_package_approved(uuid)? {
+        return Ok(());
+    }
+
+    let flagged = scan(cache_path);
+    if flagged.is_empty() {
+        app.db().approve_package(uuid)?;
+        return Ok(());
+    }
+
+    info!(
+        "holding {} for review ({} flagged files)",
+        install.ident,
+        flagged.len()
+    );
+
+    let (tx, mut rx) = oneshot::channel();
+    PENDING.lock().unwrap().insert(uuid, tx);
+
+    app.emit(
+        "quarantine_review",
+        QuarantineReview {
+            package_uuid: uuid,
+            ident: install.ident.clone(),
+            flagged,
+        },
+    )
+    .ok();
+
+    let approved = loop {
+        if let Err(err) = queue::check_cancel(cancel, options) {
+            PENDING.lock().unwrap().remove(&uuid);
+            return Err(err);
+        }
+
+        match tokio::time::timeout(Duration::from_millis(250), &mut rx).await {
+            Ok(Ok(approved)) => break approved,
+            Ok(Err(_)) => break false,
+            Err(_) => continue,
+        }
+    };
+
+    if approved {
+        app.db().approve_package(uuid)?;
+        return Ok(());
+    }
+
+    fs::remove_dir_all(cache_path)
+        .unwrap_or_else(|err| warn!("failed to remove rejected package from cache: {}", err));
+
+    Err(InstallError::Err(eyre!(i18n::t(
+        "install.rejected",
+        &[("ident", &install.ident)]
+    ))))
+}
+
+/// Answers a pending review. Returns `false` if there was none for the package.
+pub fn resolve(package_uuid: Uuid, approve: bool) -> bool {
+    match PENDING.lock().unwrap().remove(&package_uuid) {
+        Some(tx) => tx.send(approve).is_ok(),
+        None => false,
+    }
+}
+
+fn scan(cache_path: &Path) -> Vec<FlaggedFile> {
+    WalkDir::new(cache_path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let path = entry.path().strip_prefix(cache_path).ok()?.to_path_buf();
+            if compress::is_marker(&path) {
+                return None;
+            }
+
+            let reason = flag_reason(&path)?;
+
+            Some(FlaggedFile { path, reason })
+        })
+        .collect()
+}
+
+fn flag_reason(path: &Path) -> Option<FlagReason> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some(ext) if EXECUTABLE_EXTENSIONS.contains(&ext) => Some(FlagReason::Executable),
+        Some(ext) if SCRIPT_EXTENSIONS.contains(&ext) => Some(FlagReason::Script),
+        _ if path.components().count() == 1 => Some(FlagReason::Root),
+        _ => None,
+    }
+}
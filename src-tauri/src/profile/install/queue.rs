@@ -1,9 +1,8 @@
 use std::{
     collections::VecDeque,
-    fs,
     future::Future,
-    io::Cursor,
     iter,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
         Mutex, MutexGuard,
@@ -11,19 +10,27 @@ use std::{
     time::{Duration, Instant},
 };
 
-use eyre::{bail, eyre, Context, Result};
-use futures_util::StreamExt;
+use eyre::{bail, eyre, Context, OptionExt, Result};
+use futures_util::{stream, StreamExt};
 use itertools::Itertools;
 use serde::Serialize;
 use tauri::{AppHandle, Emitter};
 use tokio::sync::{futures::Notified, oneshot, Notify};
-use tracing::warn;
+use tracing::{debug, instrument, warn};
+use ts_rs::TS;
 use uuid::Uuid;
-use zip::ZipArchive;
 
-use crate::{logger, state::ManagerExt, thunderstore::VersionIdent, util::error::IoResultExt};
+use crate::{
+    logger,
+    state::ManagerExt,
+    thunderstore::{self, VersionIdent},
+    util::net::{self, Operation},
+};
 
-use super::{CancelBehavior, InstallError, InstallOptions, InstallResult, ModInstall};
+use super::{
+    checksum::{self, Verdict},
+    quarantine, CancelBehavior, InstallError, InstallOptions, InstallResult, ModInstall,
+};
 
 pub struct InstallQueue {
     state: Mutex<State>,
@@ -37,8 +44,77 @@ pub struct InstallQueue {
 #[derive(Default)]
 struct State {
     pending: VecDeque<InstallBatch>,
-    /// The profile id and package uuids of the currently installing batch.
-    processing: Option<(i64, Vec<Uuid>)>,
+    /// The profile id and mods of the currently installing batch.
+    processing: Option<(i64, Vec<ModInstall>)>,
+    /// Id of the currently installing batch and the state of each of its mods.
+    current: Option<(u64, Vec<QueueItemState>)>,
+    /// Set when only the current batch should be cancelled, instead of the whole queue.
+    cancel_only: Option<u64>,
+    next_batch_id: u64,
+}
+
+/// A mod in the install queue, as shown in the frontend.
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct QueueItem {
+    #[ts(type = "number")]
+    batch_id: u64,
+    #[ts(type = "number")]
+    profile_id: i64,
+    /// Position in the whole queue, starting at 0.
+    position: usize,
+    #[ts(type = "string")]
+    ident: VersionIdent,
+    #[ts(type = "number")]
+    file_size: u64,
+    state: QueueItemState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum QueueItemState {
+    Queued,
+    Downloading,
+    Extracting,
+    Installing,
+    Done,
+}
+
+impl From<InstallTask> for QueueItemState {
+    fn from(task: InstallTask) -> Self {
+        match task {
+            InstallTask::Download => Self::Downloading,
+            InstallTask::Extract => Self::Extracting,
+            InstallTask::Install => Self::Installing,
+        }
+    }
+}
+
+/// Changes to the queue's contents, sent to the frontend as `install_queue` events.
+#[derive(Debug, Serialize, Clone, TS)]
+#[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export)]
+enum QueueDiff {
+    /// A batch was pushed to the back of the queue.
+    Added { items: Vec<QueueItem> },
+    #[serde(rename_all = "camelCase")]
+    Updated {
+        #[ts(type = "number")]
+        batch_id: u64,
+        #[ts(type = "string")]
+        ident: VersionIdent,
+        state: QueueItemState,
+    },
+    /// A batch finished, failed or was cancelled.
+    #[serde(rename_all = "camelCase")]
+    Removed {
+        #[ts(type = "number")]
+        batch_id: u64,
+    },
+    /// The queue was reordered, replace everything.
+    Reset { items: Vec<QueueItem> },
 }
 
 impl InstallQueue {
@@ -60,6 +136,7 @@ impl InstallQueue {
     }
 
     pub fn cancel_all(&self) {
+        self.state.lock().unwrap().cancel_only = None;
         self.cancel.store(true, Ordering::SeqCst);
     }
 
@@ -81,7 +158,12 @@ impl InstallQueue {
         options: InstallOptions,
         app: &AppHandle,
     ) -> impl Future<Output = InstallResult<()>> {
-        self.handle().push_batch(mods, profile_id, options, app)
+        let mods = mods.into_iter().collect_vec();
+
+        let pushed = super::check_can_install(&mods, profile_id, app)
+            .map(|()| self.handle().push_batch(mods, profile_id, options, app));
+
+        async move { pushed?.await }
     }
 
     /// Queue mods to be installed, along with their dependencies. Returns a future that resolves
@@ -99,11 +181,51 @@ impl InstallQueue {
         allow_multiple: bool,
         app: &AppHandle,
     ) -> Result<impl Future<Output = InstallResult<()>>> {
-        self.handle()
-            .push_with_deps(mods, profile_id, options, allow_multiple, app)
+        let mods = resolve_deps(mods, profile_id, allow_multiple, app)?;
+        super::check_can_install(&mods, profile_id, app)?;
+
+        Ok(self.handle().push_batch(mods, profile_id, options, app))
     }
 }
 
+/// Adds the missing dependencies of each mod, ordered so that dependencies come first.
+fn resolve_deps(
+    mods: Vec<ModInstall>,
+    profile_id: i64,
+    allow_multiple: bool,
+    app: &AppHandle,
+) -> Result<Vec<ModInstall>> {
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+    let (_, profile) = manager.profile_by_id(profile_id)?;
+
+    if !allow_multiple && mods.len() == 1 && profile.has_mod(mods[0].uuid()) {
+        bail!("mod is already installed");
+    }
+
+    // find the missing dependencies of each mod and flatten them into one vec
+    let mods = mods
+        .into_iter()
+        .map(|install| {
+            let borrowed = install.id.borrow(&thunderstore)?;
+
+            Ok(iter::once(install).chain(
+                profile
+                    .missing_deps(borrowed.dependencies(), &thunderstore)
+                    .map(ModInstall::from),
+            ))
+        })
+        .flatten_ok()
+        .collect::<Result<Vec<_>>>()
+        .context("failed to resolve dependencies")?;
+
+    Ok(mods
+        .into_iter()
+        .unique_by(|install| install.uuid()) // remove duplicate dependencies
+        .rev() // install dependencies first
+        .collect_vec())
+}
+
 /// A RAII guard for the inner state of the [`InstallQueue`].
 pub struct InstallQueueHandle<'a> {
     state: MutexGuard<'a, State>,
@@ -133,8 +255,9 @@ impl<'a> InstallQueueHandle<'a> {
         self.state
             .processing
             .as_ref()
-            .is_some_and(|(other_profile_id, other_uuids)| {
-                *other_profile_id == profile_id && other_uuids.contains(&uuid)
+            .is_some_and(|(other_profile_id, other_mods)| {
+                *other_profile_id == profile_id
+                    && other_mods.iter().any(|install| install.uuid() == uuid)
             })
             || self.state.pending.iter().any(|batch| {
                 batch.profile_id == profile_id
@@ -160,16 +283,29 @@ impl<'a> InstallQueueHandle<'a> {
         let bytes = mods.iter().map(|install| install.file_size).sum();
 
         let batch = InstallBatch {
+            id: self.state.next_batch_id,
             mods,
             options,
             profile_id,
             on_complete: tx,
         };
 
+        self.state.next_batch_id += 1;
+
         if mod_count > 0 {
+            let start = self.items().len();
+            let items = batch_items(&batch.mods, batch.id, profile_id, None)
+                .map(|mut item| {
+                    item.position += start;
+                    item
+                })
+                .collect();
+
             self.state.pending.push_back(batch);
             self.queue.notify_push.notify_waiters();
 
+            emit_diff(QueueDiff::Added { items }, app);
+
             emit(
                 InstallEvent::AddCount {
                     mods: mod_count,
@@ -191,61 +327,168 @@ impl<'a> InstallQueueHandle<'a> {
         }
     }
 
-    fn push_with_deps(
-        &mut self,
-        mods: Vec<ModInstall>,
-        profile_id: i64,
-        options: InstallOptions,
-        allow_multiple: bool,
-        app: &AppHandle,
-    ) -> Result<impl Future<Output = InstallResult<()>>> {
-        let mods = {
-            let manager = app.lock_manager();
-            let thunderstore = app.lock_thunderstore();
-            let (_, profile) = manager.profile_by_id(profile_id)?;
+    fn pop_next(&mut self) -> Option<InstallBatch> {
+        let next = self.state.pending.pop_front();
+        self.state.processing = next
+            .as_ref()
+            .map(|batch| (batch.profile_id, batch.mods.clone()));
+        self.state.current = next
+            .as_ref()
+            .map(|batch| (batch.id, vec![QueueItemState::Queued; batch.mods.len()]));
+        next
+    }
 
-            if !allow_multiple && mods.len() == 1 && profile.has_mod(mods[0].uuid()) {
-                bail!("mod is already installed");
-            }
+    /// Everything in the queue, starting with the current batch.
+    pub fn items(&self) -> Vec<QueueItem> {
+        let current = self
+            .state
+            .processing
+            .iter()
+            .zip(&self.state.current)
+            .flat_map(|((profile_id, mods), (batch_id, states))| {
+                batch_items(mods, *batch_id, *profile_id, Some(states.as_slice()))
+            });
 
-            // find the missing dependencies of each mod and flatten them into one vec
-            let mods = mods
-                .into_iter()
-                .map(|install| {
-                    let borrowed = install.id.borrow(&thunderstore)?;
-
-                    Ok(iter::once(install).chain(
-                        profile
-                            .missing_deps(borrowed.dependencies(), &thunderstore)
-                            .map(ModInstall::from),
-                    ))
-                })
-                .flatten_ok()
-                .collect::<Result<Vec<_>>>()
-                .context("failed to resolve dependencies")?;
-
-            mods.into_iter()
-                .unique_by(|install| install.uuid()) // remove duplicate dependencies
-                .rev() // install dependencies first
-                .collect_vec()
+        let pending = self
+            .state
+            .pending
+            .iter()
+            .flat_map(|batch| batch_items(&batch.mods, batch.id, batch.profile_id, None));
+
+        current
+            .chain(pending)
+            .enumerate()
+            .map(|(position, item)| QueueItem { position, ..item })
+            .collect()
+    }
+
+    fn set_item_state(&mut self, ident: &VersionIdent, state: QueueItemState, app: &AppHandle) {
+        let (Some((_, mods)), Some((batch_id, states))) =
+            (&self.state.processing, &mut self.state.current)
+        else {
+            return;
         };
 
-        Ok(self.push_batch(mods, profile_id, options, app))
+        let Some(index) = mods.iter().position(|install| install.ident == *ident) else {
+            return;
+        };
+
+        states[index] = state;
+
+        emit_diff(
+            QueueDiff::Updated {
+                batch_id: *batch_id,
+                ident: ident.clone(),
+                state,
+            },
+            app,
+        );
     }
 
-    fn pop_next(&mut self) -> Option<InstallBatch> {
-        let next = self.state.pending.pop_front();
-        self.state.processing = next.as_ref().map(|batch| {
-            (
-                batch.profile_id,
-                batch.mods.iter().map(|install| install.uuid()).collect(),
-            )
-        });
-        next
+    /// Cancels a single batch, leaving the rest of the queue alone.
+    pub fn cancel_batch(&mut self, batch_id: u64, app: &AppHandle) -> Result<()> {
+        if self
+            .state
+            .current
+            .as_ref()
+            .is_some_and(|(id, _)| *id == batch_id)
+        {
+            self.state.cancel_only = Some(batch_id);
+            self.queue.cancel.store(true, Ordering::SeqCst);
+            return Ok(());
+        }
+
+        let index = self
+            .state
+            .pending
+            .iter()
+            .position(|batch| batch.id == batch_id)
+            .ok_or_eyre("batch is not in the queue")?;
+
+        let batch = self.state.pending.remove(index).unwrap();
+
+        emit(
+            InstallEvent::RemoveCount {
+                mods: batch.mods.len(),
+                bytes: batch.mods.iter().map(|install| install.file_size).sum(),
+            },
+            app,
+        );
+        emit_diff(QueueDiff::Removed { batch_id }, app);
+
+        batch.complete(Err(InstallError::Cancelled), app);
+
+        Ok(())
+    }
+
+    /// Moves a pending batch to the front of the queue, so it's installed next.
+    pub fn prioritize_batch(&mut self, batch_id: u64, app: &AppHandle) -> Result<()> {
+        let index = self
+            .state
+            .pending
+            .iter()
+            .position(|batch| batch.id == batch_id)
+            .ok_or_eyre("batch is not pending")?;
+
+        let batch = self.state.pending.remove(index).unwrap();
+        self.state.pending.push_front(batch);
+
+        emit_diff(
+            QueueDiff::Reset {
+                items: self.items(),
+            },
+            app,
+        );
+
+        Ok(())
+    }
+
+    /// The mods of the current and pending batches, by profile id.
+    ///
+    /// Some of the current batch's mods may have finished installing already.
+    pub fn unfinished(&self) -> Vec<(i64, Vec<VersionIdent>)> {
+        let processing = self
+            .state
+            .processing
+            .iter()
+            .map(|(profile_id, mods)| (*profile_id, mods.as_slice()));
+
+        let pending = self
+            .state
+            .pending
+            .iter()
+            .map(|batch| (batch.profile_id, batch.mods.as_slice()));
+
+        processing
+            .chain(pending)
+            .map(|(profile_id, mods)| {
+                let idents = mods.iter().map(|install| install.ident.clone()).collect();
+                (profile_id, idents)
+            })
+            .collect()
     }
 }
 
+fn batch_items<'a>(
+    mods: &'a [ModInstall],
+    batch_id: u64,
+    profile_id: i64,
+    states: Option<&'a [QueueItemState]>,
+) -> impl Iterator<Item = QueueItem> + 'a {
+    mods.iter()
+        .enumerate()
+        .map(move |(position, install)| QueueItem {
+            batch_id,
+            profile_id,
+            position,
+            ident: install.ident.clone(),
+            file_size: install.file_size,
+            state: states.map_or(QueueItemState::Queued, |states| states[position]),
+        })
+}
+
 pub struct InstallBatch {
+    id: u64,
     mods: Vec<ModInstall>,
     options: InstallOptions,
     profile_id: i64,
@@ -305,20 +548,56 @@ async fn handle_batch(batch: InstallBatch, cancel: &AtomicBool, app: &AppHandle)
     let mut result = Ok(());
     let mut reason = HideReason::Done;
 
+    // where each mod goes depends on the game's installer script, so it's only looked up once
+    let cache_paths = match batch_cache_paths(&batch, app) {
+        Ok(paths) => paths,
+        Err(err) => {
+            emit_diff(QueueDiff::Removed { batch_id: batch.id }, app);
+            batch.complete(Err(InstallError::Err(err)), app);
+            return HideReason::Error;
+        }
+    };
+
+    // later mods start downloading while waiting for the current one
+    let simultaneous = app.lock_prefs().simultaneous_downloads.max(1);
+    let mut archives = stream::iter(0..batch.mods.len())
+        .map(|i| fetch_archive(&batch, i, &cache_paths[i], cancel, app))
+        .buffered(simultaneous);
+
     for (i, install) in batch.mods.iter().enumerate() {
-        result = handle_install(&batch, i, cancel, app).await;
+        result = match archives.next().await {
+            Some(Ok(archive)) => {
+                handle_install(&batch, i, archive, &cache_paths[i], cancel, app).await
+            }
+            Some(Err(err)) => Err(err),
+            None => unreachable!("there is an archive future for every mod"),
+        };
 
         match &result {
-            Ok(()) => (),
+            Ok(()) => {
+                app.install_queue().handle().set_item_state(
+                    &install.ident,
+                    QueueItemState::Done,
+                    app,
+                );
+            }
             Err(InstallError::Cancelled) => {
                 rollback_batch(&batch, app, i).unwrap_or_else(|err| {
                     warn!("failed to rollback cancelled installation: {}", err)
                 });
 
-                // cancel all pending bathes
-                let mut handle = app.install_queue().handle();
-                for batch in handle.state.pending.drain(..) {
-                    batch.complete(Err(InstallError::Cancelled), app);
+                let queue = app.install_queue();
+                let mut handle = queue.handle();
+
+                if handle.state.cancel_only.take() == Some(batch.id) {
+                    // keep going with the rest of the queue
+                    queue.cancel.store(false, Ordering::SeqCst);
+                } else {
+                    // cancel all pending bathes
+                    for batch in handle.state.pending.drain(..) {
+                        emit_diff(QueueDiff::Removed { batch_id: batch.id }, app);
+                        batch.complete(Err(InstallError::Cancelled), app);
+                    }
                 }
 
                 reason = HideReason::Cancelled;
@@ -338,10 +617,25 @@ async fn handle_batch(batch: InstallBatch, cancel: &AtomicBool, app: &AppHandle)
         }
     }
 
+    // stops any downloads that are still running
+    drop(archives);
+
+    emit_diff(QueueDiff::Removed { batch_id: batch.id }, app);
     batch.complete(result, app);
     reason
 }
 
+fn batch_cache_paths(batch: &InstallBatch, app: &AppHandle) -> Result<Vec<PathBuf>> {
+    let prefs = app.lock_prefs();
+    let (game, _) = app.lock_manager().profile_by_id(batch.profile_id)?;
+
+    Ok(batch
+        .mods
+        .iter()
+        .map(|install| super::cache::path(&install.ident, game, &prefs))
+        .collect())
+}
+
 fn rollback_batch(batch: &InstallBatch, app: &AppHandle, count: usize) -> Result<()> {
     match batch.options.cancel_behavior {
         CancelBehavior::Individual => Ok(()),
@@ -369,17 +663,118 @@ fn rollback_batch(batch: &InstallBatch, app: &AppHandle, count: usize) -> Result
     }
 }
 
+/// Reads the mod's archive from the batch's own zips, the archive directory or the shared
+/// cache, or downloads it, unless it's already in the cache.
+async fn fetch_archive(
+    batch: &InstallBatch,
+    index: usize,
+    cache_path: &Path,
+    cancel: &AtomicBool,
+    app: &AppHandle,
+) -> InstallResult<Option<Vec<u8>>> {
+    let install = &batch.mods[index];
+
+    if super::cache::exists(cache_path) {
+        return Ok(None);
+    }
+
+    // the user's own archive and shared copies may not have a recorded hash to compare
+    // against yet, but are never trusted for one
+    let is_valid = |bytes: &Vec<u8>| match checksum::check(install, bytes, app) {
+        Ok(Verdict::Valid | Verdict::Unrecorded { .. }) => true,
+        Ok(Verdict::Mismatch { .. }) | Err(_) => {
+            warn!("local copy of {} is corrupted, ignoring it", install.ident);
+            false
+        }
+    };
+
+    // zips from elsewhere, like LAN peers, are only used if they match a hash
+    // recorded from Thunderstore
+    let is_known = |bytes: &Vec<u8>| match checksum::matches_recorded(install, bytes, app) {
+        Ok(true) => true,
+        Ok(false) | Err(_) => {
+            debug!(
+                "can't verify provided copy of {}, ignoring it",
+                install.ident
+            );
+            false
+        }
+    };
+
+    let provided = match &batch.options.archives {
+        Some(dir) => super::archive::read_in(dir, &install.ident)?.filter(is_known),
+        None => None,
+    };
+
+    let archived = match provided {
+        Some(bytes) => Some(bytes),
+        None => super::archive::read(&install.ident, &app.lock_prefs())?.filter(is_valid),
+    };
+
+    let archived = match archived {
+        Some(bytes) => Some(bytes),
+        None => super::cache::read_shared(&install.ident, &app.lock_prefs())?.filter(is_valid),
+    };
+
+    let bytes = match archived {
+        Some(bytes) => {
+            emit(
+                InstallEvent::AddProgress {
+                    mods: 0,
+                    bytes: install.file_size,
+                },
+                app,
+            );
+
+            bytes
+        }
+        None => {
+            let bytes = download_verified(install, cancel, &batch.options, app).await?;
+
+            // a copy from the mirror might not have a recorded hash to share it with
+            let shareable = checksum::matches_recorded(install, &bytes, app).unwrap_or(false);
+
+            let prefs = app.lock_prefs();
+            super::archive::write(&install.ident, &bytes, &prefs);
+            if shareable {
+                super::cache::write_shared(&install.ident, &bytes, &prefs);
+            }
+            drop(prefs);
+
+            bytes
+        }
+    };
+
+    Ok(Some(bytes))
+}
+
+#[instrument(skip_all, fields(package = %batch.mods[index].ident))]
 async fn handle_install(
     batch: &InstallBatch,
     index: usize,
+    archive: Option<Vec<u8>>,
+    cache_path: &Path,
     cancel: &AtomicBool,
     app: &AppHandle,
 ) -> InstallResult<()> {
-    match try_cache_install(batch, index, app)? {
+    let install = &batch.mods[index];
+
+    if super::cache::exists(cache_path) {
+        quarantine::review(install, cache_path, cancel, &batch.options, app).await?;
+    }
+
+    match try_cache_install(batch, index, cache_path, app)? {
         CacheStatus::Hit => Ok(()),
         CacheStatus::Miss => {
-            let bytes = download(&batch.mods[index], cancel, &batch.options, app).await?;
-            install_from_download(bytes, batch, index, cancel, app)?;
+            let bytes = match archive {
+                Some(bytes) => bytes,
+                // the cache entry was removed after it was checked
+                None => fetch_archive(batch, index, cache_path, cancel, app)
+                    .await?
+                    .ok_or_eyre("mod disappeared from the cache")?,
+            };
+
+            install_from_download(bytes, batch, index, cache_path, cancel, app).await?;
 
             Ok(())
         }
@@ -391,19 +786,22 @@ enum CacheStatus {
     Miss,
 }
 
-fn try_cache_install(batch: &InstallBatch, index: usize, app: &AppHandle) -> Result<CacheStatus> {
+fn try_cache_install(
+    batch: &InstallBatch,
+    index: usize,
+    cache_path: &Path,
+    app: &AppHandle,
+) -> Result<CacheStatus> {
     let install = &batch.mods[index];
 
-    let cache_path = super::cache::path(&install.ident, &app.lock_prefs());
-
-    if !cache_path.exists() {
+    if !super::cache::exists(cache_path) {
         return Ok(CacheStatus::Miss);
     }
 
-    emit(
-        InstallEvent::set_task(&install.ident, InstallTask::Install),
-        app,
-    );
+    set_task(&install.ident, InstallTask::Install, app);
+
+    let (game, _) = app.lock_manager().profile_by_id(batch.profile_id)?;
+    let deployer = app.lock_prefs().deploy_mode(game).deployer();
 
     let mut manager = app.lock_manager();
 
@@ -413,8 +811,9 @@ fn try_cache_install(batch: &InstallBatch, index: usize, app: &AppHandle) -> Res
     }
 
     let package_name = install.ident.full_name();
-    let mut installer = game.mod_loader.installer_for(package_name);
-    installer.install(&cache_path, package_name, profile)?;
+    let mut installer = game.installer_for(package_name);
+    installer.install(cache_path, package_name, profile, deployer)?;
+    profile.record_loader_files(package_name, cache_path);
 
     install.clone().insert_into(profile)?;
 
@@ -431,30 +830,19 @@ fn try_cache_install(batch: &InstallBatch, index: usize, app: &AppHandle) -> Res
     Ok(CacheStatus::Hit)
 }
 
+#[instrument(skip_all)]
 async fn download(
+    url: String,
     install: &ModInstall,
     cancel: &AtomicBool,
     options: &InstallOptions,
     app: &AppHandle,
 ) -> InstallResult<Vec<u8>> {
-    emit(
-        InstallEvent::set_task(&install.ident, InstallTask::Download),
-        app,
-    );
+    set_task(&install.ident, InstallTask::Download, app);
 
-    let url = format!(
-        "https://thunderstore.io/package/download/{}",
-        install.ident.path()
-    );
+    let response = request_archive(url, app).await?;
 
-    let mut stream = app
-        .http()
-        .get(url)
-        .send()
-        .await
-        .and_then(|response| response.error_for_status())
-        .map_err(|err| eyre!(err))?
-        .bytes_stream();
+    let mut stream = response.bytes_stream();
 
     let mut response = Vec::with_capacity(install.file_size as usize);
 
@@ -462,8 +850,7 @@ async fn download(
     let mut last_update = Instant::now();
     let mut last_size_update = 0u64;
 
-    while let Some(item) = stream.next().await {
-        let item = item.map_err(eyre::Report::new)?;
+    while let Some(item) = net::next_chunk(&mut stream, Operation::Download, app).await? {
         response.extend_from_slice(&item);
 
         if last_update.elapsed() >= UPDATE_DELAY {
@@ -492,53 +879,173 @@ async fn download(
     Ok(response)
 }
 
-fn install_from_download(
-    data: Vec<u8>,
-    batch: &InstallBatch,
-    index: usize,
+async fn request_archive(url: String, app: &AppHandle) -> Result<reqwest::Response> {
+    let response = net::send(app.http().get(url), Operation::Download, app)
+        .await?
+        .error_for_status()?;
+
+    Ok(response)
+}
+
+/// Downloads a mod from the download mirror if one is set, or else from Thunderstore.
+///
+/// The mirror is only a cache, so Thunderstore is used instead whenever something is
+/// wrong with the mirror's copy, be it a failed request, a truncated body or a hash
+/// that doesn't match.
+async fn download_verified(
+    install: &ModInstall,
     cancel: &AtomicBool,
+    options: &InstallOptions,
     app: &AppHandle,
-) -> InstallResult<()> {
-    let manager = app.lock_manager();
-
-    let install = &batch.mods[index];
+) -> InstallResult<Vec<u8>> {
+    let mirror = app.lock_prefs().download_mirror.clone();
 
-    let cache_path = super::cache::path(&install.ident, &app.lock_prefs());
-    let package_name = install.ident.full_name();
+    if let Some(mirror) = mirror {
+        match download_from_mirror(&mirror, install, cancel, options, app).await {
+            Ok(bytes) => return Ok(bytes),
+            Err(InstallError::Err(err)) if !net::is_cancelled(&err) => {
+                warn!(
+                    "failed to download {} from mirror, falling back to Thunderstore: {:#}",
+                    install.ident, err
+                );
+            }
+            Err(err) => return Err(err),
+        }
+    }
 
-    let (game, _) = manager.profile_by_id(batch.profile_id)?;
-    drop(manager);
+    download_from_thunderstore(install, cancel, options, app).await
+}
 
-    fs::create_dir_all(&cache_path).fs_context("creating mod cache dir", &cache_path)?;
+/// Downloads a mod from the mirror. Its hashes are never recorded, since only
+/// Thunderstore is trusted for those.
+async fn download_from_mirror(
+    mirror: &str,
+    install: &ModInstall,
+    cancel: &AtomicBool,
+    options: &InstallOptions,
+    app: &AppHandle,
+) -> InstallResult<Vec<u8>> {
+    let url = thunderstore::mirror_url(mirror, &install.ident);
+    let bytes = download(url, install, cancel, options, app).await?;
 
-    let mut installer = game.mod_loader.installer_for(package_name);
+    let err = match checksum::check(install, &bytes, app) {
+        Ok(Verdict::Valid | Verdict::Unrecorded { .. }) => return Ok(bytes),
+        Ok(Verdict::Mismatch { expected, actual }) => {
+            eyre!("hash mismatch (expected {}, got {})", expected, actual)
+        }
+        Err(err) => err,
+    };
 
+    // the bad copy has already been added to the progress
     emit(
-        InstallEvent::set_task(&install.ident, InstallTask::Extract),
+        InstallEvent::AddCount {
+            mods: 0,
+            bytes: install.file_size,
+        },
         app,
     );
 
-    let archive = ZipArchive::new(Cursor::new(data)).context("failed to open archive")?;
+    Err(err.into())
+}
+
+/// Downloads a mod from Thunderstore, retrying if the archive is corrupted or doesn't
+/// match its recorded hash.
+async fn download_from_thunderstore(
+    install: &ModInstall,
+    cancel: &AtomicBool,
+    options: &InstallOptions,
+    app: &AppHandle,
+) -> InstallResult<Vec<u8>> {
+    const MAX_ATTEMPTS: usize = 3;
 
-    installer
-        .extract(archive, package_name, cache_path.clone())
-        .inspect_err(|_| {
-            // the cached mod is probably in an invalid state, so remove it
-            fs::remove_dir_all(&cache_path).unwrap_or_else(|err| {
+    let mut last_mismatch = None;
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        if attempt > 1 {
+            // the failed attempt has already been added to the progress
+            emit(
+                InstallEvent::AddCount {
+                    mods: 0,
+                    bytes: install.file_size,
+                },
+                app,
+            );
+        }
+
+        let url = thunderstore::download_url(&install.ident);
+        let bytes = download(url, install, cancel, options, app).await?;
+
+        match checksum::verify(install, &bytes, app) {
+            Ok(Verdict::Valid | Verdict::Unrecorded { .. }) => return Ok(bytes),
+            Ok(Verdict::Mismatch { expected, actual }) => {
+                // two downloads agreeing with each other means the recorded hash is stale
+                if last_mismatch.as_ref() == Some(&actual) {
+                    warn!(
+                        "recorded hash of {} is outdated, replacing it",
+                        install.ident
+                    );
+                    checksum::record(install, &actual, app);
+                    return Ok(bytes);
+                }
+
+                warn!(
+                    "hash mismatch for {} (expected {}, got {}), downloading again",
+                    install.ident, expected, actual
+                );
+                last_mismatch = Some(actual);
+            }
+            Err(err) => {
                 warn!(
-                    "failed to clean up after failed extraction of {}: {:#}",
+                    "download of {} is invalid, downloading again: {:#}",
                     install.ident, err
                 );
-            });
-        })
-        .context("error while extracting")?;
+            }
+        }
+    }
+
+    Err(InstallError::Err(eyre!(
+        "download was corrupted {} times in a row",
+        MAX_ATTEMPTS
+    )))
+}
+
+async fn install_from_download(
+    data: Vec<u8>,
+    batch: &InstallBatch,
+    index: usize,
+    cache_path: &Path,
+    cancel: &AtomicBool,
+    app: &AppHandle,
+) -> InstallResult<()> {
+    let install = &batch.mods[index];
+    let package_name = install.ident.full_name();
+
+    let (game, _) = app.lock_manager().profile_by_id(batch.profile_id)?;
+    let deployer = app.lock_prefs().deploy_mode(game).deployer();
+
+    set_task(&install.ident, InstallTask::Extract, app);
+
+    let (compressed, deduplicate) = {
+        let prefs = app.lock_prefs();
+        (prefs.compress_cache, prefs.deduplicate_cache)
+    };
+
+    super::cache::insert(data, &install.ident, game, cache_path, compressed)?;
+
+    if deduplicate {
+        match super::dedup::dedup_entry(cache_path) {
+            Ok(0) => (),
+            Ok(saved) => debug!("deduplicated {} bytes of {}", saved, install.ident),
+            Err(err) => warn!("failed to deduplicate {}: {:#}", install.ident, err),
+        }
+    }
 
     check_cancel(cancel, &batch.options)?;
 
-    emit(
-        InstallEvent::set_task(&install.ident, InstallTask::Install),
-        app,
-    );
+    quarantine::review(install, cache_path, cancel, &batch.options, app).await?;
+    let mut installer = game.installer_for(package_name);
+
+    set_task(&install.ident, InstallTask::Install, app);
 
     let mut manager = app.lock_manager();
     let (_, profile) = manager.profile_by_id_mut(batch.profile_id)?;
@@ -547,7 +1054,8 @@ fn install_from_download(
         callback(install, profile)?;
     }
 
-    installer.install(&cache_path, package_name, profile)?;
+    installer.install(cache_path, package_name, profile, deployer)?;
+    profile.record_loader_files(package_name, cache_path);
     install.clone().insert_into(profile)?;
 
     profile.save(&app, true)?;
@@ -558,8 +1066,9 @@ fn install_from_download(
 }
 
 /// Events sent to the frontend to keep track of installation progress.
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase", tag = "type")]
+#[ts(export)]
 enum InstallEvent<'a> {
     Show,
     #[serde(rename_all = "camelCase")]
@@ -569,11 +1078,20 @@ enum InstallEvent<'a> {
     #[serde(rename_all = "camelCase")]
     AddCount {
         mods: usize,
+        #[ts(type = "number")]
         bytes: u64,
     },
     #[serde(rename_all = "camelCase")]
     AddProgress {
         mods: usize,
+        #[ts(type = "number")]
+        bytes: u64,
+    },
+    /// Sent when a pending batch is cancelled on its own.
+    #[serde(rename_all = "camelCase")]
+    RemoveCount {
+        mods: usize,
+        #[ts(type = "number")]
         bytes: u64,
     },
     #[serde(rename_all = "camelCase")]
@@ -583,16 +1101,18 @@ enum InstallEvent<'a> {
     },
 }
 
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 enum HideReason {
     Done,
     Error,
     Cancelled,
 }
 
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Clone, Copy, TS)]
 #[serde(rename_all = "camelCase")]
+#[ts(export)]
 enum InstallTask {
     Download,
     Extract,
@@ -612,7 +1132,19 @@ fn emit(event: InstallEvent, app: &AppHandle) {
     app.emit("install_event", event).ok();
 }
 
-fn check_cancel(cancel: &AtomicBool, options: &InstallOptions) -> InstallResult<()> {
+fn emit_diff(diff: QueueDiff, app: &AppHandle) {
+    app.emit("install_queue", diff).ok();
+}
+
+fn set_task(ident: &VersionIdent, task: InstallTask, app: &AppHandle) {
+    emit(InstallEvent::set_task(ident, task), app);
+
+    app.install_queue()
+        .handle()
+        .set_item_state(ident, task.into(), app);
+}
+
+pub(super) fn check_cancel(cancel: &AtomicBool, options: &InstallOptions) -> InstallResult<()> {
     if cancel.load(Ordering::SeqCst) {
         if options.cancel_behavior == CancelBehavior::Prevent {
             warn!("attempted to cancel uncancellable batch");
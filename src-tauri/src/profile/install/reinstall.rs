@@ -0,0 +1,137 @@
+//! Reinstalls a single mod from scratch, as a quick fix for corrupted installs.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Context, OptionExt, Result};
+use tauri::AppHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::{cache, deploy, InstallOptions, InstallResultExt, ModInstall, PackageInstaller};
+use crate::{profile::Profile, state::ManagerExt, util::error::IoResultExt};
+
+/// A file the user may have changed, kept in memory while the mod is reinstalled.
+struct PreservedFile {
+    relative_path: PathBuf,
+    contents: Vec<u8>,
+}
+
+/// Removes a mod from the active profile and installs it again from a freshly extracted copy.
+///
+/// The extracted cache is discarded, so the files come from the archived zip if there is one,
+/// or are downloaded otherwise. Configs and other mutable files are kept as they were,
+/// and are written back even if the install fails.
+pub async fn reinstall(uuid: Uuid, app: &AppHandle) -> Result<()> {
+    let (profile_id, install, preserved) = {
+        let prefs = app.lock_prefs();
+        let manager = app.lock_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        let profile = manager.active_profile();
+        let index = profile.index_of(uuid)?;
+        let profile_mod = &profile.mods[index];
+
+        let ts_mod = profile_mod
+            .kind
+            .as_thunderstore()
+            .ok_or_eyre("local mods can't be reinstalled")?;
+
+        let install = ModInstall::try_from_id(ts_mod.id.clone(), &thunderstore)?
+            .with_state(profile_mod.enabled)
+            .with_index(index)
+            .with_time(profile_mod.install_time)
+            .with_tags(profile_mod.tags.clone())
+            .with_note(profile_mod.note.clone())
+            .with_local_only(profile_mod.local_only);
+
+        let cache_path = cache::path(&ts_mod.ident, profile.game, &prefs);
+        let installer = profile.game.installer_for(ts_mod.ident.full_name());
+
+        let preserved = preserve_mutable_files(&cache_path, profile, &*installer);
+
+        if cache_path.exists() {
+            deploy::ensure_not_linked(Some(&ts_mod.ident), Some(profile.id), &manager, &prefs)?;
+
+            fs::remove_dir_all(&cache_path).fs_context("removing cached mod", &cache_path)?;
+        }
+
+        info!(
+            "reinstalling {}, keeping {} mutable files",
+            ts_mod.ident,
+            preserved.len()
+        );
+
+        (profile.id, install, preserved)
+    };
+
+    let result = app
+        .install_queue()
+        .install(
+            [install],
+            profile_id,
+            InstallOptions::default().before_install(Box::new(|install, profile| {
+                profile
+                    .force_remove_mod(install.uuid())
+                    .context("failed to remove existing install")
+            })),
+            app,
+        )
+        .await
+        .ignore_cancel();
+
+    // put the files back even if the install failed, since the old copy
+    // may already have been removed by then
+    let manager = app.lock_manager();
+    let (_, profile) = manager.profile_by_id(profile_id)?;
+    restore_files(preserved, profile);
+
+    result
+}
+
+/// Reads the mod's mutable files from the profile, using the cache to know which ones it has.
+fn preserve_mutable_files(
+    cache_path: &Path,
+    profile: &Profile,
+    installer: &dyn PackageInstaller,
+) -> Vec<PreservedFile> {
+    WalkDir::new(cache_path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            entry
+                .path()
+                .strip_prefix(cache_path)
+                .ok()
+                .map(PathBuf::from)
+        })
+        .filter(|relative_path| installer.is_mutable(relative_path))
+        .filter_map(|relative_path| {
+            let contents = fs::read(profile.path.join(&relative_path)).ok()?;
+
+            Some(PreservedFile {
+                relative_path,
+                contents,
+            })
+        })
+        .collect()
+}
+
+fn restore_files(preserved: Vec<PreservedFile>, profile: &Profile) {
+    for file in preserved {
+        let path = profile.path.join(&file.relative_path);
+
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&path, file.contents));
+
+        if let Err(err) = result {
+            warn!("failed to restore {}: {}", path.display(), err);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Thread and buffer settings for extraction, applied from
+//! [`Prefs::extract_threads`] and [`Prefs::write_buffer_size`].
+
+use std::{
+    num::NonZero,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    thread,
+};
+
+use eyre::{Context, Result};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use tracing::debug;
+
+use crate::prefs::Prefs;
+
+pub const DEFAULT_WRITE_BUFFER_SIZE: usize = 64;
+pub const DEFAULT_SIMULTANEOUS_DOWNLOADS: usize = 3;
+
+/// Writes extracted files in parallel. `None` if only one thread is used,
+/// in which case files are streamed straight from the archive instead.
+static POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+static WRITE_BUFFER_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_WRITE_BUFFER_SIZE * 1024);
+
+pub fn apply(prefs: &Prefs) -> Result<()> {
+    let threads = prefs
+        .extract_threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZero::get));
+
+    let pool = match threads {
+        0 | 1 => None,
+        _ => {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(|i| format!("extract-{i}"))
+                .build()
+                .context("failed to create extraction thread pool")?;
+
+            Some(Arc::new(pool))
+        }
+    };
+
+    *POOL.write().unwrap() = pool;
+
+    let buffer_size = prefs.write_buffer_size.max(1) * 1024;
+    WRITE_BUFFER_SIZE.store(buffer_size, Ordering::Relaxed);
+
+    debug!(
+        "extracting with {} threads and {} byte write buffers",
+        threads, buffer_size
+    );
+
+    Ok(())
+}
+
+pub(super) fn extract_pool() -> Option<Arc<ThreadPool>> {
+    POOL.read().unwrap().clone()
+}
+
+pub(super) fn write_buffer_size() -> usize {
+    WRITE_BUFFER_SIZE.load(Ordering::Relaxed)
+}
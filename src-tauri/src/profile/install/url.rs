@@ -0,0 +1,120 @@
+//! Installs packages from links to their Thunderstore pages, like
+//! `https://thunderstore.io/c/lethal-company/p/notnotnotswipez/MoreCompany/`.
+
+use eyre::{bail, ensure, eyre, Result};
+use tauri::{AppHandle, Url};
+use tracing::info;
+
+use super::{InstallOptions, InstallResultExt, ModInstall};
+use crate::{state::ManagerExt, thunderstore};
+
+#[derive(Debug)]
+struct PackageUrl {
+    /// Missing for links that don't specify the community, which are
+    /// assumed to be for the active game.
+    community: Option<String>,
+    owner: String,
+    name: String,
+    /// The latest version is used if this is missing.
+    version: Option<String>,
+}
+
+impl PackageUrl {
+    /// Supports package and version pages, both with and without a community,
+    /// as well as direct download links.
+    fn parse(str: &str) -> Result<Self> {
+        let url = Url::parse(str.trim()).map_err(|_| eyre!("not a valid URL"))?;
+
+        let host = url.host_str().unwrap_or_default();
+        // old links to community pages use subdomains, like `valheim.thunderstore.io`
+        let subdomain = match host.strip_suffix(".thunderstore.io") {
+            _ if matches!(host, "thunderstore.io" | "www.thunderstore.io") => None,
+            Some(subdomain) => Some(subdomain),
+            None => bail!("not a Thunderstore link"),
+        };
+
+        let segments = url
+            .path_segments()
+            .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+            .unwrap_or_else(Vec::new);
+
+        let (community, owner, name, version) = match segments.as_slice() {
+            ["c", community, "p", owner, name, rest @ ..] => {
+                let version = match rest {
+                    [] => None,
+                    ["v", version, ..] => Some(*version),
+                    _ => bail!("unsupported Thunderstore link"),
+                };
+
+                (Some(*community), *owner, *name, version)
+            }
+            ["package", "download", owner, name, version, ..] => {
+                (subdomain, *owner, *name, Some(*version))
+            }
+            ["package", owner, name, rest @ ..] => {
+                (subdomain, *owner, *name, rest.first().copied())
+            }
+            _ => bail!("the link doesn't point to a package"),
+        };
+
+        Ok(Self {
+            community: community.map(str::to_owned),
+            owner: owner.to_owned(),
+            name: name.to_owned(),
+            version: version.map(str::to_owned),
+        })
+    }
+}
+
+/// Installs the package a Thunderstore link points to into the active profile,
+/// along with its dependencies.
+pub async fn install(url: &str, app: &AppHandle) -> Result<()> {
+    let PackageUrl {
+        community,
+        owner,
+        name,
+        version,
+    } = PackageUrl::parse(url)?;
+
+    if let Some(community) = community {
+        let game = app.lock_manager().active_game;
+        ensure!(
+            game.slug == community,
+            "the link is for a different game ({}), switch to it first",
+            community
+        );
+    }
+
+    thunderstore::wait_for_fetch(app).await;
+
+    let (profile_id, install) = {
+        let manager = app.lock_manager();
+        let thunderstore = app.lock_thunderstore();
+
+        let package = thunderstore.find_package(&format!("{owner}-{name}"))?;
+        let version = match &version {
+            Some(version) => package
+                .get_version_with_num(version)
+                .ok_or_else(|| eyre!("version {version} of {owner}-{name} not found"))?,
+            None => package.latest(),
+        };
+
+        info!("installing {} from url", version.ident);
+
+        (
+            manager.active_profile().id,
+            ModInstall::new((package, version)),
+        )
+    };
+
+    app.install_queue()
+        .install_with_deps(
+            vec![install],
+            profile_id,
+            InstallOptions::default(),
+            false,
+            app,
+        )?
+        .await
+        .ignore_cancel()
+}
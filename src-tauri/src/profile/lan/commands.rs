@@ -0,0 +1,38 @@
+use tauri::{command, AppHandle};
+
+use crate::{state::ManagerExt, util::cmd::Result};
+
+use super::{LanProfile, ShareStatus};
+
+#[command]
+pub async fn start_lan_share(app: AppHandle) -> Result<ShareStatus> {
+    let profile_id = app.lock_manager().active_profile().id;
+
+    let status = app.lan_share().start(profile_id, &app).await?;
+
+    Ok(status)
+}
+
+#[command]
+pub fn stop_lan_share(app: AppHandle) {
+    app.lan_share().stop();
+}
+
+#[command]
+pub fn get_lan_share_status(app: AppHandle) -> Option<ShareStatus> {
+    app.lan_share().status()
+}
+
+#[command]
+pub async fn discover_lan_profiles() -> Result<Vec<LanProfile>> {
+    let profiles = super::discover().await?;
+
+    Ok(profiles)
+}
+
+#[command]
+pub async fn import_lan_profile(address: String, app: AppHandle) -> Result<i64> {
+    let profile_id = super::import(&address, &app).await?;
+
+    Ok(profile_id)
+}
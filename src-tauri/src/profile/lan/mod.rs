@@ -0,0 +1,440 @@
+//! Shares a profile with other Gale instances on the local network.
+//!
+//! The sharing instance runs a tiny HTTP server that serves the profile's lockfile and
+//! the original zips of its mods, from the archive or the shared cache. A peer's zip is
+//! only used if it matches the hash the importer recorded when downloading the same
+//! version from Thunderstore, so only those are fetched. They're then extracted and
+//! quarantined like any other mod. Everything else is downloaded from Thunderstore as usual.
+//!
+//! Instances find each other by broadcasting a UDP message on [`DISCOVERY_PORT`] instead
+//! of using mDNS, which would need another dependency and is often blocked on the
+//! same networks that let broadcasts through anyway.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    sync::Mutex,
+    time::Duration,
+};
+
+use eyre::{ensure, eyre, Context, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime::JoinHandle, AppHandle};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream, UdpSocket},
+    time::Instant,
+};
+use tracing::{debug, info, warn};
+
+use super::install::{
+    archive, cache,
+    lock::{self, LockFile},
+    InstallOptions,
+};
+use crate::{state::ManagerExt, thunderstore::VersionIdent, util::error::IoResultExt};
+
+pub mod commands;
+
+pub const DISCOVERY_PORT: u16 = 47651;
+const DISCOVERY_MESSAGE: &[u8] = b"gale-discover";
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Default)]
+pub struct State {
+    server: Mutex<Option<Server>>,
+}
+
+struct Server {
+    profile_id: i64,
+    port: u16,
+    tasks: [JoinHandle<()>; 2],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedProfile {
+    pub name: String,
+    pub lock: LockFile,
+}
+
+/// A profile found on the network by [`discover`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanProfile {
+    #[serde(default)]
+    pub address: String,
+    pub name: String,
+    pub game: String,
+    pub mod_count: usize,
+    pub port: u16,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareStatus {
+    pub profile_id: i64,
+    pub port: u16,
+}
+
+impl State {
+    pub fn status(&self) -> Option<ShareStatus> {
+        self.server
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|server| ShareStatus {
+                profile_id: server.profile_id,
+                port: server.port,
+            })
+    }
+
+    /// Starts sharing a profile, replacing any previously shared one.
+    pub async fn start(&self, profile_id: i64, app: &AppHandle) -> Result<ShareStatus> {
+        self.stop();
+
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0))
+            .await
+            .context("failed to start server")?;
+        let port = listener.local_addr()?.port();
+
+        let discovery = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT))
+            .await
+            .context("failed to bind discovery socket")?;
+
+        let tasks = [
+            tauri::async_runtime::spawn(serve(listener, profile_id, app.to_owned())),
+            tauri::async_runtime::spawn(answer_discovery(
+                discovery,
+                profile_id,
+                port,
+                app.to_owned(),
+            )),
+        ];
+
+        info!("sharing profile {} on port {}", profile_id, port);
+
+        *self.server.lock().unwrap() = Some(Server {
+            profile_id,
+            port,
+            tasks,
+        });
+
+        Ok(ShareStatus { profile_id, port })
+    }
+
+    pub fn stop(&self) {
+        if let Some(server) = self.server.lock().unwrap().take() {
+            for task in server.tasks {
+                task.abort();
+            }
+
+            info!("stopped sharing profile {}", server.profile_id);
+        }
+    }
+}
+
+async fn serve(listener: TcpListener, profile_id: i64, app: AppHandle) {
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                warn!("failed to accept lan connection: {}", err);
+                continue;
+            }
+        };
+
+        debug!("accepted lan connection from {}", addr);
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(err) = handle_connection(stream, profile_id, &app).await {
+                warn!("error while handling lan request from {}: {:#}", addr, err);
+            }
+        });
+    }
+}
+
+async fn handle_connection(stream: TcpStream, profile_id: i64, app: &AppHandle) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+
+    // skip the headers, we don't need them
+    let mut line = String::new();
+    while reader.read_line(&mut line).await? > 2 {
+        line.clear();
+    }
+
+    let mut stream = reader.into_inner();
+
+    let mut parts = request_line.split_whitespace();
+    let (Some("GET"), Some(path)) = (parts.next(), parts.next()) else {
+        return respond(&mut stream, "405 Method Not Allowed", "text/plain", b"").await;
+    };
+
+    match route(path, profile_id, app).await {
+        Ok(Some((content_type, body))) => respond(&mut stream, "200 OK", content_type, &body).await,
+        Ok(None) => respond(&mut stream, "404 Not Found", "text/plain", b"").await,
+        Err(err) => {
+            let body = format!("{err:#}");
+            respond(
+                &mut stream,
+                "500 Internal Server Error",
+                "text/plain",
+                body.as_bytes(),
+            )
+            .await
+        }
+    }
+}
+
+async fn route(
+    path: &str,
+    profile_id: i64,
+    app: &AppHandle,
+) -> Result<Option<(&'static str, Vec<u8>)>> {
+    if path == "/profile" {
+        let prefs = app.lock_prefs();
+        let manager = app.lock_manager();
+        let (_, profile) = manager.profile_by_id(profile_id)?;
+
+        let shared = SharedProfile {
+            name: profile.name.clone(),
+            lock: profile.lock(&prefs)?,
+        };
+
+        return Ok(Some(("application/json", serde_json::to_vec(&shared)?)));
+    }
+
+    let Some(ident) = path.strip_prefix("/mods/") else {
+        return Ok(None);
+    };
+
+    let Ok(ident) = ident.parse::<VersionIdent>() else {
+        return Ok(None);
+    };
+
+    let paths = {
+        let prefs = app.lock_prefs();
+        let manager = app.lock_manager();
+        let (_, profile) = manager.profile_by_id(profile_id)?;
+
+        // only serve mods that are actually in the shared profile
+        if !profile
+            .thunderstore_mods()
+            .any(|(ts_mod, _)| ts_mod.ident == ident)
+        {
+            return Ok(None);
+        }
+
+        // extracted mods can't be checked against their hashes, so only zips are served
+        [
+            archive::path(&ident, &prefs),
+            cache::shared_path(&ident, &prefs),
+        ]
+    };
+
+    let data = tauri::async_runtime::spawn_blocking(move || read_first(paths)).await??;
+
+    Ok(data.map(|data| ("application/zip", data)))
+}
+
+fn read_first(paths: [Option<PathBuf>; 2]) -> Result<Option<Vec<u8>>> {
+    for path in paths.into_iter().flatten() {
+        match fs::read(&path) {
+            Ok(data) => return Ok(Some(data)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err).fs_context("reading mod", &path),
+        }
+    }
+
+    Ok(None)
+}
+
+async fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> Result<()> {
+    let header = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(body).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+async fn answer_discovery(socket: UdpSocket, profile_id: i64, port: u16, app: AppHandle) {
+    let mut buf = [0u8; 64];
+
+    loop {
+        let (len, addr) = match socket.recv_from(&mut buf).await {
+            Ok(res) => res,
+            Err(err) => {
+                warn!("failed to receive discovery message: {}", err);
+                continue;
+            }
+        };
+
+        if &buf[..len] != DISCOVERY_MESSAGE {
+            continue;
+        }
+
+        let response = {
+            let manager = app.lock_manager();
+            let Ok((game, profile)) = manager.profile_by_id(profile_id) else {
+                continue;
+            };
+
+            LanProfile {
+                address: String::new(),
+                name: profile.name.clone(),
+                game: game.slug.to_string(),
+                mod_count: profile.mods.len(),
+                port,
+            }
+        };
+
+        let Ok(response) = serde_json::to_vec(&response) else {
+            continue;
+        };
+
+        if let Err(err) = socket.send_to(&response, addr).await {
+            warn!("failed to answer discovery from {}: {}", addr, err);
+        }
+    }
+}
+
+/// Broadcasts a discovery message and collects the responses of sharing instances.
+pub async fn discover() -> Result<Vec<LanProfile>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.set_broadcast(true)?;
+
+    socket
+        .send_to(DISCOVERY_MESSAGE, (Ipv4Addr::BROADCAST, DISCOVERY_PORT))
+        .await
+        .context("failed to send discovery broadcast")?;
+
+    let deadline = Instant::now() + DISCOVERY_TIMEOUT;
+    let mut found = HashMap::new();
+    let mut buf = [0u8; 1024];
+
+    while let Ok(result) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        let (len, addr) = result?;
+
+        let Ok(mut profile) = serde_json::from_slice::<LanProfile>(&buf[..len]) else {
+            continue;
+        };
+
+        let addr = SocketAddr::new(addr.ip(), profile.port);
+        profile.address = addr.to_string();
+        found.insert(addr, profile);
+    }
+
+    Ok(found.into_values().collect())
+}
+
+/// Imports a profile shared by another instance at `address`, fetching the zips of
+/// any uncached mods from it. Returns the id of the imported profile.
+pub async fn import(address: &str, app: &AppHandle) -> Result<i64> {
+    let address = parse_address(address)?;
+    let base = format!("http://{address}");
+
+    let SharedProfile { name, lock } = app
+        .http()
+        .get(format!("{base}/profile"))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .context("failed to read shared profile")?;
+
+    let game = app.lock_manager().active_game;
+    ensure!(
+        lock.game == *game.slug,
+        "shared profile is for {}, but {} is selected",
+        lock.game,
+        game.name
+    );
+
+    let (profile_id, profile_path) = {
+        let mut manager = app.lock_manager();
+        let game = manager.active_game_mut();
+
+        let profile = match game.find_profile_index(&name) {
+            Some(index) => game.set_active_profile(index)?,
+            None => game.create_profile(name, None, app.db())?,
+        };
+
+        let result = (profile.id, profile.path.clone());
+        game.save(app)?;
+
+        result
+    };
+
+    // the peer's zips are kept apart from everything else until they're verified,
+    // and deleted when the import is done
+    let dir = tempfile::Builder::new()
+        .prefix(".lan-import")
+        .tempdir_in(&profile_path)
+        .fs_context("creating import directory", &profile_path)?;
+
+    for locked in &lock.mods {
+        if cache::path(&locked.ident, game, &app.lock_prefs()).exists() {
+            continue;
+        }
+
+        if app.db().archive_hash(locked.id.version_uuid)?.is_none() {
+            // without a hash from Thunderstore there's nothing to check the peer's zip against
+            debug!(
+                "no recorded hash for {}, not fetching it from peer",
+                locked.ident
+            );
+            continue;
+        }
+
+        debug!("fetching {} from {}", locked.ident, address);
+
+        let response = app
+            .http()
+            .get(format!("{base}/mods/{}", locked.ident))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            // the mod will be downloaded from thunderstore instead
+            warn!(
+                "failed to fetch {} from peer: {}",
+                locked.ident,
+                response.status()
+            );
+            continue;
+        }
+
+        let data = response.bytes().await?;
+        let path = dir.path().join(format!("{}.zip", locked.ident));
+
+        fs::write(&path, data).fs_context("saving mod from peer", &path)?;
+    }
+
+    let options = InstallOptions::default().archives_from(dir.path().to_path_buf());
+    lock::install_lock(profile_id, lock, options, app).await?;
+
+    Ok(profile_id)
+}
+
+fn parse_address(address: &str) -> Result<SocketAddr> {
+    address
+        .parse()
+        .map_err(|_| eyre!("invalid address: {}", address))
+}
@@ -0,0 +1,201 @@
+//! Detects the CPU architecture of the machine and of game and loader binaries.
+//!
+//! Windows on ARM and ARM Linux handhelds run x64 games under emulation, in which
+//! case the usual x64 loader builds work fine. What doesn't work is loading a DLL
+//! into a game built for another architecture, which fails silently in the game,
+//! so we check for that before launching.
+
+use std::{
+    fmt::{self, Display},
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use eyre::{bail, Result};
+use tracing::{info, warn};
+
+use super::{exe_path, ManagedGame};
+use crate::util::error::IoResultExt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    X86,
+    X64,
+    Arm64,
+}
+
+impl Display for Arch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arch::X86 => write!(f, "x86"),
+            Arch::X64 => write!(f, "x64"),
+            Arch::Arm64 => write!(f, "ARM64"),
+        }
+    }
+}
+
+impl Arch {
+    /// The architecture of the machine, even if Gale itself runs under emulation.
+    pub fn host() -> Option<Self> {
+        #[cfg(target_os = "windows")]
+        if let Some(arch) = windows_native_arch() {
+            return Some(arch);
+        }
+
+        match std::env::consts::ARCH {
+            "x86" => Some(Arch::X86),
+            "x86_64" => Some(Arch::X64),
+            "aarch64" => Some(Arch::Arm64),
+            _ => None,
+        }
+    }
+
+    /// Reads the architecture of a PE (`.exe` and `.dll`) or ELF binary.
+    ///
+    /// Returns `None` for other kinds of files and unknown architectures.
+    pub fn of_binary(path: &Path) -> Result<Option<Self>> {
+        let mut file = File::open(path).fs_context("opening binary", path)?;
+
+        let mut magic = [0; 4];
+        if file.read_exact(&mut magic).is_err() {
+            return Ok(None);
+        }
+
+        let machine = match magic {
+            [b'M', b'Z', ..] => {
+                file.seek(SeekFrom::Start(0x3C))?;
+                let pe_offset = read_u32(&mut file)?;
+
+                file.seek(SeekFrom::Start(pe_offset as u64))?;
+                let mut signature = [0; 4];
+                file.read_exact(&mut signature)?;
+
+                if signature != *b"PE\0\0" {
+                    return Ok(None);
+                }
+
+                match read_u16(&mut file)? {
+                    0x014C => Arch::X86,
+                    0x8664 => Arch::X64,
+                    0xAA64 => Arch::Arm64,
+                    _ => return Ok(None),
+                }
+            }
+            [0x7F, b'E', b'L', b'F'] => {
+                file.seek(SeekFrom::Start(0x12))?;
+
+                match read_u16(&mut file)? {
+                    0x03 => Arch::X86,
+                    0x3E => Arch::X64,
+                    0xB7 => Arch::Arm64,
+                    _ => return Ok(None),
+                }
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(machine))
+    }
+}
+
+fn read_u16(file: &mut File) -> Result<u16> {
+    let mut buf = [0; 2];
+    file.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn read_u32(file: &mut File) -> Result<u32> {
+    let mut buf = [0; 4];
+    file.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+/// `PROCESSOR_ARCHITECTURE` is faked for emulated processes, but the system-wide
+/// value in the registry isn't.
+#[cfg(target_os = "windows")]
+fn windows_native_arch() -> Option<Arch> {
+    use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
+
+    let value: String = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(r"SYSTEM\CurrentControlSet\Control\Session Manager\Environment")
+        .ok()?
+        .get_value("PROCESSOR_ARCHITECTURE")
+        .ok()?;
+
+    match value.as_str() {
+        "x86" => Some(Arch::X86),
+        "AMD64" => Some(Arch::X64),
+        "ARM64" => Some(Arch::Arm64),
+        _ => None,
+    }
+}
+
+/// Like [`Arch::of_binary`], but a binary that can't be read is logged and treated as unknown.
+fn arch_or_warn(path: &Path) -> Option<Arch> {
+    Arch::of_binary(path).unwrap_or_else(|err| {
+        warn!(
+            "failed to read the architecture of {}: {:#}",
+            path.display(),
+            err
+        );
+        None
+    })
+}
+
+impl ManagedGame {
+    /// Makes sure the active profile's proxy DLL is built for the same architecture
+    /// as the game, since it's silently ignored otherwise.
+    pub(super) fn check_loader_arch(&self, game_dir: &Path) -> Result<()> {
+        let Some(proxy_dll) = self.game.mod_loader.proxy_dll_file() else {
+            return Ok(());
+        };
+
+        let dll_path = self.active_profile().path.join(&proxy_dll);
+        if !dll_path.exists() {
+            return Ok(());
+        }
+
+        let Ok(exe_path) = exe_path(game_dir) else {
+            return Ok(());
+        };
+
+        // only block the launch when both architectures are known
+        let (Some(game_arch), Some(dll_arch)) = (arch_or_warn(&exe_path), arch_or_warn(&dll_path))
+        else {
+            return Ok(());
+        };
+
+        if let Some(host_arch) = Arch::host().filter(|host| *host != game_arch) {
+            info!(
+                "{} is a {} game running on {}, expecting emulation",
+                self.game.name, game_arch, host_arch
+            );
+        }
+
+        if game_arch == dll_arch {
+            return Ok(());
+        }
+
+        let loader = self.game.mod_loader.as_str();
+
+        if game_arch == Arch::Arm64 {
+            bail!(
+                "{} runs natively on ARM64, but {} doesn't have an ARM64 build, so mods can't be loaded",
+                self.game.name,
+                loader
+            );
+        }
+
+        bail!(
+            "{}'s {} is built for {}, but {} is a {} game. Install the {} build of {} instead.",
+            loader,
+            proxy_dll,
+            dll_arch,
+            self.game.name,
+            game_arch,
+            game_arch,
+            loader
+        );
+    }
+}
@@ -1,8 +1,10 @@
-use eyre::Context;
-use itertools::Itertools;
+use std::path::PathBuf;
+
+use eyre::{Context, OptionExt};
 use tauri::{command, AppHandle};
 
-use crate::{profile::sync, state::ManagerExt, util::cmd::Result};
+use super::{detect::DetectedGame, LaunchMode};
+use crate::{game, profile::sync, state::ManagerExt, util::cmd::Result};
 
 #[command]
 pub async fn launch_game(app: AppHandle) -> Result<()> {
@@ -10,10 +12,35 @@ pub async fn launch_game(app: AppHandle) -> Result<()> {
         sync::pull_profile(false, &app).await?;
     }
 
-    let prefs = app.lock_prefs();
-    let manager = app.lock_manager();
+    super::launch(&app).await?;
+
+    Ok(())
+}
+
+/// Lists supported games that are installed, for the first-run setup.
+#[command]
+pub async fn scan_installed_games() -> Result<Vec<DetectedGame>> {
+    let games = tauri::async_runtime::spawn_blocking(super::detect::scan).await?;
+    Ok(games)
+}
+
+/// Points a game found through GOG at its folder, so it's launched directly from there.
+#[command]
+pub fn use_gog_install(slug: String, path: PathBuf, app: AppHandle) -> Result<()> {
+    let game = game::from_slug(&slug).ok_or_eyre("unknown game")?;
+
+    let mut prefs = app.lock_prefs();
+    let game_prefs = prefs.game_prefs.entry(game.slug.to_string()).or_default();
 
-    manager.active_game().launch(&prefs, &app)?;
+    game_prefs.dir_override = Some(path);
+    if let LaunchMode::Launcher = game_prefs.launch_mode {
+        game_prefs.launch_mode = LaunchMode::Direct {
+            instances: 1,
+            interval_secs: 10.0,
+        };
+    }
+
+    prefs.save(app.db())?;
 
     Ok(())
 }
@@ -25,12 +52,8 @@ pub fn get_launch_args(app: AppHandle) -> Result<String> {
 
     let game_dir = super::locate_game_dir(manager.active_game, &prefs)?;
     let (_, command) = manager.active_game().launch_command(&game_dir, &prefs)?;
-    let text = command
-        .get_args()
-        .map(|arg| format!("\"{}\"", arg.to_string_lossy()))
-        .join(" ");
 
-    Ok(text)
+    Ok(super::steam_config::quote_args(command.get_args()))
 }
 
 #[command]
@@ -43,3 +66,46 @@ pub fn open_game_dir(app: AppHandle) -> Result<()> {
 
     Ok(())
 }
+
+#[command]
+pub async fn clear_steam_launch_options(app: AppHandle) -> Result<()> {
+    let game = app.lock_manager().active_game;
+    let app_id = game
+        .platforms
+        .steam
+        .as_ref()
+        .ok_or_eyre("game is not available on Steam")?
+        .id;
+
+    super::steam_config::restore_launch_options(app_id, &app).await?;
+
+    Ok(())
+}
+
+/// Launches the game with a marker to check that the mod loader works.
+#[command]
+pub async fn test_injection(app: AppHandle) -> Result<()> {
+    super::self_test::test_injection(&app).await?;
+
+    Ok(())
+}
+
+#[command]
+pub fn get_missing_loader_files(app: AppHandle) -> Result<Vec<String>> {
+    let prefs = app.lock_prefs();
+    let manager = app.lock_manager();
+
+    let missing = manager.active_game().missing_loader_files(&prefs)?;
+
+    Ok(missing)
+}
+
+#[command]
+pub fn repair_loader_files(app: AppHandle) -> Result<()> {
+    let prefs = app.lock_prefs();
+    let manager = app.lock_manager();
+
+    manager.active_game().repair_loader_files(&prefs)?;
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+//! Steam Deck support: finding games on microSD cards and launching through Steam
+//! in Gaming Mode, so that controller configs apply.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use tracing::{debug, info};
+
+/// Where SteamOS mounts microSD cards, either directly (`/run/media/mmcblk0p1`)
+/// or by label under the user's directory (`/run/media/deck/<label>`).
+const MEDIA_DIR: &str = "/run/media";
+
+/// Whether we're running on a Steam Deck.
+pub fn is_steam_deck() -> bool {
+    static IS_DECK: OnceLock<bool> = OnceLock::new();
+
+    *IS_DECK.get_or_init(|| {
+        let is_deck = cfg!(target_os = "linux") && detect();
+        if is_deck {
+            info!("running on a Steam Deck");
+        }
+        is_deck
+    })
+}
+
+fn detect() -> bool {
+    if std::env::var("SteamDeck").is_ok_and(|value| value == "1") {
+        return true;
+    }
+
+    let read = |name: &str| {
+        fs::read_to_string(Path::new("/sys/devices/virtual/dmi/id").join(name))
+            .map(|value| value.trim().to_owned())
+            .unwrap_or_default()
+    };
+
+    let product = read("product_name");
+    read("board_vendor") == "Valve" && matches!(product.as_str(), "Jupiter" | "Galileo")
+}
+
+/// Whether Gale was started from Gaming Mode, where games have to be launched
+/// through Steam for Steam Input to work.
+pub fn is_gaming_mode() -> bool {
+    is_steam_deck()
+        && (std::env::var_os("SteamGamepadUI").is_some()
+            || std::env::var("XDG_CURRENT_DESKTOP").is_ok_and(|value| value == "gamescope"))
+}
+
+/// Directories that currently hold mounted removable media.
+fn media_mounts() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(MEDIA_DIR) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .flat_map(|path| {
+            // labelled mounts live one level down, in the user's directory
+            let nested = fs::read_dir(&path)
+                .map(|entries| {
+                    entries
+                        .filter_map(Result::ok)
+                        .map(|entry| entry.path())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            std::iter::once(path).chain(nested)
+        })
+        .filter(|path| path.join("steamapps").is_dir())
+        .collect()
+}
+
+/// Looks for a Steam app in libraries on microSD cards, for when Steam itself
+/// doesn't list the library, like when the card was inserted after Steam started.
+pub fn find_removable_app_dir(app_id: u32) -> Option<PathBuf> {
+    media_mounts().into_iter().find_map(|mount| {
+        let steamapps = mount.join("steamapps");
+        let manifest =
+            fs::read_to_string(steamapps.join(format!("appmanifest_{app_id}.acf"))).ok()?;
+
+        let install_dir = manifest.lines().find_map(|line| {
+            let value = line.trim().strip_prefix("\"installdir\"")?;
+            Some(value.trim().trim_matches('"').to_owned())
+        })?;
+
+        let path = steamapps.join("common").join(install_dir);
+        debug!(
+            "found app {} on removable media at {}",
+            app_id,
+            path.display()
+        );
+
+        path.exists().then_some(path)
+    })
+}
+
+/// Finds where a path on a microSD card is mounted now, since SteamOS has changed
+/// the mount point between versions, which breaks saved paths.
+pub fn remap_media_path(path: &Path) -> Option<PathBuf> {
+    let relative = path.strip_prefix(MEDIA_DIR).ok()?;
+
+    // the mount point is either one or two components long, try both
+    let candidates = [1, 2]
+        .into_iter()
+        .map(|depth| relative.components().skip(depth).collect::<PathBuf>())
+        .filter(|rest| !rest.as_os_str().is_empty())
+        .collect::<Vec<_>>();
+
+    media_mounts().into_iter().find_map(|mount| {
+        candidates
+            .iter()
+            .map(|rest| mount.join(rest))
+            .find(|path| path.exists())
+    })
+}
@@ -0,0 +1,127 @@
+//! Finds supported games that are already installed through one of their
+//! platforms, so the first-run setup can suggest them.
+//!
+//! Games installed through GOG are found as well, from the keys GOG Galaxy and
+//! the offline installers leave in the registry. GOG isn't a platform Gale can
+//! launch from, so those are launched directly from their folder instead.
+
+use std::path::PathBuf;
+
+use itertools::Itertools;
+use serde::Serialize;
+use tracing::{debug, info};
+
+use super::platform;
+use crate::game::{self, platform::Platform};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedGame {
+    pub slug: &'static str,
+    pub name: &'static str,
+    /// `None` for games installed through GOG.
+    pub platform: Option<Platform>,
+    pub path: PathBuf,
+}
+
+/// Checks every platform of every game. This is blocking and, on Windows,
+/// spawns a process for each Xbox Store game.
+pub fn scan() -> Vec<DetectedGame> {
+    let gog_games = gog_games();
+
+    let detected = game::all()
+        .filter_map(|game| {
+            game.platforms
+                .iter()
+                .find_map(
+                    |platform| match platform::locate_game_dir(Some(platform), game) {
+                        Ok(path) if path.is_dir() => Some(DetectedGame {
+                            slug: &game.slug,
+                            name: game.name,
+                            platform: Some(platform),
+                            path,
+                        }),
+                        Ok(_) => None,
+                        Err(err) => {
+                            debug!("{} not found on {}: {:#}", game.slug, platform, err);
+                            None
+                        }
+                    },
+                )
+                .or_else(|| {
+                    let name = normalize_name(game.name);
+                    let (_, path) = gog_games.iter().find(|(other, _)| *other == name)?;
+
+                    path.is_dir().then(|| DetectedGame {
+                        slug: &game.slug,
+                        name: game.name,
+                        platform: None,
+                        path: path.clone(),
+                    })
+                })
+        })
+        .sorted_by_key(|detected| detected.name)
+        .collect_vec();
+
+    info!("found {} installed games", detected.len());
+
+    detected
+}
+
+/// Strips everything but letters and digits, since GOG's names sometimes include
+/// trademark signs and different punctuation.
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|char| char.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Lists the games installed through GOG, as normalized names and install folders.
+#[cfg(target_os = "windows")]
+fn gog_games() -> Vec<(String, PathBuf)> {
+    use winreg::{enums::HKEY_LOCAL_MACHINE, RegKey};
+
+    let games = match RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(r"SOFTWARE\WOW6432Node\GOG.com\Games")
+    {
+        Ok(key) => key,
+        Err(err) => {
+            debug!("no GOG games found: {}", err);
+            return Vec::new();
+        }
+    };
+
+    games
+        .enum_keys()
+        .filter_map(Result::ok)
+        .filter_map(|id| {
+            let key = games.open_subkey(&id).ok()?;
+            let name: String = key.get_value("gameName").ok()?;
+            let path: String = key.get_value("path").ok()?;
+
+            Some((normalize_name(&name), PathBuf::from(path)))
+        })
+        .collect()
+}
+
+/// GOG Galaxy only exists on Windows.
+#[cfg(not(target_os = "windows"))]
+fn gog_games() -> Vec<(String, PathBuf)> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_gog_names() {
+        assert_eq!(normalize_name("Valheim™"), normalize_name("Valheim"));
+        assert_eq!(normalize_name("Core Keeper"), normalize_name("Core-Keeper"));
+        assert_ne!(
+            normalize_name("Risk of Rain"),
+            normalize_name("Risk of Rain 2")
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! Notices when Steam updates the active game by watching its app manifest, which
+//! Steam rewrites with the new build id.
+//!
+//! Updates often remove the loader's proxy DLL, so the deployment is checked again
+//! right away, and cached compatibility reports are dropped since they're filtered
+//! by build. The last seen build of each game is stored in the database, so updates
+//! that happened while Gale was closed are noticed too.
+
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use eyre::Result;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, info, warn};
+
+use super::platform;
+use crate::{
+    game::Game,
+    i18n,
+    notify::{self, Category},
+    profile::sync::compat,
+    state::ManagerExt,
+};
+
+/// Only the manifest's modification time is checked this often, which is cheap.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameUpdate {
+    /// Slug of the updated game.
+    pub game: String,
+    pub previous_build: u64,
+    pub build: u64,
+    /// Loader files the update removed from the game directory.
+    pub missing_loader_files: Vec<String>,
+}
+
+struct Watched {
+    game: Game,
+    manifest: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+/// Starts watching the active game's manifest in the background.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let mut watched = None;
+
+        loop {
+            let handle = app.clone();
+            let result = tauri::async_runtime::spawn_blocking(move || poll(watched, &handle)).await;
+
+            watched = result.unwrap_or_else(|err| {
+                warn!("failed to check for game updates: {}", err);
+                None
+            });
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+fn poll(watched: Option<Watched>, app: &AppHandle) -> Option<Watched> {
+    let game = app.lock_manager().active_game;
+
+    // the manifest is only looked up again when the active game changes
+    let watched = match watched.filter(|watched| watched.game == game) {
+        Some(watched) => watched,
+        None => Watched {
+            game,
+            manifest: platform::steam_manifest_path(game)
+                .inspect_err(|err| debug!("can't watch {} for updates: {:#}", game.slug, err))
+                .ok()??,
+            modified: None,
+        },
+    };
+
+    let modified = fs::metadata(&watched.manifest)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+
+    if modified.is_some() && modified != watched.modified {
+        if let Err(err) = check(game, app) {
+            warn!("failed to check if {} was updated: {:#}", game.slug, err);
+        }
+    }
+
+    Some(Watched {
+        modified,
+        ..watched
+    })
+}
+
+fn check(game: Game, app: &AppHandle) -> Result<()> {
+    let Some(build) = super::game_build_id(game) else {
+        return Ok(());
+    };
+
+    let previous = app.db().last_build_id(&game.slug)?;
+    if previous == Some(build) {
+        return Ok(());
+    }
+
+    app.db().record_build_id(&game.slug, build)?;
+
+    // the first build we see is just remembered
+    let Some(previous) = previous else {
+        return Ok(());
+    };
+
+    info!(
+        "{} was updated from build {} to {}",
+        game.slug, previous, build
+    );
+
+    compat::invalidate(game, app);
+
+    let missing_loader_files = {
+        let prefs = app.lock_prefs();
+        let manager = app.lock_manager();
+
+        match manager.games.get(&game) {
+            Some(managed_game) => managed_game.missing_loader_files(&prefs)?,
+            None => Vec::new(),
+        }
+    };
+
+    if !missing_loader_files.is_empty() {
+        info!(
+            "loader files missing after update: {:?}",
+            missing_loader_files
+        );
+    }
+
+    app.emit(
+        "game_updated",
+        GameUpdate {
+            game: game.slug.to_string(),
+            previous_build: previous,
+            build,
+            missing_loader_files,
+        },
+    )
+    .ok();
+
+    let message = i18n::t("notify.game_updated", &[("game", &game.name)]);
+    notify::send(Category::Updates, &message, app);
+
+    Ok(())
+}
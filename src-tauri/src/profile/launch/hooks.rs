@@ -0,0 +1,149 @@
+//! User commands that run before the game is launched and after it exits,
+//! e.g. to start companion tools alongside the game.
+//!
+//! Hooks are run through the system shell with `GALE_*` environment variables
+//! describing the profile being launched.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+
+use eyre::{ensure, eyre, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use super::is_process_running;
+use crate::{game::Game, profile::Profile, util};
+
+/// How long to wait for the game process to show up after launching.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How long the pre-launch hook may run before the launch is given up on.
+const PRE_LAUNCH_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LaunchHooks {
+    /// Runs to completion before the game is started.
+    pub pre_launch: Option<String>,
+    /// Runs once every instance of the game has exited.
+    pub post_exit: Option<String>,
+}
+
+/// The profile-specific environment passed to hooks.
+#[derive(Debug, Clone)]
+pub struct HookEnv {
+    vars: Vec<(&'static str, String)>,
+}
+
+impl HookEnv {
+    pub fn new(profile: &Profile, game: Game, game_dir: &Path) -> Self {
+        let vars = vec![
+            ("GALE_GAME", game.slug.to_string()),
+            ("GALE_GAME_DIR", game_dir.to_string_lossy().into_owned()),
+            ("GALE_PROFILE_ID", profile.id.to_string()),
+            ("GALE_PROFILE_NAME", profile.name.clone()),
+            (
+                "GALE_PROFILE_PATH",
+                profile.path.to_string_lossy().into_owned(),
+            ),
+        ];
+
+        Self { vars }
+    }
+
+    fn command(&self, hook: &str) -> Command {
+        let mut command = util::shell_command(hook);
+        command.envs(self.vars.iter().map(|(key, value)| (*key, value)));
+        command
+    }
+}
+
+impl LaunchHooks {
+    /// Runs the pre-launch hook, failing the launch if it doesn't succeed in time.
+    pub async fn run_pre_launch(&self, env: &HookEnv) -> Result<()> {
+        let Some(hook) = &self.pre_launch else {
+            return Ok(());
+        };
+
+        info!("running pre-launch hook: {}", hook);
+
+        // killed when dropped, which is what happens on a timeout
+        let mut child = tokio::process::Command::from(env.command(hook))
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to run pre-launch hook")?;
+
+        let status = tokio::time::timeout(PRE_LAUNCH_TIMEOUT, child.wait())
+            .await
+            .map_err(|_| {
+                eyre!(
+                    "pre-launch hook did not finish within {} seconds",
+                    PRE_LAUNCH_TIMEOUT.as_secs()
+                )
+            })?
+            .context("failed to run pre-launch hook")?;
+
+        ensure!(status.success(), "pre-launch hook exited with {}", status);
+
+        Ok(())
+    }
+
+    /// Waits in the background for the game to exit, then runs the post-exit hook.
+    ///
+    /// Since the game is often started through a launcher, this looks for
+    /// running processes of `exe_path` rather than waiting on a child process.
+    pub fn watch_exit(&self, env: HookEnv, exe_path: PathBuf) {
+        let Some(hook) = self.post_exit.clone() else {
+            return;
+        };
+
+        let Some(exe_name) = exe_path.file_name() else {
+            warn!("invalid game executable path, skipping post-exit hook");
+            return;
+        };
+
+        let exe_name = exe_name.to_string_lossy().into_owned();
+
+        thread::spawn(move || {
+            if !wait_for_exit(&exe_name) {
+                warn!("{} never started, skipping post-exit hook", exe_name);
+                return;
+            }
+
+            info!("running post-exit hook: {}", hook);
+
+            match env.command(&hook).status() {
+                Ok(status) if status.success() => (),
+                Ok(status) => warn!("post-exit hook exited with {}", status),
+                Err(err) => warn!("failed to run post-exit hook: {}", err),
+            }
+        });
+    }
+}
+
+/// Blocks until the game has started and then exited again.
+///
+/// Returns `false` if the game never started.
+pub fn wait_for_exit(exe_name: &str) -> bool {
+    let start = Instant::now();
+
+    while !is_process_running(exe_name) {
+        if start.elapsed() > STARTUP_TIMEOUT {
+            return false;
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    debug!("{} is running, waiting for it to exit", exe_name);
+
+    while is_process_running(exe_name) {
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    true
+}
@@ -2,32 +2,47 @@ use core::str;
 use std::{
     fs,
     path::{Path, PathBuf},
-    process::Command,
+    process::{Child, Command},
 };
 
-use eyre::{bail, ensure, eyre, OptionExt, Result};
+use eyre::{bail, ensure, eyre, Context, OptionExt, Result};
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 use tokio::time::Duration;
-use tracing::{info, warn};
+use tracing::{info, instrument, warn};
 
 use super::ManagedGame;
 use crate::{
-    game::Game,
+    game::{anti_cheat, mod_loader::ModLoaderKind, platform::Platform, Game},
     logger::log_webview_err,
+    notify,
     prefs::{GamePrefs, Prefs},
     util::{
         self,
         fs::{Overwrite, UseLinks},
     },
 };
+use hooks::{HookEnv, LaunchHooks};
+use process::ProcessOptions;
+use runtime_files::Snapshot;
 
+mod arch;
+mod deck;
 #[cfg(target_os = "linux")]
 mod linux;
 mod mod_loader;
 mod platform;
+mod repair;
+mod self_test;
+mod staging;
 
 pub mod commands;
+pub mod detect;
+pub mod game_update;
+pub mod hooks;
+pub mod process;
+pub mod runtime_files;
+pub mod steam_config;
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase", tag = "type", content = "content")]
@@ -39,18 +54,176 @@ pub enum LaunchMode {
     Direct { instances: u32, interval_secs: f32 },
 }
 
+/// Launches the active profile.
+///
+/// The prefs and manager are only locked while the launch is checked and prepared,
+/// so that a slow pre-launch hook doesn't freeze the rest of the app.
+pub async fn launch(app: &AppHandle) -> Result<()> {
+    let (profile_id, hooks, hook_env) = {
+        let prefs = app.lock_prefs();
+        let manager = app.lock_manager();
+        let game = manager.active_game();
+
+        let (hooks, hook_env) = game.check_launch(&prefs)?;
+        (game.active_profile().id, hooks, hook_env)
+    };
+
+    // nothing has been touched yet, so the hook can still call off the launch
+    hooks.run_pre_launch(&hook_env).await?;
+
+    let PreparedLaunch {
+        game,
+        game_dir,
+        profile_dir,
+        mode,
+        command,
+        options,
+        steam_options,
+        snapshot,
+        quiet,
+    } = {
+        let mut prefs = app.lock_prefs();
+        let manager = app.lock_manager();
+        let game = manager.active_game();
+
+        ensure!(
+            game.active_profile().id == profile_id,
+            "the profile was switched while the pre-launch hook was running"
+        );
+
+        let prepared = game.prepare_launch(&mut prefs)?;
+
+        // keeps track of the GPU preferences Gale has written
+        prefs.save(app.db())?;
+
+        prepared
+    };
+
+    if let Some((app_id, launch_options)) = steam_options {
+        steam_config::write_launch_options(app_id, launch_options, app)
+            .await
+            .context("failed to write launch options to Steam")?;
+    }
+
+    info!("launching {} with command {:?}", game.slug, command);
+    do_launch(command, app, mode, options)?;
+
+    let ue4ss_project = match &game.mod_loader.kind {
+        ModLoaderKind::UE4SS { project } => Some(*project),
+        _ => None,
+    };
+
+    if hooks.post_exit.is_some() || snapshot.is_some() || quiet || ue4ss_project.is_some() {
+        match exe_path(&game_dir) {
+            Ok(exe_path) => {
+                if let Some(project) = ue4ss_project {
+                    mod_loader::collect_ue4ss_log(&profile_dir, &game_dir, project, &exe_path);
+                }
+
+                if let Some(snapshot) = snapshot {
+                    snapshot.watch(exe_path.clone(), app);
+                }
+
+                if quiet {
+                    notify::watch_game(&exe_path, app);
+                }
+
+                hooks.watch_exit(hook_env, exe_path);
+            }
+            Err(err) => warn!("cannot watch for the game to exit: {:#}", err),
+        }
+    }
+
+    Ok(())
+}
+
+/// Everything needed to start the game once the profile's files are in place.
+struct PreparedLaunch {
+    game: Game,
+    game_dir: PathBuf,
+    profile_dir: PathBuf,
+    mode: LaunchMode,
+    command: Command,
+    options: ProcessOptions,
+    /// The app ID and launch options to write into Steam's config.
+    steam_options: Option<(u32, String)>,
+    snapshot: Option<Snapshot>,
+    quiet: bool,
+}
+
 impl ManagedGame {
-    pub fn launch(&self, prefs: &Prefs, app: &AppHandle) -> Result<()> {
+    /// Checks that the active profile can be launched, without changing anything yet.
+    fn check_launch(&self, prefs: &Prefs) -> Result<(LaunchHooks, HookEnv)> {
+        anti_cheat::check(self.game, prefs)?;
+
         let game_dir = locate_game_dir(self.game, prefs)?;
-        if let Err(err) = self.copy_required_files(&game_dir) {
-            warn!("failed to copy required files to game directory: {:#}", err);
-        }
+        self.check_loader_arch(&game_dir)?;
 
-        let (launch_mode, command) = self.launch_command(&game_dir, prefs)?;
-        info!("launching {} with command {:?}", self.game.slug, command);
-        do_launch(command, app, launch_mode)?;
+        let hooks = self.launch_hooks(prefs);
+        let hook_env = HookEnv::new(self.active_profile(), self.game, &game_dir);
 
-        Ok(())
+        Ok((hooks, hook_env))
+    }
+
+    fn prepare_launch(&self, prefs: &mut Prefs) -> Result<PreparedLaunch> {
+        let game_dir = locate_game_dir(self.game, prefs)?;
+
+        self.copy_required_files(&game_dir, prefs)
+            .context("failed to copy required files to game directory")?;
+
+        let (mode, mut command) = self.launch_command(&game_dir, prefs)?;
+        let steam_options = match self.steam_injection_target(&mode, prefs) {
+            Some(app_id) => {
+                let (base, options) = steam_config::split_injected(command)?;
+                command = base;
+                Some((app_id, options))
+            }
+            None => None,
+        };
+
+        let options = self.prepare_process(&mut command, &game_dir, &mode, prefs);
+
+        let snapshot = prefs
+            .track_runtime_files
+            .then(|| Snapshot::take(self.active_profile()));
+
+        Ok(PreparedLaunch {
+            game: self.game,
+            game_dir,
+            profile_dir: self.active_profile().path.clone(),
+            mode,
+            command,
+            options,
+            steam_options,
+            snapshot,
+            quiet: prefs.notifications.quiet_while_playing,
+        })
+    }
+
+    /// Launches the game directly and returns its process, so that it can be supervised.
+    ///
+    /// This is meant for dedicated servers and requires the direct launch mode,
+    /// since launching through a platform doesn't give us the actual game process.
+    pub fn spawn(&self, prefs: &mut Prefs) -> Result<Child> {
+        let game_dir = locate_game_dir(self.game, prefs)?;
+        self.check_loader_arch(&game_dir)?;
+
+        self.copy_required_files(&game_dir, prefs)
+            .context("failed to copy required files to game directory")?;
+
+        let (launch_mode, mut command) = self.launch_command(&game_dir, prefs)?;
+        ensure!(
+            matches!(launch_mode, LaunchMode::Direct { .. }),
+            "launch mode must be set to direct to supervise the game process"
+        );
+
+        let options = self.prepare_process(&mut command, &game_dir, &launch_mode, prefs);
+
+        info!("spawning {} with command {:?}", self.game.slug, command);
+        let child = command.spawn()?;
+        options.apply(child.id());
+
+        Ok(child)
     }
 
     fn launch_command(&self, game_dir: &Path, prefs: &Prefs) -> Result<(LaunchMode, Command)> {
@@ -76,6 +249,16 @@ impl ManagedGame {
         // if the game has a platform but the setting is unset, fill it in
         platform = platform.or_else(|| self.game.platforms.iter().next());
 
+        // direct launches bypass Steam Input, leaving the Deck without controls
+        let launch_mode = if deck::is_gaming_mode() && matches!(platform, Some(Platform::Steam)) {
+            if matches!(launch_mode, LaunchMode::Direct { .. }) {
+                info!("launching through Steam since we're in Gaming Mode");
+            }
+            LaunchMode::Launcher
+        } else {
+            launch_mode
+        };
+
         let mut command = match (&launch_mode, platform) {
             // If the setting is `Launcher` and we have a platform, use the platform-specific
             // launch command (if there is one). Otherwise, fall back to direct execution.
@@ -94,6 +277,10 @@ impl ManagedGame {
 
         mod_loader::add_args(&mut command, &profile.path, &self.game.mod_loader)?;
 
+        if let Some(anti_cheat) = &self.game.anti_cheat {
+            command.args(anti_cheat.launch_args());
+        }
+
         if let Some(custom_args) = game_custom_args {
             command.args(custom_args);
         }
@@ -105,7 +292,61 @@ impl ManagedGame {
         Ok((launch_mode, command))
     }
 
-    fn copy_required_files(&self, game_dir: &Path) -> Result<()> {
+    /// The Steam app to write launch options for, if the user has opted in
+    /// and the game is launched through Steam.
+    fn steam_injection_target(&self, mode: &LaunchMode, prefs: &Prefs) -> Option<u32> {
+        let game_prefs = prefs.game_prefs.get(&*self.game.slug)?;
+        if !game_prefs.inject_steam_launch_options || !matches!(mode, LaunchMode::Launcher) {
+            return None;
+        }
+
+        let platform = game_prefs
+            .platform
+            .or_else(|| self.game.platforms.iter().next());
+
+        match platform {
+            Some(Platform::Steam) => self.game.platforms.steam.as_ref().map(|steam| steam.id),
+            _ => None,
+        }
+    }
+
+    fn launch_hooks(&self, prefs: &Prefs) -> LaunchHooks {
+        prefs
+            .game_prefs
+            .get(&*self.game.slug)
+            .and_then(|prefs| prefs.launch_hooks.get(&self.active_profile().id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Prepares `command` with the active profile's process options and returns them,
+    /// so they can be applied once the game has started.
+    fn prepare_process(
+        &self,
+        command: &mut Command,
+        game_dir: &Path,
+        mode: &LaunchMode,
+        prefs: &mut Prefs,
+    ) -> ProcessOptions {
+        let options = prefs
+            .game_prefs
+            .get(&*self.game.slug)
+            .and_then(|prefs| prefs.process_options.get(&self.active_profile().id))
+            .cloned()
+            .unwrap_or_default();
+
+        options.prepare(command, mode);
+
+        // the GPU preference is keyed by the game executable
+        if let Ok(game_exe) = exe_path(game_dir) {
+            options.apply_gpu_preference(&game_exe, &mut prefs.gpu_preference_exes);
+        }
+
+        options
+    }
+
+    /// Files and directories in the profile root that need to be in the game directory.
+    fn required_files(&self) -> Result<Vec<fs::DirEntry>> {
         const INCLUDE_DIRS: [&str; 2] = ["doorstop_libs", "dotnet"];
         const EXCLUDES: [&str; 2] = ["profile.json", "mods.yml"];
 
@@ -125,7 +366,33 @@ impl ManagedGame {
                 let is_included_dir = INCLUDE_DIRS.iter().any(|dir| *dir == name);
 
                 return is_file || is_included_dir;
-            });
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    #[instrument(skip_all)]
+    fn copy_required_files(&self, game_dir: &Path, prefs: &Prefs) -> Result<()> {
+        if let ModLoaderKind::UE4SS { project } = &self.game.mod_loader.kind {
+            mod_loader::deploy_ue4ss(
+                &self.active_profile().path,
+                game_dir,
+                project,
+                &self.path.join(mod_loader::UE4SS_MANIFEST_NAME),
+            )?;
+        }
+
+        let entries = self.required_files()?;
+
+        let staged = prefs
+            .game_prefs
+            .get(&*self.game.slug)
+            .is_some_and(|prefs| prefs.stage_game_files);
+
+        if staged {
+            return staging::deploy(&entries, self.game, game_dir, prefs);
+        }
 
         for entry in entries {
             info!(
@@ -146,11 +413,20 @@ impl ManagedGame {
     }
 }
 
-fn do_launch(mut command: Command, app: &AppHandle, mode: LaunchMode) -> Result<()> {
+fn do_launch(
+    mut command: Command,
+    app: &AppHandle,
+    mode: LaunchMode,
+    options: ProcessOptions,
+) -> Result<()> {
     match mode {
-        LaunchMode::Launcher | LaunchMode::Direct { instances: 1, .. } => {
+        LaunchMode::Launcher => {
             command.spawn()?;
         }
+        LaunchMode::Direct { instances: 1, .. } => {
+            let child = command.spawn()?;
+            options.apply(child.id());
+        }
         LaunchMode::Direct { instances: 0, .. } => bail!("instances must be greater than 0"),
         LaunchMode::Direct {
             instances,
@@ -159,12 +435,13 @@ fn do_launch(mut command: Command, app: &AppHandle, mode: LaunchMode) -> Result<
             let app = app.clone();
             tauri::async_runtime::spawn(async move {
                 for i in 0..instances {
-                    if let Err(err) = command.spawn() {
-                        log_webview_err(
+                    match command.spawn() {
+                        Ok(child) => options.apply(child.id()),
+                        Err(err) => log_webview_err(
                             "Failed to launch game",
                             eyre!("Launch command {} failed: {}.", i, err),
                             &app,
-                        );
+                        ),
                     }
                     tokio::time::sleep(Duration::from_secs_f32(interval_secs)).await;
                 }
@@ -175,7 +452,15 @@ fn do_launch(mut command: Command, app: &AppHandle, mode: LaunchMode) -> Result<
     Ok(())
 }
 
-fn locate_game_dir(game: Game, prefs: &Prefs) -> Result<PathBuf> {
+/// Gets the build id of the installed game, which is only known for Steam games.
+pub fn game_build_id(game: Game) -> Option<u64> {
+    platform::steam_build_id(game)
+        .inspect_err(|err| warn!("failed to read build id of {}: {:#}", game.slug, err))
+        .ok()
+        .flatten()
+}
+
+pub fn locate_game_dir(game: Game, prefs: &Prefs) -> Result<PathBuf> {
     let game_prefs = prefs.game_prefs.get(&*game.slug);
 
     let path = if let Some(GamePrefs {
@@ -184,7 +469,20 @@ fn locate_game_dir(game: Game, prefs: &Prefs) -> Result<PathBuf> {
     }) = game_prefs
     {
         info!("using game directory override at {}", path.display());
-        path.to_path_buf()
+
+        let remapped = if path.exists() {
+            None
+        } else {
+            deck::remap_media_path(path)
+        };
+
+        match remapped {
+            Some(remapped) => {
+                info!("microSD card was remounted at {}", remapped.display());
+                remapped
+            }
+            None => path.to_path_buf(),
+        }
     } else {
         let platform = game_prefs
             .and_then(|prefs| prefs.platform)
@@ -211,6 +509,27 @@ fn locate_game_dir(game: Game, prefs: &Prefs) -> Result<PathBuf> {
     Ok(path)
 }
 
+#[cfg(windows)]
+fn is_process_running(exe_name: &str) -> bool {
+    use std::os::windows::process::CommandExt;
+
+    const NO_WINDOW: u32 = 0x08000000;
+
+    Command::new("tasklist")
+        .args(["/FI", &format!("IMAGENAME eq {exe_name}"), "/NH"])
+        .creation_flags(NO_WINDOW)
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(exe_name))
+}
+
+#[cfg(not(windows))]
+fn is_process_running(exe_name: &str) -> bool {
+    Command::new("pgrep")
+        .args(["-f", exe_name])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
 fn exe_path(game_dir: &Path) -> Result<PathBuf> {
     game_dir
         .read_dir()?
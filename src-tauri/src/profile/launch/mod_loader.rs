@@ -1,15 +1,18 @@
 use std::{
-    fs,
+    fs, io,
     path::{Path, PathBuf},
     process::Command,
+    thread,
 };
 
-use eyre::{bail, Context, OptionExt, Result};
-use tracing::{info, warn};
+use eyre::{bail, ensure, Context, OptionExt, Result};
+use tracing::{debug, info, warn};
+use walkdir::WalkDir;
 
+use super::hooks;
 use crate::{
     game::mod_loader::{ModLoader, ModLoaderKind},
-    util::error::IoResultExt,
+    util::{self, error::IoResultExt, fs::JsonStyle},
 };
 
 pub fn add_args(command: &mut Command, profile_dir: &Path, mod_loader: &ModLoader) -> Result<()> {
@@ -22,6 +25,8 @@ pub fn add_args(command: &mut Command, profile_dir: &Path, mod_loader: &ModLoade
         ModLoaderKind::Shimloader {} => add_shimloader_args(command, profile_dir),
         ModLoaderKind::Lovely {} => add_lovely_args(command, profile_dir),
         ModLoaderKind::ReturnOfModding { .. } => add_return_of_modding_args(command, profile_dir),
+        // can't be pointed at the profile, see deploy_ue4ss
+        ModLoaderKind::UE4SS { .. } => Ok(()),
     }
 }
 
@@ -182,3 +187,240 @@ fn add_return_of_modding_args(command: &mut Command, profile_dir: &Path) -> Resu
 
     Ok(())
 }
+
+fn ue4ss_binaries_dir(game_dir: &Path, project: &str) -> PathBuf {
+    game_dir.join(project).join("Binaries").join("Win64")
+}
+
+/// Where the files copied by [`deploy_ue4ss`] are recorded, relative to the game's
+/// data directory. It's shared by the game's profiles, so that switching profiles
+/// still removes what the last one deployed.
+pub const UE4SS_MANIFEST_NAME: &str = "ue4ss_deployed.json";
+
+const UE4SS_LOG_NAME: &str = "UE4SS.log";
+
+/// Copies UE4SS and its mods next to the game's executable, since it only looks for
+/// them there.
+///
+/// Every copied file is recorded in the manifest at `manifest_path`, and only those
+/// are removed before the next deploy. That way mods from the last launch don't stick
+/// around, without touching files the game ships with or the user put there by hand.
+/// Those are also never overwritten.
+pub fn deploy_ue4ss(
+    profile_dir: &Path,
+    game_dir: &Path,
+    project: &str,
+    manifest_path: &Path,
+) -> Result<()> {
+    remove_deployed(game_dir, manifest_path)?;
+
+    let binaries_dir = ue4ss_binaries_dir(game_dir, project);
+    let loader_dir = profile_dir.join("ue4ss");
+    let loader_proxy = loader_dir.join("dwmapi.dll");
+
+    if !loader_proxy.exists() {
+        debug!("UE4SS is not enabled, not deploying it");
+        return Ok(());
+    }
+
+    info!("copying UE4SS to {}", binaries_dir.display());
+
+    let mut deployed = Vec::new();
+    let result = copy_ue4ss(profile_dir, game_dir, project, &mut deployed);
+
+    // record whatever was copied, even if something failed halfway
+    let deployed = deployed
+        .into_iter()
+        .filter_map(|path| path.strip_prefix(game_dir).ok().map(Path::to_path_buf))
+        .collect::<Vec<_>>();
+
+    util::fs::write_json(manifest_path, &deployed, JsonStyle::Pretty)
+        .context("failed to write UE4SS manifest")?;
+
+    result
+}
+
+fn copy_ue4ss(
+    profile_dir: &Path,
+    game_dir: &Path,
+    project: &str,
+    deployed: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let binaries_dir = ue4ss_binaries_dir(game_dir, project);
+    let loader_dir = profile_dir.join("ue4ss");
+
+    deploy_file(
+        &loader_dir.join("dwmapi.dll"),
+        &binaries_dir.join("dwmapi.dll"),
+        deployed,
+    )?;
+    deploy_dir(&loader_dir, &binaries_dir.join("ue4ss"), deployed)?;
+
+    let logic_mods = profile_dir.join("LogicMods");
+    if logic_mods.exists() {
+        let logic_mods_dir = game_dir
+            .join(project)
+            .join("Content")
+            .join("Paks")
+            .join("LogicMods");
+
+        deploy_dir(&logic_mods, &logic_mods_dir, deployed)?;
+    }
+
+    Ok(())
+}
+
+fn deploy_dir(src: &Path, dest: &Path, deployed: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in WalkDir::new(src) {
+        let entry = entry?;
+
+        // the log is written by UE4SS itself and collected after the game exits
+        if !entry.file_type().is_file() || entry.file_name() == UE4SS_LOG_NAME {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(src)?;
+        deploy_file(entry.path(), &dest.join(relative), deployed)?;
+    }
+
+    Ok(())
+}
+
+fn deploy_file(src: &Path, dest: &Path, deployed: &mut Vec<PathBuf>) -> Result<()> {
+    if dest.exists() {
+        // anything left at this point wasn't put there by Gale
+        warn!("{} already exists, not replacing it", dest.display());
+        return Ok(());
+    }
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).fs_context("creating directory", parent)?;
+    }
+
+    fs::copy(src, dest).fs_context("copying file", dest)?;
+    deployed.push(dest.to_path_buf());
+
+    Ok(())
+}
+
+/// Removes the files listed in the manifest, along with directories that are left empty.
+fn remove_deployed(game_dir: &Path, manifest_path: &Path) -> Result<()> {
+    if !manifest_path.exists() {
+        return Ok(());
+    }
+
+    let deployed: Vec<PathBuf> =
+        util::fs::read_json(manifest_path).context("failed to read UE4SS manifest")?;
+
+    for relative in &deployed {
+        ensure!(
+            util::fs::is_enclosed(relative),
+            "invalid path in UE4SS manifest: {}",
+            relative.display()
+        );
+
+        let path = game_dir.join(relative);
+        match fs::remove_file(&path) {
+            Ok(()) => (),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err).fs_context("removing deployed file", &path),
+        }
+
+        // stops at the first directory that still has something in it
+        for dir in path.ancestors().skip(1) {
+            if dir == game_dir || fs::remove_dir(dir).is_err() {
+                break;
+            }
+        }
+    }
+
+    fs::remove_file(manifest_path).fs_context("removing UE4SS manifest", manifest_path)?;
+
+    Ok(())
+}
+
+/// Copies UE4SS's log into the profile once the game exits, since the loader writes
+/// it to the game directory.
+pub fn collect_ue4ss_log(profile_dir: &Path, game_dir: &Path, project: &str, exe_path: &Path) {
+    let Some(exe_name) = exe_path.file_name() else {
+        return;
+    };
+
+    let exe_name = exe_name.to_string_lossy().into_owned();
+    let source = ue4ss_binaries_dir(game_dir, project)
+        .join("ue4ss")
+        .join(UE4SS_LOG_NAME);
+    let target = profile_dir.join("ue4ss").join(UE4SS_LOG_NAME);
+
+    thread::spawn(move || {
+        if !hooks::wait_for_exit(&exe_name) {
+            debug!("game didn't start, not collecting UE4SS log");
+            return;
+        }
+
+        if let Err(err) = fs::copy(&source, &target) {
+            warn!("failed to copy UE4SS log to profile: {}", err);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn ue4ss_deploy_only_removes_its_own_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let profile_dir = temp.path().join("profile");
+        let game_dir = temp.path().join("game");
+        let manifest_path = temp.path().join(UE4SS_MANIFEST_NAME);
+
+        write(&profile_dir.join("ue4ss/dwmapi.dll"), "proxy");
+        write(&profile_dir.join("ue4ss/Mods/Example/main.lua"), "mod");
+        write(&profile_dir.join("LogicMods/Example.pak"), "pak");
+
+        let binaries_dir = game_dir.join("Game/Binaries/Win64");
+        let logic_mods_dir = game_dir.join("Game/Content/Paks/LogicMods");
+        write(&logic_mods_dir.join("Shipped.pak"), "shipped");
+        write(&binaries_dir.join("ue4ss/Mods/Manual/main.lua"), "manual");
+
+        deploy_ue4ss(&profile_dir, &game_dir, "Game", &manifest_path).unwrap();
+
+        assert!(binaries_dir.join("dwmapi.dll").exists());
+        assert!(binaries_dir.join("ue4ss/Mods/Example/main.lua").exists());
+        assert!(logic_mods_dir.join("Example.pak").exists());
+
+        fs::remove_dir_all(profile_dir.join("ue4ss")).unwrap();
+        deploy_ue4ss(&profile_dir, &game_dir, "Game", &manifest_path).unwrap();
+
+        assert!(!binaries_dir.join("dwmapi.dll").exists());
+        assert!(!binaries_dir.join("ue4ss/Mods/Example").exists());
+        assert!(!logic_mods_dir.join("Example.pak").exists());
+
+        assert!(logic_mods_dir.join("Shipped.pak").exists());
+        assert!(binaries_dir.join("ue4ss/Mods/Manual/main.lua").exists());
+    }
+
+    #[test]
+    fn ue4ss_deploy_keeps_existing_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let profile_dir = temp.path().join("profile");
+        let game_dir = temp.path().join("game");
+        let manifest_path = temp.path().join(UE4SS_MANIFEST_NAME);
+
+        write(&profile_dir.join("ue4ss/dwmapi.dll"), "proxy");
+
+        let proxy = game_dir.join("Game/Binaries/Win64/dwmapi.dll");
+        write(&proxy, "user's own");
+
+        deploy_ue4ss(&profile_dir, &game_dir, "Game", &manifest_path).unwrap();
+        deploy_ue4ss(&profile_dir, &game_dir, "Game", &manifest_path).unwrap();
+
+        assert_eq!(fs::read_to_string(&proxy).unwrap(), "user's own");
+    }
+}
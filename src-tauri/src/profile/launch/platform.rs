@@ -55,7 +55,7 @@ fn create_steam_command(game_dir: &Path, game: Game, prefs: &Prefs) -> Result<Co
 }
 
 #[cfg(target_os = "windows")]
-fn create_base_steam_command() -> Result<Command> {
+pub fn create_base_steam_command() -> Result<Command> {
     let path = match read_steam_registry() {
         Ok(install_dir) => {
             let exe_path = install_dir.join("steam.exe");
@@ -82,7 +82,7 @@ fn create_base_steam_command() -> Result<Command> {
 }
 
 #[cfg(target_os = "linux")]
-fn create_base_steam_command() -> Result<Command> {
+pub fn create_base_steam_command() -> Result<Command> {
     use tracing::debug;
 
     if let Ok(path) = which::which("steam") {
@@ -167,11 +167,44 @@ fn steam_game_dir(game: Game) -> Result<PathBuf> {
     };
 
     let steam_dir = steamlocate::SteamDir::locate().context("failed to find steam install")?;
-    let (app, lib) = steam_dir
+
+    match steam_dir.find_app(steam.id)? {
+        Some((app, lib)) => Ok(lib.resolve_app_dir(&app)),
+        None => super::deck::is_steam_deck()
+            .then(|| super::deck::find_removable_app_dir(steam.id))
+            .flatten()
+            .ok_or_eyre("could not find app in steam library, is the game not installed?"),
+    }
+}
+
+/// Reads the build id of the installed game from its Steam app manifest.
+pub fn steam_build_id(game: Game) -> Result<Option<u64>> {
+    let Some(steam) = &game.platforms.steam else {
+        return Ok(None);
+    };
+
+    let steam_dir = steamlocate::SteamDir::locate().context("failed to find steam install")?;
+
+    Ok(steam_dir
         .find_app(steam.id)?
-        .ok_or_eyre("could not find app in steam library, is the game not installed?")?;
+        .and_then(|(app, _)| app.build_id))
+}
+
+/// Finds the Steam app manifest of the installed game, which Steam rewrites
+/// whenever it updates the game.
+pub fn steam_manifest_path(game: Game) -> Result<Option<PathBuf>> {
+    let Some(steam) = &game.platforms.steam else {
+        return Ok(None);
+    };
+
+    let steam_dir = steamlocate::SteamDir::locate().context("failed to find steam install")?;
 
-    Ok(lib.resolve_app_dir(&app))
+    Ok(steam_dir.find_app(steam.id)?.map(|(_, library)| {
+        library
+            .path()
+            .join("steamapps")
+            .join(format!("appmanifest_{}.acf", steam.id))
+    }))
 }
 
 #[cfg(windows)]
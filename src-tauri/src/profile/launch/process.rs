@@ -0,0 +1,251 @@
+//! OS-level options for the game process: priority, CPU affinity and, on Windows,
+//! the preferred GPU.
+//!
+//! Priority and affinity need a handle to the game process, so they only take effect
+//! when the game is launched directly. The GPU preference is a per-executable registry
+//! entry and works regardless of the launch mode. The executables Gale has written one
+//! for are remembered in [`Prefs::gpu_preference_exes`].
+//!
+//! [`Prefs::gpu_preference_exes`]: crate::prefs::Prefs::gpu_preference_exes
+
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use eyre::{ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::LaunchMode;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ProcessOptions {
+    pub priority: Option<ProcessPriority>,
+    /// Indices of the logical processors the game may run on. Empty means all of them.
+    pub cpu_affinity: Vec<u32>,
+    pub gpu_preference: Option<GpuPreference>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessPriority {
+    Idle,
+    BelowNormal,
+    Normal,
+    AboveNormal,
+    High,
+}
+
+impl ProcessPriority {
+    #[cfg(windows)]
+    fn creation_flag(self) -> u32 {
+        match self {
+            Self::Idle => 0x0040,
+            Self::BelowNormal => 0x4000,
+            Self::Normal => 0x0020,
+            Self::AboveNormal => 0x8000,
+            Self::High => 0x0080,
+        }
+    }
+
+    #[cfg(unix)]
+    fn niceness(self) -> i32 {
+        match self {
+            Self::Idle => 19,
+            Self::BelowNormal => 10,
+            Self::Normal => 0,
+            Self::AboveNormal => -5,
+            Self::High => -10,
+        }
+    }
+}
+
+/// Values of the `GpuPreference` registry entry used by Windows' graphics settings.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GpuPreference {
+    Default = 0,
+    PowerSaving = 1,
+    HighPerformance = 2,
+}
+
+impl ProcessOptions {
+    pub fn validate(&self) -> Result<()> {
+        let cpus = std::thread::available_parallelism().map_or(64, |n| n.get() as u32);
+
+        for &cpu in &self.cpu_affinity {
+            ensure!(
+                cpu < cpus.min(64),
+                "processor {} does not exist (this machine has {})",
+                cpu,
+                cpus
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Sets up the command before the game is started.
+    #[allow(unused_variables)] // only used on windows
+    pub fn prepare(&self, command: &mut Command, mode: &LaunchMode) {
+        #[cfg(windows)]
+        if let (Some(priority), LaunchMode::Direct { .. }) = (self.priority, mode) {
+            use std::os::windows::process::CommandExt;
+
+            command.creation_flags(priority.creation_flag());
+        }
+    }
+
+    /// Sets the GPU preference of the game's executable, or removes it once the option
+    /// is turned off.
+    ///
+    /// `written` holds the executables Gale has set a preference for. Only those are
+    /// ever cleared, so a preference the user chose in Windows' graphics settings is
+    /// left alone.
+    #[allow(unused_variables)] // only used on windows
+    pub fn apply_gpu_preference(&self, exe_path: &Path, written: &mut Vec<PathBuf>) {
+        #[cfg(windows)]
+        {
+            let is_written = written.iter().any(|path| path == exe_path);
+
+            match self.gpu_preference {
+                Some(preference) => match set_gpu_preference(exe_path, preference) {
+                    Ok(()) if !is_written => written.push(exe_path.to_path_buf()),
+                    Ok(()) => (),
+                    Err(err) => warn!("failed to set GPU preference: {:#}", err),
+                },
+                None if is_written => match clear_gpu_preference(exe_path) {
+                    Ok(()) => written.retain(|path| path != exe_path),
+                    Err(err) => warn!("failed to clear GPU preference: {:#}", err),
+                },
+                None => (),
+            }
+        }
+
+        #[cfg(not(windows))]
+        if self.gpu_preference.is_some() {
+            warn!("GPU preference is only supported on Windows");
+        }
+    }
+
+    /// Applies the options to a directly launched game process.
+    pub fn apply(&self, pid: u32) {
+        #[cfg(unix)]
+        if let Some(priority) = self.priority {
+            let niceness = priority.niceness().to_string();
+            run("renice", &["-n", &niceness, "-p", &pid.to_string()])
+                .unwrap_or_else(|err| warn!("failed to set process priority: {:#}", err));
+        }
+
+        if !self.cpu_affinity.is_empty() {
+            set_affinity(pid, &self.cpu_affinity)
+                .unwrap_or_else(|err| warn!("failed to set CPU affinity: {:#}", err));
+        }
+    }
+}
+
+#[cfg(windows)]
+fn set_gpu_preference(exe_path: &Path, preference: GpuPreference) -> Result<()> {
+    use tracing::info;
+    use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+
+    let (key, _) = RegKey::predef(HKEY_CURRENT_USER)
+        .create_subkey(r"Software\Microsoft\DirectX\UserGpuPreferences")?;
+
+    let value = format!("GpuPreference={};", preference as u8);
+    key.set_value(exe_path.as_os_str(), &value)?;
+
+    info!(
+        "set GPU preference of {} to {:?}",
+        exe_path.display(),
+        preference
+    );
+
+    Ok(())
+}
+
+#[cfg(windows)]
+fn clear_gpu_preference(exe_path: &Path) -> Result<()> {
+    use std::io;
+
+    use tracing::info;
+    use winreg::{
+        enums::{HKEY_CURRENT_USER, KEY_SET_VALUE},
+        RegKey,
+    };
+
+    let key = match RegKey::predef(HKEY_CURRENT_USER).open_subkey_with_flags(
+        r"Software\Microsoft\DirectX\UserGpuPreferences",
+        KEY_SET_VALUE,
+    ) {
+        Ok(key) => key,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+
+    match key.delete_value(exe_path.as_os_str()) {
+        Ok(()) => info!("cleared GPU preference of {}", exe_path.display()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+        Err(err) => return Err(err.into()),
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn set_affinity(pid: u32, cpus: &[u32]) -> Result<()> {
+    use itertools::Itertools;
+    use tracing::info;
+
+    let list = cpus.iter().join(",");
+    run("taskset", &["-p", "-c", &list, &pid.to_string()])?;
+
+    info!("set CPU affinity of process {} to {}", pid, list);
+    Ok(())
+}
+
+#[cfg(windows)]
+fn set_affinity(pid: u32, cpus: &[u32]) -> Result<()> {
+    use tracing::info;
+
+    let mask = cpus.iter().fold(0u64, |mask, cpu| mask | (1 << cpu));
+    let script = format!("(Get-Process -Id {pid}).ProcessorAffinity = {mask}");
+    run("powershell", &["-NoProfile", "-Command", &script])?;
+
+    info!("set CPU affinity of process {} to {:#x}", pid, mask);
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn set_affinity(_pid: u32, _cpus: &[u32]) -> Result<()> {
+    eyre::bail!("CPU affinity is not supported on this platform")
+}
+
+#[cfg_attr(not(any(unix, windows)), allow(dead_code))]
+fn run(program: &str, args: &[&str]) -> Result<()> {
+    let mut command = Command::new(program);
+    command.args(args);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        const NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(NO_WINDOW);
+    }
+
+    let Output { status, stderr, .. } = command
+        .output()
+        .with_context(|| format!("failed to run {program}"))?;
+
+    ensure!(
+        status.success(),
+        "{} failed: {}",
+        program,
+        String::from_utf8_lossy(&stderr).trim()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! Detects loader files that went missing from the game directory, which is what
+//! Steam's "verify integrity of game files" does to proxy DLLs.
+
+use eyre::Result;
+use tracing::info;
+
+use super::{locate_game_dir, ManagedGame};
+use crate::prefs::Prefs;
+
+impl ManagedGame {
+    /// Finds the active profile's loader files that are missing from the game directory.
+    ///
+    /// This only reports anything if the profile was deployed before, which is the case
+    /// when some of its files are still there but the proxy DLL is not.
+    pub fn missing_loader_files(&self, prefs: &Prefs) -> Result<Vec<String>> {
+        let Some(proxy_dll) = self.game.mod_loader.proxy_dll_file() else {
+            return Ok(Vec::new());
+        };
+
+        // an unknown game directory is reported elsewhere, no need to fail here too
+        let Ok(game_dir) = locate_game_dir(self.game, prefs) else {
+            return Ok(Vec::new());
+        };
+
+        let (present, missing): (Vec<_>, Vec<_>) = self
+            .required_files()?
+            .into_iter()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .partition(|name| game_dir.join(name).exists());
+
+        let broken = !present.is_empty() && missing.contains(&proxy_dll);
+
+        Ok(if broken { missing } else { Vec::new() })
+    }
+
+    /// Copies the active profile's loader files back into the game directory.
+    pub fn repair_loader_files(&self, prefs: &Prefs) -> Result<()> {
+        let game_dir = locate_game_dir(self.game, prefs)?;
+
+        info!("redeploying loader files to {}", game_dir.display());
+        self.copy_required_files(&game_dir, prefs)
+    }
+}
@@ -0,0 +1,219 @@
+//! Optionally records the files that mods create while the game is running, like
+//! generated configs and save data, so that they can be removed along with the mod.
+//!
+//! The profile's config and mod directories are listed before launching and again
+//! once the game exits. New files are attributed to the mod whose directory they're
+//! in, or failing that, the mod whose name appears in their path.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    thread,
+};
+
+use eyre::{Context, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{debug, info, warn};
+use walkdir::WalkDir;
+
+use super::hooks;
+use crate::{
+    profile::Profile,
+    state::ManagerExt,
+    util::{self, fs::JsonStyle},
+};
+
+/// Names shorter than this match too many unrelated paths.
+const MIN_NAME_LEN: usize = 4;
+
+/// The recorded files of each mod, by full name and relative to the profile.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RuntimeFiles {
+    files: HashMap<String, Vec<PathBuf>>,
+}
+
+fn state_path(profile: &Profile) -> PathBuf {
+    profile.path.join("_state").join("runtime_files.json")
+}
+
+fn read_state(profile: &Profile) -> RuntimeFiles {
+    util::fs::read_json(state_path(profile)).unwrap_or_default()
+}
+
+fn write_state(state: &RuntimeFiles, profile: &Profile) -> Result<()> {
+    let path = state_path(profile);
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    util::fs::write_json(path, state, JsonStyle::Pretty)
+}
+
+/// The files in a profile before the game was launched.
+pub struct Snapshot {
+    profile_id: i64,
+    files: HashSet<PathBuf>,
+}
+
+impl Snapshot {
+    pub fn take(profile: &Profile) -> Self {
+        Self {
+            profile_id: profile.id,
+            files: scan(profile),
+        }
+    }
+
+    /// Waits in the background for the game to exit, then records the files
+    /// that appeared in the meantime.
+    pub fn watch(self, exe_path: PathBuf, app: &AppHandle) {
+        let Some(exe_name) = exe_path.file_name() else {
+            warn!("invalid game executable path, not tracking runtime files");
+            return;
+        };
+
+        let exe_name = exe_name.to_string_lossy().into_owned();
+        let app = app.clone();
+
+        thread::spawn(move || {
+            if !hooks::wait_for_exit(&exe_name) {
+                return;
+            }
+
+            let manager = app.lock_manager();
+            let Ok((_, profile)) = manager.profile_by_id(self.profile_id) else {
+                return;
+            };
+
+            if let Err(err) = self.record(profile) {
+                warn!("failed to record runtime files: {:#}", err);
+            }
+        });
+    }
+
+    fn record(self, profile: &Profile) -> Result<()> {
+        let new_files = scan(profile)
+            .into_iter()
+            .filter(|file| !self.files.contains(file))
+            .collect_vec();
+
+        if new_files.is_empty() {
+            return Ok(());
+        }
+
+        let mut state = read_state(profile);
+        let mut count = 0;
+
+        for file in new_files {
+            let Some(owner) = find_owner(&file, profile) else {
+                debug!("no mod found for runtime file {}", file.display());
+                continue;
+            };
+
+            let files = state.files.entry(owner).or_default();
+            if !files.contains(&file) {
+                files.push(file);
+                count += 1;
+            }
+        }
+
+        write_state(&state, profile)?;
+
+        info!("recorded {} runtime files in {}", count, profile.name);
+
+        Ok(())
+    }
+}
+
+/// Lists the files in the profile's config directories and the directories of its mods.
+fn scan(profile: &Profile) -> HashSet<PathBuf> {
+    let mod_loader = &profile.game.mod_loader;
+
+    let mod_dirs = profile.mods.iter().filter_map(|profile_mod| {
+        let name = profile_mod.full_name();
+        profile.game.installer_for(&name).mod_dir(&name, profile)
+    });
+
+    let dirs = mod_loader
+        .mod_config_dirs()
+        .iter()
+        .map(|dir| profile.path.join(dir))
+        .chain(mod_dirs)
+        .unique();
+
+    dirs.flat_map(|dir| WalkDir::new(dir).into_iter().filter_map(Result::ok))
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            entry
+                .path()
+                .strip_prefix(&profile.path)
+                .ok()
+                .map(Path::to_path_buf)
+        })
+        .filter(|path| !path.starts_with("_state"))
+        .collect()
+}
+
+fn find_owner(file: &Path, profile: &Profile) -> Option<String> {
+    let by_dir = profile.mods.iter().find(|profile_mod| {
+        let name = profile_mod.full_name();
+        profile
+            .game
+            .installer_for(&name)
+            .mod_dir(&name, profile)
+            .is_some_and(|dir| profile.path.join(file).starts_with(dir))
+    });
+
+    if let Some(profile_mod) = by_dir {
+        return Some(profile_mod.full_name().into_owned());
+    }
+
+    // config files are often named after the mod or its plugin GUID,
+    // like `com.author.MyMod.cfg`
+    let path = normalize(&file.to_string_lossy());
+
+    profile
+        .mods
+        .iter()
+        .map(|profile_mod| (profile_mod, normalize(profile_mod.ident().name())))
+        .filter(|(_, name)| name.len() >= MIN_NAME_LEN && path.contains(name.as_str()))
+        .max_by_key(|(_, name)| name.len())
+        .map(|(profile_mod, _)| profile_mod.full_name().into_owned())
+}
+
+fn normalize(str: &str) -> String {
+    str.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|char| char.to_ascii_lowercase())
+        .collect()
+}
+
+impl Profile {
+    /// Moves the files `package_name` created at runtime to the recycle bin and
+    /// forgets about them. Returns how many files were removed.
+    pub fn trash_runtime_files(&self, package_name: &str) -> Result<usize> {
+        let mut state = read_state(self);
+        let Some(files) = state.files.remove(package_name) else {
+            return Ok(0);
+        };
+
+        let existing = files
+            .iter()
+            .map(|file| self.path.join(file))
+            .filter(|path| path.exists())
+            .collect_vec();
+
+        if !existing.is_empty() {
+            trash::delete_all(&existing).context("failed to move files to recycle bin")?;
+        }
+
+        write_state(&state, self)?;
+
+        info!(
+            "removed {} runtime files of {}",
+            existing.len(),
+            package_name
+        );
+
+        Ok(existing.len())
+    }
+}
@@ -0,0 +1,256 @@
+//! A quick check that the mod loader actually gets into the game, before users
+//! start looking for problems in individual mods.
+//!
+//! Loaders that run Lua scripts get a small marker mod, which writes a heartbeat file
+//! to the profile as soon as the loader runs it. Loaders that only take compiled
+//! plugins write their own log on startup instead, so for those a log written after
+//! the launch counts as the heartbeat. The test fails if the game exits before there
+//! is one, or nothing happens within [`TIMEOUT`].
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use eyre::{ensure, Context, Result};
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+use super::{exe_path, is_process_running, locate_game_dir, ManagedGame};
+use crate::{game::mod_loader::ModLoaderKind, prefs::Prefs};
+
+const TIMEOUT: Duration = Duration::from_secs(180);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const HEARTBEAT_FILE_NAME: &str = "gale_self_test";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum SelfTestResult {
+    /// The loader ran.
+    Passed { evidence: Evidence },
+    /// The game closed before the loader showed any sign of life.
+    GameExited,
+    /// The loader never showed up, or the game didn't start at all.
+    TimedOut,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Evidence {
+    /// The marker mod wrote its heartbeat.
+    Marker,
+    /// The loader wrote its log.
+    Log,
+}
+
+/// Files of a marker mod, relative to the profile.
+struct Marker {
+    /// Removed again once the test is over.
+    dir: &'static str,
+    files: Vec<(&'static str, String)>,
+}
+
+fn marker(kind: &ModLoaderKind, heartbeat: &Path) -> Option<Marker> {
+    // long brackets, so that backslashes in windows paths aren't escapes
+    let script = format!(
+        "local file = io.open([[{}]], \"w\")\nfile:write(\"ok\")\nfile:close()\n",
+        heartbeat.display()
+    );
+
+    match kind {
+        ModLoaderKind::UE4SS { .. } => Some(Marker {
+            dir: "ue4ss/Mods/GaleSelfTest",
+            files: vec![("Scripts/main.lua", script), ("enabled.txt", String::new())],
+        }),
+        ModLoaderKind::ReturnOfModding { .. } => {
+            let manifest = json!({
+                "name": "SelfTest",
+                "version_number": "1.0.0",
+                "website_url": "",
+                "description": "Checks that Gale can load mods",
+                "dependencies": [],
+            });
+
+            Some(Marker {
+                dir: "ReturnOfModding/plugins/Gale-SelfTest",
+                files: vec![
+                    ("main.lua", script),
+                    ("manifest.json", manifest.to_string()),
+                ],
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Launches the game to check that the loader is injected. The result is sent
+/// as a `self_test_result` event once known.
+pub async fn test_injection(app: &AppHandle) -> Result<()> {
+    let test = {
+        let prefs = app.lock_prefs();
+        let manager = app.lock_manager();
+
+        manager.active_game().prepare_test(&prefs)?
+    };
+
+    if let Err(err) = super::launch(app).await {
+        test.clean_up();
+        return Err(err);
+    }
+
+    let app = app.clone();
+    thread::spawn(move || {
+        let result = test.watch();
+        info!("injection test finished: {:?}", result);
+
+        test.clean_up();
+        app.emit("self_test_result", result).ok();
+    });
+
+    Ok(())
+}
+
+struct SelfTest {
+    heartbeat: PathBuf,
+    log: Option<PathBuf>,
+    marker_dir: Option<PathBuf>,
+    exe_name: Option<String>,
+    started: SystemTime,
+}
+
+impl ManagedGame {
+    /// Adds the marker mod, if the loader takes one, and notes what to watch for.
+    fn prepare_test(&self, prefs: &Prefs) -> Result<SelfTest> {
+        let profile = self.active_profile();
+        let mod_loader = &self.game.mod_loader;
+
+        let heartbeat = profile.path.join(HEARTBEAT_FILE_NAME);
+        fs::remove_file(&heartbeat).ok();
+
+        let marker = marker(&mod_loader.kind, &heartbeat);
+        // the marker is more reliable, since old logs might be touched by other things
+        let log = match marker {
+            Some(_) => None,
+            None => mod_loader.log_path().map(|path| profile.path.join(path)),
+        };
+
+        ensure!(
+            marker.is_some() || log.is_some(),
+            "{} can't be tested yet",
+            mod_loader.as_str()
+        );
+
+        let marker_dir = match marker {
+            Some(marker) => {
+                let dir = profile.path.join(marker.dir);
+                write_marker(&dir, &marker.files).context("failed to add marker mod")?;
+                Some(dir)
+            }
+            None => None,
+        };
+
+        // without it, the test just waits until the timeout if the game exits early
+        let exe_name = locate_game_dir(self.game, prefs)
+            .and_then(|game_dir| exe_path(&game_dir))
+            .ok()
+            .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()));
+
+        info!(
+            "testing {} injection into {}",
+            mod_loader.as_str(),
+            self.game.slug
+        );
+
+        Ok(SelfTest {
+            heartbeat,
+            log,
+            marker_dir,
+            exe_name,
+            started: SystemTime::now(),
+        })
+    }
+}
+
+impl SelfTest {
+    fn watch(&self) -> SelfTestResult {
+        watch(
+            &self.heartbeat,
+            self.log.as_deref(),
+            self.started,
+            self.exe_name.as_deref(),
+        )
+    }
+
+    fn clean_up(&self) {
+        clean_up(self.marker_dir.as_deref(), &self.heartbeat);
+    }
+}
+
+fn write_marker(dir: &Path, files: &[(&str, String)]) -> Result<()> {
+    for (name, contents) in files {
+        let path = dir.join(name);
+
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(&path, contents)?;
+    }
+
+    Ok(())
+}
+
+fn clean_up(marker_dir: Option<&Path>, heartbeat: &Path) {
+    if let Some(dir) = marker_dir {
+        if let Err(err) = fs::remove_dir_all(dir) {
+            warn!("failed to remove marker mod: {}", err);
+        }
+    }
+
+    fs::remove_file(heartbeat).ok();
+}
+
+fn watch(
+    heartbeat: &Path,
+    log: Option<&Path>,
+    started: SystemTime,
+    exe_name: Option<&str>,
+) -> SelfTestResult {
+    let deadline = Instant::now() + TIMEOUT;
+    let mut seen_running = false;
+
+    while Instant::now() < deadline {
+        if heartbeat.exists() {
+            return SelfTestResult::Passed {
+                evidence: Evidence::Marker,
+            };
+        }
+
+        if log.is_some_and(|log| modified_since(log, started)) {
+            return SelfTestResult::Passed {
+                evidence: Evidence::Log,
+            };
+        }
+
+        if let Some(exe_name) = exe_name {
+            let running = is_process_running(exe_name);
+
+            if seen_running && !running {
+                return SelfTestResult::GameExited;
+            }
+
+            seen_running |= running;
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    SelfTestResult::TimedOut
+}
+
+fn modified_since(path: &Path, time: SystemTime) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| modified > time)
+}
@@ -0,0 +1,153 @@
+//! Stages changes to the game directory in a separate directory and swaps them in
+//! all at once, for games and launchers that lock files or scan the directory
+//! while it is being written to.
+//!
+//! The staging directory is a hidden sibling of the game directory, so that it's on
+//! the same drive and every entry can be moved in with a single rename.
+
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{bail, ensure, Context, Result};
+use tracing::{info, instrument, warn};
+
+use super::{exe_path, is_process_running};
+use crate::{
+    game::Game,
+    prefs::{access, Prefs},
+    util::{
+        self,
+        error::IoResultExt,
+        fs::{Overwrite, UseLinks},
+    },
+};
+
+const BACKUP_DIR: &str = ".replaced";
+
+/// Stages `entries` and swaps them into `game_dir`. Fails if the game is running,
+/// since its files can't be replaced then.
+#[instrument(name = "deploy_staged", skip_all)]
+pub fn deploy(entries: &[fs::DirEntry], game: Game, game_dir: &Path, prefs: &Prefs) -> Result<()> {
+    let running = exe_path(game_dir).is_ok_and(|exe| {
+        exe.file_name()
+            .is_some_and(|name| is_process_running(&name.to_string_lossy()))
+    });
+
+    ensure!(
+        !running,
+        "{} is already running, close it and try again",
+        game.name
+    );
+
+    access::check(game_dir, game, prefs)?;
+    let staging_dir = staging_dir(game_dir)?;
+
+    stage(entries, &staging_dir).context("failed to stage files")?;
+    swap(&staging_dir, game_dir).context("failed to swap in staged files")
+}
+
+/// A hidden directory next to `game_dir`, like `.Lethal Company.gale-staging`.
+fn staging_dir(game_dir: &Path) -> Result<PathBuf> {
+    let (Some(parent), Some(name)) = (game_dir.parent(), game_dir.file_name()) else {
+        bail!("game directory {} has no parent", game_dir.display());
+    };
+
+    let mut staging_name = OsString::from(".");
+    staging_name.push(name);
+    staging_name.push(".gale-staging");
+
+    Ok(parent.join(staging_name))
+}
+
+fn stage(entries: &[fs::DirEntry], staging_dir: &Path) -> Result<()> {
+    if staging_dir.exists() {
+        fs::remove_dir_all(staging_dir).fs_context("clearing staging directory", staging_dir)?;
+    }
+
+    fs::create_dir_all(staging_dir).fs_context("creating staging directory", staging_dir)?;
+
+    for entry in entries {
+        let target = staging_dir.join(entry.file_name());
+
+        if entry.file_type()?.is_file() {
+            fs::copy(entry.path(), &target).fs_context("staging file", &target)?;
+        } else {
+            util::fs::copy_dir(entry.path(), &target, Overwrite::Yes, UseLinks::No)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves everything in `staging_dir` into `game_dir`, replacing existing entries.
+///
+/// The replaced entries are kept until every move has succeeded, so that a
+/// failure halfway through can be rolled back.
+fn swap(staging_dir: &Path, game_dir: &Path) -> Result<()> {
+    let backup_dir = staging_dir.join(BACKUP_DIR);
+    fs::create_dir_all(&backup_dir).fs_context("creating backup directory", &backup_dir)?;
+
+    let names = staging_dir
+        .read_dir()
+        .fs_context("reading staging directory", staging_dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name())
+        .filter(|name| name != BACKUP_DIR)
+        .collect::<Vec<_>>();
+
+    let mut swapped = Vec::new();
+
+    for name in &names {
+        let target = game_dir.join(name);
+        let backup = backup_dir.join(name);
+
+        let replaced = target.exists();
+        if replaced {
+            if let Err(err) = fs::rename(&target, &backup) {
+                rollback(&swapped, staging_dir, game_dir);
+                return Err(err).fs_context("moving aside file", &target);
+            }
+        }
+
+        if let Err(err) = fs::rename(staging_dir.join(name), &target) {
+            if replaced {
+                fs::rename(backup, &target).ok();
+            }
+
+            rollback(&swapped, staging_dir, game_dir);
+            return Err(err).fs_context("swapping in file", &target);
+        }
+
+        swapped.push((name.clone(), replaced));
+    }
+
+    info!(
+        "swapped {} staged entries into {}",
+        swapped.len(),
+        game_dir.display()
+    );
+
+    fs::remove_dir_all(staging_dir).fs_context("removing staging directory", staging_dir)?;
+
+    Ok(())
+}
+
+fn rollback(swapped: &[(OsString, bool)], staging_dir: &Path, game_dir: &Path) {
+    let backup_dir = staging_dir.join(BACKUP_DIR);
+
+    for (name, replaced) in swapped.iter().rev() {
+        let target = game_dir.join(name);
+
+        let mut result = fs::rename(&target, staging_dir.join(name));
+        if *replaced {
+            result = result.and_then(|_| fs::rename(backup_dir.join(name), &target));
+        }
+
+        if let Err(err) = result {
+            warn!("failed to roll back {}: {}", target.display(), err);
+        }
+    }
+}
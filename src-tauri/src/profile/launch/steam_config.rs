@@ -0,0 +1,636 @@
+//! Writes launch options into Steam's `localconfig.vdf`, so that launching the game
+//! from Steam itself also loads the active profile. Only the config of the account
+//! Steam logs into is edited, the other accounts on the machine are left alone.
+//!
+//! Steam keeps the file in memory and overwrites it when it exits, so it has to be
+//! shut down before the file is edited. The next launch then starts it back up.
+//!
+//! The launch options the user had before Gale first replaced them are kept next
+//! to the config, and put back by [`restore_launch_options`].
+
+use std::{
+    collections::{hash_map, HashMap},
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+
+use eyre::{bail, ensure, Context, OptionExt, Result};
+use itertools::Itertools;
+use tauri::AppHandle;
+use tracing::{debug, info, warn};
+
+use crate::{
+    prefs::access,
+    util::{
+        self,
+        error::IoResultExt,
+        fs::{JsonStyle, PathExt},
+    },
+};
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+const BACKUP_EXTENSION: &str = "vdf.gale-backup";
+
+/// Holds the user's own launch options of each app Gale has written to, by app id.
+const ORIGINALS_FILE_NAME: &str = "gale_launch_options.json";
+
+/// Formats arguments the way they are entered in Steam's launch options.
+pub fn quote_args<'a>(args: impl IntoIterator<Item = &'a OsStr>) -> String {
+    args.into_iter()
+        .map(|arg| format!("\"{}\"", arg.to_string_lossy()))
+        .join(" ")
+}
+
+/// Splits off the arguments after `-applaunch <id>` in `command`, which are meant to
+/// go into Steam's config, and returns a command that launches the game without them.
+pub fn split_injected(command: Command) -> Result<(Command, String)> {
+    let args = command.get_args().collect_vec();
+
+    let split = args
+        .iter()
+        .position(|arg| *arg == "-applaunch")
+        .map(|index| index + 2)
+        .filter(|&index| index <= args.len())
+        .ok_or_eyre("launch command does not go through Steam")?;
+
+    let (base, injected) = args.split_at(split);
+
+    let mut new_command = Command::new(command.get_program());
+    new_command.args(base);
+
+    Ok((new_command, quote_args(injected.iter().copied())))
+}
+
+/// Sets the launch options of `app_id` for the Steam account that was logged in last,
+/// once the user has allowed Gale to edit its config.
+pub async fn write_launch_options(app_id: u32, options: String, app: &AppHandle) -> Result<()> {
+    edit_launch_options(app_id, Some(options), app).await
+}
+
+/// Puts back the launch options `app_id` had before Gale first wrote to them.
+pub async fn restore_launch_options(app_id: u32, app: &AppHandle) -> Result<()> {
+    edit_launch_options(app_id, None, app).await
+}
+
+async fn edit_launch_options(app_id: u32, options: Option<String>, app: &AppHandle) -> Result<()> {
+    let steam_dir = steamlocate::SteamDir::locate().context("failed to find steam install")?;
+    let config_dir = active_config_dir(steam_dir.path())?;
+
+    access::request(&config_dir, "Steam launch options", app).await?;
+
+    // this might wait for Steam to shut down, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || {
+        set_launch_options(&config_dir, app_id, options.as_deref())
+    })
+    .await?
+}
+
+/// Sets the launch options of `app_id` in the config in `config_dir`, or restores the
+/// user's own if `options` is `None`.
+fn set_launch_options(config_dir: &Path, app_id: u32, options: Option<&str>) -> Result<()> {
+    let path = config_dir.join("localconfig.vdf");
+    let originals_path = config_dir.join(ORIGINALS_FILE_NAME);
+
+    let mut originals: HashMap<u32, String> = match originals_path.clone().exists_or_none() {
+        Some(path) => util::fs::read_json(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?,
+        None => HashMap::new(),
+    };
+
+    let text = fs::read_to_string(&path).fs_context("reading Steam config", &path)?;
+    let mut root = parse(&text).with_context(|| format!("failed to parse {}", path.display()))?;
+
+    let entry = app_entry(&mut root, app_id)?;
+    let current = get(entry, "LaunchOptions").unwrap_or_default();
+
+    let options = match options {
+        Some(options) => {
+            if let hash_map::Entry::Vacant(vacant) = originals.entry(app_id) {
+                // saved before the config is touched, so they're never lost
+                vacant.insert(current.clone());
+                util::fs::write_json(&originals_path, &originals, JsonStyle::Pretty)
+                    .context("failed to save the original launch options")?;
+            }
+
+            options.to_owned()
+        }
+        None => match originals.remove(&app_id) {
+            Some(original) => original,
+            None => {
+                debug!("launch options of app {} were never changed", app_id);
+                return Ok(());
+            }
+        },
+    };
+
+    if current != options {
+        set(entry, "LaunchOptions", options);
+
+        if steam_running() {
+            info!("shutting down Steam to edit its config");
+            shutdown_steam()?;
+        }
+
+        // a copy of the config from before Gale first edited it
+        let backup = path.with_extension(BACKUP_EXTENSION);
+        if !backup.exists() {
+            fs::write(&backup, &text).fs_context("backing up Steam config", &backup)?;
+        }
+
+        fs::write(&path, write(&root)).fs_context("writing Steam config", &path)?;
+
+        info!(
+            "set launch options for app {} in {}",
+            app_id,
+            path.display()
+        );
+    } else {
+        debug!("launch options in {} are up to date", path.display());
+    }
+
+    // only forget the original once it's back in place
+    util::fs::write_json(&originals_path, &originals, JsonStyle::Pretty)
+        .context("failed to save the original launch options")?;
+
+    Ok(())
+}
+
+/// Finds the config directory of the account Steam logs into when it starts, which
+/// is the one marked as most recent in `loginusers.vdf`.
+fn active_config_dir(steam_dir: &Path) -> Result<PathBuf> {
+    let users_path = steam_dir.join("config").join("loginusers.vdf");
+    let text = fs::read_to_string(&users_path).fs_context("reading Steam users", &users_path)?;
+    let root = parse(&text).with_context(|| format!("failed to parse {}", users_path.display()))?;
+
+    let steam_id = most_recent_user(&root).ok_or_eyre("no Steam account has logged in yet")?;
+    let dir = steam_dir
+        .join("userdata")
+        .join(account_id(steam_id).to_string())
+        .join("config");
+
+    ensure!(
+        dir.join("localconfig.vdf").exists(),
+        "could not find the Steam config of the active account"
+    );
+
+    Ok(dir)
+}
+
+/// Returns the 64-bit ID of the user marked as most recent, falling back to the
+/// latest login if none of them are.
+fn most_recent_user(root: &Node) -> Option<u64> {
+    let Some(Node::Section(users)) = child(root, "users") else {
+        return None;
+    };
+
+    users
+        .iter()
+        .filter(|entry| entry.condition.is_none())
+        .filter_map(|entry| {
+            let steam_id = entry.key.parse::<u64>().ok()?;
+            let most_recent = get(&entry.node, "MostRecent").is_some_and(|value| value == "1");
+            let timestamp = get(&entry.node, "Timestamp")
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or_default();
+
+            Some((most_recent, timestamp, steam_id))
+        })
+        .max()
+        .map(|(_, _, steam_id)| steam_id)
+}
+
+/// Userdata directories are named after the account ID, which is the lower half of
+/// the 64-bit Steam ID.
+fn account_id(steam_id: u64) -> u32 {
+    (steam_id & 0xFFFF_FFFF) as u32
+}
+
+#[cfg(windows)]
+fn steam_running() -> bool {
+    use std::os::windows::process::CommandExt;
+
+    const NO_WINDOW: u32 = 0x08000000;
+
+    Command::new("tasklist")
+        .args(["/FI", "IMAGENAME eq steam.exe", "/NH"])
+        .creation_flags(NO_WINDOW)
+        .output()
+        .is_ok_and(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .to_lowercase()
+                .contains("steam.exe")
+        })
+}
+
+#[cfg(not(windows))]
+fn steam_running() -> bool {
+    Command::new("pgrep")
+        .args(["-x", "steam"])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+fn shutdown_steam() -> Result<()> {
+    let mut command = base_steam_command()?;
+    command
+        .arg("-shutdown")
+        .spawn()
+        .context("failed to shut down Steam")?;
+
+    let start = Instant::now();
+    while steam_running() {
+        if start.elapsed() > SHUTDOWN_TIMEOUT {
+            bail!("Steam did not shut down in time, close it and try again");
+        }
+
+        thread::sleep(Duration::from_millis(500));
+    }
+
+    // give steam a moment to finish writing its files
+    thread::sleep(Duration::from_secs(1));
+
+    Ok(())
+}
+
+#[cfg(any(windows, target_os = "linux"))]
+fn base_steam_command() -> Result<Command> {
+    super::platform::create_base_steam_command()
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn base_steam_command() -> Result<Command> {
+    bail!("restarting Steam is not supported on this platform")
+}
+
+/// A node in a text VDF (KeyValues) document. Keys keep their original order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Value(String),
+    Section(Vec<Entry>),
+}
+
+/// A key and its node, along with a conditional like `[$WIN32]` if it has one.
+///
+/// Conditionals are kept as they are, so that writing the document back doesn't
+/// make platform-specific entries apply everywhere.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    key: String,
+    node: Node,
+    condition: Option<String>,
+}
+
+impl Entry {
+    fn new(key: &str, node: Node) -> Self {
+        Self {
+            key: key.to_owned(),
+            node,
+            condition: None,
+        }
+    }
+
+    /// Entries behind a conditional only apply on some platforms, so lookups skip them.
+    fn matches(&self, key: &str) -> bool {
+        self.condition.is_none() && self.key.eq_ignore_ascii_case(key)
+    }
+}
+
+fn app_entry(root: &mut Node, app_id: u32) -> Result<&mut Node> {
+    let mut node = root;
+
+    for key in ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"] {
+        node = section(node, key)?;
+    }
+
+    section(node, &app_id.to_string())
+}
+
+/// Gets or creates the section named `key`. Keys are matched case-insensitively,
+/// since Steam isn't consistent about their casing.
+fn section<'a>(node: &'a mut Node, key: &str) -> Result<&'a mut Node> {
+    let Node::Section(children) = node else {
+        bail!("expected a section above {}", key);
+    };
+
+    let index = match children.iter().position(|entry| entry.matches(key)) {
+        Some(index) => index,
+        None => {
+            children.push(Entry::new(key, Node::Section(Vec::new())));
+            children.len() - 1
+        }
+    };
+
+    Ok(&mut children[index].node)
+}
+
+fn child<'a>(node: &'a Node, key: &str) -> Option<&'a Node> {
+    let Node::Section(children) = node else {
+        return None;
+    };
+
+    children
+        .iter()
+        .find(|entry| entry.matches(key))
+        .map(|entry| &entry.node)
+}
+
+fn get(node: &Node, key: &str) -> Option<String> {
+    match child(node, key) {
+        Some(Node::Value(value)) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+fn set(node: &mut Node, key: &str, value: String) {
+    let Node::Section(children) = node else {
+        warn!("tried to set {} on a value", key);
+        return;
+    };
+
+    match children.iter_mut().find(|entry| entry.matches(key)) {
+        Some(entry) => entry.node = Node::Value(value),
+        None => children.push(Entry::new(key, Node::Value(value))),
+    }
+}
+
+fn parse(text: &str) -> Result<Node> {
+    let mut tokens = Tokens {
+        chars: text.chars().peekable(),
+        peeked: None,
+    };
+
+    let children = parse_section(&mut tokens, true)?;
+    Ok(Node::Section(children))
+}
+
+fn parse_section(tokens: &mut Tokens, top_level: bool) -> Result<Vec<Entry>> {
+    let mut children = Vec::new();
+
+    loop {
+        let key = match tokens.next()? {
+            Some(Token::String(key)) => key,
+            Some(Token::Close) if !top_level => return Ok(children),
+            None if top_level => return Ok(children),
+            Some(Token::Open | Token::Close) => bail!("unexpected brace"),
+            Some(Token::Condition(_)) => bail!("unexpected conditional"),
+            None => bail!("unexpected end of file"),
+        };
+
+        // sections have their conditional before the brace, values after the value
+        let mut condition = tokens.condition()?;
+
+        let node = match tokens.next()? {
+            Some(Token::String(value)) => {
+                if condition.is_none() {
+                    condition = tokens.condition()?;
+                }
+
+                Node::Value(value)
+            }
+            Some(Token::Open) => Node::Section(parse_section(tokens, false)?),
+            _ => bail!("expected a value for {}", key),
+        };
+
+        children.push(Entry {
+            key,
+            node,
+            condition,
+        });
+    }
+}
+
+enum Token {
+    String(String),
+    Open,
+    Close,
+    Condition(String),
+}
+
+struct Tokens<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    peeked: Option<Token>,
+}
+
+impl Tokens<'_> {
+    fn next(&mut self) -> Result<Option<Token>> {
+        if let Some(token) = self.peeked.take() {
+            return Ok(Some(token));
+        }
+
+        loop {
+            let Some(c) = self.chars.next() else {
+                return Ok(None);
+            };
+
+            match c {
+                '{' => return Ok(Some(Token::Open)),
+                '}' => return Ok(Some(Token::Close)),
+                '"' => return self.quoted().map(|string| Some(Token::String(string))),
+                '[' => {
+                    return self
+                        .conditional()
+                        .map(|string| Some(Token::Condition(string)))
+                }
+                '/' if self.chars.peek() == Some(&'/') => {
+                    // skip comments until the end of the line
+                    self.chars
+                        .by_ref()
+                        .take_while(|&c| c != '\n')
+                        .for_each(drop);
+                }
+                c if c.is_whitespace() => (),
+                c => bail!("unexpected character {:?}", c),
+            }
+        }
+    }
+
+    /// Takes the next token if it's a conditional.
+    fn condition(&mut self) -> Result<Option<String>> {
+        match self.next()? {
+            Some(Token::Condition(condition)) => Ok(Some(condition)),
+            token => {
+                self.peeked = token;
+                Ok(None)
+            }
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String> {
+        let mut string = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => match self.chars.next() {
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some(c) => string.push(c),
+                    None => bail!("unterminated string"),
+                },
+                Some(c) => string.push(c),
+                None => bail!("unterminated string"),
+            }
+        }
+    }
+
+    fn conditional(&mut self) -> Result<String> {
+        let mut string = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some(']') => return Ok(string),
+                Some('\n') | None => bail!("unterminated conditional"),
+                Some(c) => string.push(c),
+            }
+        }
+    }
+}
+
+fn write(root: &Node) -> String {
+    let mut out = String::new();
+
+    if let Node::Section(children) = root {
+        write_section(children, 0, &mut out);
+    }
+
+    out
+}
+
+fn write_section(children: &[Entry], depth: usize, out: &mut String) {
+    let indent = "\t".repeat(depth);
+
+    for entry in children {
+        let key = escape(&entry.key);
+        let condition = match &entry.condition {
+            Some(condition) => format!("\t\t[{condition}]"),
+            None => String::new(),
+        };
+
+        match &entry.node {
+            Node::Value(value) => {
+                let value = escape(value);
+                out.push_str(&format!("{indent}\"{key}\"\t\t\"{value}\"{condition}\n"));
+            }
+            Node::Section(children) => {
+                out.push_str(&format!("{indent}\"{key}\"{condition}\n{indent}{{\n"));
+                write_section(children, depth + 1, out);
+                out.push_str(&format!("{indent}}}\n"));
+            }
+        }
+    }
+}
+
+fn escape(string: &str) -> String {
+    string
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCAL_CONFIG: &str = include_str!("../../../tests/fixtures/steam/localconfig.vdf");
+    const CONDITIONALS: &str =
+        include_str!("../../../tests/fixtures/steam/localconfig_conditionals.vdf");
+    const LOGIN_USERS: &str = include_str!("../../../tests/fixtures/steam/loginusers.vdf");
+
+    #[test]
+    fn round_trip_is_exact() {
+        for text in [LOCAL_CONFIG, CONDITIONALS, LOGIN_USERS] {
+            let root = parse(text).unwrap();
+            assert_eq!(write(&root), text);
+        }
+    }
+
+    #[test]
+    fn reads_escaped_values() {
+        let mut root = parse(LOCAL_CONFIG).unwrap();
+        let entry = app_entry(&mut root, 1966720).unwrap();
+
+        assert_eq!(
+            get(entry, "launchoptions").as_deref(),
+            Some(r#""C:\Games\BepInEx\run.bat" %command%"#)
+        );
+    }
+
+    #[test]
+    fn set_only_touches_launch_options() {
+        let mut root = parse(LOCAL_CONFIG).unwrap();
+        set(
+            app_entry(&mut root, 632360).unwrap(),
+            "LaunchOptions",
+            "--foo \"bar\"".into(),
+        );
+
+        let text = write(&root);
+        let mut reparsed = parse(&text).unwrap();
+        let entry = app_entry(&mut reparsed, 632360).unwrap();
+
+        assert_eq!(
+            get(entry, "LaunchOptions").as_deref(),
+            Some("--foo \"bar\"")
+        );
+        assert_eq!(get(entry, "LastPlayed").as_deref(), Some("1704067200"));
+        assert_eq!(text.lines().count(), LOCAL_CONFIG.lines().count() + 1);
+    }
+
+    #[test]
+    fn keeps_conditionals() {
+        let mut root = parse(CONDITIONALS).unwrap();
+
+        // the conditional entries don't count as the app's launch options
+        let entry = app_entry(&mut root, 1966720).unwrap();
+        assert_eq!(get(entry, "LaunchOptions"), None);
+        set(entry, "LaunchOptions", "%command% --gale".into());
+
+        let text = write(&root);
+        assert!(text.contains("\"%command% -windowed\"\t\t[$WIN32]"));
+        assert!(text.contains("\"PROTON_LOG=1 %command%\"\t\t[$LINUX]"));
+        assert!(text.contains("\"632360\"\t\t[$OSX]\n"));
+        assert!(text.contains("\"LaunchOptions\"\t\t\"%command% --gale\"\n"));
+
+        assert_eq!(parse(&text).unwrap(), root);
+    }
+
+    #[test]
+    fn restores_original_launch_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("localconfig.vdf");
+        fs::write(&path, LOCAL_CONFIG).unwrap();
+
+        let launch_options = |app_id| {
+            let mut root = parse(&fs::read_to_string(&path).unwrap()).unwrap();
+            get(app_entry(&mut root, app_id).unwrap(), "launchoptions")
+        };
+
+        let original = launch_options(1966720);
+        assert!(original.is_some());
+
+        set_launch_options(dir.path(), 1966720, Some("--first")).unwrap();
+        set_launch_options(dir.path(), 1966720, Some("--second")).unwrap();
+        assert_eq!(launch_options(1966720).as_deref(), Some("--second"));
+
+        set_launch_options(dir.path(), 1966720, None).unwrap();
+        assert_eq!(launch_options(1966720), original);
+
+        // nothing to restore anymore
+        set_launch_options(dir.path(), 1966720, None).unwrap();
+        assert_eq!(launch_options(1966720), original);
+    }
+
+    #[test]
+    fn finds_most_recent_user() {
+        let root = parse(LOGIN_USERS).unwrap();
+        let steam_id = most_recent_user(&root).unwrap();
+
+        assert_eq!(steam_id, 76561198000000002);
+        assert_eq!(account_id(steam_id), 39734274);
+    }
+}
@@ -7,6 +7,7 @@ use std::{
 use chrono::{DateTime, Utc};
 use export::modpack::ModpackArgs;
 use eyre::{anyhow, ensure, eyre, Context, ContextCompat, OptionExt, Result};
+use internment::Intern;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, Manager};
@@ -23,13 +24,22 @@ use crate::{
     util::fs::PathExt,
 };
 
+pub mod bulk;
 pub mod commands;
 pub mod export;
+pub mod health;
 pub mod import;
 pub mod install;
+pub mod lan;
 pub mod launch;
+pub mod prune;
+pub mod report;
+pub mod server;
 pub mod sync;
+pub mod toggles;
 pub mod update;
+pub mod webhook;
+pub mod workshop;
 
 mod actions;
 mod query;
@@ -76,6 +86,12 @@ pub struct Profile {
     pub sync: Option<sync::SyncProfileData>,
     pub custom_args: Vec<String>,
     pub custom_args_enabled: bool,
+    pub workshop_items: Vec<workshop::WorkshopItem>,
+    /// Paths of the files installed by the mod loader package, relative to the profile.
+    pub loader_files: Vec<PathBuf>,
+    pub notes: Option<String>,
+    /// Markdown shown to anyone importing the profile, such as modpack instructions.
+    pub readme: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -86,6 +102,22 @@ pub struct ProfileMod {
     #[serde(default = "Utc::now")]
     pub install_time: DateTime<Utc>,
 
+    /// The package's categories at the time it was installed.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub categories: HashSet<Intern<String>>,
+
+    /// User-defined labels used to organize the mod list.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+
+    /// Kept out of pushed sync profiles and left alone by pulls, for personal
+    /// client-side mods like FPS counters.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub local_only: bool,
+
     #[serde(flatten)]
     pub kind: ProfileModKind,
 }
@@ -114,6 +146,10 @@ impl ProfileMod {
             kind,
             install_time: Utc::now(),
             enabled: true,
+            categories: HashSet::new(),
+            tags: Vec::new(),
+            note: None,
+            local_only: false,
         }
     }
 
@@ -222,7 +258,7 @@ impl ProfileModKind {
 }
 
 impl Profile {
-    fn is_valid_name(name: &str) -> bool {
+    pub(crate) fn is_valid_name(name: &str) -> bool {
         const FORBIDDEN: &[char] = &['\\', '/', ':', '*', '?', '"', '<', '>', '|'];
 
         !name.is_empty()
@@ -254,6 +290,52 @@ impl Profile {
             .ok_or_eyre("mod not found in profile")
     }
 
+    /// Replaces the tags of a mod, dropping blank and duplicate ones.
+    pub fn set_mod_tags(&mut self, uuid: Uuid, tags: Vec<String>) -> Result<()> {
+        let profile_mod = self.get_mod_mut(uuid)?;
+
+        profile_mod.tags = tags
+            .into_iter()
+            .map(|tag| tag.trim().to_owned())
+            .filter(|tag| !tag.is_empty())
+            .unique()
+            .collect();
+
+        Ok(())
+    }
+
+    pub fn set_mod_note(&mut self, uuid: Uuid, note: Option<String>) -> Result<()> {
+        self.get_mod_mut(uuid)?.note = normalize_note(note);
+        Ok(())
+    }
+
+    pub fn set_mod_local_only(&mut self, uuid: Uuid, local_only: bool) -> Result<()> {
+        self.get_mod_mut(uuid)?.local_only = local_only;
+        Ok(())
+    }
+
+    /// Config files linked to the profile's local only mods.
+    ///
+    /// This relies on the linked config being up to date, see [`Profile::refresh_config`].
+    pub fn local_only_config(&self) -> Vec<PathBuf> {
+        self.mods
+            .iter()
+            .filter(|profile_mod| profile_mod.local_only)
+            .filter_map(|profile_mod| self.linked_config.get(&profile_mod.uuid()))
+            .cloned()
+            .collect()
+    }
+
+    /// Collects the distinct tags of all mods in the profile.
+    pub fn tags(&self) -> Vec<String> {
+        self.mods
+            .iter()
+            .flat_map(|profile_mod| profile_mod.tags.iter().cloned())
+            .unique()
+            .sorted()
+            .collect()
+    }
+
     pub fn has_mod(&self, uuid: Uuid) -> bool {
         self.get_mod(uuid).is_ok()
     }
@@ -297,17 +379,30 @@ impl Profile {
     }
 
     fn log_path(&self) -> Result<PathBuf> {
-        let mod_loader = &self.game.mod_loader;
-        let relative = mod_loader
-            .log_path()
-            .ok_or_else(|| eyre!("log file is unsupported for {}", mod_loader.as_str()))?;
-
         self.path
-            .join(relative)
+            .join(self.relative_log_path()?)
             .exists_or_none()
             .ok_or_eyre("no log file found")
     }
 
+    /// The directory the loader writes its log to, which may exist before the log itself.
+    fn log_dir(&self) -> Result<PathBuf> {
+        let path = self.path.join(self.relative_log_path()?);
+
+        path.parent()
+            .map(Path::to_path_buf)
+            .and_then(|dir| dir.exists_or_none())
+            .ok_or_eyre("log folder not found, try launching the game first")
+    }
+
+    fn relative_log_path(&self) -> Result<&str> {
+        let mod_loader = &self.game.mod_loader;
+
+        mod_loader
+            .log_path()
+            .ok_or_else(|| eyre!("log file is unsupported for {}", mod_loader.as_str()))
+    }
+
     fn to_frontend(&self) -> FrontendProfile {
         FrontendProfile {
             id: self.id,
@@ -316,6 +411,9 @@ impl Profile {
             sync: self.sync.clone(),
             custom_args: self.custom_args.clone(),
             custom_args_enabled: self.custom_args_enabled,
+            workshop_items: self.workshop_items.clone(),
+            notes: self.notes.clone(),
+            readme: self.readme.clone(),
         }
     }
 
@@ -349,6 +447,9 @@ pub struct FrontendProfile {
     sync: Option<sync::SyncProfileData>,
     custom_args: Vec<String>,
     custom_args_enabled: bool,
+    workshop_items: Vec<workshop::WorkshopItem>,
+    notes: Option<String>,
+    readme: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -552,13 +653,13 @@ impl ModManager {
                 continue;
             }
 
-            let game = game::from_slug(&saved_profile.game_slug).ok_or_else(|| {
-                eyre!(
-                    "profile {} is in unknown game: {}",
-                    saved_profile.name,
-                    saved_profile.game_slug
-                )
-            })?;
+            let Some(game) = game::from_slug(&saved_profile.game_slug) else {
+                warn!(
+                    "skipping profile {} in unknown game: {}",
+                    saved_profile.name, saved_profile.game_slug
+                );
+                continue;
+            };
 
             let profile = Profile {
                 path,
@@ -573,6 +674,10 @@ impl ModManager {
                 sync: saved_profile.sync_data,
                 custom_args: saved_profile.custom_args.unwrap_or_default(),
                 custom_args_enabled: saved_profile.custom_args_enabled.unwrap_or(false),
+                workshop_items: saved_profile.workshop_items.unwrap_or_default(),
+                loader_files: saved_profile.loader_files.unwrap_or_default(),
+                notes: saved_profile.notes,
+                readme: saved_profile.readme,
             };
 
             manager
@@ -713,12 +818,14 @@ impl ModManager {
     }
 
     fn add_saved_game(&mut self, base_path: &Path, saved_game: db::ManagedGameData) -> Result<()> {
-        let game = game::from_slug(&saved_game.slug).ok_or_else(|| {
-            eyre!(
-                "unknown game in save: {} (has Gale been downgraded?)",
+        // the data is left alone, so it comes back if the game is defined again
+        let Some(game) = game::from_slug(&saved_game.slug) else {
+            warn!(
+                "skipping unknown game in save: {} (was it removed, or has Gale been downgraded?)",
                 saved_game.slug
-            )
-        })?;
+            );
+            return Ok(());
+        };
 
         let managed_game = ManagedGame {
             id: saved_game.id,
@@ -751,3 +858,9 @@ impl ModManager {
         self.active_profile().save(app, notify_frontend)
     }
 }
+
+/// Trims a note and treats empty ones as unset.
+pub fn normalize_note(note: Option<String>) -> Option<String> {
+    note.map(|note| note.trim().to_owned())
+        .filter(|note| !note.is_empty())
+}
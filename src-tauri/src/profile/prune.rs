@@ -0,0 +1,307 @@
+//! Finds ways to free up disk space in the active game's profiles and the mod cache:
+//!
+//! - identical files shipped by different mods of a profile, which can be hard linked
+//!   to each other,
+//! - disabled mods, whose files stay in the profile so that they can be enabled again,
+//! - cached versions that no profile uses.
+//!
+//! Suggestions are sent to the frontend, which passes back the ones the user picks.
+//! Since they come back from outside, paths are checked again before anything is touched.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{ensure, OptionExt, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::{install::cache, Profile};
+use crate::{
+    state::ManagerExt,
+    util::{self, error::IoResultExt, fs::PathExt},
+};
+
+/// Smaller duplicates aren't worth the hashing.
+const MIN_DUPLICATE_SIZE: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestion {
+    #[serde(flatten)]
+    pub action: PruneAction,
+    /// The profile the suggestion is for, or `None` for the cache.
+    pub profile_id: Option<i64>,
+    pub profile_name: Option<String>,
+    /// The mod or file the suggestion is about.
+    pub subject: String,
+    /// Roughly how many bytes applying it frees up.
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum PruneAction {
+    /// Replace every file but the first with a hard link to it.
+    LinkDuplicates { paths: Vec<PathBuf> },
+    /// Uninstall a disabled mod. It stays in the cache, so reinstalling is quick.
+    RemoveDisabled { uuid: Uuid },
+    /// Delete a cached version.
+    ClearCache { path: PathBuf },
+}
+
+/// Analyzes the profiles of the active game, biggest savings first.
+pub fn analyze(app: &AppHandle) -> Result<Vec<Suggestion>> {
+    debug!("looking for prune suggestions");
+
+    // takes all the locks itself
+    let unused_cache = cache::prepare_soft_clear(app.clone())?;
+
+    let mut suggestions = unused_cache
+        .into_iter()
+        .map(|path| Suggestion {
+            bytes: util::fs::get_directory_size(&path),
+            subject: cache_entry_name(&path),
+            profile_id: None,
+            profile_name: None,
+            action: PruneAction::ClearCache { path },
+        })
+        .collect_vec();
+
+    let manager = app.lock_manager();
+
+    for profile in &manager.active_game().profiles {
+        find_disabled(profile, &mut suggestions);
+        find_duplicates(profile, &mut suggestions);
+    }
+
+    suggestions.retain(|suggestion| suggestion.bytes > 0);
+    suggestions.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+
+    Ok(suggestions)
+}
+
+fn cache_entry_name(path: &Path) -> String {
+    match path.parent() {
+        Some(package) => format!(
+            "{}-{}",
+            util::fs::file_name_owned(package),
+            util::fs::file_name_owned(path)
+        ),
+        None => util::fs::file_name_owned(path),
+    }
+}
+
+fn find_disabled(profile: &Profile, suggestions: &mut Vec<Suggestion>) {
+    for profile_mod in profile
+        .mods
+        .iter()
+        .filter(|profile_mod| !profile_mod.enabled)
+    {
+        let full_name = profile_mod.full_name();
+
+        let Some(mod_dir) = profile
+            .game
+            .installer_for(&full_name)
+            .mod_dir(&full_name, profile)
+        else {
+            continue;
+        };
+
+        suggestions.push(Suggestion {
+            bytes: util::fs::get_directory_size(&mod_dir),
+            subject: profile_mod.ident().to_string(),
+            profile_id: Some(profile.id),
+            profile_name: Some(profile.name.clone()),
+            action: PruneAction::RemoveDisabled {
+                uuid: profile_mod.uuid(),
+            },
+        });
+    }
+}
+
+fn find_duplicates(profile: &Profile, suggestions: &mut Vec<Suggestion>) {
+    let mod_dirs = profile
+        .mods
+        .iter()
+        .filter_map(|profile_mod| {
+            let full_name = profile_mod.full_name();
+            profile
+                .game
+                .installer_for(&full_name)
+                .mod_dir(&full_name, profile)
+        })
+        .filter(|dir| dir.is_dir());
+
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+
+    for dir in mod_dirs {
+        let files = WalkDir::new(dir)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file());
+
+        for file in files {
+            let Ok(metadata) = file.metadata() else {
+                continue;
+            };
+
+            // files linked from the cache don't take up any space of their own
+            if metadata.len() >= MIN_DUPLICATE_SIZE && is_only_link(&metadata) {
+                by_size
+                    .entry(metadata.len())
+                    .or_default()
+                    .push(file.into_path());
+            }
+        }
+    }
+
+    for (size, paths) in by_size {
+        if paths.len() < 2 {
+            continue;
+        }
+
+        let by_hash = paths
+            .into_iter()
+            .filter_map(|path| match util::fs::checksum(&path) {
+                Ok(hash) => Some((hash, path)),
+                Err(err) => {
+                    warn!("failed to hash {}: {}", path.display(), err);
+                    None
+                }
+            })
+            .into_group_map();
+
+        for paths in by_hash.into_values().filter(|paths| paths.len() > 1) {
+            let subject = paths[0]
+                .strip_prefix(&profile.path)
+                .unwrap_or(&paths[0])
+                .to_string_lossy()
+                .into_owned();
+
+            suggestions.push(Suggestion {
+                bytes: size * (paths.len() as u64 - 1),
+                subject,
+                profile_id: Some(profile.id),
+                profile_name: Some(profile.name.clone()),
+                action: PruneAction::LinkDuplicates { paths },
+            });
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_only_link(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    metadata.nlink() == 1
+}
+
+#[cfg(not(unix))]
+fn is_only_link(_metadata: &fs::Metadata) -> bool {
+    true
+}
+
+/// Applies the given suggestions and returns how many bytes were freed.
+pub fn apply(suggestions: Vec<Suggestion>, app: &AppHandle) -> Result<u64> {
+    let mut freed = 0;
+
+    for suggestion in suggestions {
+        let bytes = suggestion.bytes;
+
+        match apply_one(suggestion, app) {
+            Ok(()) => freed += bytes,
+            Err(err) => warn!("failed to apply prune suggestion: {:#}", err),
+        }
+    }
+
+    info!("pruning freed up {} bytes", freed);
+
+    Ok(freed)
+}
+
+fn apply_one(suggestion: Suggestion, app: &AppHandle) -> Result<()> {
+    match suggestion.action {
+        PruneAction::ClearCache { path } => {
+            let cache_dir = app.lock_prefs().cache_dir();
+            ensure!(
+                is_inside(&cache_dir, &path),
+                "{} is not in the cache",
+                path.display()
+            );
+
+            fs::remove_dir_all(&path).fs_context("removing cached mod", &path)?;
+        }
+        PruneAction::RemoveDisabled { uuid } => {
+            let profile_id = suggestion
+                .profile_id
+                .ok_or_eyre("suggestion has no profile")?;
+
+            let mut manager = app.lock_manager();
+            let (_, profile) = manager.profile_by_id_mut(profile_id)?;
+
+            ensure!(
+                !profile.get_mod(uuid)?.enabled,
+                "mod has been enabled since"
+            );
+            profile.force_remove_mod(uuid)?;
+            profile.save(app, true)?;
+        }
+        PruneAction::LinkDuplicates { paths } => {
+            let profile_id = suggestion
+                .profile_id
+                .ok_or_eyre("suggestion has no profile")?;
+
+            let manager = app.lock_manager();
+            let (_, profile) = manager.profile_by_id(profile_id)?;
+
+            ensure!(
+                paths.iter().all(|path| is_inside(&profile.path, path)),
+                "files are not in the profile"
+            );
+
+            link_duplicates(&paths)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn is_inside(dir: &Path, path: &Path) -> bool {
+    path.strip_prefix(dir)
+        .is_ok_and(|relative| !relative.as_os_str().is_empty() && util::fs::is_enclosed(relative))
+}
+
+fn link_duplicates(paths: &[PathBuf]) -> Result<()> {
+    let Some((original, duplicates)) = paths.split_first() else {
+        return Ok(());
+    };
+
+    let hash = util::fs::checksum(original).fs_context("hashing file", original)?;
+
+    for duplicate in duplicates {
+        // the file might have changed since it was analyzed
+        if util::fs::checksum(duplicate).ok() != Some(hash) {
+            warn!("{} has changed, skipping", duplicate.display());
+            continue;
+        }
+
+        let mut temp = duplicate.clone();
+        temp.add_ext("prune");
+
+        fs::hard_link(original, &temp).fs_context("linking file", &temp)?;
+        fs::rename(&temp, duplicate)
+            .inspect_err(|_| {
+                fs::remove_file(&temp).ok();
+            })
+            .fs_context("replacing file", duplicate)?;
+    }
+
+    Ok(())
+}
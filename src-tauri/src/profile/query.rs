@@ -1,7 +1,8 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, collections::HashSet};
 
 use chrono::{DateTime, Utc};
 use eyre::Result;
+use internment::Intern;
 use tracing::warn;
 
 use super::{Dependant, LocalMod, Profile, ProfileMod, ProfileModKind};
@@ -14,6 +15,10 @@ use crate::thunderstore::{
 struct QueryableProfileMod<'a> {
     enabled: bool,
     install_time: DateTime<Utc>,
+    categories: &'a HashSet<Intern<String>>,
+    tags: &'a [String],
+    note: Option<&'a str>,
+    local_only: bool,
     kind: QueryableProfileModKind<'a>,
     index: usize,
 }
@@ -40,6 +45,10 @@ impl<'a> QueryableProfileMod<'a> {
         Ok(QueryableProfileMod {
             enabled: profile_mod.enabled,
             install_time: profile_mod.install_time,
+            categories: &profile_mod.categories,
+            tags: &profile_mod.tags,
+            note: profile_mod.note.as_deref(),
+            local_only: profile_mod.local_only,
             kind,
             index,
         })
@@ -67,9 +76,37 @@ impl Queryable for QueryableProfileMod<'_> {
             return false;
         }
 
+        if args
+            .installed_after
+            .is_some_and(|after| self.install_time < after)
+            || args
+                .installed_before
+                .is_some_and(|before| self.install_time > before)
+        {
+            return false;
+        }
+
+        if !args.matches_tags(self.tags) {
+            return false;
+        }
+
         match &self.kind {
-            Kind::Local(local) => local.matches(args),
-            Kind::Thunderstore(remote) => remote.matches(args),
+            Kind::Local(local) => args.has_update != Some(true) && local.matches(args),
+            Kind::Thunderstore(remote) => {
+                if let Some(has_update) = args.has_update {
+                    let latest = remote.package.latest().parsed_version();
+                    if (remote.version.parsed_version() < latest) != has_update {
+                        return false;
+                    }
+                }
+
+                // prefer the categories recorded at install time, since mods
+                // installed before they were recorded have none
+                match self.categories.is_empty() {
+                    true => remote.matches(args),
+                    false => remote.matches_with_categories(args, self.categories),
+                }
+            }
         }
     }
 
@@ -139,6 +176,9 @@ impl Profile {
                 FrontendProfileMod {
                     data,
                     enabled: queryable.enabled,
+                    tags: queryable.tags.to_vec(),
+                    note: queryable.note.map(str::to_owned),
+                    local_only: queryable.local_only,
                     config_file: self.linked_config.get(&uuid).cloned(),
                 }
             })
@@ -157,8 +197,8 @@ impl Queryable for LocalMod {
         self.description.as_deref()
     }
 
-    fn matches(&self, _args: &QueryModsArgs) -> bool {
-        true
+    fn matches(&self, args: &QueryModsArgs) -> bool {
+        args.matches_author(self.author.as_deref()) && args.matches_size(self.file_size)
     }
 
     fn cmp(&self, other: &Self, args: &QueryModsArgs) -> Ordering {
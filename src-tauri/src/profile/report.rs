@@ -0,0 +1,203 @@
+//! Collects information about a misbehaving mod for a bug report to its author.
+//!
+//! If the package's website is a GitHub repository, the report is opened as a prefilled
+//! issue there. Otherwise, the package's Thunderstore page is opened instead and the
+//! report is left for the user to copy.
+
+use std::fmt::Write;
+
+use eyre::{OptionExt, Result};
+use serde::Serialize;
+use tauri::Url;
+use uuid::Uuid;
+
+use super::{launch, Profile, ProfileMod};
+use crate::{
+    prefs::Prefs,
+    profile::install::{cache, queue::InstallQueueHandle},
+    thunderstore::Thunderstore,
+};
+
+/// How many lines of the log file to include at most.
+const MAX_LOG_LINES: usize = 40;
+/// GitHub rejects overly long URLs, so the body is cut off after this many characters.
+const MAX_BODY_LEN: usize = 6000;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModReport {
+    pub title: String,
+    pub body: String,
+    /// Where the report should be filed.
+    pub url: String,
+    /// Whether `url` already contains the report.
+    pub prefilled: bool,
+}
+
+impl Profile {
+    pub fn report_mod(
+        &self,
+        uuid: Uuid,
+        prefs: &Prefs,
+        thunderstore: &Thunderstore,
+        queue: &InstallQueueHandle,
+    ) -> Result<ModReport> {
+        let profile_mod = self.get_mod(uuid)?;
+        let ts_mod = profile_mod
+            .kind
+            .as_thunderstore()
+            .ok_or_eyre("only thunderstore mods can be reported")?;
+
+        let borrowed = ts_mod.id.borrow(thunderstore)?;
+        let ident = &ts_mod.ident;
+
+        let title = format!("Issue with {} {}", ident.name(), ident.version());
+
+        let mut body = String::new();
+
+        writeln!(body, "### Environment\n")?;
+        writeln!(body, "- Mod: `{}`", ident)?;
+        writeln!(body, "- Game: {}", self.game.name)?;
+        if let Some(build_id) = launch::game_build_id(self.game) {
+            writeln!(body, "- Game build: {}", build_id)?;
+        }
+        writeln!(body, "- Mod loader: {}", self.game.mod_loader.as_str())?;
+        writeln!(body, "- Mod manager: Gale {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(body, "- Total mods in profile: {}", self.mods.len())?;
+
+        writeln!(body, "\n### Diagnostics\n")?;
+        for line in self.diagnose(profile_mod, prefs, thunderstore, queue) {
+            writeln!(body, "- {}", line)?;
+        }
+
+        writeln!(body, "\n### Description\n")?;
+        writeln!(
+            body,
+            "<!-- Describe what went wrong and how to reproduce it -->"
+        )?;
+
+        if let Some(excerpt) = self.log_excerpt(ident.name()) {
+            writeln!(body, "\n### Log excerpt\n")?;
+            writeln!(body, "```\n{}\n```", excerpt)?;
+        }
+
+        let website = borrowed.version.website_url.as_str();
+
+        let (url, prefilled) = match github_issue_url(website, &title, &body) {
+            Some(url) => (url.to_string(), true),
+            None => (borrowed.package.url(self.game), false),
+        };
+
+        Ok(ModReport {
+            title,
+            body,
+            url,
+            prefilled,
+        })
+    }
+
+    fn diagnose(
+        &self,
+        profile_mod: &ProfileMod,
+        prefs: &Prefs,
+        thunderstore: &Thunderstore,
+        queue: &InstallQueueHandle,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        if !profile_mod.enabled {
+            lines.push("The mod is disabled".to_owned());
+        }
+
+        let mut missing = Vec::new();
+        let mut disabled = Vec::new();
+
+        for dep in profile_mod.dependencies(thunderstore) {
+            match self.get_mod(dep.package.uuid) {
+                Ok(installed) if !installed.enabled => disabled.push(format!("`{}`", dep.ident())),
+                Ok(_) => (),
+                Err(_) => missing.push(format!("`{}`", dep.ident())),
+            }
+        }
+
+        if !missing.is_empty() {
+            lines.push(format!("Missing dependencies: {}", missing.join(", ")));
+        }
+
+        if !disabled.is_empty() {
+            lines.push(format!("Disabled dependencies: {}", disabled.join(", ")));
+        }
+
+        let uuid = profile_mod.uuid();
+        if let Ok(Some(update)) = self.check_update(uuid, true, thunderstore, queue) {
+            lines.push(format!("An update is available: `{}`", update.latest.ident));
+        }
+
+        let ident = profile_mod.ident();
+        if !cache::path(&ident, self.game, prefs).exists() {
+            lines.push("The mod is not in the download cache".to_owned());
+        }
+
+        let mod_dir = self
+            .game
+            .installer_for(ident.full_name())
+            .mod_dir(ident.full_name(), self);
+
+        if let Some(mod_dir) = mod_dir {
+            if !mod_dir.exists() {
+                lines.push("The mod's files are missing from the profile".to_owned());
+            }
+        }
+
+        if lines.is_empty() {
+            lines.push("No problems found".to_owned());
+        }
+
+        lines
+    }
+
+    /// Picks the last log lines which mention the mod or contain errors.
+    fn log_excerpt(&self, mod_name: &str) -> Option<String> {
+        let log = std::fs::read_to_string(self.log_path().ok()?).ok()?;
+
+        let lines = log
+            .lines()
+            .filter(|line| {
+                line.contains(mod_name) || line.contains("Error") || line.contains("Exception")
+            })
+            .collect::<Vec<_>>();
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        let start = lines.len().saturating_sub(MAX_LOG_LINES);
+        Some(lines[start..].join("\n"))
+    }
+}
+
+/// Builds a "new issue" URL if `website` points to a GitHub repository.
+fn github_issue_url(website: &str, title: &str, body: &str) -> Option<Url> {
+    let website = Url::parse(website).ok()?;
+
+    if website.host_str() != Some("github.com") {
+        return None;
+    }
+
+    let mut segments = website
+        .path_segments()?
+        .filter(|segment| !segment.is_empty());
+    let (owner, repo) = (segments.next()?, segments.next()?);
+    let repo = repo.trim_end_matches(".git");
+
+    let body = match body.char_indices().nth(MAX_BODY_LEN) {
+        Some((index, _)) => format!("{}\n\n(truncated)", &body[..index]),
+        None => body.to_owned(),
+    };
+
+    Url::parse_with_params(
+        &format!("https://github.com/{owner}/{repo}/issues/new"),
+        [("title", title), ("body", body.as_str())],
+    )
+    .ok()
+}
@@ -0,0 +1,25 @@
+use std::path::PathBuf;
+
+use tauri::{command, AppHandle};
+
+use super::steamcmd;
+use crate::{db::server::ServerIncident, state::ManagerExt, util::cmd::Result};
+
+#[command]
+pub fn get_server_incidents(app: AppHandle) -> Result<Vec<ServerIncident>> {
+    let profile_id = app.lock_manager().active_profile().id;
+
+    let incidents = app.db().get_incidents(profile_id)?;
+
+    Ok(incidents)
+}
+
+/// Installs or updates the active game's dedicated server, returning its directory.
+#[command]
+pub async fn install_dedicated_server(app: AppHandle) -> Result<PathBuf> {
+    let game = app.lock_manager().active_game;
+
+    let path = steamcmd::install(game, &app).await?;
+
+    Ok(path)
+}
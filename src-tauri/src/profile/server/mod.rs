@@ -0,0 +1,277 @@
+//! Light server management for dedicated-server profiles: keeping the server's mods
+//! up to date and restarting the server if it crashes.
+
+use std::{
+    fs,
+    process::{Child, ExitStatus},
+    time::Duration,
+};
+
+use eyre::{bail, Context, Result};
+use serde_json::json;
+use tauri::AppHandle;
+use tokio::time::Instant;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    i18n,
+    notify::{self, Category},
+    profile::update,
+    state::ManagerExt,
+    thunderstore, util,
+};
+
+pub mod commands;
+pub mod steamcmd;
+
+/// How often the server process is checked for exits.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Default)]
+pub struct ServerOptions {
+    pub auto_update: Option<AutoUpdateOptions>,
+    pub watchdog: Option<WatchdogOptions>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AutoUpdateOptions {
+    /// How often to check for updates.
+    pub interval: Duration,
+    /// How long to wait between running the warning hooks and restarting.
+    pub warning: Duration,
+    /// A shell command to run before restarting, e.g. to warn players with an RCON tool.
+    pub warn_command: Option<String>,
+    /// A URL to POST a Discord-compatible message to before restarting.
+    pub warn_url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchdogOptions {
+    /// How many times the server may be restarted after crashing before giving up.
+    pub max_restarts: u32,
+    /// How many lines of the log to save with each incident.
+    pub log_lines: usize,
+}
+
+/// Runs and supervises the active profile's server until it exits or an error occurs.
+///
+/// With auto updates enabled, the server is periodically checked for mod updates.
+/// When some are found, the warning hooks are run, the server is stopped,
+/// the updates are applied and the server is started again.
+///
+/// With the watchdog enabled, crashes are recorded as incidents and the server is restarted.
+pub async fn run(options: ServerOptions, app: &AppHandle) -> Result<()> {
+    let mut server = spawn_server(app)?;
+    let mut restarts = 0;
+
+    let mut next_update = options
+        .auto_update
+        .as_ref()
+        .map(|auto_update| Instant::now() + auto_update.interval);
+
+    info!("supervising server process {}", server.id());
+
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        if let Some(status) = server.try_wait()? {
+            let Some(watchdog) = &options.watchdog else {
+                info!("server exited with {}", status);
+                return Ok(());
+            };
+
+            if status.success() {
+                info!("server exited normally");
+                return Ok(());
+            }
+
+            let restart = restarts < watchdog.max_restarts;
+            record_crash(status, watchdog, restart, app);
+
+            if !restart {
+                notify::send(
+                    Category::Server,
+                    &i18n::t("notify.server_stopped", &[]),
+                    app,
+                );
+                bail!("server crashed {} times, giving up", restarts + 1);
+            }
+
+            notify::send(
+                Category::Server,
+                &i18n::t("notify.server_restarted", &[]),
+                app,
+            );
+
+            restarts += 1;
+            warn!(
+                "server crashed with {}, restarting ({}/{})",
+                status, restarts, watchdog.max_restarts
+            );
+
+            server = spawn_server(app)?;
+            continue;
+        }
+
+        let (Some(auto_update), Some(deadline)) = (&options.auto_update, next_update) else {
+            continue;
+        };
+
+        if Instant::now() < deadline {
+            continue;
+        }
+
+        next_update = Some(Instant::now() + auto_update.interval);
+
+        server = check_updates(auto_update, server, app).await?;
+    }
+}
+
+/// Checks for updates and applies them if there are any. Returns the restarted server,
+/// or the same one if nothing was updated. The server is restarted even if the updates
+/// fail to apply, which is reported on its own.
+async fn check_updates(
+    options: &AutoUpdateOptions,
+    server: Child,
+    app: &AppHandle,
+) -> Result<Child> {
+    let game = app.lock_manager().active_game;
+    if let Err(err) = thunderstore::refresh_packages(game, app).await {
+        warn!("failed to fetch packages, skipping update check: {:#}", err);
+        return Ok(server);
+    }
+
+    let updates = pending_updates(app);
+    if updates.is_empty() {
+        debug!("no updates available");
+        return Ok(server);
+    }
+
+    info!("found {} updates, restarting server", updates.len());
+
+    run_warning_hooks(options, updates.len(), app).await;
+    tokio::time::sleep(options.warning).await;
+
+    stop_server(server).await?;
+
+    let result = update::update_mods(updates, true, app).await;
+
+    // the server has to come back up either way
+    let server = spawn_server(app)?;
+
+    if let Err(err) = result {
+        error!("failed to apply updates: {:#}", err);
+        notify::send(
+            Category::Server,
+            &i18n::t("notify.server_update_failed", &[]),
+            app,
+        );
+    }
+
+    Ok(server)
+}
+
+fn spawn_server(app: &AppHandle) -> Result<Child> {
+    let mut prefs = app.lock_prefs();
+    let manager = app.lock_manager();
+
+    let server = manager
+        .active_game()
+        .spawn(&mut prefs)
+        .context("failed to start server")?;
+
+    // keeps track of the GPU preferences Gale has written
+    prefs.save(app.db())?;
+
+    Ok(server)
+}
+
+async fn stop_server(mut server: Child) -> Result<()> {
+    info!("stopping server process {}", server.id());
+
+    // waiting for the process blocks, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || -> Result<()> {
+        server.kill().context("failed to stop server")?;
+        server.wait()?;
+        Ok(())
+    })
+    .await?
+}
+
+fn record_crash(status: ExitStatus, options: &WatchdogOptions, restarted: bool, app: &AppHandle) {
+    let manager = app.lock_manager();
+    let profile = manager.active_profile();
+
+    let log_tail = profile
+        .log_path()
+        .and_then(|path| fs::read_to_string(path).context("failed to read log file"))
+        .map(|log| {
+            let lines = log.lines().collect::<Vec<_>>();
+            let start = lines.len().saturating_sub(options.log_lines);
+            lines[start..].join("\n")
+        })
+        .inspect_err(|err| warn!("failed to capture log of crashed server: {:#}", err))
+        .ok();
+
+    app.db()
+        .insert_incident(profile.id, status.code(), log_tail.as_deref(), restarted)
+        .unwrap_or_else(|err| warn!("failed to record server incident: {:#}", err));
+}
+
+fn pending_updates(app: &AppHandle) -> Vec<Uuid> {
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+    let install_queue = app.install_queue().handle();
+
+    let profile = manager.active_profile();
+
+    profile
+        .mods
+        .iter()
+        .filter_map(|profile_mod| {
+            profile
+                .check_update(profile_mod.uuid(), true, &thunderstore, &install_queue)
+                .ok()
+                .flatten()
+                .map(|update| update.package.uuid)
+        })
+        .collect()
+}
+
+async fn run_warning_hooks(options: &AutoUpdateOptions, update_count: usize, app: &AppHandle) {
+    let message = i18n::t(
+        "server.restart_warning",
+        &[
+            ("seconds", &options.warning.as_secs()),
+            ("count", &update_count),
+        ],
+    );
+
+    if let Some(command) = &options.warn_command {
+        let mut command = tokio::process::Command::from(util::shell_command(command));
+        command
+            .env("GALE_UPDATE_COUNT", update_count.to_string())
+            .env("GALE_RESTART_DELAY", options.warning.as_secs().to_string());
+
+        match command.status().await {
+            Ok(status) if status.success() => (),
+            Ok(status) => warn!("warning command exited with {}", status),
+            Err(err) => warn!("failed to run warning command: {}", err),
+        }
+    }
+
+    if let Some(url) = &options.warn_url {
+        let result = app
+            .http()
+            .post(url)
+            .json(&json!({ "content": message }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        if let Err(err) = result {
+            warn!("failed to send warning webhook: {}", err);
+        }
+    }
+}
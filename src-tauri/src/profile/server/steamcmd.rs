@@ -0,0 +1,156 @@
+//! Installing and updating dedicated servers with steamcmd.
+//!
+//! A steamcmd found on the PATH is preferred, otherwise Gale downloads its own copy to
+//! the data directory. Servers are installed to `<data dir>/servers/<game slug>`, which
+//! then becomes the game directory of that game.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use eyre::{ensure, Context, OptionExt, Result};
+use tauri::{AppHandle, Emitter};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::Command,
+};
+use tracing::{debug, info};
+
+use crate::{game::Game, prefs::access, state::ManagerExt, util::error::IoResultExt};
+
+#[cfg(windows)]
+const DOWNLOAD_URL: &str = "https://steamcdn-a.akamaihd.net/client/installer/steamcmd.zip";
+#[cfg(target_os = "macos")]
+const DOWNLOAD_URL: &str = "https://steamcdn-a.akamaihd.net/client/installer/steamcmd_osx.tar.gz";
+#[cfg(all(unix, not(target_os = "macos")))]
+const DOWNLOAD_URL: &str = "https://steamcdn-a.akamaihd.net/client/installer/steamcmd_linux.tar.gz";
+
+#[cfg(windows)]
+const EXE_NAME: &str = "steamcmd.exe";
+#[cfg(not(windows))]
+const EXE_NAME: &str = "steamcmd.sh";
+
+/// Installs or updates the dedicated server for `game`, and sets it as the game's directory.
+///
+/// The output of steamcmd is forwarded to the frontend as `steamcmd_output` events.
+pub async fn install(game: Game, app: &AppHandle) -> Result<PathBuf> {
+    ensure!(game.server, "{} is not a dedicated server", game.name);
+
+    let app_id = game
+        .platforms
+        .steam
+        .as_ref()
+        .ok_or_eyre("server is not available on Steam")?
+        .id;
+
+    let steamcmd = find_or_download(app).await?;
+    let dir = app.lock_prefs().data_dir.join("servers").join(&*game.slug);
+    access::request(&dir, "dedicated server", app).await?;
+
+    fs::create_dir_all(&dir).fs_context("creating server directory", &dir)?;
+
+    info!(
+        "installing {} (app {}) to {} with steamcmd",
+        game.name,
+        app_id,
+        dir.display()
+    );
+
+    // force_install_dir has to come before login
+    let mut child = Command::new(&steamcmd)
+        .arg("+force_install_dir")
+        .arg(&dir)
+        .args(["+login", "anonymous", "+app_update"])
+        .arg(app_id.to_string())
+        .args(["validate", "+quit"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to run steamcmd at {}", steamcmd.display()))?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_eyre("failed to read steamcmd output")?;
+    let mut lines = BufReader::new(stdout).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        debug!("steamcmd: {}", line);
+        app.emit("steamcmd_output", line).ok();
+    }
+
+    let status = child.wait().await?;
+    ensure!(status.success(), "steamcmd exited with {}", status);
+
+    info!("server installed successfully");
+
+    let mut prefs = app.lock_prefs();
+    prefs
+        .game_prefs
+        .entry(game.slug.to_string())
+        .or_default()
+        .dir_override = Some(dir.clone());
+    prefs.save(app.db())?;
+
+    Ok(dir)
+}
+
+async fn find_or_download(app: &AppHandle) -> Result<PathBuf> {
+    if let Ok(path) = which::which("steamcmd") {
+        return Ok(path);
+    }
+
+    let dir = app.lock_prefs().data_dir.join("steamcmd");
+    let exe = dir.join(EXE_NAME);
+
+    if exe.exists() {
+        return Ok(exe);
+    }
+
+    info!("downloading steamcmd to {}", dir.display());
+
+    let bytes = app
+        .http()
+        .get(DOWNLOAD_URL)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    // unpacking is blocking work, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || extract(&bytes, &dir))
+        .await?
+        .context("failed to extract steamcmd")?;
+
+    Ok(exe)
+}
+
+#[cfg(windows)]
+fn extract(bytes: &[u8], dir: &Path) -> Result<()> {
+    crate::util::zip::extract(std::io::Cursor::new(bytes), dir)?;
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn extract(bytes: &[u8], dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)?;
+
+    let archive = dir.join("steamcmd.tar.gz");
+    fs::write(&archive, bytes).fs_context("writing archive", &archive)?;
+
+    let status = std::process::Command::new("tar")
+        .arg("-xzf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(dir)
+        .status()
+        .context("failed to run tar")?;
+
+    fs::remove_file(&archive).ok();
+    ensure!(status.success(), "tar exited with {}", status);
+
+    Ok(())
+}
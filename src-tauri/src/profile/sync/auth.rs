@@ -7,12 +7,14 @@ use std::{
 use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
 use chrono::{DateTime, Utc};
 use eyre::{eyre, Context, OptionExt, Result};
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager, Url};
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 
-use crate::{db::Db, state::ManagerExt};
+use super::RequestBuilderExt;
+use crate::{db::Db, i18n, state::ManagerExt, util::net};
 
 pub struct State {
     creds: Mutex<Option<AuthCredentials>>,
@@ -109,6 +111,9 @@ pub async fn login_with_oauth(app: &AppHandle) -> Result<User> {
         _ = tokio::time::sleep(OAUTH_TIMEOUT) => {
             Err(eyre!("auth callback timed out"))
         }
+        _ = net::cancelled() => {
+            Err(eyre!(i18n::t("network.cancelled", &[])))
+        }
     }
 }
 
@@ -118,6 +123,67 @@ pub async fn handle_callback(url: String, app: &AppHandle) -> Result<()> {
     Ok(())
 }
 
+/// Revokes all of the user's refresh tokens on the server and signs out locally,
+/// which ends the session on every device.
+pub async fn logout_everywhere(app: &AppHandle) -> Result<()> {
+    super::request(Method::POST, "/auth/revoke", app)
+        .await
+        .send_queued(app)
+        .await?
+        .error_for_status()?;
+
+    info!("revoked all sessions");
+
+    app.sync_auth().set_creds(None, app.db())?;
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDeletion {
+    deleted_profiles: u32,
+}
+
+/// Deletes the user's account and all of their synced profiles from the server.
+///
+/// Local profiles that were synced by the user are kept, but marked as missing.
+pub async fn delete_account(app: &AppHandle) -> Result<AccountDeletion> {
+    let user = user_info(app).ok_or_eyre("not logged in")?;
+
+    let deletion: AccountDeletion = super::request(Method::DELETE, "/user/me", app)
+        .await
+        .send_queued(app)
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    info!(
+        "deleted account data, including {} synced profiles",
+        deletion.deleted_profiles
+    );
+
+    app.sync_auth().set_creds(None, app.db())?;
+
+    let mut manager = app.lock_manager();
+
+    let owned_profiles = manager
+        .games
+        .values_mut()
+        .flat_map(|game| game.profiles.iter_mut())
+        .filter_map(|profile| profile.sync.as_mut())
+        .filter(|sync| sync.owner.discord_id == user.discord_id);
+
+    for sync in owned_profiles {
+        sync.missing = true;
+    }
+
+    manager.save_all(app)?;
+
+    Ok(deletion)
+}
+
 #[derive(Debug, Deserialize)]
 struct JwtPayload {
     exp: i64,
@@ -190,7 +256,7 @@ async fn request_token(refresh_token: String, app: &AppHandle) -> Result<String>
         .http()
         .post(format!("{}/auth/token", *super::API_URL))
         .json(&GrantTokenRequest { refresh_token })
-        .send()
+        .send_queued(app)
         .await?
         .error_for_status()?
         .json()
@@ -1,8 +1,14 @@
+use std::{collections::HashMap, path::PathBuf};
+
 use tauri::{command, AppHandle};
 
-use crate::{state::ManagerExt, util::cmd::Result};
+use crate::{state::ManagerExt, thunderstore::VersionIdent, util::cmd::Result};
 
-use super::{auth, ListedSyncProfile, SyncProfileMetadata};
+use super::{
+    auth,
+    compat::{self, Compatibility},
+    ListedSyncProfile, SyncProfileMetadata, SyncRevision,
+};
 
 #[command]
 pub async fn read_sync_profile(id: String, app: AppHandle) -> Result<SyncProfileMetadata> {
@@ -19,10 +25,24 @@ pub async fn create_sync_profile(app: AppHandle) -> Result<String> {
 }
 
 #[command]
-pub async fn push_sync_profile(app: AppHandle) -> Result<()> {
+pub async fn push_sync_profile(message: Option<String>, app: AppHandle) -> Result<()> {
     let id = app.lock_manager().active_profile().id;
 
-    super::push_profile(&app, id).await?;
+    super::push_profile(&app, id, message).await?;
+
+    Ok(())
+}
+
+#[command]
+pub async fn get_sync_revisions(app: AppHandle) -> Result<Vec<SyncRevision>> {
+    let revisions = super::active_revisions(&app).await?;
+
+    Ok(revisions)
+}
+
+#[command]
+pub async fn restore_sync_revision(id: String, remote: bool, app: AppHandle) -> Result<()> {
+    super::restore_revision(&id, remote, &app).await?;
 
     Ok(())
 }
@@ -69,6 +89,20 @@ pub async fn get_owned_sync_profiles(app: AppHandle) -> Result<Vec<ListedSyncPro
     Ok(results)
 }
 
+#[command]
+pub fn set_profile_readme(readme: Option<String>, app: AppHandle) -> Result<()> {
+    super::set_readme(readme, &app)?;
+
+    Ok(())
+}
+
+#[command]
+pub fn set_config_enforced(file: PathBuf, enforced: bool, app: AppHandle) -> Result<()> {
+    super::set_config_enforced(file, enforced, &app)?;
+
+    Ok(())
+}
+
 #[command]
 pub async fn login(app: AppHandle) -> Result<auth::User> {
     let user = auth::login_with_oauth(&app).await?;
@@ -83,9 +117,44 @@ pub async fn logout(app: AppHandle) -> Result<()> {
     Ok(())
 }
 
+#[command]
+pub async fn logout_everywhere(app: AppHandle) -> Result<()> {
+    auth::logout_everywhere(&app).await?;
+
+    Ok(())
+}
+
+#[command]
+pub async fn delete_account(app: AppHandle) -> Result<auth::AccountDeletion> {
+    let deletion = auth::delete_account(&app).await?;
+
+    Ok(deletion)
+}
+
 #[command]
 pub async fn get_user(app: AppHandle) -> Result<Option<auth::User>> {
     let user = auth::user_info(&app);
 
     Ok(user)
 }
+
+#[command]
+pub async fn get_mod_compatibility(
+    idents: Vec<VersionIdent>,
+    app: AppHandle,
+) -> Result<HashMap<String, Compatibility>> {
+    let compat = compat::get(idents, &app).await?;
+
+    Ok(compat)
+}
+
+#[command]
+pub async fn report_mod_compatibility(
+    ident: VersionIdent,
+    works: bool,
+    app: AppHandle,
+) -> Result<()> {
+    compat::submit(ident, works, &app).await?;
+
+    Ok(())
+}
@@ -0,0 +1,193 @@
+//! Community compatibility notes: whether a package version works with a given
+//! build of the game, as reported by signed in users through the sync server.
+//!
+//! The reports for a game are downloaded in one go and cached on disk for
+//! [`CACHE_DURATION`]. If the server can't be reached, the stale copy is used.
+
+use std::{collections::HashMap, fs, path::PathBuf, time::Duration};
+
+use eyre::{OptionExt, Result};
+use itertools::Itertools;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{debug, warn};
+
+use super::{auth, RequestBuilderExt};
+use crate::{
+    game::Game,
+    profile::launch,
+    state::ManagerExt,
+    thunderstore::VersionIdent,
+    util::{self, fs::JsonStyle},
+};
+
+const CACHE_DURATION: Duration = Duration::from_secs(6 * 60 * 60);
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompatReport {
+    /// Full name of the package.
+    package: String,
+    version: String,
+    game_version: String,
+    works: u32,
+    broken: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Compatibility {
+    /// The game build the counts are for. If the installed build is unknown,
+    /// this is `None` and the counts cover every build.
+    game_version: Option<String>,
+    works: u32,
+    broken: u32,
+}
+
+impl Compatibility {
+    /// Whether more users report the version broken than working on the installed build.
+    pub fn is_broken(&self) -> bool {
+        self.game_version.is_some() && self.broken > self.works
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SubmitRequest<'a> {
+    package: &'a str,
+    version: &'a str,
+    game_version: String,
+    works: bool,
+}
+
+fn cache_path(game: Game, app: &AppHandle) -> PathBuf {
+    app.lock_prefs()
+        .data_dir
+        .join("compat")
+        .join(format!("{}.json", game.slug))
+}
+
+/// Looks up the reports for the given package versions on the installed build
+/// of the active game. Versions without any reports are left out.
+pub async fn get(
+    idents: Vec<VersionIdent>,
+    app: &AppHandle,
+) -> Result<HashMap<String, Compatibility>> {
+    if !app.lock_prefs().compatibility_notes {
+        return Ok(HashMap::new());
+    }
+
+    let game = app.lock_manager().active_game;
+    let reports = reports(game, app).await?;
+    let game_version = launch::game_build_id(game).map(|id| id.to_string());
+
+    let index = reports
+        .iter()
+        .filter(|report| {
+            game_version
+                .as_ref()
+                .map_or(true, |version| *version == report.game_version)
+        })
+        .into_group_map_by(|report| (report.package.as_str(), report.version.as_str()));
+
+    let compat = idents
+        .iter()
+        .filter_map(|ident| {
+            let reports = index.get(&(ident.full_name(), ident.version()))?;
+
+            let compat = Compatibility {
+                game_version: game_version.clone(),
+                works: reports.iter().map(|report| report.works).sum(),
+                broken: reports.iter().map(|report| report.broken).sum(),
+            };
+
+            Some((ident.to_string(), compat))
+        })
+        .collect();
+
+    Ok(compat)
+}
+
+/// Reports whether a package version works on the installed build of the active game.
+pub async fn submit(ident: VersionIdent, works: bool, app: &AppHandle) -> Result<()> {
+    auth::user_info(app).ok_or_eyre("you need to be logged in to report compatibility")?;
+
+    let game = app.lock_manager().active_game;
+    let game_version = launch::game_build_id(game)
+        .ok_or_eyre("couldn't detect the installed version of the game")?
+        .to_string();
+
+    super::request(Method::POST, format!("/compat/{}", game.slug), app)
+        .await
+        .json(&SubmitRequest {
+            package: ident.full_name(),
+            version: ident.version(),
+            game_version,
+            works,
+        })
+        .send_queued(app)
+        .await?
+        .error_for_status()?;
+
+    // refetch next time so the new report shows up
+    invalidate(game, app);
+
+    Ok(())
+}
+
+/// Drops the cached reports of `game`, so they're fetched again next time.
+pub fn invalidate(game: Game, app: &AppHandle) {
+    fs::remove_file(cache_path(game, app)).ok();
+}
+
+async fn reports(game: Game, app: &AppHandle) -> Result<Vec<CompatReport>> {
+    let path = cache_path(game, app);
+
+    let fresh = fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| {
+            modified
+                .elapsed()
+                .is_ok_and(|elapsed| elapsed < CACHE_DURATION)
+        });
+
+    if fresh {
+        if let Ok(reports) = util::fs::read_json(&path) {
+            return Ok(reports);
+        }
+    }
+
+    match fetch(game, app).await {
+        Ok(reports) => {
+            let result = fs::create_dir_all(path.parent().unwrap())
+                .map_err(Into::into)
+                .and_then(|_| util::fs::write_json(&path, &reports, JsonStyle::Compact));
+
+            if let Err(err) = result {
+                warn!("failed to cache compatibility reports: {:#}", err);
+            }
+
+            Ok(reports)
+        }
+        Err(err) => {
+            warn!("failed to fetch compatibility reports: {:#}", err);
+            // notes are optional, so don't fail if there's nothing cached either
+            Ok(util::fs::read_json(&path).unwrap_or_default())
+        }
+    }
+}
+
+async fn fetch(game: Game, app: &AppHandle) -> Result<Vec<CompatReport>> {
+    debug!("fetching compatibility reports for {}", game.slug);
+
+    let reports = super::request(Method::GET, format!("/compat/{}", game.slug), app)
+        .await
+        .send_queued(app)
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(reports)
+}
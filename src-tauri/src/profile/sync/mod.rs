@@ -1,23 +1,42 @@
-use std::{borrow::Cow, env, fmt::Display, io::Cursor, sync::LazyLock};
+use std::{
+    borrow::Cow, fmt::Display, fs, io::Cursor, mem, path::PathBuf, sync::LazyLock, time::Duration,
+};
 
+use bytes::Bytes;
 use chrono::{DateTime, Utc};
-use eyre::{bail, eyre, Context, OptionExt, Result};
-use reqwest::{Method, StatusCode};
+use eyre::{bail, ensure, eyre, Context, OptionExt, Result};
+use reqwest::{header, Method, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Semaphore;
+use tracing::{info, warn};
 
 use crate::{
-    profile::{import::ImportOptions, install::InstallOptions},
+    i18n,
+    profile::{
+        export::ExportOptions,
+        import::ImportOptions,
+        install::InstallOptions,
+        webhook::{self, WebhookEvent},
+    },
     state::ManagerExt,
+    util::net::{self, Operation},
 };
 
 pub mod auth;
 pub mod commands;
+pub mod compat;
 pub mod socket;
 
-const API_URL: LazyLock<Cow<'static, str>> = LazyLock::new(|| match env::var("GALE_SYNC_URL") {
-    Ok(var) => var.into(),
-    Err(_) => "https://gale.kesomannen.com/api".into(),
+/// Debug builds can set `GALE_SYNC_URL` to point Gale at a local sync server,
+/// release builds always use the real one.
+static API_URL: LazyLock<Cow<'static, str>> = LazyLock::new(|| {
+    #[cfg(debug_assertions)]
+    if let Ok(var) = std::env::var("GALE_SYNC_URL") {
+        return var.into();
+    }
+
+    "https://gale.kesomannen.com/api".into()
 });
 
 async fn request(method: Method, path: impl Display, app: &AppHandle) -> reqwest::RequestBuilder {
@@ -28,6 +47,74 @@ async fn request(method: Method, path: impl Display, app: &AppHandle) -> reqwest
     req
 }
 
+/// At most this many requests are sent to the sync server at once, the rest wait in line.
+const MAX_CONCURRENT_REQUESTS: usize = 2;
+const MAX_RETRIES: u32 = 5;
+/// How long to wait after the first rate limited response that doesn't
+/// specify `Retry-After`, doubled for every retry after that.
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(5);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(120);
+
+static REQUEST_QUEUE: LazyLock<Semaphore> =
+    LazyLock::new(|| Semaphore::new(MAX_CONCURRENT_REQUESTS));
+
+trait RequestBuilderExt {
+    /// Sends the request once there's room in the queue, retrying if the
+    /// server responds with `429 Too Many Requests`.
+    async fn send_queued(self, app: &AppHandle) -> Result<Response>;
+}
+
+impl RequestBuilderExt for RequestBuilder {
+    async fn send_queued(self, app: &AppHandle) -> Result<Response> {
+        let _permit = REQUEST_QUEUE.acquire().await?;
+
+        let mut retries = 0;
+
+        loop {
+            let request = self.try_clone().ok_or_eyre("request cannot be retried")?;
+            let response = net::send(request, Operation::Sync, app).await?;
+
+            if response.status() != StatusCode::TOO_MANY_REQUESTS || retries >= MAX_RETRIES {
+                if retries > 0 {
+                    app.emit("status_update", None::<String>).ok();
+                }
+
+                return Ok(response);
+            }
+
+            let delay = retry_delay(&response, retries);
+            retries += 1;
+
+            warn!(
+                "rate limited by sync server, retrying in {:?} ({}/{})",
+                delay, retries, MAX_RETRIES
+            );
+
+            let message = i18n::t("sync.rate_limited", &[("seconds", &delay.as_secs())]);
+            app.emit("status_update", Some(message)).ok();
+
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+fn retry_delay(response: &Response, retries: u32) -> Duration {
+    response
+        .headers()
+        .get(header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| match value.trim().parse::<u64>() {
+            Ok(seconds) => Some(Duration::from_secs(seconds)),
+            // otherwise it's an http date
+            Err(_) => {
+                let date = DateTime::parse_from_rfc2822(value).ok()?;
+                (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+            }
+        })
+        .unwrap_or(BASE_RETRY_DELAY * 2u32.pow(retries))
+        .min(MAX_RETRY_DELAY)
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct CreateSyncProfileResponse {
@@ -56,6 +143,25 @@ pub struct SyncProfileData {
     updated_at: DateTime<Utc>,
     #[serde(default)]
     missing: bool,
+    /// Config files that are reset to the owner's version on every pull,
+    /// relative to the profile directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    enforced_config: Vec<PathBuf>,
+}
+
+impl SyncProfileData {
+    pub fn enforced_config(&self) -> &[PathBuf] {
+        &self.enforced_config
+    }
+}
+
+/// A previously pushed version of a synced profile.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRevision {
+    id: String,
+    created_at: DateTime<Utc>,
+    message: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -84,6 +190,7 @@ impl From<SyncProfileMetadata> for SyncProfileData {
             synced_at: value.updated_at,
             updated_at: value.updated_at,
             missing: false,
+            enforced_config: value.manifest.enforced_config,
         }
     }
 }
@@ -93,13 +200,17 @@ async fn create_profile(app: &AppHandle) -> Result<String> {
         bail!("not logged in");
     };
 
+    let options = ExportOptions::for_sync(app);
+
     let bytes = {
-        let manager = app.lock_manager();
+        let mut manager = app.lock_manager();
+        manager.active_profile_mut().refresh_config();
+
         let game = manager.active_game();
         let profile = game.active_profile();
 
         let mut bytes = Cursor::new(Vec::new());
-        super::export::export_zip(profile, &mut bytes, game.game)
+        super::export::export_zip(profile, &mut bytes, game.game, options)
             .context("failed to export profile")?;
 
         bytes.into_inner()
@@ -108,7 +219,7 @@ async fn create_profile(app: &AppHandle) -> Result<String> {
     let response: CreateSyncProfileResponse = request(Method::POST, "/profile", app)
         .await
         .body(bytes)
-        .send()
+        .send_queued(app)
         .await?
         .error_for_status()?
         .json()
@@ -126,6 +237,7 @@ async fn create_profile(app: &AppHandle) -> Result<String> {
             synced_at: response.updated_at,
             updated_at: response.updated_at,
             missing: false,
+            enforced_config: Vec::new(),
         });
 
         profile.save(&app, true)?;
@@ -134,9 +246,14 @@ async fn create_profile(app: &AppHandle) -> Result<String> {
     Ok(id)
 }
 
-pub async fn push_profile(app: &AppHandle, profile_id: i64) -> Result<()> {
+/// Uploads a new revision of the profile, optionally described by `message`.
+pub async fn push_profile(app: &AppHandle, profile_id: i64, message: Option<String>) -> Result<()> {
+    let options = ExportOptions::for_sync(app);
+
     let (id, bytes) = {
-        let manager = app.lock_manager();
+        let mut manager = app.lock_manager();
+        manager.profile_by_id_mut(profile_id)?.1.refresh_config();
+
         let (game, profile) = manager.profile_by_id(profile_id)?;
 
         let id = profile
@@ -146,15 +263,20 @@ pub async fn push_profile(app: &AppHandle, profile_id: i64) -> Result<()> {
             .ok_or_eyre("profile is not synced")?;
 
         let mut bytes = Cursor::new(Vec::new());
-        super::export::export_zip(profile, &mut bytes, game).context("failed to export profile")?;
+        super::export::export_zip(profile, &mut bytes, game, options)
+            .context("failed to export profile")?;
 
         (id, bytes.into_inner())
     };
 
-    let response: CreateSyncProfileResponse = request(Method::PUT, format!("/profile/{id}"), app)
-        .await
+    let mut req = request(Method::PUT, format!("/profile/{id}"), app).await;
+    if let Some(message) = super::normalize_note(message) {
+        req = req.query(&[("message", message)]);
+    }
+
+    let response: CreateSyncProfileResponse = req
         .body(bytes)
-        .send()
+        .send_queued(app)
         .await?
         .error_for_status()?
         .json()
@@ -169,6 +291,8 @@ pub async fn push_profile(app: &AppHandle, profile_id: i64) -> Result<()> {
         sync_data.updated_at = response.updated_at;
 
         profile.save(&app, true)?;
+
+        webhook::fire(WebhookEvent::SyncPushed, profile, Vec::new(), app);
     };
 
     Ok(())
@@ -207,6 +331,30 @@ async fn disconnect_profile(delete: bool, app: &AppHandle) -> Result<()> {
     Ok(())
 }
 
+/// Sets the active profile's README, which is shared with everyone importing it.
+///
+/// Synced profiles can only be edited by their owner, since the README would
+/// otherwise be overwritten the next time the profile is pulled.
+fn set_readme(readme: Option<String>, app: &AppHandle) -> Result<()> {
+    let user = auth::user_info(app);
+
+    let mut manager = app.lock_manager();
+    let profile = manager.active_profile_mut();
+
+    if let Some(sync) = &profile.sync {
+        let is_owner = user.is_some_and(|user| user.discord_id == sync.owner.discord_id);
+        ensure!(
+            is_owner,
+            "only the owner of a synced profile can edit its README"
+        );
+    }
+
+    profile.readme = super::normalize_note(readme);
+    profile.save(app, true)?;
+
+    Ok(())
+}
+
 async fn clone_profile(id: &str, override_name: Option<String>, app: &AppHandle) -> Result<()> {
     let metadata = read_profile(id, app).await?;
 
@@ -237,44 +385,214 @@ pub async fn pull_profile(dry_run: bool, app: &AppHandle) -> Result<()> {
             download_and_import_file(Some(name), metadata.into(), app).await
         }
         metadata => {
-            let mut manager = app.lock_manager();
-            let (_, profile) = manager.profile_by_id_mut(profile_id)?;
-
-            let Some(sync) = profile.sync.as_mut() else {
-                return Ok(());
-            };
-
-            match metadata {
-                Some(metadata) => {
-                    *sync = SyncProfileData {
-                        synced_at: sync.synced_at,
-                        ..metadata.into()
-                    };
+            let found = metadata.is_some();
+
+            {
+                let mut manager = app.lock_manager();
+                let (_, profile) = manager.profile_by_id_mut(profile_id)?;
+
+                let Some(sync) = profile.sync.as_mut() else {
+                    return Ok(());
+                };
+
+                match metadata {
+                    Some(metadata) => {
+                        *sync = SyncProfileData {
+                            synced_at: sync.synced_at,
+                            // keep the list matching the version we last pulled,
+                            // or the owner's unpushed changes
+                            enforced_config: mem::take(&mut sync.enforced_config),
+                            ..metadata.into()
+                        };
+                    }
+                    None => sync.missing = true,
                 }
-                None => sync.missing = true,
+
+                profile.save(&app, true)?;
             }
 
-            profile.save(&app, true)?;
+            if found && !dry_run {
+                enforce_config(&id, profile_id, app).await?;
+            }
 
             Ok(())
         }
     }
 }
 
-async fn download_and_import_file(
-    override_name: Option<String>,
-    sync_profile: SyncProfileData,
-    app: &AppHandle,
-) -> Result<()> {
-    let path = format!("/profile/{}", sync_profile.id);
-    let bytes = request(Method::GET, path, app)
+/// Resets the profile's enforced config files to the synced version, undoing any local edits.
+///
+/// This is skipped for the owner, whose local files are the source of truth.
+async fn enforce_config(id: &str, profile_id: i64, app: &AppHandle) -> Result<()> {
+    let user = auth::user_info(app);
+
+    let (files, profile_path) = {
+        let manager = app.lock_manager();
+        let (_, profile) = manager.profile_by_id(profile_id)?;
+
+        let Some(sync) = &profile.sync else {
+            return Ok(());
+        };
+
+        let is_owner = user.is_some_and(|user| user.discord_id == sync.owner.discord_id);
+        if is_owner || sync.enforced_config.is_empty() {
+            return Ok(());
+        }
+
+        (sync.enforced_config.clone(), profile.path.clone())
+    };
+
+    let bytes = download_profile(id, app).await?;
+    let data = super::import::read_file(Cursor::new(bytes)).context("failed to read profile")?;
+
+    let result = super::import::restore_config_files(&profile_path, &data.path, &files);
+
+    fs::remove_dir_all(&data.path).ok();
+
+    let count = result.context("failed to restore enforced config")?;
+    if count > 0 {
+        info!("restored {} enforced config files", count);
+    }
+
+    Ok(())
+}
+
+/// Marks or unmarks a config file of the active profile as enforced.
+///
+/// The change reaches subscribers the next time the profile is pushed.
+fn set_config_enforced(file: PathBuf, enforced: bool, app: &AppHandle) -> Result<()> {
+    let user = auth::user_info(app);
+
+    let mut manager = app.lock_manager();
+    let profile = manager.active_profile_mut();
+
+    let sync = profile.sync.as_mut().ok_or_eyre("profile is not synced")?;
+
+    let is_owner = user.is_some_and(|user| user.discord_id == sync.owner.discord_id);
+    ensure!(
+        is_owner,
+        "only the owner of a synced profile can enforce config"
+    );
+
+    sync.enforced_config.retain(|path| *path != file);
+    if enforced {
+        sync.enforced_config.push(file);
+    }
+
+    profile.save(app, true)?;
+
+    Ok(())
+}
+
+async fn download_profile(id: &str, app: &AppHandle) -> Result<Bytes> {
+    let bytes = request(Method::GET, format!("/profile/{id}"), app)
         .await
-        .send()
+        .send_queued(app)
         .await?
         .error_for_status()?
         .bytes()
         .await?;
 
+    Ok(bytes)
+}
+
+async fn get_revisions(id: &str, app: &AppHandle) -> Result<Vec<SyncRevision>> {
+    let revisions = request(Method::GET, format!("/profile/{id}/revisions"), app)
+        .await
+        .send_queued(app)
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(revisions)
+}
+
+async fn active_revisions(app: &AppHandle) -> Result<Vec<SyncRevision>> {
+    let id = active_sync_data(app)?.id;
+
+    get_revisions(&id, app).await
+}
+
+fn active_sync_data(app: &AppHandle) -> Result<SyncProfileData> {
+    app.lock_manager()
+        .active_profile()
+        .sync
+        .clone()
+        .ok_or_eyre("profile is not synced")
+}
+
+/// Rolls the active profile back to an earlier revision.
+///
+/// With `remote`, the revision is restored on the server, so subscribers
+/// receive it on their next pull. Otherwise only the local copy is replaced,
+/// leaving the profile outdated until it's pulled again.
+async fn restore_revision(revision_id: &str, remote: bool, app: &AppHandle) -> Result<()> {
+    let sync = active_sync_data(app)?;
+    let id = sync.id.clone();
+
+    if remote {
+        let is_owner =
+            auth::user_info(app).is_some_and(|user| user.discord_id == sync.owner.discord_id);
+        ensure!(is_owner, "only the owner can restore a revision remotely");
+
+        request(
+            Method::POST,
+            format!("/profile/{id}/revisions/{revision_id}/restore"),
+            app,
+        )
+        .await
+        .send_queued(app)
+        .await?
+        .error_for_status()?;
+
+        return pull_profile(false, app).await;
+    }
+
+    let revision = get_revisions(&id, app)
+        .await?
+        .into_iter()
+        .find(|revision| revision.id == revision_id)
+        .ok_or_eyre("revision not found")?;
+
+    let name = app.lock_manager().active_profile().name.clone();
+
+    let bytes = request(
+        Method::GET,
+        format!("/profile/{id}/revisions/{revision_id}"),
+        app,
+    )
+    .await
+    .send_queued(app)
+    .await?
+    .error_for_status()?
+    .bytes()
+    .await?;
+
+    let sync = SyncProfileData {
+        synced_at: revision.created_at,
+        ..sync
+    };
+
+    import_file(bytes, Some(name), sync, app).await
+}
+
+async fn download_and_import_file(
+    override_name: Option<String>,
+    sync_profile: SyncProfileData,
+    app: &AppHandle,
+) -> Result<()> {
+    let bytes = download_profile(&sync_profile.id, app).await?;
+
+    import_file(bytes, override_name, sync_profile, app).await
+}
+
+async fn import_file(
+    bytes: Bytes,
+    override_name: Option<String>,
+    sync_profile: SyncProfileData,
+    app: &AppHandle,
+) -> Result<()> {
     let mut data =
         super::import::read_file(Cursor::new(bytes)).context("failed to read profile")?;
 
@@ -305,7 +623,7 @@ async fn download_and_import_file(
 async fn delete_profile(id: &str, app: &AppHandle) -> Result<()> {
     request(Method::DELETE, format!("/profile/{id}"), app)
         .await
-        .send()
+        .send_queued(app)
         .await?
         .error_for_status()?;
 
@@ -315,7 +633,7 @@ async fn delete_profile(id: &str, app: &AppHandle) -> Result<()> {
 async fn get_profile_meta(id: &str, app: &AppHandle) -> Result<Option<SyncProfileMetadata>> {
     let res = request(Method::GET, format!("/profile/{id}/meta"), app)
         .await
-        .send()
+        .send_queued(app)
         .await?
         .error_for_status();
 
@@ -338,7 +656,7 @@ pub async fn read_profile(id: &str, app: &AppHandle) -> Result<SyncProfileMetada
 async fn get_owned_profiles(app: &AppHandle) -> Result<Vec<ListedSyncProfile>> {
     let user: FullUserInfo = request(Method::GET, "/user/me", app)
         .await
-        .send()
+        .send_queued(app)
         .await?
         .error_for_status()?
         .json()
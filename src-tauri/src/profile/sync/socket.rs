@@ -12,6 +12,8 @@ use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 use crate::{
+    i18n,
+    notify::{self, Category},
     profile::{ModManager, Profile},
     state::ManagerExt,
 };
@@ -157,8 +159,17 @@ async fn read(app: &AppHandle, mut receiver: SplitStream<WebSocket>) {
                     let info = profile.sync.as_mut().unwrap();
                     info.updated_at = metadata.updated_at;
                     info.owner = metadata.owner.clone();
+                    // pushing from here already syncs the profile
+                    let is_behind = info.synced_at < metadata.updated_at;
 
                     profile.save(&app, true).ok();
+
+                    if !is_behind {
+                        continue;
+                    }
+
+                    let message = i18n::t("notify.sync_updated", &[("profile", &profile.name)]);
+                    notify::send(Category::Sync, &message, &app);
                 }
             }
             ServerMessage::ProfileNotFound { id } | ServerMessage::ProfileDeleted { id } => {
@@ -0,0 +1,108 @@
+//! Toggle sets: just the enabled state of each mod in a profile.
+//!
+//! These are meant to be shared between copies of the same modpack, for example
+//! a "performance mode" that turns off the heavier visual mods. Mods are keyed by
+//! full name, so the versions don't have to line up.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Context, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::Profile;
+use crate::util::error::IoResultExt;
+
+pub const FILE_EXTENSION: &str = "gts";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ToggleSet {
+    /// The name of the profile the set was exported from.
+    pub profile: String,
+    pub mods: BTreeMap<String, bool>,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ToggleSetReport {
+    pub enabled: usize,
+    pub disabled: usize,
+    /// Mods in the set that the profile doesn't have.
+    pub missing: Vec<String>,
+}
+
+impl Profile {
+    pub fn toggle_set(&self) -> ToggleSet {
+        let mods = self
+            .mods
+            .iter()
+            .map(|profile_mod| (profile_mod.full_name().into_owned(), profile_mod.enabled))
+            .collect();
+
+        ToggleSet {
+            profile: self.name.clone(),
+            mods,
+        }
+    }
+
+    /// Writes the toggle set to `dir`, returning the path of the new file.
+    pub fn export_toggle_set(&self, dir: &Path) -> Result<PathBuf> {
+        let mut path = dir.join(format!("{} toggles", self.name));
+        path.set_extension(FILE_EXTENSION);
+
+        let json = serde_json::to_string_pretty(&self.toggle_set())?;
+        fs::write(&path, json).fs_context("writing toggle set", &path)?;
+
+        Ok(path)
+    }
+
+    /// Enables and disables mods to match the set. Mods that aren't in the set
+    /// are left as they are.
+    pub fn apply_toggle_set(&mut self, set: &ToggleSet) -> Result<ToggleSetReport> {
+        let mut report = ToggleSetReport::default();
+
+        let changed = self
+            .mods
+            .iter()
+            .filter(|profile_mod| {
+                set.mods
+                    .get(&*profile_mod.full_name())
+                    .is_some_and(|enabled| *enabled != profile_mod.enabled)
+            })
+            .map(|profile_mod| (profile_mod.uuid(), !profile_mod.enabled))
+            .collect_vec();
+
+        for (uuid, enabled) in changed {
+            self.force_toggle_mod(uuid)?;
+
+            match enabled {
+                true => report.enabled += 1,
+                false => report.disabled += 1,
+            }
+        }
+
+        report.missing = set
+            .mods
+            .keys()
+            .filter(|name| {
+                !self
+                    .mods
+                    .iter()
+                    .any(|profile_mod| profile_mod.full_name() == name.as_str())
+            })
+            .cloned()
+            .collect();
+
+        Ok(report)
+    }
+}
+
+pub fn read_file(path: &Path) -> Result<ToggleSet> {
+    let json = fs::read_to_string(path).fs_context("reading toggle set", path)?;
+    serde_json::from_str(&json).context("invalid toggle set file")
+}
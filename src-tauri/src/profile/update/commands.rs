@@ -1,6 +1,7 @@
 use tauri::{command, AppHandle};
 use uuid::Uuid;
 
+use super::{loader::LoaderInfo, HeldBackUpdate};
 use crate::{state::ManagerExt, thunderstore::ModId, util::cmd::Result};
 
 #[command]
@@ -17,6 +18,35 @@ pub async fn update_mods(uuids: Vec<Uuid>, respect_ignored: bool, app: AppHandle
     Ok(())
 }
 
+#[command]
+pub async fn update_mods_safely(
+    uuids: Vec<Uuid>,
+    respect_ignored: bool,
+    app: AppHandle,
+) -> Result<Vec<HeldBackUpdate>> {
+    let held_back = super::update_mods_safely(uuids, respect_ignored, &app).await?;
+
+    Ok(held_back)
+}
+
+#[command]
+pub fn get_loader_info(app: AppHandle) -> Option<LoaderInfo> {
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+    let install_queue = app.install_queue().handle();
+
+    manager
+        .active_profile()
+        .loader_info(&thunderstore, &install_queue)
+}
+
+#[command]
+pub async fn update_loader(app: AppHandle) -> Result<()> {
+    super::loader::update_loader(&app).await?;
+
+    Ok(())
+}
+
 #[command]
 pub fn ignore_update(version_uuid: Uuid, app: AppHandle) -> Result<()> {
     let mut manager = app.lock_manager();
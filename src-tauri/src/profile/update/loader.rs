@@ -0,0 +1,75 @@
+//! Keeps track of the mod loader package separately from regular mods,
+//! so that it can be checked and updated on its own.
+
+use serde::Serialize;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::{
+    profile::{install::queue::InstallQueueHandle, Profile, Result, ThunderstoreMod},
+    state::ManagerExt,
+    thunderstore::{Thunderstore, VersionIdent},
+};
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LoaderInfo {
+    pub name: &'static str,
+    pub full_name: VersionIdent,
+    pub package_uuid: Uuid,
+    pub version: String,
+    /// The newest version, if it's newer than the installed one.
+    pub latest: Option<semver::Version>,
+}
+
+impl Profile {
+    /// Finds the mod loader's package among the profile's mods.
+    pub fn loader_mod(&self) -> Option<&ThunderstoreMod> {
+        self.mods
+            .iter()
+            .filter_map(|profile_mod| profile_mod.kind.as_thunderstore())
+            .find(|ts_mod| {
+                self.game
+                    .mod_loader
+                    .is_loader_package(ts_mod.ident.full_name())
+            })
+    }
+
+    pub fn loader_info(
+        &self,
+        thunderstore: &Thunderstore,
+        install_queue: &InstallQueueHandle,
+    ) -> Option<LoaderInfo> {
+        let ts_mod = self.loader_mod()?;
+
+        // loader updates are shown even if they're ignored in the mod list
+        let latest = self
+            .check_update(ts_mod.id.package_uuid, false, thunderstore, install_queue)
+            .ok()
+            .flatten()
+            .map(|update| update.latest.parsed_version().clone());
+
+        Some(LoaderInfo {
+            name: self.game.mod_loader.as_str(),
+            full_name: ts_mod.ident.clone(),
+            package_uuid: ts_mod.id.package_uuid,
+            version: ts_mod.ident.version().to_owned(),
+            latest,
+        })
+    }
+}
+
+/// Updates the active profile's mod loader to its latest version, without touching other mods.
+pub async fn update_loader(app: &AppHandle) -> Result<()> {
+    let uuid = app
+        .lock_manager()
+        .active_profile()
+        .loader_mod()
+        .map(|ts_mod| ts_mod.id.package_uuid);
+
+    let Some(uuid) = uuid else {
+        return Ok(());
+    };
+
+    super::update_mods(vec![uuid], false, app).await
+}
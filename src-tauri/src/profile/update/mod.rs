@@ -1,25 +1,33 @@
 use chrono::{DateTime, Utc};
 use eyre::Context;
-use itertools::Itertools;
+use itertools::{Either, Itertools};
+use serde::Serialize;
 use tauri::AppHandle;
+use tracing::info;
 use uuid::Uuid;
 
 use super::install::{InstallOptions, ModInstall};
 use crate::{
     profile::{
         install::{queue::InstallQueueHandle, InstallResultExt},
+        sync::compat::{self, Compatibility},
+        webhook::{self, WebhookEvent},
         Profile, Result,
     },
     state::ManagerExt,
-    thunderstore::{ModId, PackageListing, PackageVersion, Thunderstore},
+    thunderstore::{ModId, PackageListing, PackageVersion, Thunderstore, VersionIdent},
 };
 
 pub mod commands;
+pub mod loader;
 
 pub struct AvailableUpdate<'a> {
     pub enabled: bool,
     pub index: usize,
     pub install_time: DateTime<Utc>,
+    pub tags: &'a [String],
+    pub note: Option<&'a str>,
+    pub local_only: bool,
     pub package: &'a PackageListing,
     pub current: &'a PackageVersion,
     pub latest: &'a PackageVersion,
@@ -31,6 +39,9 @@ impl From<AvailableUpdate<'_>> for ModInstall {
             .with_state(value.enabled)
             .with_index(value.index)
             .with_time(value.install_time)
+            .with_tags(value.tags.to_vec())
+            .with_note(value.note.map(str::to_owned))
+            .with_local_only(value.local_only)
     }
 }
 
@@ -78,6 +89,9 @@ impl Profile {
             latest: package.latest(),
             enabled: profile_mod.enabled,
             install_time: profile_mod.install_time,
+            tags: &profile_mod.tags,
+            note: profile_mod.note.as_deref(),
+            local_only: profile_mod.local_only,
         }))
     }
 }
@@ -90,15 +104,17 @@ pub async fn change_version(mod_id: ModId, app: &AppHandle) -> Result<()> {
         let profile = manager.active_profile();
 
         let index = profile.index_of(mod_id.package_uuid)?;
-        let enabled = profile.mods[index].enabled;
-        let install_time = profile.mods[index].install_time;
+        let profile_mod = &profile.mods[index];
 
         (
             profile.id,
             ModInstall::try_from_id(mod_id, &thunderstore)?
-                .with_state(enabled)
+                .with_state(profile_mod.enabled)
                 .with_index(index)
-                .with_time(install_time),
+                .with_time(profile_mod.install_time)
+                .with_tags(profile_mod.tags.clone())
+                .with_note(profile_mod.note.clone())
+                .with_local_only(profile_mod.local_only),
         )
     };
 
@@ -106,27 +122,79 @@ pub async fn change_version(mod_id: ModId, app: &AppHandle) -> Result<()> {
 }
 
 pub async fn update_mods(uuids: Vec<Uuid>, respect_ignored: bool, app: &AppHandle) -> Result<()> {
-    let (profile_id, installs) = {
-        let mut manager = app.lock_manager();
-        let thunderstore = app.lock_thunderstore();
-        let install_queue = app.install_queue().handle();
+    let (profile_id, installs) = collect_updates(uuids, respect_ignored, app)?;
 
-        let profile = manager.active_profile_mut();
+    install_updates(installs, profile_id, app).await
+}
 
-        let installs = uuids
-            .into_iter()
-            .filter_map(|uuid| {
-                profile
-                    .check_update(uuid, respect_ignored, &thunderstore, &install_queue)
-                    .transpose()
-            })
-            .map_ok(ModInstall::from)
-            .collect::<Result<Vec<_>>>()?;
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeldBackUpdate {
+    /// The version that wasn't installed.
+    ident: VersionIdent,
+    #[serde(flatten)]
+    compat: Compatibility,
+}
 
-        (profile.id, installs)
-    };
+/// Like [`update_mods`], but skips versions that the community reports as broken
+/// on the installed game build. Returns the updates that were held back.
+pub async fn update_mods_safely(
+    uuids: Vec<Uuid>,
+    respect_ignored: bool,
+    app: &AppHandle,
+) -> Result<Vec<HeldBackUpdate>> {
+    let (profile_id, installs) = collect_updates(uuids, respect_ignored, app)?;
 
-    install_updates(installs, profile_id, app).await
+    let idents = installs
+        .iter()
+        .map(|install| install.ident().clone())
+        .collect_vec();
+    let mut reports = compat::get(idents, app).await?;
+
+    let (installs, held_back): (Vec<_>, Vec<_>) = installs.into_iter().partition_map(|install| {
+        let ident = install.ident().clone();
+
+        match reports.remove(&ident.to_string()) {
+            Some(compat) if compat.is_broken() => Either::Right(HeldBackUpdate { ident, compat }),
+            _ => Either::Left(install),
+        }
+    });
+
+    if !held_back.is_empty() {
+        info!(
+            "held back {} updates reported as broken: {}",
+            held_back.len(),
+            held_back.iter().map(|update| &update.ident).join(", ")
+        );
+    }
+
+    install_updates(installs, profile_id, app).await?;
+
+    Ok(held_back)
+}
+
+fn collect_updates(
+    uuids: Vec<Uuid>,
+    respect_ignored: bool,
+    app: &AppHandle,
+) -> Result<(i64, Vec<ModInstall>)> {
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+    let install_queue = app.install_queue().handle();
+
+    let profile = manager.active_profile();
+
+    let installs = uuids
+        .into_iter()
+        .filter_map(|uuid| {
+            profile
+                .check_update(uuid, respect_ignored, &thunderstore, &install_queue)
+                .transpose()
+        })
+        .map_ok(ModInstall::from)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((profile.id, installs))
 }
 
 async fn install_updates(
@@ -134,7 +202,13 @@ async fn install_updates(
     profile_id: i64,
     app: &AppHandle,
 ) -> Result<()> {
-    app.install_queue()
+    let idents = installs
+        .iter()
+        .map(|install| install.ident().to_string())
+        .collect_vec();
+
+    let result = app
+        .install_queue()
         .install_with_deps(
             installs,
             profile_id,
@@ -154,6 +228,13 @@ async fn install_updates(
             true,
             app,
         )?
-        .await
-        .ignore_cancel()
+        .await;
+
+    if result.is_ok() && !idents.is_empty() {
+        if let Ok((_, profile)) = app.lock_manager().profile_by_id(profile_id) {
+            webhook::fire(WebhookEvent::UpdatesApplied, profile, idents, app);
+        }
+    }
+
+    result.ignore_cancel()
 }
@@ -0,0 +1,180 @@
+//! Sends notifications about profile events to user-configured webhooks.
+//!
+//! Payloads are Discord-compatible by default (`{"content": "..."}`). A webhook can
+//! instead specify a template, in which placeholders such as `{{profile}}` are replaced.
+//! If the rendered template is valid JSON, it's sent as the body directly. The values
+//! are escaped for that, so that a profile name with quotes can't break the payload.
+
+use std::{
+    collections::HashSet,
+    sync::{LazyLock, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::AppHandle;
+use tracing::{debug, warn};
+
+use crate::{
+    i18n,
+    profile::Profile,
+    state::ManagerExt,
+    thunderstore::{Thunderstore, VersionIdent},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookEvent {
+    UpdatesAvailable,
+    UpdatesApplied,
+    SyncPushed,
+}
+
+impl WebhookEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::UpdatesAvailable => "updates available",
+            WebhookEvent::UpdatesApplied => "updates applied",
+            WebhookEvent::SyncPushed => "sync pushed",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub url: String,
+    pub events: HashSet<WebhookEvent>,
+    /// Supports `{{event}}`, `{{profile}}`, `{{game}}`, `{{count}}` and `{{mods}}`.
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+/// Mod versions we've already sent an "updates available" event for, so that the
+/// same updates aren't announced again after every fetch.
+static NOTIFIED_UPDATES: LazyLock<Mutex<HashSet<(i64, VersionIdent)>>> =
+    LazyLock::new(Default::default);
+
+struct Context {
+    event: WebhookEvent,
+    profile: String,
+    game: String,
+    mods: Vec<String>,
+}
+
+impl Context {
+    fn new(event: WebhookEvent, profile: &Profile, mods: Vec<String>) -> Self {
+        Self {
+            event,
+            profile: profile.name.clone(),
+            game: profile.game.name.to_owned(),
+            mods,
+        }
+    }
+
+    /// Replaces the placeholders in `template` with the values, passed through `encode`.
+    fn render(&self, template: &str, encode: impl Fn(&str) -> String) -> String {
+        template
+            .replace("{{event}}", &encode(self.event.as_str()))
+            .replace("{{profile}}", &encode(&self.profile))
+            .replace("{{game}}", &encode(&self.game))
+            .replace("{{count}}", &self.mods.len().to_string())
+            .replace("{{mods}}", &encode(&self.mods.join("\n")))
+    }
+
+    fn default_message(&self) -> String {
+        let count = self.mods.len();
+        let summary = match self.event {
+            WebhookEvent::UpdatesAvailable => {
+                i18n::t("webhook.updates_available", &[("count", &count)])
+            }
+            WebhookEvent::UpdatesApplied => {
+                i18n::t("webhook.updates_applied", &[("count", &count)])
+            }
+            WebhookEvent::SyncPushed => i18n::t("webhook.sync_pushed", &[]),
+        };
+
+        let mut message = format!("**{}** ({}): {}", self.profile, self.game, summary);
+
+        for name in &self.mods {
+            message.push_str("\n- ");
+            message.push_str(name);
+        }
+
+        message
+    }
+
+    fn body(&self, webhook: &Webhook) -> Value {
+        match &webhook.template {
+            Some(template) => serde_json::from_str(&self.render(template, escape_json))
+                .unwrap_or_else(|_| json!({ "content": self.render(template, str::to_owned) })),
+            None => json!({ "content": self.default_message() }),
+        }
+    }
+}
+
+/// Escapes `value` to go inside a JSON string.
+fn escape_json(value: &str) -> String {
+    let quoted = Value::from(value).to_string();
+    quoted[1..quoted.len() - 1].to_owned()
+}
+
+/// Fires `event` for a profile to all webhooks subscribed to it.
+///
+/// This returns immediately; requests are sent in the background and failures are only logged.
+pub fn fire(event: WebhookEvent, profile: &Profile, mods: Vec<String>, app: &AppHandle) {
+    let context = Context::new(event, profile, mods);
+
+    let app = app.to_owned();
+    tauri::async_runtime::spawn(async move {
+        let requests = app
+            .lock_prefs()
+            .webhooks
+            .iter()
+            .filter(|webhook| webhook.events.contains(&event))
+            .map(|webhook| (webhook.url.clone(), context.body(webhook)))
+            .collect::<Vec<_>>();
+
+        for (url, body) in requests {
+            debug!("sending webhook for {:?} to {}", event, url);
+
+            let result = app
+                .http()
+                .post(&url)
+                .json(&body)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            if let Err(err) = result {
+                warn!("failed to send webhook to {}: {}", url, err);
+            }
+        }
+    });
+}
+
+/// Fires [`WebhookEvent::UpdatesAvailable`] for any updates in the profile
+/// that haven't been announced yet.
+pub fn notify_updates(profile: &Profile, thunderstore: &Thunderstore, app: &AppHandle) {
+    let install_queue = app.install_queue().handle();
+    let mut notified = NOTIFIED_UPDATES.lock().unwrap();
+
+    let new_updates = profile
+        .mods
+        .iter()
+        .filter_map(|profile_mod| {
+            profile
+                .check_update(profile_mod.uuid(), true, thunderstore, &install_queue)
+                .ok()
+                .flatten()
+        })
+        .filter(|update| notified.insert((profile.id, update.latest.ident.clone())))
+        .map(|update| update.latest.ident.to_string())
+        .collect::<Vec<_>>();
+
+    drop(install_queue);
+
+    if !new_updates.is_empty() {
+        fire(WebhookEvent::UpdatesAvailable, profile, new_updates, app);
+    }
+}
@@ -0,0 +1,65 @@
+//! Steam Workshop items that a profile depends on.
+//!
+//! Some games distribute part of their mods through the Workshop, with the mod loader
+//! only referencing them. Gale can't install those itself, so the profile keeps track of
+//! their IDs and the user subscribes to them through the Steam client.
+//!
+//! Subscriptions are never changed by Gale. The Steam client has no protocol for it,
+//! and steamcmd only downloads items without subscribing the account. Adding an item
+//! opens its page instead, and removing one leaves the subscription alone, since other
+//! profiles may still use it.
+
+use eyre::{ensure, eyre, Context, OptionExt, Result};
+use serde::{Deserialize, Serialize};
+
+use super::Profile;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkshopItem {
+    pub id: u64,
+    /// A name to show instead of the ID, since Gale doesn't query the Workshop.
+    pub name: Option<String>,
+}
+
+impl Profile {
+    pub fn add_workshop_item(&mut self, item: WorkshopItem) -> Result<()> {
+        ensure!(
+            !self.workshop_items.iter().any(|other| other.id == item.id),
+            "workshop item is already in the profile"
+        );
+
+        self.workshop_items.push(item);
+        Ok(())
+    }
+
+    pub fn remove_workshop_item(&mut self, id: u64) -> Result<()> {
+        let index = self
+            .workshop_items
+            .iter()
+            .position(|item| item.id == id)
+            .ok_or_eyre("workshop item not found")?;
+
+        self.workshop_items.remove(index);
+        Ok(())
+    }
+}
+
+/// Parses a Workshop item ID, either on its own or from the item's page URL.
+pub fn parse_id(input: &str) -> Result<u64> {
+    let input = input.trim();
+
+    let id = match input.split_once("id=") {
+        Some((_, rest)) => rest.split('&').next().unwrap_or(rest),
+        None => input,
+    };
+
+    id.parse()
+        .map_err(|_| eyre!("{} is not a valid workshop item", input))
+}
+
+/// Opens the item's page in the Steam client, where the user can subscribe or unsubscribe.
+pub fn open_in_steam(id: u64) -> Result<()> {
+    open::that_detached(format!("steam://url/CommunityFilePage/{id}"))
+        .context("failed to open steam")
+}
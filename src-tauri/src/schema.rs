@@ -0,0 +1,28 @@
+//! Versioning of the payloads exchanged with the frontend.
+//!
+//! [`VERSION`] is bumped whenever a command or event payload changes shape. The frontend
+//! sends its own copy on startup and is turned away if they differ, so a stale frontend
+//! fails loudly instead of misreading payloads later on.
+//!
+//! Payloads deriving [`ts_rs::TS`] have their TypeScript types generated into
+//! `src/lib/bindings` by `cargo test export_bindings` (`pnpm types`).
+
+use eyre::eyre;
+use tauri::command;
+
+use crate::util::cmd::Result;
+
+pub const VERSION: u32 = 1;
+
+#[command]
+pub fn check_schema_version(version: u32) -> Result<()> {
+    if version != VERSION {
+        return Err(eyre!(
+            "the frontend expects schema version {version}, but the backend uses version \
+             {VERSION}. Try reinstalling Gale"
+        )
+        .into());
+    }
+
+    Ok(())
+}
@@ -1,13 +1,18 @@
-use std::sync::{Mutex, MutexGuard};
+use std::{
+    sync::{Mutex, MutexGuard},
+    time::Duration,
+};
 
 use eyre::{Context, Result};
 use tauri::{command, AppHandle, Manager};
 
 use crate::{
     db::{self, Db},
+    ipc,
     prefs::Prefs,
-    profile::{self, install::queue::InstallQueue, sync, ModManager},
+    profile::{self, install::queue::InstallQueue, lan, sync, ModManager},
     thunderstore::{self, Thunderstore},
+    util,
 };
 
 pub struct AppState {
@@ -19,6 +24,8 @@ pub struct AppState {
     pub install_queue: InstallQueue,
     pub sync_auth: sync::auth::State,
     pub sync_socket: sync::socket::State,
+    pub lan_share: lan::State,
+    pub ipc_server: ipc::State,
     pub is_first_run: bool,
 }
 
@@ -36,9 +43,13 @@ impl AppState {
     }
 }
 
+/// Other timeouts are per request, see [`util::net`].
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
 pub fn setup(app: &AppHandle) -> Result<()> {
     let http = reqwest::Client::builder()
         .user_agent(concat!("Kesomannen-Gale/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(CONNECT_TIMEOUT)
         .build()
         .context("failed to init http client")?;
 
@@ -60,6 +71,8 @@ pub fn setup(app: &AppHandle) -> Result<()> {
         sync_auth: sync::auth::State::new(creds),
         sync_socket: sync::socket::State::new(app.to_owned()),
         install_queue: InstallQueue::new(app.to_owned()),
+        lan_share: lan::State::default(),
+        ipc_server: ipc::State::default(),
         is_first_run: !db_existed && !migrated,
     };
 
@@ -108,6 +121,14 @@ pub trait ManagerExt<R> {
     fn sync_socket(&self) -> &sync::socket::State {
         &self.app_state().sync_socket
     }
+
+    fn lan_share(&self) -> &lan::State {
+        &self.app_state().lan_share
+    }
+
+    fn ipc_server(&self) -> &ipc::State {
+        &self.app_state().ipc_server
+    }
 }
 
 impl<T, R> ManagerExt<R> for T
@@ -124,3 +145,8 @@ where
 pub fn is_first_run(app: AppHandle) -> bool {
     app.app_state().is_first_run
 }
+
+#[command]
+pub fn is_portable() -> bool {
+    util::path::is_portable()
+}
@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use tauri::{command, AppHandle};
+
+use super::{Theme, ThemeInfo};
+use crate::{state::ManagerExt, util::cmd::Result};
+
+#[command]
+pub fn get_themes(app: AppHandle) -> Result<Vec<ThemeInfo>> {
+    let themes = super::list(&app.lock_prefs())?;
+
+    Ok(themes)
+}
+
+#[command]
+pub fn get_theme(id: String, app: AppHandle) -> Result<Theme> {
+    let theme = super::get(&id, &app.lock_prefs())?;
+
+    Ok(theme)
+}
+
+#[command]
+pub fn import_theme(path: PathBuf, app: AppHandle) -> Result<ThemeInfo> {
+    let info = super::import(&path, &app.lock_prefs())?;
+
+    Ok(info)
+}
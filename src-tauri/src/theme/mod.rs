@@ -0,0 +1,216 @@
+//! User-provided theme packs, stored in `<data dir>/themes`.
+//!
+//! A theme is either a JSON file with color palettes and/or raw CSS variables,
+//! or a CSS file containing only custom property declarations. Themes are validated
+//! when loaded so that they can't pull in remote resources or inject other CSS.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{bail, ensure, Context, OptionExt, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{prefs::Prefs, util::error::IoResultExt};
+
+pub mod commands;
+
+const SHADES: &[&str] = &[
+    "50", "100", "200", "300", "400", "500", "600", "700", "800", "900", "950",
+];
+const CATEGORIES: &[&str] = &["primary", "accent"];
+
+/// The on-disk format of JSON themes.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThemeFile {
+    name: Option<String>,
+    author: Option<String>,
+    #[serde(default)]
+    palettes: BTreeMap<String, BTreeMap<String, String>>,
+    #[serde(default)]
+    variables: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeInfo {
+    /// The file name of the theme, used to refer to it in prefs.
+    pub id: String,
+    pub name: String,
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Theme {
+    #[serde(flatten)]
+    pub info: ThemeInfo,
+    /// CSS custom properties to set on the document root.
+    pub variables: BTreeMap<String, String>,
+}
+
+fn themes_dir(prefs: &Prefs) -> PathBuf {
+    prefs.data_dir.join("themes")
+}
+
+/// Lists the valid themes in the themes directory, skipping broken ones.
+pub fn list(prefs: &Prefs) -> Result<Vec<ThemeInfo>> {
+    let dir = themes_dir(prefs);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut themes = Vec::new();
+
+    for entry in dir
+        .read_dir()
+        .fs_context("reading themes directory", &dir)?
+    {
+        let path = entry?.path();
+        if !is_theme_file(&path) {
+            continue;
+        }
+
+        match read(&path) {
+            Ok(theme) => themes.push(theme.info),
+            Err(err) => warn!("skipping invalid theme at {}: {:#}", path.display(), err),
+        }
+    }
+
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(themes)
+}
+
+pub fn get(id: &str, prefs: &Prefs) -> Result<Theme> {
+    ensure!(!id.contains(['/', '\\']) && id != "..", "invalid theme id");
+
+    read(&themes_dir(prefs).join(id))
+}
+
+/// Validates a theme file and copies it into the themes directory.
+pub fn import(path: &Path, prefs: &Prefs) -> Result<ThemeInfo> {
+    ensure!(is_theme_file(path), "themes must be .json or .css files");
+
+    let theme = read(path)?;
+
+    let dir = themes_dir(prefs);
+    fs::create_dir_all(&dir).fs_context("creating themes directory", &dir)?;
+
+    let target = dir.join(&theme.info.id);
+    fs::copy(path, &target).fs_context("copying theme", &target)?;
+
+    Ok(theme.info)
+}
+
+fn is_theme_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|ext| ext == "json" || ext == "css")
+}
+
+fn read(path: &Path) -> Result<Theme> {
+    let id = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_eyre("invalid theme file name")?
+        .to_owned();
+
+    let default_name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| id.clone());
+
+    let text = fs::read_to_string(path).fs_context("reading theme", path)?;
+
+    let file = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&text).context("failed to parse theme")?,
+        Some("css") => ThemeFile {
+            name: None,
+            author: None,
+            palettes: BTreeMap::new(),
+            variables: parse_css(&text)?,
+        },
+        _ => bail!("unsupported theme format"),
+    };
+
+    let mut variables = BTreeMap::new();
+
+    for (category, shades) in file.palettes {
+        ensure!(
+            CATEGORIES.contains(&category.as_str()),
+            "unknown palette {}",
+            category
+        );
+
+        for (shade, value) in shades {
+            ensure!(SHADES.contains(&shade.as_str()), "unknown shade {}", shade);
+            variables.insert(format!("--color-{category}-{shade}"), value);
+        }
+    }
+
+    variables.extend(file.variables);
+
+    for (name, value) in &variables {
+        validate_variable(name, value)?;
+    }
+
+    Ok(Theme {
+        info: ThemeInfo {
+            id,
+            name: file.name.unwrap_or(default_name),
+            author: file.author,
+        },
+        variables,
+    })
+}
+
+/// Reads custom property declarations, optionally wrapped in a `:root` block.
+fn parse_css(text: &str) -> Result<BTreeMap<String, String>> {
+    let body = text.trim();
+    let body = match body.strip_prefix(":root") {
+        Some(rest) => rest
+            .trim()
+            .strip_prefix('{')
+            .and_then(|rest| rest.trim_end().strip_suffix('}'))
+            .ok_or_eyre("malformed :root block")?,
+        None => body,
+    };
+
+    body.split(';')
+        .map(str::trim)
+        .filter(|declaration| !declaration.is_empty())
+        .map(|declaration| {
+            let (name, value) = declaration
+                .split_once(':')
+                .ok_or_eyre("malformed declaration")?;
+
+            Ok((name.trim().to_owned(), value.trim().to_owned()))
+        })
+        .collect()
+}
+
+fn validate_variable(name: &str, value: &str) -> Result<()> {
+    let valid_name = name.strip_prefix("--").is_some_and(|rest| {
+        !rest.is_empty()
+            && rest
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    });
+    ensure!(valid_name, "invalid variable name {}", name);
+
+    let lower = value.to_ascii_lowercase();
+    ensure!(
+        !value.contains([';', '{', '}', '<', '>', '\\'])
+            && !lower.contains("url(")
+            && !lower.contains("@import"),
+        "invalid value for {}",
+        name
+    );
+
+    Ok(())
+}
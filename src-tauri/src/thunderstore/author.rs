@@ -0,0 +1,109 @@
+//! Tracks the packages in namespaces the user has registered as their own
+//! (see [`Prefs::owned_namespaces`](crate::prefs::Prefs::owned_namespaces)).
+//!
+//! Download counts and ratings are snapshotted once a day after fetching packages,
+//! so they can be shown over time.
+
+use eyre::Result;
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::debug;
+use uuid::Uuid;
+
+use super::{PackageListing, Thunderstore, VersionIdent};
+use crate::{db::stats::PackageStats, state::ManagerExt};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorPackage {
+    pub uuid: Uuid,
+    pub full_name: String,
+    pub latest_version: String,
+    pub downloads: u32,
+    pub rating: u32,
+    pub history: Vec<PackageStats>,
+    /// Dependencies of the latest version which have newer versions available.
+    pub outdated_dependencies: Vec<OutdatedDependency>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutdatedDependency {
+    pub current: VersionIdent,
+    pub latest: VersionIdent,
+}
+
+fn owned_packages<'a>(
+    namespaces: &'a [String],
+    thunderstore: &'a Thunderstore,
+) -> impl Iterator<Item = &'a PackageListing> + 'a {
+    thunderstore.packages.values().filter(|package| {
+        namespaces
+            .iter()
+            .any(|namespace| namespace.eq_ignore_ascii_case(package.owner()))
+    })
+}
+
+/// Records today's statistics of the user's packages.
+pub fn record_stats(app: &AppHandle) -> Result<()> {
+    let namespaces = app.lock_prefs().owned_namespaces.clone();
+
+    if namespaces.is_empty() {
+        return Ok(());
+    }
+
+    let thunderstore = app.lock_thunderstore();
+    let mut count = 0;
+
+    for package in owned_packages(&namespaces, &thunderstore) {
+        app.db().record_package_stats(
+            package.uuid,
+            package.total_downloads(),
+            package.rating_score,
+        )?;
+        count += 1;
+    }
+
+    debug!("recorded stats of {} owned packages", count);
+
+    Ok(())
+}
+
+pub fn dashboard(app: &AppHandle) -> Result<Vec<AuthorPackage>> {
+    let namespaces = app.lock_prefs().owned_namespaces.clone();
+    let thunderstore = app.lock_thunderstore();
+
+    owned_packages(&namespaces, &thunderstore)
+        .map(|package| {
+            let latest = package.latest();
+
+            Ok(AuthorPackage {
+                uuid: package.uuid,
+                full_name: package.full_name().to_owned(),
+                latest_version: latest.version().to_owned(),
+                downloads: package.total_downloads(),
+                rating: package.rating_score,
+                history: app.db().get_package_stats(package.uuid)?,
+                outdated_dependencies: outdated_dependencies(&latest.dependencies, &thunderstore),
+            })
+        })
+        .collect()
+}
+
+fn outdated_dependencies(
+    dependencies: &[VersionIdent],
+    thunderstore: &Thunderstore,
+) -> Vec<OutdatedDependency> {
+    dependencies
+        .iter()
+        .filter_map(|current| {
+            let package = thunderstore.find_package(current.full_name()).ok()?;
+            let latest = &package.latest().ident;
+
+            (latest != current).then(|| OutdatedDependency {
+                current: current.clone(),
+                latest: latest.clone(),
+            })
+        })
+        .collect()
+}
@@ -1,5 +1,6 @@
-use std::{fmt::Display, path::PathBuf, time::Instant};
+use std::{fmt::Display, fs, path::PathBuf, time::Instant};
 
+use chrono::DateTime;
 use eyre::{Context, Result};
 use serde::Deserialize;
 use tauri::AppHandle;
@@ -51,7 +52,8 @@ pub async fn get_markdown(
         let ident = mod_id.borrow(&thunderstore)?.ident();
 
         format!(
-            "https://thunderstore.io/api/experimental/package/{}/{}/{}/{}/",
+            "{}/api/experimental/package/{}/{}/{}/{}/",
+            *super::API_URL,
             ident.owner(),
             ident.name(),
             ident.version(),
@@ -81,6 +83,12 @@ impl Thunderstore {
                 for package in mods {
                     self.packages.insert(package.uuid, package);
                 }
+
+                self.count_dependants();
+                self.last_fetched = fs::metadata(cache_path(game, prefs))
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .map(DateTime::from);
             }
             Ok(None) => (),
             Err(err) => warn!("failed to read cache: {}", err),
@@ -88,7 +96,7 @@ impl Thunderstore {
     }
 }
 
-fn get_packages(game: Game, prefs: &Prefs) -> Result<Option<Vec<PackageListing>>> {
+pub(super) fn get_packages(game: Game, prefs: &Prefs) -> Result<Option<Vec<PackageListing>>> {
     let start = Instant::now();
     let path = cache_path(game, prefs);
 
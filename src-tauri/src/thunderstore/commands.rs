@@ -1,14 +1,22 @@
 use eyre::anyhow;
-use tauri::{command, AppHandle};
+use tauri::{command, AppHandle, Url};
+use uuid::Uuid;
 
 use super::{
+    author::AuthorPackage,
+    contents::PackageFile,
+    feed::RecentlyUpdated,
+    links::PackageLinks,
+    markdown,
     models::FrontendMod,
     query::{self, QueryModsArgs},
+    rating::RateResult,
+    search::GlobalSearchResult,
 };
 use crate::{
     logger,
     state::ManagerExt,
-    thunderstore::{cache::MarkdownKind, ModId},
+    thunderstore::{cache::MarkdownKind, IndexStatus, ModId},
     util::cmd::Result,
 };
 
@@ -31,6 +39,20 @@ pub fn stop_querying_thunderstore(app: AppHandle) {
     app.lock_thunderstore().current_query = None;
 }
 
+#[command]
+pub fn get_index_status(app: AppHandle) -> IndexStatus {
+    super::fetch::status(&app)
+}
+
+/// Fetches the package index right away. Progress is reported through `status_update` events.
+#[command]
+pub async fn refresh_index(app: AppHandle) -> Result<IndexStatus> {
+    let game = app.lock_manager().active_game;
+    super::refresh_packages(game, &app).await?;
+
+    Ok(super::fetch::status(&app))
+}
+
 #[command]
 pub fn trigger_mod_fetch(app: AppHandle) -> Result<()> {
     let write_directly = {
@@ -60,8 +82,75 @@ pub async fn get_markdown(
     kind: MarkdownKind,
     app: AppHandle,
 ) -> Result<Option<String>> {
-    let content = super::cache::get_markdown(kind, mod_ref, &app).await?;
-    Ok(content)
+    // relative links point to files next to the readme on the package's page
+    let base = {
+        let game = app.lock_manager().active_game;
+        let thunderstore = app.lock_thunderstore();
+        mod_ref.borrow(&thunderstore)?.package.url(game)
+    };
+
+    let Some(content) = super::cache::get_markdown(kind, mod_ref, &app).await? else {
+        return Ok(None);
+    };
+
+    Ok(Some(markdown::render(&content, Url::parse(&base).ok())))
+}
+
+#[command]
+pub fn render_markdown(source: String) -> String {
+    markdown::render(&source, None)
+}
+
+#[command]
+pub async fn get_package_links(mod_ref: ModId, app: AppHandle) -> Result<PackageLinks> {
+    let links = super::links::resolve(mod_ref, &app).await?;
+    Ok(links)
+}
+
+#[command]
+pub async fn search_all_games(
+    query: String,
+    max_count: usize,
+    app: AppHandle,
+) -> Result<Vec<GlobalSearchResult>> {
+    let results = tauri::async_runtime::spawn_blocking(move || {
+        super::search::search(&query, max_count, &app)
+    })
+    .await??;
+
+    Ok(results)
+}
+
+#[command]
+pub async fn get_package_contents(mod_ref: ModId, app: AppHandle) -> Result<Vec<PackageFile>> {
+    let ident = mod_ref.borrow(&app.lock_thunderstore())?.ident().clone();
+
+    let files = super::contents::list(&ident, &app).await?;
+    Ok(files)
+}
+
+#[command]
+pub fn get_recently_updated(app: AppHandle) -> Result<RecentlyUpdated> {
+    let feed = super::feed::recently_updated(&app)?;
+    Ok(feed)
+}
+
+#[command]
+pub fn mark_feed_visited(app: AppHandle) -> Result<()> {
+    super::feed::mark_visited(&app)?;
+    Ok(())
+}
+
+#[command]
+pub async fn rate_package(package_uuid: Uuid, rated: bool, app: AppHandle) -> Result<RateResult> {
+    let result = super::rating::rate(package_uuid, rated, &app).await?;
+    Ok(result)
+}
+
+#[command]
+pub fn get_author_dashboard(app: AppHandle) -> Result<Vec<AuthorPackage>> {
+    let packages = super::author::dashboard(&app)?;
+    Ok(packages)
 }
 
 #[command]
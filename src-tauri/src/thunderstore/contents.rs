@@ -0,0 +1,221 @@
+//! Lists and reads the files of a package without downloading the whole archive.
+//!
+//! The file list of a zip is stored in its central directory at the end of the archive,
+//! so we only need to request that part of the file using HTTP range requests. Single
+//! files can then be fetched the same way, using the offsets in the directory.
+
+use std::io::Read;
+
+use eyre::{bail, ensure, eyre, OptionExt, Result};
+use flate2::read::DeflateDecoder;
+use reqwest::{header, StatusCode};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use super::{download_url, VersionIdent};
+use crate::state::ManagerExt;
+
+/// End of central directory record signature.
+const EOCD_SIGNATURE: u32 = 0x06054b50;
+/// Central directory file header signature.
+const CDFH_SIGNATURE: u32 = 0x02014b50;
+/// Local file header signature.
+const LFH_SIGNATURE: u32 = 0x04034b50;
+/// Size of the EOCD record, excluding the comment.
+const EOCD_SIZE: usize = 22;
+/// Size of a central directory file header, excluding the variable length fields.
+const CDFH_SIZE: usize = 46;
+/// Size of a local file header, excluding the variable length fields.
+const LFH_SIZE: usize = 30;
+/// Local headers usually repeat the central directory's extra field, but they're allowed
+/// to differ. This much extra room is requested so we don't need another round trip.
+const LFH_EXTRA_SLACK: usize = 1024;
+/// Files larger than this aren't read into memory.
+const MAX_READ_SIZE: u64 = 1024 * 1024;
+/// The EOCD can be followed by a comment of at most this many bytes.
+const MAX_COMMENT_SIZE: usize = u16::MAX as usize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageFile {
+    pub path: String,
+    pub size: u64,
+    pub compressed_size: u64,
+    pub is_dir: bool,
+    #[serde(skip)]
+    compression: u16,
+    /// Offset of the file's local header in the archive.
+    #[serde(skip)]
+    offset: u64,
+}
+
+pub async fn list(ident: &VersionIdent, app: &AppHandle) -> Result<Vec<PackageFile>> {
+    let url = download_url(ident);
+
+    let tail = fetch_range(
+        &url,
+        format!("bytes=-{}", EOCD_SIZE + MAX_COMMENT_SIZE),
+        app,
+    )
+    .await?;
+
+    let eocd_pos = tail
+        .windows(4)
+        .rposition(|window| read_u32(window, 0) == EOCD_SIGNATURE)
+        .ok_or_eyre("archive is not a valid zip file")?;
+
+    let eocd = &tail[eocd_pos..];
+    ensure!(eocd.len() >= EOCD_SIZE, "archive is not a valid zip file");
+
+    let entry_count = read_u16(eocd, 10) as usize;
+    let cd_size = read_u32(eocd, 12);
+    let cd_offset = read_u32(eocd, 16);
+
+    if cd_size == u32::MAX || cd_offset == u32::MAX {
+        bail!("zip64 archives are not supported");
+    }
+
+    if entry_count == 0 {
+        return Ok(Vec::new());
+    }
+
+    // an empty central directory can't hold any entries, and would underflow the range
+    ensure!(cd_size > 0, "archive is not a valid zip file");
+
+    let range = format!(
+        "bytes={}-{}",
+        cd_offset,
+        cd_offset as u64 + cd_size as u64 - 1
+    );
+    let central_directory = fetch_range(&url, range, app).await?;
+
+    parse_central_directory(&central_directory, entry_count)
+}
+
+/// Reads a single small file from a package, like its manifest. Returns `None` if
+/// the package doesn't have a file at `path`.
+pub async fn read(ident: &VersionIdent, path: &str, app: &AppHandle) -> Result<Option<Vec<u8>>> {
+    let Some(file) = list(ident, app)
+        .await?
+        .into_iter()
+        .find(|file| file.path.eq_ignore_ascii_case(path))
+    else {
+        return Ok(None);
+    };
+
+    // the sizes come from the archive, so neither can be trusted to bound the other
+    ensure!(
+        file.size <= MAX_READ_SIZE && file.compressed_size <= MAX_READ_SIZE,
+        "{} is too large",
+        path
+    );
+
+    let end =
+        file.offset + (LFH_SIZE + file.path.len() + LFH_EXTRA_SLACK) as u64 + file.compressed_size;
+    let range = format!("bytes={}-{}", file.offset, end - 1);
+    let data = fetch_range(&download_url(ident), range, app).await?;
+
+    ensure!(
+        data.len() >= LFH_SIZE && read_u32(&data, 0) == LFH_SIGNATURE,
+        "invalid local file header"
+    );
+
+    let name_len = read_u16(&data, 26) as usize;
+    let extra_len = read_u16(&data, 28) as usize;
+    let start = LFH_SIZE + name_len + extra_len;
+
+    let compressed = data
+        .get(start..start + file.compressed_size as usize)
+        .ok_or_eyre("file data is truncated")?;
+
+    let bytes = match file.compression {
+        0 => compressed.to_vec(),
+        8 => {
+            let mut bytes = Vec::with_capacity(file.size as usize);
+            DeflateDecoder::new(compressed)
+                .take(MAX_READ_SIZE + 1)
+                .read_to_end(&mut bytes)?;
+
+            ensure!(bytes.len() as u64 <= MAX_READ_SIZE, "{} is too large", path);
+            bytes
+        }
+        method => bail!("unsupported compression method {}", method),
+    };
+
+    Ok(Some(bytes))
+}
+
+async fn fetch_range(url: &str, range: String, app: &AppHandle) -> Result<Vec<u8>> {
+    let response = app
+        .http()
+        .get(url)
+        .header(header::RANGE, range)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    // if the server ignores the range header, we'd end up downloading the whole file anyways
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+        bail!("server does not support range requests");
+    }
+
+    Ok(response.bytes().await?.to_vec())
+}
+
+fn parse_central_directory(data: &[u8], entry_count: usize) -> Result<Vec<PackageFile>> {
+    let mut files = Vec::with_capacity(entry_count);
+    let mut pos = 0;
+
+    for _ in 0..entry_count {
+        let header = data
+            .get(pos..pos + CDFH_SIZE)
+            .ok_or_eyre("central directory is truncated")?;
+
+        ensure!(
+            read_u32(header, 0) == CDFH_SIGNATURE,
+            "invalid central directory entry"
+        );
+
+        let compression = read_u16(header, 10);
+        let compressed_size = read_u32(header, 20) as u64;
+        let size = read_u32(header, 24) as u64;
+        let name_len = read_u16(header, 28) as usize;
+        let extra_len = read_u16(header, 30) as usize;
+        let comment_len = read_u16(header, 32) as usize;
+        let offset = read_u32(header, 42) as u64;
+
+        let name_start = pos + CDFH_SIZE;
+        let name = data
+            .get(name_start..name_start + name_len)
+            .ok_or_else(|| eyre!("central directory is truncated"))?;
+
+        let path = String::from_utf8_lossy(name).replace('\\', "/");
+        let is_dir = path.ends_with('/');
+
+        files.push(PackageFile {
+            path,
+            size,
+            compressed_size,
+            is_dir,
+            compression,
+            offset,
+        });
+
+        pos = name_start + name_len + extra_len + comment_len;
+    }
+
+    Ok(files)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
@@ -0,0 +1,53 @@
+//! Packages in the active community that were updated since the user last
+//! opened the "what's new" feed.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use eyre::Result;
+use itertools::Itertools;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use super::{FrontendMod, IntoFrontendMod};
+use crate::state::ManagerExt;
+
+/// How far back to look if the feed has never been opened for a game.
+const FIRST_VISIT_DAYS: i64 = 7;
+const MAX_ENTRIES: usize = 100;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentlyUpdated {
+    /// The previous visit, or `None` if the feed hasn't been opened for this game.
+    pub since: Option<DateTime<Utc>>,
+    /// Newest first.
+    pub mods: Vec<FrontendMod>,
+}
+
+pub fn recently_updated(app: &AppHandle) -> Result<RecentlyUpdated> {
+    let manager = app.lock_manager();
+    let thunderstore = app.lock_thunderstore();
+
+    let since = app.db().last_visit(&manager.active_game().game.slug)?;
+    let cutoff = since.unwrap_or_else(|| Utc::now() - TimeDelta::days(FIRST_VISIT_DAYS));
+
+    let profile = manager.active_profile();
+
+    let mods = thunderstore
+        .latest()
+        .filter(|borrowed| {
+            let package = borrowed.package;
+            package.date_updated > cutoff && !package.is_deprecated && !package.has_nsfw_content
+        })
+        .sorted_by(|a, b| b.package.date_updated.cmp(&a.package.date_updated))
+        .take(MAX_ENTRIES)
+        .map(|borrowed| borrowed.into_frontend(Some(profile)))
+        .collect();
+
+    Ok(RecentlyUpdated { since, mods })
+}
+
+/// Moves the start of the feed to now, so only later updates show up next time.
+pub fn mark_visited(app: &AppHandle) -> Result<()> {
+    let slug = app.lock_manager().active_game().game.slug.to_string();
+    app.db().record_visit(&slug, Utc::now())
+}
@@ -6,20 +6,87 @@ use std::{
 };
 
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
 use eyre::{Context, Result};
 use flate2::read::GzDecoder;
 use indexmap::IndexMap;
+use serde::Serialize;
 use tauri::{AppHandle, Emitter};
 use tokio::sync::mpsc;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, instrument, warn};
+
+use super::{author, rating};
+use crate::{
+    game::Game,
+    i18n, images, logger,
+    profile::webhook,
+    state::ManagerExt,
+    thunderstore::PackageListing,
+    util::net::{self, Operation},
+};
 
-use crate::{game::Game, logger, state::ManagerExt, thunderstore::PackageListing};
+/// Minutes between automatic fetches, unless the game's prefs say otherwise.
+const DEFAULT_FETCH_INTERVAL: u32 = 15;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexStatus {
+    pub last_fetched: Option<DateTime<Utc>>,
+    pub is_fetching: bool,
+    pub package_count: usize,
+    /// Minutes between automatic fetches for the active game.
+    pub fetch_interval: u32,
+    /// Whether the index is older than the fetch interval.
+    pub is_stale: bool,
+}
 
-pub(super) async fn fetch_package_loop(game: Game, app: AppHandle) {
-    const FETCH_INTERVAL: Duration = Duration::from_secs(60 * 15);
+fn fetch_interval(game: Game, app: &AppHandle) -> u32 {
+    app.lock_prefs()
+        .game_prefs
+        .get(&*game.slug)
+        .and_then(|prefs| prefs.fetch_interval)
+        .unwrap_or(DEFAULT_FETCH_INTERVAL)
+        .max(1)
+}
+
+/// How long until the next fetch is due, or `None` if it already is.
+fn time_until_due(game: Game, app: &AppHandle) -> Option<Duration> {
+    let interval = Duration::from_secs(u64::from(fetch_interval(game, app)) * 60);
+    let last_fetched = app.lock_thunderstore().last_fetched?;
+    let elapsed = (Utc::now() - last_fetched).to_std().ok()?;
 
+    interval.checked_sub(elapsed)
+}
+
+pub fn status(app: &AppHandle) -> IndexStatus {
+    let game = app.lock_manager().active_game;
+    let fetch_interval = fetch_interval(game, app);
+    let is_stale = time_until_due(game, app).is_none();
+
+    let thunderstore = app.lock_thunderstore();
+
+    IndexStatus {
+        last_fetched: thunderstore.last_fetched,
+        is_fetching: thunderstore.is_fetching,
+        package_count: thunderstore.packages.len(),
+        fetch_interval,
+        is_stale,
+    }
+}
+
+pub(super) async fn fetch_package_loop(game: Game, app: AppHandle) {
     let mut is_first = true;
 
+    // a recent cache is as good as a fetch, so wait until it goes stale
+    if let Some(remaining) = time_until_due(game, &app) {
+        info!("package cache is recent, fetching again in {:?}", remaining);
+
+        app.lock_thunderstore().packages_fetched = true;
+        is_first = false;
+
+        tokio::time::sleep(remaining).await;
+    }
+
     loop {
         let fetch_automatically = app.lock_prefs().fetch_mods_automatically;
 
@@ -29,11 +96,29 @@ pub(super) async fn fetch_package_loop(game: Game, app: AppHandle) {
             break;
         };
 
-        if let Err(err) = loop_iter(game, &mut is_first, &app).await {
-            logger::log_webview_err("Error while fetching packages from Thunderstore", err, &app);
+        match loop_iter(game, &mut is_first, &app).await {
+            Ok(()) => {
+                if let Err(err) = author::record_stats(&app) {
+                    warn!("failed to record package stats: {:#}", err);
+                }
+
+                if let Err(err) = rating::flush_queue(&app).await {
+                    warn!("failed to send queued ratings: {:#}", err);
+                }
+
+                images::pregenerate(app.clone());
+            }
+            Err(err) => {
+                logger::log_webview_err(
+                    "Error while fetching packages from Thunderstore",
+                    err,
+                    &app,
+                );
+            }
         }
 
-        tokio::time::sleep(FETCH_INTERVAL).await;
+        let interval = fetch_interval(game, &app);
+        tokio::time::sleep(Duration::from_secs(u64::from(interval) * 60)).await;
     }
 
     async fn loop_iter(game: Game, is_first: &mut bool, app: &AppHandle) -> Result<()> {
@@ -52,7 +137,13 @@ pub(super) async fn fetch_package_loop(game: Game, app: AppHandle) {
         *is_first &= result.is_err();
 
         // notify frontend to show any mod updates that were just fetched
-        app.lock_manager().active_profile().notify_frontend(app)?;
+        let manager = app.lock_manager();
+        let profile = manager.active_profile();
+        profile.notify_frontend(app)?;
+
+        if result.is_ok() {
+            webhook::notify_updates(profile, &state, app);
+        }
 
         result
     }
@@ -67,6 +158,7 @@ static EXCLUDED_PACKAGES: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
         .collect()
 });
 
+#[instrument(skip_all, fields(game = %game.slug))]
 pub(super) async fn fetch_packages(
     game: Game,
     write_directly: bool,
@@ -74,21 +166,15 @@ pub(super) async fn fetch_packages(
 ) -> Result<()> {
     let start_time = Instant::now();
 
-    let index_url = format!(
-        "https://thunderstore.io/c/{}/api/v1/package-listing-index/",
-        game.slug
-    );
+    let index_url = index_url(&super::API_URL, &game.slug);
 
-    let bytes = app
-        .http()
-        .get(index_url)
-        .send()
+    let bytes = net::send(app.http().get(index_url), Operation::Index, app)
         .await?
         .error_for_status()?
         .bytes()
         .await?;
 
-    let urls: Vec<String> = serde_json::from_reader(GzDecoder::new(&bytes[..]))?;
+    let urls = parse_index(&bytes)?;
 
     let mut package_count = 0;
     let mut package_buffer = IndexMap::new();
@@ -103,19 +189,8 @@ pub(super) async fn fetch_packages(
     });
 
     while let Some(chunk) = rx.recv().await {
-        let mut text = String::new();
-        let mut decoder = GzDecoder::new(&chunk[..]);
-        decoder.read_to_string(&mut text)?;
-
-        let packages: Vec<PackageListing> = serde_json::from_str(&text)?;
-
-        let packages = packages
+        let packages = parse_chunk(&chunk)?
             .into_iter()
-            .filter(|package| {
-                !EXCLUDED_PACKAGES
-                    .iter()
-                    .any(|excluded| package.full_name() == *excluded)
-            })
             .map(|package| (package.uuid, package));
 
         if write_directly {
@@ -137,11 +212,14 @@ pub(super) async fn fetch_packages(
 
     state.packages_fetched = true;
     state.is_fetching = false;
+    state.last_fetched = Some(Utc::now());
 
     if !write_directly {
         state.packages = package_buffer;
     }
 
+    state.count_dependants();
+
     debug!(
         "fetched {} packages for {} in {:?}",
         state.packages.len(),
@@ -156,7 +234,7 @@ pub(super) async fn fetch_packages(
     fn emit_update(mods: usize, app: &AppHandle) {
         app.emit(
             "status_update",
-            Some(format!("Fetching mods from Thunderstore... {mods}")),
+            Some(i18n::t("fetch.status", &[("count", &mods)])),
         )
         .ok();
     }
@@ -167,7 +245,10 @@ pub(super) async fn fetch_packages(
         app: AppHandle,
     ) -> Result<()> {
         for url in urls {
-            let bytes = app.http().get(url).send().await?.bytes().await?;
+            let bytes = net::send(app.http().get(url), Operation::Index, &app)
+                .await?
+                .bytes()
+                .await?;
             tx.send(bytes)
                 .await
                 .context("chunk channel closed too early")?;
@@ -177,6 +258,51 @@ pub(super) async fn fetch_packages(
     }
 }
 
+/// The index lists the URLs of the chunks that make up a game's package listing.
+pub(super) fn index_url(base: &str, game_slug: &str) -> String {
+    format!("{base}/c/{game_slug}/api/v1/package-listing-index/")
+}
+
+pub(super) fn parse_index(bytes: &[u8]) -> Result<Vec<String>> {
+    let urls = serde_json::from_reader(GzDecoder::new(bytes))?;
+    Ok(urls)
+}
+
+/// Decodes a chunk of the package listing, leaving out excluded packages.
+pub(super) fn parse_chunk(bytes: &[u8]) -> Result<Vec<PackageListing>> {
+    let mut text = String::new();
+    GzDecoder::new(bytes).read_to_string(&mut text)?;
+
+    let packages: Vec<PackageListing> = serde_json::from_str(&text)?;
+
+    Ok(packages
+        .into_iter()
+        .filter(|package| {
+            !EXCLUDED_PACKAGES
+                .iter()
+                .any(|excluded| package.full_name() == *excluded)
+        })
+        .collect())
+}
+
+/// Fetches the package list once, unless a fetch is already in progress.
+pub async fn refresh_packages(game: Game, app: &AppHandle) -> Result<()> {
+    {
+        let mut state = app.lock_thunderstore();
+        if state.is_fetching {
+            debug!("refresh skipped due to ongoing fetch");
+            return Ok(());
+        }
+
+        state.is_fetching = true;
+    }
+
+    let result = fetch_packages(game, false, app).await;
+    app.lock_thunderstore().is_fetching = false;
+
+    result
+}
+
 pub async fn wait_for_fetch(app: &AppHandle) {
     loop {
         if app.lock_thunderstore().packages_fetched() {
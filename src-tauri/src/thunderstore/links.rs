@@ -0,0 +1,121 @@
+//! Resolves the links shown for each package, so they don't have to be looked up
+//! when they're clicked. Results are cached in the database by version.
+
+use eyre::Result;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Url};
+use tracing::warn;
+
+use super::{
+    cache::{self, MarkdownKind},
+    ModId,
+};
+use crate::state::ManagerExt;
+
+const TABLE: &str = "links_cache";
+
+/// Hosts whose links are assumed to point at source repositories.
+const FORGES: &[&str] = &["github.com", "gitlab.com", "codeberg.org", "bitbucket.org"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageLinks {
+    /// The package's page on Thunderstore.
+    thunderstore: String,
+    /// The website from the package's manifest.
+    website: Option<String>,
+    /// The source repository, taken from the website or the readme.
+    source: Option<String>,
+    donation: Option<String>,
+}
+
+/// Gets the links for a package version, resolving them if they aren't cached yet.
+pub async fn resolve(mod_id: ModId, app: &AppHandle) -> Result<PackageLinks> {
+    if let Some(Some(cached)) = app.db().get_cached(TABLE, mod_id.version_uuid)? {
+        match serde_json::from_str(&cached) {
+            Ok(links) => return Ok(links),
+            Err(err) => warn!("failed to parse cached links: {:#}", err),
+        }
+    }
+
+    let (thunderstore, website, donation) = {
+        let game = app.lock_manager().active_game;
+        let thunderstore = app.lock_thunderstore();
+        let borrowed = mod_id.borrow(&thunderstore)?;
+
+        let website = borrowed.version.website_url.as_str();
+        let donation = borrowed.package.donation_link.as_deref();
+
+        (
+            borrowed.package.url(game),
+            parse_web_url(website),
+            donation.and_then(parse_web_url),
+        )
+    };
+
+    let source = match website.as_deref().filter(|url| is_repo(url)) {
+        Some(url) => Some(url.to_owned()),
+        None => cache::get_markdown(MarkdownKind::Readme, mod_id, app)
+            .await
+            .inspect_err(|err| warn!("failed to get readme to find source link: {:#}", err))
+            .ok()
+            .flatten()
+            .and_then(|readme| find_repo(&readme)),
+    };
+
+    let links = PackageLinks {
+        thunderstore,
+        website,
+        source,
+        donation,
+    };
+
+    let content = serde_json::to_string(&links)?;
+    app.db()
+        .insert_cached(TABLE, mod_id.version_uuid, Some(&content))?;
+
+    Ok(links)
+}
+
+/// Only allows http(s) links, since these end up being opened by the system.
+fn parse_web_url(str: &str) -> Option<String> {
+    let url = Url::parse(str.trim()).ok()?;
+
+    matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+}
+
+/// Whether `url` points to a repository on a known forge, like `https://github.com/owner/repo`.
+fn is_repo(url: &str) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return false;
+    };
+
+    let on_forge = url
+        .host_str()
+        .is_some_and(|host| FORGES.contains(&host.trim_start_matches("www.")));
+
+    let segments = url.path_segments().map_or(0, |segments| {
+        segments.filter(|segment| !segment.is_empty()).count()
+    });
+
+    on_forge && segments >= 2
+}
+
+/// Finds the first repository link in a readme, trimmed to the repository itself.
+fn find_repo(readme: &str) -> Option<String> {
+    readme
+        .split(|char: char| char.is_whitespace() || "()[]<>\"'".contains(char))
+        .filter(|word| word.starts_with("https://") || word.starts_with("http://"))
+        .find(|word| is_repo(word))
+        .and_then(|word| {
+            let mut url = Url::parse(word).ok()?;
+            let segments = url.path_segments()?.take(2).join("/");
+
+            url.set_path(segments.trim_end_matches(".git"));
+            url.set_query(None);
+            url.set_fragment(None);
+
+            Some(url.to_string())
+        })
+}
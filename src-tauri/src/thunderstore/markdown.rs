@@ -0,0 +1,68 @@
+//! Renders READMEs and changelogs to HTML before they reach the webview.
+//!
+//! Package authors can put raw HTML in their markdown, so the output goes through
+//! [`ammonia`], which only keeps a known set of tags and attributes. Relative links are
+//! resolved against the package's page on Thunderstore, and embedded images are routed
+//! through the local image cache.
+
+use std::borrow::Cow;
+
+use ammonia::{Builder, UrlRelative};
+use pulldown_cmark::{html, Options, Parser};
+use tauri::Url;
+
+use crate::images;
+
+/// Renders `source` to sanitized HTML. Relative links are resolved against `base`,
+/// or dropped if there is none.
+pub fn render(source: &str, base: Option<Url>) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+
+    let mut unsafe_html = String::with_capacity(source.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, Parser::new_ext(source, options));
+
+    Builder::default()
+        .add_tags(&["input"])
+        .add_tag_attributes("input", &["type", "checked", "disabled"])
+        // code blocks keep their language for highlighting
+        .add_tag_attributes("code", &["class"])
+        .set_tag_attribute_value("a", "target", "_blank")
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .add_url_schemes(&[images::PROTOCOL])
+        .url_relative(UrlRelative::PassThrough)
+        .attribute_filter(move |element, attribute, value| {
+            rewrite_url(element, attribute, value, base.as_ref())
+        })
+        .clean(&unsafe_html)
+        .to_string()
+}
+
+fn rewrite_url<'a>(
+    element: &str,
+    attribute: &str,
+    value: &'a str,
+    base: Option<&Url>,
+) -> Option<Cow<'a, str>> {
+    if !matches!(attribute, "href" | "src") {
+        return Some(value.into());
+    }
+
+    let absolute: Cow<str> = match Url::parse(value) {
+        Ok(_) => value.into(),
+        // anchors point within the rendered page itself
+        Err(_) if value.starts_with('#') => return Some(value.into()),
+        // otherwise they would resolve against the app's own origin
+        Err(_) => String::from(base?.join(value).ok()?).into(),
+    };
+
+    let is_web = absolute.starts_with("https://") || absolute.starts_with("http://");
+
+    if element == "img" && attribute == "src" && is_web {
+        Some(images::media_url(&absolute).into())
+    } else {
+        Some(absolute)
+    }
+}
@@ -1,32 +1,73 @@
 use std::{
-    collections::{HashSet, VecDeque},
+    borrow::Cow,
+    collections::{HashMap, HashSet, VecDeque},
     hash::Hash,
     iter::FusedIterator,
+    mem,
     str::{self},
+    sync::LazyLock,
 };
 
+use chrono::{DateTime, Utc};
 use eyre::{eyre, Result};
 use indexmap::IndexMap;
+use itertools::Itertools;
 use query::QueryModsArgs;
 use serde::{Deserialize, Serialize};
 use tauri::{async_runtime::JoinHandle, AppHandle};
 use uuid::Uuid;
 
-use crate::{game::Game, state::ManagerExt};
+use crate::{game::Game, memory::HeapSize, state::ManagerExt};
 
+pub mod author;
 pub mod cache;
 pub mod commands;
+pub mod contents;
+pub mod feed;
+pub mod links;
+pub mod markdown;
 pub mod query;
+pub mod rating;
+pub mod search;
 pub mod token;
 
 mod fetch;
-pub use fetch::wait_for_fetch;
+pub use fetch::{refresh_packages, wait_for_fetch, IndexStatus};
 
 mod models;
 pub use models::*;
 
-mod ident;
-pub use ident::*;
+pub use gale_core::ident::*;
+
+/// The base of every Thunderstore API request. Debug builds can set `GALE_THUNDERSTORE_URL`
+/// to point Gale at a mock server instead, like `scripts/mock_thunderstore.py`. Release
+/// builds always use the real one, so the variable can't redirect downloads.
+pub static API_URL: LazyLock<Cow<'static, str>> = LazyLock::new(|| {
+    #[cfg(debug_assertions)]
+    if let Ok(var) = std::env::var("GALE_THUNDERSTORE_URL") {
+        return var.trim_end_matches('/').to_owned().into();
+    }
+
+    "https://thunderstore.io".into()
+});
+
+pub fn download_url(ident: &VersionIdent) -> String {
+    download_url_at(&API_URL, ident)
+}
+
+fn download_url_at(base: &str, ident: &VersionIdent) -> String {
+    format!("{}/package/download/{}", base, ident.path())
+}
+
+/// Where a package archive is found on a mirror of Thunderstore's CDN, which
+/// serves them as `live/repository/packages/<ident>.zip`.
+pub fn mirror_url(mirror: &str, ident: &VersionIdent) -> String {
+    format!(
+        "{}/live/repository/packages/{}.zip",
+        mirror.trim_end_matches('/'),
+        ident
+    )
+}
 
 pub fn start(app: &AppHandle) {
     query::setup(app);
@@ -117,19 +158,43 @@ pub struct Thunderstore {
     packages_fetched: bool,
     /// Whether a [`fetch_mods`] task is currently running.
     is_fetching: bool,
+    /// When the package index was last fetched, or when the cache was written
+    /// if it hasn't been fetched since starting.
+    last_fetched: Option<DateTime<Utc>>,
     // IndexMap is not used for ordering here, but for fast iteration,
     // since we iterate over all mods when resolving identifiers and querying.
     packages: IndexMap<Uuid, PackageListing>,
+    /// How many packages directly depend on each package, by full name.
+    /// Recomputed whenever the package index changes.
+    dependant_counts: HashMap<String, usize>,
     current_query: Option<QueryModsArgs>,
 }
 
+impl HeapSize for Thunderstore {
+    fn heap_size(&self) -> usize {
+        let entry_size = mem::size_of::<Uuid>() + mem::size_of::<PackageListing>();
+
+        let count_size = mem::size_of::<String>() + mem::size_of::<usize>();
+
+        self.packages.capacity() * entry_size
+            + self.dependant_counts.capacity() * count_size
+            + self
+                .packages
+                .values()
+                .map(HeapSize::heap_size)
+                .sum::<usize>()
+    }
+}
+
 impl Thunderstore {
     pub fn new() -> Self {
         Self {
             fetch_loop_handle: None,
             packages_fetched: false,
             is_fetching: false,
+            last_fetched: None,
             packages: IndexMap::new(),
+            dependant_counts: HashMap::new(),
             current_query: None,
         }
     }
@@ -202,6 +267,37 @@ impl Thunderstore {
         Ok((package, version).into())
     }
 
+    /// How many packages in the community have a latest version that depends on `package`.
+    pub fn dependant_count(&self, package: &PackageListing) -> usize {
+        self.dependant_counts
+            .get(package.full_name())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn count_dependants(&mut self) {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+
+        for borrowed in self.latest() {
+            let dependencies = borrowed.dependencies().map(|dep| dep.full_name()).unique();
+
+            for full_name in dependencies {
+                *counts.entry(full_name.to_owned()).or_default() += 1;
+            }
+        }
+
+        self.dependant_counts = counts;
+    }
+
+    /// Aborts the fetch loop, which isn't restarted until the next call to [`Thunderstore::switch_game`].
+    pub fn stop_fetching(&mut self) {
+        if let Some(handle) = self.fetch_loop_handle.take() {
+            handle.abort();
+        }
+
+        self.is_fetching = false;
+    }
+
     /// Switches the active game, clearing the package map and aborting ongoing fetch tasks.
     pub fn switch_game(&mut self, game: Game, app: AppHandle) {
         if let Some(handle) = self.fetch_loop_handle.take() {
@@ -210,7 +306,9 @@ impl Thunderstore {
 
         self.is_fetching = false;
         self.packages_fetched = false;
+        self.last_fetched = None;
         self.packages = IndexMap::new();
+        self.dependant_counts = HashMap::new();
 
         self.read_and_insert_cache(game, &app.lock_prefs());
 
@@ -275,3 +373,6 @@ impl Thunderstore {
         }
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -279,6 +279,9 @@ pub struct FrontendVersion {
 pub struct FrontendProfileMod {
     pub enabled: bool,
     pub config_file: Option<PathBuf>,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    pub local_only: bool,
     #[serde(flatten)]
     pub data: FrontendMod,
 }
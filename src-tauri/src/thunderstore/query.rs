@@ -1,5 +1,6 @@
 use std::{cmp::Ordering, collections::HashSet, time::Duration};
 
+use chrono::{DateTime, Utc};
 use eyre::Result;
 use internment::Intern;
 use itertools::Itertools;
@@ -55,6 +56,44 @@ pub struct QueryModsArgs {
     pub include_enabled: bool,
     pub sort_by: SortBy,
     pub sort_order: SortOrder,
+    /// Only include packages by this author (case insensitive).
+    pub author: Option<String>,
+    /// Only include installed mods with (or without) a newer version available.
+    pub has_update: Option<bool>,
+    pub installed_after: Option<DateTime<Utc>>,
+    pub installed_before: Option<DateTime<Utc>>,
+    /// Minimum file size in bytes.
+    pub min_size: Option<u64>,
+    /// Maximum file size in bytes.
+    pub max_size: Option<u64>,
+    /// Only include installed mods with at least one of these tags.
+    #[serde(default)]
+    pub include_tags: HashSet<String>,
+}
+
+impl QueryModsArgs {
+    pub fn matches_author(&self, author: Option<&str>) -> bool {
+        match (&self.author, author) {
+            (None, _) => true,
+            (Some(wanted), Some(author)) => wanted.trim().eq_ignore_ascii_case(author),
+            (Some(_), None) => false,
+        }
+    }
+
+    pub fn matches_categories(&self, categories: &HashSet<Intern<String>>) -> bool {
+        (self.include_categories.is_empty() || !self.include_categories.is_disjoint(categories))
+            && (self.exclude_categories.is_empty()
+                || self.exclude_categories.is_disjoint(categories))
+    }
+
+    pub fn matches_tags(&self, tags: &[String]) -> bool {
+        self.include_tags.is_empty() || tags.iter().any(|tag| self.include_tags.contains(tag))
+    }
+
+    pub fn matches_size(&self, size: u64) -> bool {
+        self.min_size.map_or(true, |min| size >= min)
+            && self.max_size.map_or(true, |max| size <= max)
+    }
 }
 
 pub async fn query_loop(app: AppHandle) -> Result<()> {
@@ -110,27 +149,7 @@ impl Queryable for BorrowedMod<'_> {
     }
 
     fn matches(&self, args: &QueryModsArgs) -> bool {
-        let pkg = self.package;
-
-        if !args.include_nsfw && pkg.has_nsfw_content
-            || !args.include_deprecated && pkg.is_deprecated
-        {
-            return false;
-        }
-
-        if !args.include_categories.is_empty()
-            && args.include_categories.is_disjoint(&pkg.categories)
-        {
-            return false;
-        }
-
-        if !args.exclude_categories.is_empty()
-            && !args.exclude_categories.is_disjoint(&pkg.categories)
-        {
-            return false;
-        }
-
-        true
+        self.matches_with_categories(args, &self.package.categories)
     }
 
     fn cmp(&self, other: &Self, args: &QueryModsArgs) -> Ordering {
@@ -157,6 +176,28 @@ impl Queryable for BorrowedMod<'_> {
     }
 }
 
+impl BorrowedMod<'_> {
+    /// Like [`Queryable::matches`], but checks `categories` instead of the package's
+    /// current ones.
+    pub fn matches_with_categories(
+        &self,
+        args: &QueryModsArgs,
+        categories: &HashSet<Intern<String>>,
+    ) -> bool {
+        let pkg = self.package;
+
+        if !args.include_nsfw && pkg.has_nsfw_content
+            || !args.include_deprecated && pkg.is_deprecated
+        {
+            return false;
+        }
+
+        args.matches_categories(categories)
+            && args.matches_author(Some(pkg.owner()))
+            && args.matches_size(self.version.file_size)
+    }
+}
+
 impl IntoFrontendMod for BorrowedMod<'_> {
     fn into_frontend(self, profile: Option<&Profile>) -> FrontendMod {
         let pkg = self.package;
@@ -0,0 +1,119 @@
+//! Rates packages on Thunderstore using the user's API token (see [`super::token`]).
+//!
+//! Ratings made while Thunderstore can't be reached are stored in the database
+//! and sent after the next successful package fetch.
+
+use eyre::{Context, OptionExt, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::AppHandle;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use super::token;
+use crate::state::ManagerExt;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum RateResult {
+    /// The rating was applied, with the package's new score.
+    Sent { score: u32 },
+    /// Thunderstore couldn't be reached, so the rating will be sent later.
+    Queued,
+}
+
+#[derive(Deserialize)]
+struct RateResponse {
+    score: u32,
+}
+
+pub async fn rate(package_uuid: Uuid, rated: bool, app: &AppHandle) -> Result<RateResult> {
+    let token = token::get()
+        .context("failed to get thunderstore API token")?
+        .ok_or_eyre("no thunderstore API token found")?;
+
+    match send(package_uuid, rated, &token, app).await {
+        Ok(score) => {
+            set_score(package_uuid, score, app);
+            // this rating overrides any queued one
+            app.db().remove_pending_rating(package_uuid)?;
+
+            Ok(RateResult::Sent { score })
+        }
+        Err(err) if is_offline(&err) => {
+            info!(
+                "thunderstore is unreachable, queueing rating of {}",
+                package_uuid
+            );
+            app.db().queue_rating(package_uuid, rated)?;
+
+            Ok(RateResult::Queued)
+        }
+        Err(err) => Err(err).context("failed to rate package"),
+    }
+}
+
+/// Sends the ratings that were queued while offline.
+pub async fn flush_queue(app: &AppHandle) -> Result<()> {
+    let pending = app.db().pending_ratings()?;
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let Some(token) = token::get()? else {
+        warn!(
+            "no thunderstore API token found, keeping {} queued ratings",
+            pending.len()
+        );
+        return Ok(());
+    };
+
+    debug!("sending {} queued ratings", pending.len());
+
+    for (package_uuid, rated) in pending {
+        match send(package_uuid, rated, &token, app).await {
+            Ok(score) => set_score(package_uuid, score, app),
+            // still offline, try again after the next fetch
+            Err(err) if is_offline(&err) => break,
+            Err(err) => warn!("failed to send queued rating of {}: {}", package_uuid, err),
+        }
+
+        app.db().remove_pending_rating(package_uuid)?;
+    }
+
+    Ok(())
+}
+
+async fn send(
+    package_uuid: Uuid,
+    rated: bool,
+    token: &str,
+    app: &AppHandle,
+) -> reqwest::Result<u32> {
+    let url = format!("{}/api/v1/package/{package_uuid}/rate/", *super::API_URL);
+    let target_state = if rated { "rated" } else { "unrated" };
+
+    let response: RateResponse = app
+        .http()
+        .post(url)
+        .bearer_auth(token)
+        .json(&json!({ "target_state": target_state }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(response.score)
+}
+
+fn is_offline(err: &reqwest::Error) -> bool {
+    err.is_connect() || err.is_timeout()
+}
+
+fn set_score(package_uuid: Uuid, score: u32, app: &AppHandle) {
+    if let Some(package) = app.lock_thunderstore().packages.get_mut(&package_uuid) {
+        package.rating_score = score;
+    }
+}
@@ -0,0 +1,126 @@
+//! Searches the package indexes of every managed game at once, so a mod can be
+//! found without switching to its game first.
+//!
+//! The active game is searched in memory, while other games use their cached
+//! index on disk. Games that have never been fetched are skipped.
+
+use eyre::Result;
+use itertools::Itertools;
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use super::{cache, PackageListing};
+use crate::{game::Game, state::ManagerExt};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalSearchResult {
+    /// Slug of the game the package belongs to.
+    pub game: String,
+    pub game_name: String,
+    pub uuid: Uuid,
+    pub full_name: String,
+    pub latest_version: String,
+    pub description: String,
+    pub downloads: u32,
+    pub rating: u32,
+    pub is_deprecated: bool,
+}
+
+impl GlobalSearchResult {
+    fn new(package: &PackageListing, game: Game) -> Self {
+        let latest = package.latest();
+
+        Self {
+            game: game.slug.to_string(),
+            game_name: game.name.to_owned(),
+            uuid: package.uuid,
+            full_name: package.full_name().to_owned(),
+            latest_version: latest.version().to_owned(),
+            description: latest.description.to_string(),
+            downloads: package.total_downloads(),
+            rating: package.rating_score,
+            is_deprecated: package.is_deprecated,
+        }
+    }
+}
+
+struct SearchTerm {
+    full: String,
+    /// Package names use underscores instead of spaces.
+    package: String,
+}
+
+impl SearchTerm {
+    fn new(query: &str) -> Self {
+        let full = query.trim().to_lowercase();
+        let package = full.replace(' ', "_");
+
+        Self { full, package }
+    }
+
+    fn matches(&self, package: &PackageListing) -> bool {
+        package.full_name().to_lowercase().contains(&self.package)
+            || package
+                .latest()
+                .description
+                .to_lowercase()
+                .contains(&self.full)
+    }
+}
+
+/// Searches all managed games for packages matching `query`, most downloaded first.
+pub fn search(query: &str, max_count: usize, app: &AppHandle) -> Result<Vec<GlobalSearchResult>> {
+    let term = SearchTerm::new(query);
+    if term.full.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let (active_game, other_games) = {
+        let manager = app.lock_manager();
+        let others = manager
+            .games
+            .keys()
+            .copied()
+            .filter(|game| *game != manager.active_game)
+            .collect_vec();
+
+        (manager.active_game, others)
+    };
+
+    let mut results = {
+        let thunderstore = app.lock_thunderstore();
+
+        thunderstore
+            .packages
+            .values()
+            .filter(|package| term.matches(package))
+            .map(|package| GlobalSearchResult::new(package, active_game))
+            .collect_vec()
+    };
+
+    for game in other_games {
+        let packages = {
+            let prefs = app.lock_prefs();
+            cache::get_packages(game, &prefs)
+        };
+
+        match packages {
+            Ok(Some(packages)) => results.extend(
+                packages
+                    .iter()
+                    .filter(|package| term.matches(package))
+                    .map(|package| GlobalSearchResult::new(package, game)),
+            ),
+            Ok(None) => debug!("no cached index for {}, skipping", game.slug),
+            Err(err) => warn!("failed to read cached index of {}: {:#}", game.slug, err),
+        }
+    }
+
+    results.sort_by(|a, b| b.downloads.cmp(&a.downloads));
+    results.truncate(max_count);
+
+    Ok(results)
+}
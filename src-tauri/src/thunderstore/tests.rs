@@ -0,0 +1,144 @@
+//! Drives fetching and downloading against `scripts/mock_thunderstore.py`, which serves
+//! the fixtures in `tests/fixtures/thunderstore`. Needs Python 3.10 or newer, the tests
+//! are skipped if it isn't installed.
+
+use std::{
+    fs,
+    io::{self, Cursor},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use walkdir::WalkDir;
+
+use super::{download_url_at, fetch, VersionIdent};
+use crate::util;
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The mock server on a free port. It's killed when dropped, even if the test fails.
+struct MockServer {
+    child: Child,
+    url: String,
+}
+
+impl MockServer {
+    /// Returns `None` if Python isn't installed.
+    fn start() -> Option<Self> {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("failed to find a free port")
+            .port();
+
+        let python = if cfg!(windows) { "python" } else { "python3" };
+        let script = manifest_dir().join("../scripts/mock_thunderstore.py");
+
+        let child = match Command::new(python)
+            .arg(script)
+            .args(["--port", &port.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                eprintln!("{python} not found, skipping");
+                return None;
+            }
+            Err(err) => panic!("failed to start the mock server: {err}"),
+        };
+
+        let start = Instant::now();
+        while TcpStream::connect(("127.0.0.1", port)).is_err() {
+            assert!(
+                start.elapsed() < STARTUP_TIMEOUT,
+                "mock server didn't start in time"
+            );
+
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        Some(Self {
+            child,
+            url: format!("http://127.0.0.1:{port}"),
+        })
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+async fn get(url: &str) -> bytes::Bytes {
+    reqwest::get(url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .unwrap_or_else(|err| panic!("request to {url} failed: {err}"))
+        .bytes()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn fetches_package_listing() {
+    let Some(server) = MockServer::start() else {
+        return;
+    };
+
+    let index = get(&fetch::index_url(&server.url, "lethal-company")).await;
+    let urls = fetch::parse_index(&index).unwrap();
+    assert_eq!(urls.len(), 1);
+
+    let chunk = get(&urls[0]).await;
+    let packages = fetch::parse_chunk(&chunk).unwrap();
+    let names = packages
+        .iter()
+        .map(|package| package.full_name())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, ["BepInEx-BepInExPack", "Example-Mod"]);
+}
+
+#[tokio::test]
+async fn downloads_package() {
+    let Some(server) = MockServer::start() else {
+        return;
+    };
+    let ident = "Example-Mod-1.0.0".parse::<VersionIdent>().unwrap();
+
+    let archive = get(&download_url_at(&server.url, &ident)).await;
+
+    let dir = tempfile::tempdir().unwrap();
+    util::zip::extract(Cursor::new(archive), dir.path()).unwrap();
+
+    let expected = manifest_dir()
+        .join("tests/fixtures/thunderstore/files")
+        .join(ident.to_string());
+
+    for entry in WalkDir::new(&expected).into_iter().filter_map(Result::ok) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(&expected).unwrap();
+        let actual = fs::read(dir.path().join(relative))
+            .unwrap_or_else(|err| panic!("{} is missing: {err}", relative.display()));
+
+        assert_eq!(
+            actual,
+            fs::read(entry.path()).unwrap(),
+            "{} differs",
+            relative.display()
+        );
+    }
+}
@@ -1,24 +1,61 @@
 use std::{
     fmt::{self, Display},
+    path::Path,
     result::Result as StdResult,
 };
 
 use serde::Serialize;
 
+use super::fs::InsufficientSpace;
+use crate::profile::install::InstallError;
+
 #[derive(Debug)]
 pub struct CommandError(eyre::Error);
 
+impl CommandError {
+    fn insufficient_space(&self) -> Option<&InsufficientSpace> {
+        // install errors that aren't unwrapped with `ignore_cancel` hide the original report
+        match self.0.downcast_ref::<InstallError>() {
+            Some(InstallError::Err(report)) => report.downcast_ref(),
+            _ => self.0.downcast_ref(),
+        }
+    }
+}
+
 impl Display for CommandError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:#}", self.0)
     }
 }
 
+/// Errors that the frontend handles specially. These are sent as objects tagged
+/// with their `kind`, while any other error is sent as a plain string.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum KnownError<'a> {
+    InsufficientSpace {
+        message: String,
+        path: &'a Path,
+        required: u64,
+        available: u64,
+    },
+}
+
 impl Serialize for CommandError {
     fn serialize<S>(&self, serializer: S) -> StdResult<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
+        if let Some(err) = self.insufficient_space() {
+            return KnownError::InsufficientSpace {
+                message: self.to_string(),
+                path: &err.path,
+                required: err.required,
+                available: err.available,
+            }
+            .serialize(serializer);
+        }
+
         serializer.serialize_str(&self.to_string())
     }
 }
@@ -11,6 +11,9 @@ use walkdir::WalkDir;
 use zip::ZipArchive;
 
 use super::error::IoResultExt;
+use crate::i18n;
+
+pub use gale_core::archive::is_enclosed;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Overwrite {
@@ -99,6 +102,56 @@ pub fn get_directory_size(path: impl AsRef<Path>) -> u64 {
         .sum()
 }
 
+/// Returned by [`ensure_free_space`] when a drive is too full.
+#[derive(Debug)]
+pub struct InsufficientSpace {
+    pub path: PathBuf,
+    pub required: u64,
+    pub available: u64,
+}
+
+impl std::fmt::Display for InsufficientSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = i18n::t(
+            "install.insufficient_space",
+            &[
+                ("path", &self.path.display()),
+                ("required", &(self.required / 1_000_000)),
+                ("available", &(self.available / 1_000_000)),
+            ],
+        );
+
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for InsufficientSpace {}
+
+/// Checks that the drive of `path` has at least `required` bytes of free space.
+///
+/// `path` doesn't need to exist; its closest existing ancestor is checked instead.
+/// On failure, the report can be downcast to [`InsufficientSpace`], which commands
+/// send to the frontend as a structured error.
+pub fn ensure_free_space(path: &Path, required: u64) -> eyre::Result<()> {
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or(path);
+
+    let available = fs4::available_space(existing).fs_context("checking free space", existing)?;
+
+    if available < required {
+        return Err(InsufficientSpace {
+            path: existing.to_path_buf(),
+            required,
+            available,
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
 pub fn read_json<T: DeserializeOwned>(path: impl AsRef<Path>) -> eyre::Result<T> {
     let string = fs::read_to_string(path)?;
     let result = serde_json::from_str(&string)?;
@@ -143,34 +196,6 @@ pub fn file_name_owned(path: impl AsRef<Path>) -> String {
         .into_owned()
 }
 
-pub fn is_enclosed(path: impl AsRef<Path>) -> bool {
-    use std::path::Component;
-
-    if path
-        .as_ref()
-        .as_os_str()
-        .to_str()
-        .is_some_and(|str| str.contains('\0'))
-    {
-        return false;
-    }
-
-    let mut depth = 0usize;
-    for component in path.as_ref().components() {
-        match component {
-            Component::Prefix(_) | Component::RootDir => return false,
-            Component::ParentDir => match depth.checked_sub(1) {
-                Some(new_depth) => depth = new_depth,
-                None => return false,
-            },
-            Component::Normal(_) => depth += 1,
-            Component::CurDir => (),
-        }
-    }
-
-    true
-}
-
 pub trait PathExt: Sized {
     fn exists_or_none(self) -> Option<Self>;
     fn add_ext(&mut self, extension: impl AsRef<OsStr>);
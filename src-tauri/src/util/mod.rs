@@ -1,10 +1,11 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, process::Command};
 
 use itertools::Itertools;
 
 pub mod cmd;
 pub mod error;
 pub mod fs;
+pub mod net;
 pub mod path;
 pub mod window;
 pub mod zip;
@@ -22,3 +23,16 @@ pub fn cmp_ignore_case(a: impl AsRef<str>, b: impl AsRef<str>) -> Ordering {
         .find(|&ordering| ordering != Ordering::Equal)
         .unwrap_or(Ordering::Equal)
 }
+
+/// Creates a command that runs `command` through the platform's shell.
+pub fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
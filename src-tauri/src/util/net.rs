@@ -0,0 +1,154 @@
+//! Timeouts and cancellation for outgoing requests, so that a stalled connection ends
+//! in an error instead of a spinner that never goes away.
+//!
+//! Requests that take longer than a few seconds show a "still trying" message in the
+//! status bar, where the user can cancel every pending request at once.
+
+use std::{error::Error, fmt, future::Future, time::Duration};
+
+use bytes::Bytes;
+use eyre::{eyre, Result};
+use futures_util::{Stream, StreamExt};
+use reqwest::{RequestBuilder, Response};
+use tauri::{command, AppHandle, Emitter};
+use tokio::sync::{futures::Notified, Notify};
+use tracing::warn;
+
+use crate::i18n;
+
+/// When to tell the user that a request is taking a while.
+const SLOW_AFTER: Duration = Duration::from_secs(5);
+
+static CANCEL: Notify = Notify::const_new();
+
+/// What a request is for, which decides how long it may take.
+#[derive(Debug, Clone, Copy)]
+pub enum Operation {
+    /// The package index of a game, which can be tens of megabytes.
+    Index,
+    /// A package archive. The timeout applies to the gap between two chunks, since
+    /// a big download on a slow connection is fine as long as it keeps moving.
+    Download,
+    /// Any request to the sync server, including auth.
+    Sync,
+}
+
+impl Operation {
+    pub fn timeout(self) -> Duration {
+        match self {
+            Operation::Index => Duration::from_secs(90),
+            Operation::Download => Duration::from_secs(30),
+            Operation::Sync => Duration::from_secs(30),
+        }
+    }
+
+    fn service(self) -> &'static str {
+        match self {
+            Operation::Index | Operation::Download => "Thunderstore",
+            Operation::Sync => "the sync server",
+        }
+    }
+}
+
+/// Sends `request`, giving up after the operation's timeout or when cancelled.
+///
+/// Except for downloads, the timeout also covers reading the response body.
+pub async fn send(
+    request: RequestBuilder,
+    operation: Operation,
+    app: &AppHandle,
+) -> Result<Response> {
+    let request = match operation {
+        Operation::Download => request,
+        _ => request.timeout(operation.timeout()),
+    };
+
+    watch(request.send(), operation, app).await
+}
+
+/// Waits for the next chunk of a streamed response, failing if none arrives within
+/// the operation's timeout.
+pub async fn next_chunk<S>(
+    stream: &mut S,
+    operation: Operation,
+    app: &AppHandle,
+) -> Result<Option<Bytes>>
+where
+    S: Stream<Item = reqwest::Result<Bytes>> + Unpin,
+{
+    watch(async { stream.next().await.transpose() }, operation, app).await
+}
+
+/// Resolves when the user cancels pending requests.
+pub fn cancelled() -> Notified<'static> {
+    CANCEL.notified()
+}
+
+/// Whether `err` comes from the user cancelling the request, rather than it failing.
+pub fn is_cancelled(err: &eyre::Report) -> bool {
+    err.downcast_ref::<Cancelled>().is_some()
+}
+
+#[derive(Debug)]
+struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", i18n::t("network.cancelled", &[]))
+    }
+}
+
+impl Error for Cancelled {}
+
+async fn watch<F, T>(future: F, operation: Operation, app: &AppHandle) -> Result<T>
+where
+    F: Future<Output = reqwest::Result<T>>,
+{
+    let cancelled = cancelled();
+    let slow = tokio::time::sleep(SLOW_AFTER);
+    let deadline = tokio::time::sleep(operation.timeout());
+    tokio::pin!(future, cancelled, slow, deadline);
+
+    let mut is_slow = false;
+
+    let result = loop {
+        tokio::select! {
+            result = &mut future => break result.map_err(eyre::Report::new),
+            _ = &mut slow, if !is_slow => {
+                is_slow = true;
+                set_waiting(Some(operation), app);
+            }
+            _ = &mut deadline => {
+                warn!("request to {} timed out", operation.service());
+                break Err(eyre!(i18n::t(
+                    "network.timed_out",
+                    &[
+                        ("service", &operation.service()),
+                        ("seconds", &operation.timeout().as_secs()),
+                    ],
+                )));
+            }
+            _ = &mut cancelled => break Err(eyre::Report::new(Cancelled)),
+        }
+    };
+
+    if is_slow {
+        set_waiting(None, app);
+    }
+
+    result
+}
+
+fn set_waiting(operation: Option<Operation>, app: &AppHandle) {
+    let message =
+        operation.map(|operation| i18n::t("network.slow", &[("service", &operation.service())]));
+
+    app.emit("network_waiting", operation.is_some()).ok();
+    app.emit("status_update", message).ok();
+}
+
+/// Fails every request that is currently waiting on the network.
+#[command]
+pub fn cancel_requests() {
+    CANCEL.notify_waiters();
+}
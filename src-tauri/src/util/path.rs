@@ -1,16 +1,115 @@
-use std::path::PathBuf;
+use std::{env, path::PathBuf, sync::OnceLock};
+
+use serde::Serialize;
 
 pub const APP_GUID: &str = "com.kesomannen.gale";
 
+/// Name of the marker file that turns on portable mode when placed next to the executable.
+pub const PORTABLE_FILE_NAME: &str = "portable.txt";
+
+/// Whether Gale keeps all of its data next to the executable instead of in the
+/// user's app data directories.
+///
+/// This is turned on by either the `--portable` flag or a [`PORTABLE_FILE_NAME`]
+/// file in the executable's directory.
+pub fn is_portable() -> bool {
+    portable_dir().is_some()
+}
+
+/// The directory that holds everything in portable mode.
+pub fn portable_dir() -> Option<&'static PathBuf> {
+    static DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+    DIR.get_or_init(|| {
+        let exe_dir = env::current_exe().ok()?.parent()?.to_path_buf();
+
+        let enabled =
+            env::args().any(|arg| arg == "--portable") || exe_dir.join(PORTABLE_FILE_NAME).exists();
+
+        enabled.then_some(exe_dir)
+    })
+    .as_ref()
+}
+
+/// Directories set from the command line or environment, which take precedence
+/// over both the prefs and the OS defaults and are never saved.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirOverrides {
+    /// Set with `--data-dir` or `GALE_DATA_DIR`.
+    pub data_dir: Option<PathBuf>,
+    /// Set with `--cache-dir` or `GALE_CACHE_DIR`.
+    pub cache_dir: Option<PathBuf>,
+    /// Set with `--temp-dir` or `GALE_TEMP_DIR`.
+    pub temp_dir: Option<PathBuf>,
+}
+
+pub fn dir_overrides() -> &'static DirOverrides {
+    static OVERRIDES: OnceLock<DirOverrides> = OnceLock::new();
+
+    OVERRIDES.get_or_init(|| {
+        let args = env::args().collect::<Vec<_>>();
+
+        DirOverrides {
+            data_dir: find_override(&args, "--data-dir", "GALE_DATA_DIR"),
+            cache_dir: find_override(&args, "--cache-dir", "GALE_CACHE_DIR"),
+            temp_dir: find_override(&args, "--temp-dir", "GALE_TEMP_DIR"),
+        }
+    })
+}
+
+/// Flags win over environment variables. Both `--flag path` and `--flag=path` work.
+fn find_override(args: &[String], flag: &str, var: &str) -> Option<PathBuf> {
+    let from_args = args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == flag {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(flag)?.strip_prefix('=').map(str::to_owned)
+        }
+    });
+
+    from_args
+        .or_else(|| env::var(var).ok())
+        .filter(|value| !value.trim().is_empty())
+        .map(PathBuf::from)
+}
+
+/// A machine-wide location for shared downloads that every user can write to.
+pub fn shared_cache_dir() -> PathBuf {
+    #[cfg(windows)]
+    let base = env::var_os("ProgramData")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+        .join("Gale");
+
+    #[cfg(target_os = "macos")]
+    let base = PathBuf::from("/Users/Shared/Gale");
+
+    // the only standard place regular users can create directories in that survives
+    // reboots, entries unused for a while may get cleaned up but that's fine for a cache
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let base = PathBuf::from("/var/tmp/gale");
+
+    base.join("cache")
+}
+
 pub fn default_app_config_dir() -> PathBuf {
     app_dir("config", dirs_next::config_dir())
 }
 
 pub fn default_app_data_dir() -> PathBuf {
+    if let Some(dir) = &dir_overrides().data_dir {
+        return dir.clone();
+    }
+
     app_dir("data", dirs_next::data_dir())
 }
 
 fn app_dir(id: &str, base: Option<PathBuf>) -> PathBuf {
+    if let Some(dir) = portable_dir() {
+        return dir.join(id);
+    }
+
     let mut path = base.unwrap_or_else(|| panic!("failed to resolve {id} dir"));
     path.push(APP_GUID);
     path
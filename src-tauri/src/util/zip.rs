@@ -1,14 +1,13 @@
 use std::{
     fs::{self, File},
     io::{self, Read, Seek},
-    path::{Path, PathBuf},
+    path::Path,
 };
 
+use gale_core::archive::entry_path;
 use tracing::{debug, warn};
 use zip::ZipArchive;
 
-use crate::util;
-
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 
@@ -25,18 +24,10 @@ pub fn extract(src: impl Read + Seek, target: &Path) -> io::Result<()> {
             continue; // we create the necessary dirs when copying files instead
         }
 
-        let relative = match cfg!(unix) {
-            true => PathBuf::from(file.name().replace('\\', "/")),
-            false => PathBuf::from(file.name()),
-        };
-
-        if !util::fs::is_enclosed(&relative) {
-            warn!(
-                "file {} escapes the archive root, skipping",
-                relative.display()
-            );
+        let Some(relative) = entry_path(file.name()) else {
+            warn!("file {} escapes the archive root, skipping", file.name());
             continue;
-        }
+        };
 
         let output_path = target.join(relative);
 
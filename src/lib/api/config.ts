@@ -1,5 +1,12 @@
 import { invoke } from '$lib/invoke';
-import type { ConfigEntryId, ConfigFile, ConfigValue } from '$lib/types';
+import type {
+	BatchConfigEdit,
+	BatchConfigEditResult,
+	ConfigEntryId,
+	ConfigFile,
+	ConfigPresetData,
+	ConfigValue
+} from '$lib/types';
 
 const idToArgs = (id: ConfigEntryId) => ({
 	file: id.file.relativePath,
@@ -17,7 +24,19 @@ export const resetEntry = (id: ConfigEntryId) =>
 	invoke<ConfigValue>('reset_config_entry', {
 		...idToArgs(id)
 	});
+export const batchEdit = (edit: BatchConfigEdit, dryRun: boolean) =>
+	invoke<BatchConfigEditResult>('batch_edit_config', { edit, dryRun });
 export const openFile = (file: ConfigFile) =>
 	invoke('open_config_file', { file: file.relativePath });
 export const deleteFile = (file: ConfigFile) =>
 	invoke('delete_config_file', { file: file.relativePath });
+export const exportPresetFile = (name: string, files: string[], dir: string) =>
+	invoke('export_config_preset_file', { name, files, dir });
+export const exportPresetCode = (name: string, files: string[]) =>
+	invoke<string>('export_config_preset_code', { name, files });
+export const readPresetFile = (path: string) =>
+	invoke<ConfigPresetData>('read_config_preset_file', { path });
+export const readPresetCode = (key: string) =>
+	invoke<ConfigPresetData>('read_config_preset_code', { key });
+export const importPreset = (id: string) => invoke('import_config_preset', { id });
+export const discardPreset = (id: string) => invoke('discard_config_preset', { id });
@@ -3,4 +3,5 @@ export * as logger from './logger';
 export * as prefs from './prefs';
 export * as profile from './profile';
 export * as state from './state';
+export * as theme from './theme';
 export * as thunderstore from './thunderstore';
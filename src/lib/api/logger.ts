@@ -1,4 +1,12 @@
 import { invoke } from '$lib/invoke';
+import type { LogEntry, LogLevel } from '$lib/types';
 
 export const openGaleLog = () => invoke('open_gale_log');
+export const exportTrace = (dir: string, minutes: number) =>
+	invoke('export_trace', { dir, minutes });
 export const logErr = (msg: string) => invoke('log_err', { msg });
+export const getLogs = (
+	subsystem: string | null,
+	minLevel: LogLevel | null,
+	limit: number | null
+) => invoke<LogEntry[]>('get_logs', { subsystem, minLevel, limit });
@@ -1,7 +1,20 @@
 import { invoke } from '$lib/invoke';
-import type { Prefs, Zoom } from '$lib/types';
+import type { DirOverrides, Prefs, SettingsImportReport, Zoom } from '$lib/types';
 
 export const get = () => invoke<Prefs>('get_prefs');
 export const set = (value: Prefs) => invoke('set_prefs', { value });
+export const getDirOverrides = () => invoke<DirOverrides>('get_dir_overrides');
+export const acknowledgeAntiCheat = () => invoke('acknowledge_anti_cheat');
+export const revokePathAccess = (path: string) => invoke('revoke_path_access', { path });
 export const zoomWindow = (value: Zoom) => invoke('zoom_window', { value });
+export const getLanguages = () => invoke<string[]>('get_languages');
 export const getSystemFonts = () => invoke<string[]>('get_system_fonts');
+export const exportSettings = (
+	dir: string,
+	includePaths: boolean,
+	frontend: { [key: string]: unknown }
+) => invoke<string>('export_settings', { dir, includePaths, frontend });
+export const importSettings = (path: string) =>
+	invoke<SettingsImportReport>('import_settings', { path });
+export const enableKioskMode = (pin: string | null) => invoke('enable_kiosk_mode', { pin });
+export const disableKioskMode = (pin: string | null) => invoke('disable_kiosk_mode', { pin });
@@ -1,5 +1,5 @@
 import { invoke } from '$lib/invoke';
-import type { LegacyImportData, ModpackArgs, R2ImportData } from '$lib/types';
+import type { LegacyImportData, ModpackArgs, ModpackUpdates, R2ImportData } from '$lib/types';
 
 export const code = () => invoke<string>('export_code');
 export const file = (dir: string) => invoke('export_file', { dir });
@@ -11,3 +11,5 @@ export const copyDependencyStrings = () => invoke('copy_dependency_strings');
 export const copyDebugInfo = () => invoke('copy_debug_info');
 export const generateChangelog = (args: ModpackArgs, all: boolean) =>
 	invoke<string>('generate_changelog', { args, all });
+export const getModpackUpdates = () => invoke<ModpackUpdates>('get_modpack_updates');
+export const applyModpackUpdates = () => invoke<ModpackArgs>('apply_modpack_updates');
@@ -1,5 +1,12 @@
 import { invoke } from '$lib/invoke';
-import type { LegacyImportData, R2ImportData } from '$lib/types';
+import type {
+	LegacyImportData,
+	LocalModMetadata,
+	LocalModPreview,
+	ModId,
+	ModListMatch,
+	R2ImportData
+} from '$lib/types';
 
 export const profile = (data: LegacyImportData, importAll: boolean) =>
 	invoke('import_profile', { data, importAll });
@@ -7,9 +14,18 @@ export const readCode = (key: string) => invoke<LegacyImportData>('read_profile_
 export const readFile = (path: string) => invoke<LegacyImportData>('read_profile_file', { path });
 export const readBase64 = (base64: string) =>
 	invoke<LegacyImportData>('read_profile_base64', { base64 });
-export const localMod = (path: string) => invoke('import_local_mod', { path });
-export const localModBase64 = (base64: string) => invoke('import_local_mod_base64', { base64 });
+export const previewLocalMod = (path: string) =>
+	invoke<LocalModPreview>('preview_local_mod', { path });
+export const previewLocalModBase64 = (base64: string) =>
+	invoke<LocalModPreview>('preview_local_mod_base64', { base64 });
+export const localMod = (path: string, metadata: LocalModMetadata | null = null) =>
+	invoke('import_local_mod', { path, metadata });
+export const localModBase64 = (base64: string, metadata: LocalModMetadata | null = null) =>
+	invoke('import_local_mod_base64', { base64, metadata });
 export const getR2modmanInfo = (path: string | null) =>
 	invoke<R2ImportData | null>('get_r2modman_info', { path });
 export const r2modman = (path: string, include: boolean[]) =>
 	invoke('import_r2modman', { path, include });
+export const resolveModList = (text: string) =>
+	invoke<ModListMatch[]>('resolve_mod_list', { text });
+export const modList = (ids: ModId[]) => invoke('install_mod_list', { ids });
@@ -1,5 +1,6 @@
 import { invoke } from '$lib/invoke';
 import type {
+	CustomGameDefinition,
 	Game,
 	GameInfo,
 	ModActionResponse,
@@ -7,7 +8,14 @@ import type {
 	ManagedGameInfo,
 	QueryModsArgs,
 	ModId,
-	MarkdownType
+	MarkdownType,
+	ModReport,
+	BulkAction,
+	BulkReport,
+	ToggleSetReport,
+	HealthReport,
+	PruneSuggestion,
+	RequiredBy
 } from '$lib/types';
 
 export * as export from './export';
@@ -20,6 +28,10 @@ export * as update from './update';
 export const getGameInfo = () => invoke<GameInfo>('get_game_info');
 export const favoriteGame = (slug: string) => invoke('favorite_game', { slug });
 export const setActiveGame = (slug: string) => invoke('set_active_game', { slug });
+export const getCustomGames = () => invoke<CustomGameDefinition[]>('get_custom_games');
+export const saveCustomGame = (definition: CustomGameDefinition, replaces: string | null) =>
+	invoke('save_custom_game', { definition, replaces });
+export const deleteCustomGame = (slug: string) => invoke('delete_custom_game', { slug });
 export const getInfo = () => invoke<ManagedGameInfo>('get_profile_info');
 export const setActive = (index: number) => invoke('set_active_profile', { index });
 export const query = (args: QueryModsArgs) => invoke<ProfileQuery>('query_profile', { args });
@@ -29,19 +41,47 @@ export const create = (name: string, overridePath: string | null) =>
 export const deleteProfile = (index: number) => invoke('delete_profile', { index });
 export const rename = (name: string) => invoke('rename_profile', { name });
 export const duplicate = (name: string) => invoke('duplicate_profile', { name });
+export const move = (dir: string | null) => invoke('move_profile', { dir });
 export const removeMod = (uuid: string) => invoke<ModActionResponse>('remove_mod', { uuid });
 export const toggleMod = (uuid: string) => invoke<ModActionResponse>('toggle_mod', { uuid });
 export const forceRemoveMods = (uuids: string[]) => invoke('force_remove_mods', { uuids });
 export const forceToggleMods = (uuids: string[]) => invoke('force_toggle_mods', { uuids });
 export const setAllModsState = (enable: boolean) =>
 	invoke<number>('set_all_mods_state', { enable });
+export const exportToggleSet = (dir: string) => invoke('export_toggle_set', { dir });
+export const importToggleSet = (path: string) =>
+	invoke<ToggleSetReport>('import_toggle_set', { path });
+export const getHealthReport = () => invoke<HealthReport | null>('get_health_report');
+export const runHealthCheck = () => invoke<HealthReport>('run_health_check');
+export const getPruneSuggestions = () => invoke<PruneSuggestion[]>('get_prune_suggestions');
+export const applyPruneSuggestions = (suggestions: PruneSuggestion[]) =>
+	invoke<number>('apply_prune_suggestions', { suggestions });
 export const removeDisabledMods = () => invoke<number>('remove_disabled_mods');
+export const runBulkAction = (action: BulkAction, uuids: string[]) =>
+	invoke<BulkReport>('run_bulk_action', { action, uuids });
+export const setModTags = (uuid: string, tags: string[]) =>
+	invoke('set_mod_tags', { uuid, tags });
+export const setModNote = (uuid: string, note: string | null) =>
+	invoke('set_mod_note', { uuid, note });
+export const setModLocalOnly = (uuid: string, localOnly: boolean) =>
+	invoke('set_mod_local_only', { uuid, localOnly });
+export const setNotes = (notes: string | null) => invoke('set_profile_notes', { notes });
+export const removeAllMods = (keepLoader: boolean) =>
+	invoke<number>('remove_all_mods', { keepLoader });
 export const getDependants = (uuid: string) => invoke<string[]>('get_dependants', { uuid });
+export const getRequiredBy = (uuid: string) => invoke<RequiredBy>('get_required_by', { uuid });
 export const openDir = () => invoke('open_profile_dir');
 export const openModDir = (uuid: string) => invoke('open_mod_dir', { uuid });
 export const openGameLog = () => invoke('open_game_log');
+export const openGameLogDir = () => invoke('open_game_log_dir');
+export const reportMod = (uuid: string) => invoke<ModReport>('report_mod', { uuid });
 export const createDesktopShortcut = () => invoke('create_desktop_shortcut');
 export const getLocalMarkdown = (uuid: string, type: MarkdownType) =>
 	invoke<string | null>('get_local_markdown', { uuid, kind: type });
+export const installDedicatedServer = () => invoke<string>('install_dedicated_server');
+export const addWorkshopItem = (input: string, name: string | null) =>
+	invoke('add_workshop_item', { input, name });
+export const removeWorkshopItem = (id: number) => invoke('remove_workshop_item', { id });
+export const openWorkshopItem = (id: number) => invoke('open_workshop_item', { id });
 export const setCustomArgs = (customArgs: string[], enabled: boolean) =>
 	invoke('set_custom_args', { customArgs, enabled });
@@ -1,11 +1,35 @@
 import { invoke } from '$lib/invoke';
-import type { ModId } from '$lib/types';
+import type {
+	AuthorBundle,
+	DependencyPreview,
+	ForeignFile,
+	ModId,
+	QueueItem
+} from '$lib/types';
 
 export const allMods = () => invoke('install_all_mods');
 export const mod = (id: ModId) => invoke('install_mod', { id });
+export const previewDependencies = (modId: ModId) =>
+	invoke<DependencyPreview>('preview_dependencies', { modRef: modId });
+export const modExcluding = (id: ModId, excluded: string[], optional: ModId[]) =>
+	invoke('install_mod_excluding', { id, excluded, optional });
+export const fromUrl = (url: string) => invoke('install_from_url', { url });
+export const previewAuthorBundle = (owner: string) =>
+	invoke<AuthorBundle>('preview_author_bundle', { owner });
+export const authorBundle = (owner: string) => invoke('install_author_bundle', { owner });
+export const reinstall = (uuid: string) => invoke('reinstall_mod', { uuid });
 export const cancelAll = () => invoke('cancel_all_installs');
+export const getQueue = () => invoke<QueueItem[]>('get_install_queue');
+export const cancelBatch = (batchId: number) => invoke('cancel_install_batch', { batchId });
+export const prioritizeBatch = (batchId: number) =>
+	invoke('prioritize_install_batch', { batchId });
+export const resolveQuarantine = (packageUuid: string, approve: boolean) =>
+	invoke('resolve_quarantine', { packageUuid, approve });
 export const clearDownloadCache = (soft: boolean) =>
 	invoke<number>('clear_download_cache', { soft });
+export const deduplicateCache = () => invoke<number>('deduplicate_cache');
 export const getDownloadSize = (modId: ModId) =>
 	invoke<number>('get_download_size', { modRef: modId });
 export const hasPendingInstallations = () => invoke<boolean>('has_pending_installations');
+export const getForeignFiles = () => invoke<ForeignFile[]>('get_foreign_files');
+export const trashForeignFiles = (paths: string[]) => invoke('trash_foreign_files', { paths });
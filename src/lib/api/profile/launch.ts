@@ -1,5 +1,13 @@
 import { invoke } from '$lib/invoke';
+import type { DetectedGame } from '$lib/types';
 
 export const launchGame = () => invoke('launch_game');
 export const getArgs = () => invoke<string>('get_launch_args');
+export const scanInstalledGames = () => invoke<DetectedGame[]>('scan_installed_games');
+export const useGogInstall = (slug: string, path: string) =>
+	invoke('use_gog_install', { slug, path });
 export const openGameDir = () => invoke('open_game_dir');
+export const clearSteamLaunchOptions = () => invoke('clear_steam_launch_options');
+export const getMissingLoaderFiles = () => invoke<string[]>('get_missing_loader_files');
+export const repairLoaderFiles = () => invoke('repair_loader_files');
+export const testInjection = () => invoke('test_injection');
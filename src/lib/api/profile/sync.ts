@@ -1,15 +1,34 @@
 import { invoke } from '$lib/invoke';
-import type { ListedSyncProfile, SyncImportData, SyncUser } from '$lib/types';
+import type {
+	AccountDeletion,
+	Compatibility,
+	ListedSyncProfile,
+	SyncImportData,
+	SyncRevision,
+	SyncUser
+} from '$lib/types';
 
 export const read = (id: string) => invoke<SyncImportData>('read_sync_profile', { id });
 export const create = () => invoke<string>('create_sync_profile');
-export const push = () => invoke('push_sync_profile');
+export const push = (message: string | null = null) => invoke('push_sync_profile', { message });
+export const getRevisions = () => invoke<SyncRevision[]>('get_sync_revisions');
+export const restoreRevision = (id: string, remote: boolean) =>
+	invoke('restore_sync_revision', { id, remote });
 export const clone = (id: string, name: string) => invoke('clone_sync_profile', { id, name });
 export const disconnect = (del: boolean) => invoke('disconnect_sync_profile', { delete: del });
 export const deleteProfile = (id: string) => invoke('delete_sync_profile', { id });
 export const pull = () => invoke('pull_sync_profile');
 export const fetch = () => invoke('fetch_sync_profile');
+export const setReadme = (readme: string | null) => invoke('set_profile_readme', { readme });
+export const setConfigEnforced = (file: string, enforced: boolean) =>
+	invoke('set_config_enforced', { file, enforced });
 export const getOwned = () => invoke<ListedSyncProfile[]>('get_owned_sync_profiles');
 export const login = () => invoke<SyncUser>('login');
 export const logout = () => invoke('logout');
+export const logoutEverywhere = () => invoke('logout_everywhere');
+export const deleteAccount = () => invoke<AccountDeletion>('delete_account');
 export const getUser = () => invoke<SyncUser | null>('get_user');
+export const getCompatibility = (idents: string[]) =>
+	invoke<Record<string, Compatibility>>('get_mod_compatibility', { idents });
+export const reportCompatibility = (ident: string, works: boolean) =>
+	invoke('report_mod_compatibility', { ident, works });
@@ -1,7 +1,11 @@
 import { invoke } from '$lib/invoke';
-import type { ModId } from '$lib/types';
+import type { HeldBackUpdate, LoaderInfo, ModId } from '$lib/types';
 
 export const changeModVersion = (id: ModId) => invoke('change_mod_version', { id });
 export const mods = (uuids: string[], respectIgnored: boolean) =>
 	invoke('update_mods', { uuids, respectIgnored });
+export const modsSafely = (uuids: string[], respectIgnored: boolean) =>
+	invoke<HeldBackUpdate[]>('update_mods_safely', { uuids, respectIgnored });
 export const ignore = (versionUuid: string) => invoke('ignore_update', { versionUuid });
+export const getLoaderInfo = () => invoke<LoaderInfo | null>('get_loader_info');
+export const loader = () => invoke('update_loader');
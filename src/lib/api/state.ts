@@ -1,3 +1,16 @@
 import { invoke } from '$lib/invoke';
+import type { AuditEntry, AuditFilter, MemoryReport, NotificationCategory } from '$lib/types';
 
 export const isFirstRun = () => invoke<boolean>('is_first_run');
+export const isPortable = () => invoke<boolean>('is_portable');
+export const getMemoryReport = () => invoke<MemoryReport>('get_memory_report');
+export const shouldNotify = (category: NotificationCategory) =>
+	invoke<boolean>('should_notify', { category });
+export const cancelRequests = () => invoke('cancel_requests');
+export const getAuditLog = (filter?: AuditFilter) =>
+	invoke<AuditEntry[]>('get_audit_log', { filter: filter ?? null });
+
+/** Must match `schema::VERSION` in the backend, bump both when a payload changes shape. */
+export const SCHEMA_VERSION = 1;
+
+export const checkSchemaVersion = () => invoke('check_schema_version', { version: SCHEMA_VERSION });
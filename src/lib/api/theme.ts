@@ -0,0 +1,6 @@
+import { invoke } from '$lib/invoke';
+import type { Theme, ThemeInfo } from '$lib/types';
+
+export const list = () => invoke<ThemeInfo[]>('get_themes');
+export const get = (id: string) => invoke<Theme>('get_theme', { id });
+export const importFile = (path: string) => invoke<ThemeInfo>('import_theme', { path });
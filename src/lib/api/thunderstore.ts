@@ -1,11 +1,34 @@
 import { invoke } from '$lib/invoke';
-import type { MarkdownType, Mod, ModId, QueryModsArgs } from '$lib/types';
+import type {
+	AuthorPackage,
+	GlobalSearchResult,
+	IndexStatus,
+	MarkdownType,
+	Mod,
+	ModId,
+	PackageLinks,
+	QueryModsArgs,
+	RateResult,
+	RecentlyUpdated
+} from '$lib/types';
 
 export const query = (args: QueryModsArgs) => invoke<Mod[]>('query_thunderstore', { args });
 export const stopQuerying = () => invoke('stop_querying_thunderstore');
 export const triggerModFetch = () => invoke('trigger_mod_fetch');
+export const getIndexStatus = () => invoke<IndexStatus>('get_index_status');
+export const refreshIndex = () => invoke<IndexStatus>('refresh_index');
 export const getMarkdown = (id: ModId, type: MarkdownType) =>
 	invoke<string | null>('get_markdown', { modRef: id, kind: type });
+export const renderMarkdown = (source: string) => invoke<string>('render_markdown', { source });
+export const getPackageLinks = (id: ModId) =>
+	invoke<PackageLinks>('get_package_links', { modRef: id });
+export const searchAllGames = (query: string, maxCount: number) =>
+	invoke<GlobalSearchResult[]>('search_all_games', { query, maxCount });
+export const getRecentlyUpdated = () => invoke<RecentlyUpdated>('get_recently_updated');
+export const markFeedVisited = () => invoke('mark_feed_visited');
+export const ratePackage = (packageUuid: string, rated: boolean) =>
+	invoke<RateResult>('rate_package', { packageUuid, rated });
+export const getAuthorDashboard = () => invoke<AuthorPackage[]>('get_author_dashboard');
 export const setToken = (token: string) => invoke('set_thunderstore_token', { token });
 export const hasToken = () => invoke<boolean>('has_thunderstore_token');
 export const clearToken = () => invoke('clear_thunderstore_token');
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HideReason } from "./HideReason";
+import type { InstallTask } from "./InstallTask";
+
+/**
+ * Events sent to the frontend to keep track of installation progress.
+ */
+export type InstallEvent = { "type": "show" } | { "type": "hide", reason: HideReason, } | { "type": "addCount", mods: number, bytes: number, } | { "type": "addProgress", mods: number, bytes: number, } | { "type": "removeCount", mods: number, bytes: number, } | { "type": "setTask", name: string, task: InstallTask, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QueueItem } from "./QueueItem";
+import type { QueueItemState } from "./QueueItemState";
+
+/**
+ * Changes to the queue's contents, sent to the frontend as `install_queue` events.
+ */
+export type QueueDiff = { "type": "added", items: Array<QueueItem>, } | { "type": "updated", batchId: number, ident: string, state: QueueItemState, } | { "type": "removed", batchId: number, } | { "type": "reset", items: Array<QueueItem>, };
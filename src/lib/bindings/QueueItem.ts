@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QueueItemState } from "./QueueItemState";
+
+/**
+ * A mod in the install queue, as shown in the frontend.
+ */
+export type QueueItem = { batchId: number, profileId: number, 
+/**
+ * Position in the whole queue, starting at 0.
+ */
+position: number, ident: string, fileSize: number, state: QueueItemState, };
@@ -5,6 +5,22 @@ export function isNum(value: ConfigValue) {
 	return value.type === 'int' || value.type === 'float';
 }
 
+export function valueToString(val: ConfigValue) {
+	switch (val.type) {
+		case 'bool':
+			return val.content ? 'True' : 'False';
+		case 'string':
+			return val.content;
+		case 'int':
+		case 'float':
+			return val.content.value.toString();
+		case 'enum':
+			return val.content.options[val.content.index];
+		case 'flags':
+			return val.content.indicies.map((i) => val.content.options[i]).join(', ');
+	}
+}
+
 export async function setConfigEntry(id: ConfigEntryId, value: ConfigValue) {
 	if (isNum(value) && value.content.value === null) return;
 
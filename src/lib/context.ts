@@ -1,11 +1,26 @@
 import { writable } from 'svelte/store';
-import type { ModContextItem } from './types';
+import { ModType, type Mod, type ModContextItem } from './types';
 import { open } from '@tauri-apps/plugin-shell';
+import * as api from './api';
+import { pushInfoToast } from './toast';
 
 function openIfNotNull(url: string | null) {
 	if (url !== null) open(url);
 }
 
+async function openSource(mod: Mod) {
+	let links = await api.thunderstore.getPackageLinks({
+		packageUuid: mod.uuid,
+		versionUuid: mod.versionUuid
+	});
+
+	if (links.source === null) {
+		pushInfoToast({ message: `No source code link found for ${mod.name}.` });
+	} else {
+		open(links.source);
+	}
+}
+
 export const defaultContextItems: ModContextItem[] = [
 	{
 		label: 'Open website',
@@ -13,6 +28,12 @@ export const defaultContextItems: ModContextItem[] = [
 		onclick: (mod) => openIfNotNull(mod.websiteUrl),
 		showFor: (mod) => mod.websiteUrl !== null && mod.websiteUrl.length > 0
 	},
+	{
+		label: 'Open source code',
+		icon: 'mdi:source-branch',
+		onclick: openSource,
+		showFor: (mod) => mod.type === ModType.Remote
+	},
 	{
 		label: 'Donate',
 		icon: 'mdi:heart',
@@ -8,6 +8,19 @@ type Error = {
 	message: string;
 };
 
+// errors that the backend sends as objects instead of plain strings
+type KnownError = {
+	kind: 'insufficientSpace';
+	message: string;
+	path: string;
+	required: number;
+	available: number;
+};
+
+function isKnownError(error: unknown): error is KnownError {
+	return typeof error === 'object' && error !== null && 'kind' in error;
+}
+
 listen<Error>('error', (evt) =>
 	pushToast({
 		type: 'error',
@@ -19,7 +32,8 @@ export async function invoke<T = void>(cmd: string, args?: any): Promise<T> {
 	try {
 		return await tauriInvoke<T>(cmd, args);
 	} catch (error: any) {
-		let errStr = error as string;
+		let errStr = isKnownError(error) ? error.message : (error as string);
+
 		let name = `Failed to ${toSentenceCase(cmd).toLowerCase()}`;
 		let message = errStr[0].toUpperCase() + errStr.slice(1);
 
@@ -27,6 +41,12 @@ export async function invoke<T = void>(cmd: string, args?: any): Promise<T> {
 			message += '.';
 		}
 
+		if (isKnownError(error) && error.kind === 'insufficientSpace') {
+			name = 'Not enough disk space';
+			message +=
+				' Free up some space, or move the cache and profiles to another drive in the settings.';
+		}
+
 		pushError({ name, message });
 		throw error;
 	}
@@ -0,0 +1,21 @@
+import * as api from '$lib/api';
+import { listen } from '@tauri-apps/api/event';
+import { pushToast, type Toast } from './toast';
+import type { NotificationCategory } from './types';
+
+listen<{ category: NotificationCategory; message: string }>('notification', (evt) =>
+	pushToast({
+		type: 'info',
+		message: evt.payload.message
+	})
+);
+
+/**
+ * Shows a toast about something the user didn't ask for, unless its category is turned off
+ * or a game is running. The backend has already checked its own notifications.
+ */
+export async function notify(category: NotificationCategory, toast: Toast) {
+	if (await api.state.shouldNotify(category)) {
+		pushToast(toast);
+	}
+}
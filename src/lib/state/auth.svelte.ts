@@ -18,6 +18,17 @@ class AuthState {
 		await api.profile.sync.logout();
 		this.user = null;
 	};
+
+	logoutEverywhere = async () => {
+		await api.profile.sync.logoutEverywhere();
+		this.user = null;
+	};
+
+	deleteAccount = async () => {
+		const deletion = await api.profile.sync.deleteAccount();
+		this.user = null;
+		return deletion;
+	};
 }
 
 const auth = new AuthState();
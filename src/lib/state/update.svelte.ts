@@ -1,3 +1,4 @@
+import * as api from '$lib/api';
 import { check, type Update } from '@tauri-apps/plugin-updater';
 
 class UpdateState {
@@ -8,7 +9,8 @@ class UpdateState {
 		if (this.isChecking) return;
 
 		this.isChecking = true;
-		this.next = await check();
+		// the installer would put a second copy in the user's program files
+		this.next = (await api.state.isPortable()) ? null : await check();
 		this.isChecking = false;
 	};
 }
@@ -1,6 +1,7 @@
 import { platform } from '@tauri-apps/plugin-os';
 import { PersistedState } from 'runed';
 import getPalette from 'tailwindcss-palette-generator';
+import type { Theme } from './types';
 
 export const defaultColors = {
 	slate: {
@@ -350,6 +351,24 @@ export function refreshColor(category: ColorCategory) {
 	setColor(category, getColor(category));
 }
 
+let themeVariables: string[] = [];
+
+/** Applies a theme pack on top of the chosen colors, or removes it if `theme` is null. */
+export function applyTheme(theme: Theme | null) {
+	for (const name of themeVariables) {
+		root.style.removeProperty(name);
+	}
+
+	refreshColor('accent');
+	refreshColor('primary');
+
+	themeVariables = theme ? Object.keys(theme.variables) : [];
+
+	for (const [name, value] of Object.entries(theme?.variables ?? {})) {
+		root.style.setProperty(name, value);
+	}
+}
+
 const defaultFont = 'Nunito Sans';
 
 export function setFont(fontFamily: string) {
@@ -365,4 +384,23 @@ export function refreshFont() {
 	setFont(getFont());
 }
 
+const localSettingKeys = ['primaryColor', 'accentColor', 'font', 'useNativeMenu'];
+
+/** Collects the settings kept in local storage, to export them along with the prefs. */
+export function getLocalSettings() {
+	let settings: { [key: string]: string } = {};
+	for (const key of localSettingKeys) {
+		let value = localStorage.getItem(key);
+		if (value !== null) settings[key] = value;
+	}
+	return settings;
+}
+
+export function setLocalSettings(settings: { [key: string]: unknown }) {
+	for (const key of localSettingKeys) {
+		let value = settings[key];
+		if (typeof value === 'string') localStorage.setItem(key, value);
+	}
+}
+
 export const useNativeMenu = new PersistedState('useNativeMenu', platform() === 'windows' ? false : true);
@@ -42,6 +42,45 @@ export type ConfigRange = {
 	end: number;
 };
 
+export type BatchConfigEdit = {
+	entry: string;
+	section: string | null;
+	files: string[];
+	value: string;
+};
+
+export type BatchConfigChange = {
+	file: string;
+	section: string;
+	entry: string;
+	oldValue: ConfigValue;
+	newValue: ConfigValue;
+};
+
+export type BatchConfigSkip = {
+	file: string;
+	section: string;
+	entry: string;
+	reason: string;
+};
+
+export type BatchConfigEditResult = {
+	changes: BatchConfigChange[];
+	skipped: BatchConfigSkip[];
+};
+
+export type ConfigPresetManifest = {
+	name: string;
+	mods: string[];
+	files: string[];
+};
+
+export type ConfigPresetData = {
+	manifest: ConfigPresetManifest;
+	id: string;
+	missingMods: string[];
+};
+
 export type ConfigFileType<T extends string, C = {}> = { type: T } & C;
 
 export type ConfigFile = { relativePath: string; displayName: string | null } & (
@@ -57,6 +96,14 @@ export type ProfileInfo = {
 	sync: SyncProfileInfo | null;
 	customArgs: string[];
 	customArgsEnabled: boolean;
+	workshopItems: WorkshopItem[];
+	notes: string | null;
+	readme: string | null;
+};
+
+export type WorkshopItem = {
+	id: number;
+	name: string | null;
 };
 
 export type SyncProfileInfo = {
@@ -65,6 +112,17 @@ export type SyncProfileInfo = {
 	syncedAt: string;
 	updatedAt: string;
 	missing: boolean;
+	enforcedConfig?: string[];
+};
+
+export type AccountDeletion = {
+	deletedProfiles: number;
+};
+
+export type SyncRevision = {
+	id: string;
+	createdAt: string;
+	message: string | null;
 };
 
 export type ListedSyncProfile = {
@@ -93,6 +151,15 @@ export type GameInfo = {
 	favorites: string[];
 };
 
+/** A game definition in the same format as the built-in `games.json`. */
+export type CustomGameDefinition = {
+	name: string;
+	slug?: string;
+	modLoader: { name: string; [key: string]: unknown };
+	platforms?: Record<string, unknown>;
+	[key: string]: unknown;
+};
+
 export type Mod = {
 	name: string;
 	description: string | null;
@@ -117,6 +184,9 @@ export type Mod = {
 	enabled?: boolean | null;
 	icon: string | null;
 	configFile: string | null;
+	tags?: string[];
+	note?: string | null;
+	localOnly?: boolean;
 };
 
 export type ModVersion = {
@@ -153,6 +223,13 @@ export type QueryModsArgs = {
 	sortBy: SortBy;
 	sortOrder: SortOrder;
 	maxCount: number;
+	author?: string | null;
+	hasUpdate?: boolean | null;
+	installedAfter?: string | null;
+	installedBefore?: string | null;
+	minSize?: number | null;
+	maxSize?: number | null;
+	includeTags?: string[];
 };
 
 export type QueryModsArgsWithoutMax = Omit<QueryModsArgs, 'maxCount'>;
@@ -177,14 +254,12 @@ export type ModActionResponse =
 	| { type: 'done' }
 	| { type: 'hasDependants'; dependants: Dependant[] };
 
-export type InstallTask = 'download' | 'extract' | 'install';
-
-export type InstallEvent =
-	| { type: 'show' }
-	| { type: 'hide'; reason: 'done' | 'error' | 'cancelled' }
-	| { type: 'addCount'; mods: number; bytes: number }
-	| { type: 'addProgress'; mods: number; bytes: number }
-	| { type: 'setTask'; name: string; task: InstallTask };
+// generated from the backend, see src-tauri/src/schema.rs
+export type { InstallEvent } from './bindings/InstallEvent';
+export type { InstallTask } from './bindings/InstallTask';
+export type { QueueDiff } from './bindings/QueueDiff';
+export type { QueueItem } from './bindings/QueueItem';
+export type { QueueItemState } from './bindings/QueueItemState';
 
 export type ModpackArgs = {
 	name: string;
@@ -208,15 +283,39 @@ export type Game = {
 	favorite: boolean;
 	modLoader: ModLoader;
 	popular: boolean;
+	server: boolean;
+	antiCheat: AntiCheat | null;
 };
 
+export type AntiCheatRisk = 'onlineBannable' | 'requiresBypass';
+
+export type AntiCheat = {
+	system: string | null;
+	risk: AntiCheatRisk;
+	note: string | null;
+	bypassArgs: string[];
+};
+
+export type GameUpdate = {
+	game: string;
+	previousBuild: number;
+	build: number;
+	missingLoaderFiles: string[];
+};
+
+export type SelfTestResult =
+	| { status: 'passed'; evidence: 'marker' | 'log' }
+	| { status: 'gameExited' }
+	| { status: 'timedOut' };
+
 export enum ModLoader {
 	BepInEx = 'BepInEx',
 	MelonLoader = 'MelonLoader',
 	Northstar = 'Northstar',
 	GDWeave = 'GDWeave',
 	ReturnOfModding = 'ReturnOfModding',
-	BepisLoader = 'BepisLoader'
+	BepisLoader = 'BepisLoader',
+	UE4SS = 'UE4SS'
 }
 
 export type PackageCategory = {
@@ -232,6 +331,16 @@ export type LaunchMode =
 	| { type: 'launcher'; content?: undefined }
 	| { type: 'direct'; content: { instances: number; intervalSecs: number } };
 
+export type Compatibility = {
+	gameVersion: string | null;
+	works: number;
+	broken: number;
+};
+
+export type HeldBackUpdate = Compatibility & {
+	ident: string;
+};
+
 export type AvailableUpdate = {
 	fullName: string;
 	ignore: boolean;
@@ -241,11 +350,31 @@ export type AvailableUpdate = {
 	new: string;
 };
 
+export type BulkAction = 'update' | 'remove' | 'toggle';
+
+export type BulkReport = {
+	succeeded: string[];
+	failed: {
+		uuid: string;
+		name: string | null;
+		error: string;
+	}[];
+};
+
+export type LoaderInfo = {
+	name: string;
+	fullName: string;
+	packageUuid: string;
+	version: string;
+	latest: string | null;
+};
+
 export type ProfileQuery = {
 	mods: Mod[];
 	totalModCount: number;
 	unknownMods: Dependant[];
 	updates: AvailableUpdate[];
+	tags: string[];
 };
 
 export type ImportData =
@@ -272,6 +401,7 @@ type ProfileManifest = {
 	mods: ProfileManifestMod[];
 	community: string | null;
 	ignoredUpdates: string[];
+	readme?: string | null;
 };
 
 type ProfileManifestMod = {
@@ -284,6 +414,147 @@ type ProfileManifestMod = {
 	};
 };
 
+export type AuthorBundle = {
+	packages: string[];
+	installed: number;
+	dependencies: number;
+	downloadSize: number;
+};
+
+export type DependencyPreview = {
+	entries: PreviewEntry[];
+	optional: PreviewEntry[];
+	downloadSize: number;
+};
+
+export type PreviewEntry = {
+	id: ModId;
+	ident: string;
+	fileSize: number;
+	installed: boolean;
+	cached: boolean;
+};
+
+export type ForeignFile = {
+	path: string;
+	isDir: boolean;
+	size: number;
+};
+
+export type PlannedFile = {
+	source: string;
+	target: string | null;
+	tracked: boolean;
+	mutable: boolean;
+};
+
+export type LocalModPreview = {
+	hasManifest: boolean;
+	name: string;
+	author: string | null;
+	version: string | null;
+	plan: PlannedFile[];
+};
+
+export type LocalModMetadata = {
+	name: string;
+	author: string | null;
+	version: string | null;
+	description: string | null;
+};
+
+export type ModListMatch = {
+	input: string;
+	candidates: ModListCandidate[];
+};
+
+export type ModListCandidate = {
+	id: ModId;
+	fullName: string;
+	exact: boolean;
+};
+
+export type ToggleSetReport = {
+	enabled: number;
+	disabled: number;
+	missing: string[];
+};
+
+export type MemoryReport = {
+	process: number | null;
+	thunderstore: {
+		packages: number;
+		versions: number;
+		bytes: number;
+	};
+	games: {
+		slug: string;
+		profiles: number;
+		mods: number;
+		configFiles: number;
+		bytes: number;
+	}[];
+};
+
+export type AuditEntry = {
+	at: string;
+	user: string;
+	command: string;
+	gameSlug: string | null;
+	profileName: string | null;
+	args: string;
+	/** Null while the command is running, or if it never reported back. */
+	succeeded: boolean | null;
+	error: string | null;
+};
+
+export type AuditFilter = {
+	command?: string;
+	profileName?: string;
+	since?: string;
+	limit?: number;
+};
+
+export type LogLevel = 'trace' | 'debug' | 'info' | 'warn' | 'error';
+
+export type LogEntry = {
+	timestamp: string;
+	level: LogLevel;
+	target: string;
+	message: string;
+};
+
+export type HealthReport = {
+	checkedAt: string;
+	game: string;
+	issues: HealthIssue[];
+};
+
+export type HealthIssue = {
+	kind: HealthIssueKind;
+	profile: string | null;
+	subject: string;
+};
+
+export type HealthIssueKind =
+	| 'missingFiles'
+	| 'missingDependency'
+	| 'emptyCacheEntry'
+	| 'unusedCache'
+	| 'missingLoaderFiles';
+
+export type PruneSuggestion = PruneAction & {
+	profileId: number | null;
+	profileName: string | null;
+	subject: string;
+	bytes: number;
+};
+
+export type PruneAction =
+	| { type: 'linkDuplicates'; paths: string[] }
+	| { type: 'removeDisabled'; uuid: string }
+	| { type: 'clearCache'; path: string };
+
 export type R2ImportData = {
 	path: string;
 	profiles: string[];
@@ -292,23 +563,112 @@ export type R2ImportData = {
 
 export type Prefs = {
 	dataDir: string;
-	cacheDir: string;
 	fetchModsAutomatically: boolean;
 	pullBeforeLaunch: boolean;
+	language: string;
+	theme: string | null;
+	archiveDir: string | null;
+	cacheDir: string | null;
+	sharedCache: boolean;
+	compressCache: boolean;
+	deduplicateCache: boolean;
+	tempDir: string | null;
+	configEditor: string | null;
+	extractThreads: number | null;
+	simultaneousDownloads: number;
+	writeBufferSize: number;
+	downloadMirror: string | null;
+	webhooks: Webhook[];
+	ownedNamespaces: string[];
+	quarantineNewMods: boolean;
+	shareNotes: boolean;
+	compatibilityNotes: boolean;
+	scheduledHealthChecks: boolean;
+	trackRuntimeFiles: boolean;
+	removeRuntimeFiles: boolean;
+	ipcServer: boolean;
+	logLevels: { [subsystem: string]: LogLevel };
+	notifications: NotificationPrefs;
+	kioskMode: boolean;
+	kioskPin: string | null;
+	allowedPaths: AllowedPath[];
+	gpuPreferenceExes: string[];
 	zoomFactor: number;
 	gamePrefs: Map<string, GamePrefs>;
 };
 
+export type NotificationCategory = 'updates' | 'sync' | 'errors' | 'server';
+
+export type NotificationPrefs = { [category in NotificationCategory]: boolean } & {
+	quietWhilePlaying: boolean;
+};
+
+export type SettingsImportReport = {
+	frontend: { [key: string]: unknown };
+	skipped: string[];
+};
+
+export type DirOverrides = {
+	dataDir: string | null;
+	cacheDir: string | null;
+	tempDir: string | null;
+};
+
+export type AllowedPath = {
+	path: string;
+	reason: string;
+	approvedAt: string;
+};
+
+export type WebhookEvent = 'updatesAvailable' | 'updatesApplied' | 'syncPushed';
+
+export type Webhook = {
+	url: string;
+	events: WebhookEvent[];
+	template: string | null;
+};
+
 export type GamePrefs = {
 	dirOverride: string | null;
 	customArgs: string[];
 	customArgsEnabled: boolean;
 	launchMode: LaunchMode;
 	platform: Platform | null;
+	deployMode: DeployMode;
+	stageGameFiles: boolean;
+	antiCheatAcknowledged: boolean;
+	injectSteamLaunchOptions: boolean;
+	processOptions: Record<string, ProcessOptions>;
+	launchHooks: Record<string, LaunchHooks>;
+	fetchInterval: number | null;
+};
+
+export type LaunchHooks = {
+	preLaunch: string | null;
+	postExit: string | null;
+};
+
+export type DeployMode = 'hardLink' | 'symlink' | 'copy';
+
+export type ProcessPriority = 'idle' | 'belowNormal' | 'normal' | 'aboveNormal' | 'high';
+
+export type GpuPreference = 'default' | 'powerSaving' | 'highPerformance';
+
+export type ProcessOptions = {
+	priority: ProcessPriority | null;
+	cpuAffinity: number[];
+	gpuPreference: GpuPreference | null;
 };
 
 export type Platform = 'steam' | 'epicGames' | 'oculus' | 'origin' | 'xboxStore';
 
+export type DetectedGame = {
+	slug: string;
+	name: string;
+	platform: Platform | null;
+	path: string;
+};
+
 export type ContextItem = {
 	label: string;
 	icon?: string;
@@ -316,6 +676,26 @@ export type ContextItem = {
 	children?: ContextItem[];
 };
 
+export type RequiredBy = {
+	installed: number;
+	community: number;
+};
+
+export type IndexStatus = {
+	lastFetched: string | null;
+	isFetching: boolean;
+	packageCount: number;
+	fetchInterval: number;
+	isStale: boolean;
+};
+
+export type PackageLinks = {
+	thunderstore: string;
+	website: string | null;
+	source: string | null;
+	donation: string | null;
+};
+
 export type ModContextItem = {
 	label: string;
 	icon?: string;
@@ -327,3 +707,86 @@ export type ModContextItem = {
 export type Zoom = { factor: number } | { delta: number };
 
 export type MarkdownType = 'readme' | 'changelog';
+
+export type PackageStats = {
+	date: string;
+	downloads: number;
+	rating: number;
+};
+
+export type OutdatedDependency = {
+	current: string;
+	latest: string;
+};
+
+export type RecentlyUpdated = {
+	since: string | null;
+	mods: Mod[];
+};
+
+export type GlobalSearchResult = {
+	game: string;
+	gameName: string;
+	uuid: string;
+	fullName: string;
+	latestVersion: string;
+	description: string;
+	downloads: number;
+	rating: number;
+	isDeprecated: boolean;
+};
+
+export type AuthorPackage = {
+	uuid: string;
+	fullName: string;
+	latestVersion: string;
+	downloads: number;
+	rating: number;
+	history: PackageStats[];
+	outdatedDependencies: OutdatedDependency[];
+};
+
+export type DependencyUpdate = {
+	uuid: string;
+	current: string;
+	latest: string;
+	changelogUrl: string;
+};
+
+export type ModpackUpdates = {
+	versionNumber: string;
+	nextVersionNumber: string;
+	updates: DependencyUpdate[];
+};
+
+export type ModReport = {
+	title: string;
+	body: string;
+	url: string;
+	prefilled: boolean;
+};
+
+export type RateResult = { type: 'sent'; score: number } | { type: 'queued' };
+
+export type FlagReason = 'executable' | 'script' | 'root';
+
+export type FlaggedFile = {
+	path: string;
+	reason: FlagReason;
+};
+
+export type QuarantineReview = {
+	packageUuid: string;
+	ident: string;
+	flagged: FlaggedFile[];
+};
+
+export type ThemeInfo = {
+	id: string;
+	name: string;
+	author: string | null;
+};
+
+export type Theme = ThemeInfo & {
+	variables: Record<string, string>;
+};
@@ -7,6 +7,7 @@ import {
 	ModType
 } from './types';
 import { convertFileSrc } from '@tauri-apps/api/core';
+import { platform } from '@tauri-apps/plugin-os';
 import games from './state/game.svelte';
 import * as api from '$lib/api';
 
@@ -88,10 +89,13 @@ export function communityUrl(path: string) {
 	return `https://thunderstore.io/c/${games.active?.slug}/p/${path}/`;
 }
 
-export function modIconSrc(mod: Mod) {
+/**
+ * Returns the icon of a mod. Lists should pass `thumbnail` to get a small, pre-generated version.
+ */
+export function modIconSrc(mod: Mod, thumbnail = false) {
 	if (mod.type === 'remote') {
 		let fullName = `${mod.author}-${mod.name}-${mod.version}`;
-		return thunderstoreIconUrl(fullName);
+		return thumbnail ? cachedImageUrl('icon', fullName, 'thumb') : thunderstoreIconUrl(fullName);
 	} else if (mod.icon !== null) {
 		let path = mod.enabled === false ? mod.icon + '.old' : mod.icon;
 		return convertFileSrc(path);
@@ -100,12 +104,25 @@ export function modIconSrc(mod: Mod) {
 	}
 }
 
-export function gameIconSrc(game: Game) {
-	return `https://raw.githubusercontent.com/Kesomannen/gale/refs/heads/master/images/games/${game.slug}.webp`;
+/**
+ * Returns a URL to an image in the local image cache, which downloads and resizes it on first use.
+ */
+function cachedImageUrl(kind: 'icon' | 'game', name: string, size: number | 'thumb') {
+	return imageProtocolUrl(`${kind}/${name}/${size}`);
 }
 
-export function thunderstoreIconUrl(fullName: string) {
-	return `https://gcdn.thunderstore.io/live/repository/icons/${fullName}.png`;
+function imageProtocolUrl(path: string) {
+	return platform() === 'windows'
+		? `http://gale-img.localhost/${path}`
+		: `gale-img://localhost/${path}`;
+}
+
+export function gameIconSrc(game: Game, size = 128) {
+	return cachedImageUrl('game', game.slug, size);
+}
+
+export function thunderstoreIconUrl(fullName: string, size = 128) {
+	return cachedImageUrl('icon', fullName, size);
 }
 
 export function capitalize(str: string): string {
@@ -163,6 +180,9 @@ export function emptyOrUndefined(str: string) {
 	return str;
 }
 
+/**
+ * Gets a mod's readme or changelog, rendered to sanitized HTML.
+ */
 export async function getMarkdown(mod: Mod, type: MarkdownType, useLatest = false) {
 	switch (mod.type) {
 		case ModType.Remote: